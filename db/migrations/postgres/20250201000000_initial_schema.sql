@@ -0,0 +1,39 @@
+-- initial schema for the kaku application
+
+-- create table for project
+create table project (
+    project_id uuid primary key,
+    universe_id uuid not null,
+    created_at timestamptz not null default now(),
+    project_name text not null,
+    slug text not null unique,
+    locked boolean not null default false
+);
+
+-- create index for projects by universe
+create index idx_project_universe on project(universe_id);
+
+-- create table for note
+create table note (
+    note_id uuid primary key,
+    imported_at timestamptz not null default now(),
+    stylo_id uuid not null,
+    project_id uuid not null references project(project_id) on delete cascade,
+    content text not null
+);
+
+-- create index for notes by project
+create index idx_note_project on note(project_id, imported_at);
+
+-- create table for thought
+create table thought (
+    thought_id uuid primary key,
+    parent_id uuid references thought(thought_id),
+    imported_at timestamptz not null default now(),
+    stylo_id uuid not null,
+    project_id uuid not null references project(project_id) on delete cascade,
+    content text not null
+);
+
+-- create index for thought children
+create index idx_thought_parent on thought(parent_id, imported_at);
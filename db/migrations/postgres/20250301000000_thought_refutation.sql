@@ -0,0 +1,4 @@
+-- thoughts may be refuted by other thoughts
+alter table thought
+    add column refuted_by uuid references thought(thought_id),
+    add column status text not null default 'standing' check (status in ('standing', 'disputed', 'invalidated'));
@@ -0,0 +1,3 @@
+-- thoughts may be tagged
+alter table thought add column tags text[] not null default '{}';
+create index idx_thought_tags on thought using gin (tags);
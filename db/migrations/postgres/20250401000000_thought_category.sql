@@ -0,0 +1,4 @@
+-- thoughts may be filed in hierarchical categories
+create extension if not exists ltree;
+alter table thought add column category ltree;
+create index idx_thought_category on thought using gist (category);
@@ -0,0 +1,14 @@
+-- thoughts may link to other thoughts, possibly in other projects
+alter table thought add column links jsonb not null default '[]';
+
+-- reverse index of the links, the target is not a foreign key so links to
+-- trashed thoughts are kept dangling
+create table thought_backlink (
+    target_id uuid not null,
+    source_id uuid not null references thought(thought_id) on delete cascade,
+    kind text not null check (kind in ('references', 'supports', 'contradicts', 'extends')),
+    primary key (target_id, source_id, kind)
+);
+
+-- create index for the links held by a thought
+create index idx_thought_backlink_source on thought_backlink(source_id);
@@ -0,0 +1,3 @@
+-- thoughts and notes may cite the bibliographic references of their project
+alter table thought add column reference_ids uuid[] not null default '{}';
+alter table note add column reference_ids uuid[] not null default '{}';
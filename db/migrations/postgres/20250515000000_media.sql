@@ -0,0 +1,3 @@
+-- thoughts and notes may be accompanied by medias of the media store
+alter table thought add column media jsonb not null default '[]';
+alter table note add column media jsonb not null default '[]';
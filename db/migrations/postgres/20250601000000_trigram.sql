@@ -0,0 +1,2 @@
+-- trigram similarity is used to suggest project slugs close to unknown ones
+create extension if not exists pg_trgm;
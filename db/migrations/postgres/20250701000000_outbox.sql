@@ -0,0 +1,6 @@
+-- events written with the changes of the books, waiting to be dispatched
+create table outbox (
+    position bigserial primary key,
+    event_id uuid not null unique,
+    event jsonb not null
+);
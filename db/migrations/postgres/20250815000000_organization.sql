@@ -0,0 +1,15 @@
+-- organizations accountable for the stylos and their universe
+create table organization (
+    organization_id uuid primary key,
+    name text not null,
+    certificate jsonb not null,
+    public_key text not null,
+    created_at timestamptz not null default now()
+);
+
+-- each organization has exactly one universe, created with it
+create table universe (
+    universe_id uuid primary key,
+    organization_id uuid not null unique references organization(organization_id),
+    created_at timestamptz not null default now()
+);
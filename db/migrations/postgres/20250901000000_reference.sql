@@ -0,0 +1,13 @@
+-- bibliographic references of a project, cited by its notes and thoughts
+create table reference (
+    reference_id uuid primary key,
+    project_id uuid not null references project(project_id) on delete cascade,
+    entry_type text not null,
+    citation_key text not null,
+    authors text[] not null default '{}',
+    title text not null,
+    year integer,
+    pages text,
+    extra_fields jsonb not null default '{}',
+    unique (project_id, citation_key)
+);
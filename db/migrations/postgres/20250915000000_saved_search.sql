@@ -0,0 +1,23 @@
+-- searches a stylo keeps to run them again, named uniquely per stylo
+create table saved_search (
+    saved_search_id uuid primary key,
+    stylo_id uuid not null,
+    name text not null,
+    query text not null,
+    created_at timestamptz not null default now(),
+    unique (stylo_id, name)
+);
+
+-- documents matching a saved search that its stylo has not seen yet
+create table saved_search_result (
+    saved_search_id uuid not null references saved_search(saved_search_id) on delete cascade,
+    stylo_id uuid not null,
+    kind text not null,
+    id uuid not null,
+    project_id uuid not null,
+    matched_at timestamptz not null,
+    primary key (saved_search_id, id)
+);
+
+-- create index for the new results by stylo
+create index idx_saved_search_result_stylo on saved_search_result(stylo_id, matched_at);
@@ -0,0 +1,4 @@
+-- trigram indexes finding the contents holding words close to the ones of
+-- fuzzy searches
+create index idx_note_content_trgm on note using gin (content gin_trgm_ops);
+create index idx_thought_content_trgm on thought using gin (content gin_trgm_ops);
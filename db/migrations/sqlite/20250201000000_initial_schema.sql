@@ -0,0 +1,39 @@
+-- initial schema for the kaku application
+
+-- create table for project
+create table project (
+    project_id blob primary key,
+    universe_id blob not null,
+    created_at text not null,
+    project_name text not null,
+    slug text not null unique,
+    locked boolean not null default false
+);
+
+-- create index for projects by universe
+create index idx_project_universe on project(universe_id);
+
+-- create table for note
+create table note (
+    note_id blob primary key,
+    imported_at text not null,
+    stylo_id blob not null,
+    project_id blob not null references project(project_id) on delete cascade,
+    content text not null
+);
+
+-- create index for notes by project
+create index idx_note_project on note(project_id, imported_at);
+
+-- create table for thought
+create table thought (
+    thought_id blob primary key,
+    parent_id blob references thought(thought_id),
+    imported_at text not null,
+    stylo_id blob not null,
+    project_id blob not null references project(project_id) on delete cascade,
+    content text not null
+);
+
+-- create index for thought children
+create index idx_thought_parent on thought(parent_id, imported_at);
@@ -0,0 +1,10 @@
+-- thoughts may be questions and answer questions
+alter table thought add column variation text not null default 'thought' check (variation in ('thought', 'question'));
+alter table thought add column answers blob references thought(thought_id);
+alter table thought add column answered boolean not null default false;
+
+-- create index for thoughts by project
+create index idx_thought_project on thought(project_id, imported_at);
+
+-- create index for the answers of a question
+create index idx_thought_answers on thought(answers);
@@ -0,0 +1,3 @@
+-- thoughts may be refuted by other thoughts
+alter table thought add column refuted_by blob references thought(thought_id);
+alter table thought add column status text not null default 'standing' check (status in ('standing', 'disputed', 'invalidated'));
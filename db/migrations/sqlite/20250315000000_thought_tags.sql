@@ -0,0 +1,2 @@
+-- thoughts may be tagged, tags are stored as a JSON array
+alter table thought add column tags text not null default '[]';
@@ -0,0 +1,3 @@
+-- thoughts may be filed in hierarchical categories, stored as dotted paths
+alter table thought add column category text;
+create index idx_thought_category on thought(project_id, category);
@@ -0,0 +1,4 @@
+-- thoughts and notes may cite the bibliographic references of their project,
+-- stored as a JSON array
+alter table thought add column reference_ids text not null default '[]';
+alter table note add column reference_ids text not null default '[]';
@@ -0,0 +1,4 @@
+-- thoughts and notes may be accompanied by medias of the media store, stored
+-- as a JSON array
+alter table thought add column media text not null default '[]';
+alter table note add column media text not null default '[]';
@@ -0,0 +1,6 @@
+-- events written with the changes of the books, waiting to be dispatched
+create table outbox (
+    position integer primary key autoincrement,
+    event_id text not null unique,
+    event text not null
+);
@@ -0,0 +1,13 @@
+-- stylos granted by an organization to write on its behalf
+create table stylo (
+    stylo_id blob primary key,
+    owner_organization_id blob not null,
+    actor_organization_id blob not null,
+    created_at text not null,
+    display_name text not null,
+    is_locked boolean not null default false,
+    email text not null
+);
+
+-- create index for stylos by owner
+create index idx_stylo_owner on stylo(owner_organization_id, created_at);
@@ -0,0 +1,15 @@
+-- organizations accountable for the stylos and their universe
+create table organization (
+    organization_id blob primary key,
+    name text not null,
+    certificate text not null,
+    public_key text not null,
+    created_at text not null
+);
+
+-- each organization has exactly one universe, created with it
+create table universe (
+    universe_id blob primary key,
+    organization_id blob not null unique references organization(organization_id),
+    created_at text not null
+);
@@ -0,0 +1,14 @@
+-- bibliographic references of a project, cited by its notes and thoughts,
+-- the authors and the extra fields are stored as JSON
+create table reference (
+    reference_id blob primary key,
+    project_id blob not null references project(project_id) on delete cascade,
+    entry_type text not null,
+    citation_key text not null,
+    authors text not null default '[]',
+    title text not null,
+    year integer,
+    pages text,
+    extra_fields text not null default '{}',
+    unique (project_id, citation_key)
+);
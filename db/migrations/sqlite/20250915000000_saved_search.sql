@@ -0,0 +1,23 @@
+-- searches a stylo keeps to run them again, named uniquely per stylo
+create table saved_search (
+    saved_search_id blob primary key,
+    stylo_id blob not null,
+    name text not null,
+    query text not null,
+    created_at text not null,
+    unique (stylo_id, name)
+);
+
+-- documents matching a saved search that its stylo has not seen yet
+create table saved_search_result (
+    saved_search_id blob not null references saved_search(saved_search_id) on delete cascade,
+    stylo_id blob not null,
+    kind text not null,
+    id blob not null,
+    project_id blob not null,
+    matched_at text not null,
+    primary key (saved_search_id, id)
+);
+
+-- create index for the new results by stylo
+create index idx_saved_search_result_stylo on saved_search_result(stylo_id, matched_at);
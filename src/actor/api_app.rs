@@ -1,16 +1,31 @@
-use axum::extract::{Path, State};
+use axum::extract::{DefaultBodyLimit, FromRef, Multipart, Path, Query, State};
+use axum::http::HeaderMap;
 use axum::http::StatusCode;
-use axum::response::IntoResponse;
-use axum::routing::delete;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
 use axum::Json;
 use axum::{routing::post, Router};
 use chrono::DateTime;
+use futures_util::{stream, StreamExt};
 use serde::Deserialize;
+use serde_json::json;
 use std::sync::Arc;
+use tokio::sync::broadcast;
 use uuid::Uuid;
 
-use crate::models::{CreateNoteCommand, CreateProjectCommand, CreateThoughtCommand};
-use crate::service::{ThoughtService, ThoughtServiceError};
+use crate::models::{
+    Category, CreateNoteCommand, CreateOrganizationCommand, CreateProjectCommand,
+    CreateReferenceCommand, CreateSavedSearchCommand, CreateStyloCommand, CreateThoughtCommand,
+    Media, ModifySavedSearchCommand, ModifyStyloCommand, ModifyThoughtCommand, QuerySyntaxError,
+    SearchFilter, SearchMode, ThoughtFilter, ThoughtLink, ThoughtVariation,
+};
+use crate::service::{
+    AuditLogService, EventStreamFilter, EventStreamService, OrganizationService,
+    OrganizationServiceError, ProjectStatsService, SavedSearchService, SavedSearchServiceError,
+    StreamedEvent, StyloService, StyloServiceError, ThoughtSearchService,
+    ThoughtSearchServiceError, ThoughtService, ThoughtServiceError,
+};
 
 /// Request payload for creating a new note.
 /// This represents the JSON body that clients should send when creating a note.
@@ -28,6 +43,14 @@ struct CreateNoteRequest {
     /// The content of the note.
     /// This contains the actual text/information of the note.
     pub content: String,
+
+    /// The references of the project cited by the note.
+    #[serde(default)]
+    pub references: Vec<Uuid>,
+
+    /// The medias accompanying the note, uploaded beforehand.
+    #[serde(default)]
+    pub media: Vec<Media>,
 }
 
 /// Request payload for creating a new project.
@@ -46,17 +69,250 @@ struct CreateThoughtRequest {
     pub imported_at: DateTime<chrono::Utc>,
     pub stylo_id: Uuid,
     pub content: String,
+
+    /// The thought this new thought is chained to, if any.
+    /// It must belong to the same project.
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+
+    /// Whether the new thought is a plain thought or a question.
+    #[serde(default)]
+    pub variation: ThoughtVariation,
+
+    /// The question this new thought answers, if any.
+    /// It must be a question of the same project.
+    #[serde(default)]
+    pub answers: Option<Uuid>,
+
+    /// The tags of the new thought.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// The category the new thought is filed in, if any.
+    #[serde(default)]
+    pub category: Option<Category>,
+
+    /// The thoughts the new thought links to.
+    #[serde(default)]
+    pub links: Vec<ThoughtLink>,
+
+    /// The references of the project cited by the new thought.
+    #[serde(default)]
+    pub references: Vec<Uuid>,
+
+    /// The medias accompanying the new thought, uploaded beforehand.
+    #[serde(default)]
+    pub media: Vec<Media>,
+}
+
+/// Request payload for renaming a tag in a project.
+#[derive(Deserialize)]
+struct RenameTagRequest {
+    /// The tag to rename.
+    pub tag: String,
+
+    /// The new name of the tag, it must not be used in the project yet.
+    pub new_tag: String,
+}
+
+/// Request payload for merging tags in a project.
+#[derive(Deserialize)]
+struct MergeTagsRequest {
+    /// The tags to merge.
+    pub tags: Vec<String>,
+
+    /// The tag they are merged into.
+    pub into: String,
+}
+
+/// Request payload for refuting a thought.
+#[derive(Deserialize)]
+struct RefuteThoughtRequest {
+    /// The thought refuting the thought.
+    pub refuted_by: Uuid,
+}
+
+/// Query parameters for fetching the descendants of a thought.
+#[derive(Deserialize)]
+struct DescendantsQuery {
+    /// Maximum depth of the returned tree, unlimited if not set.
+    pub depth: Option<usize>,
+}
+
+/// Query parameters for searching notes and thoughts.
+#[derive(Deserialize)]
+struct SearchQuery {
+    /// The words to search.
+    pub q: String,
+
+    /// Only search in the project with this slug.
+    pub project: Option<String>,
+
+    /// Only search thoughts carrying this tag.
+    pub tag: Option<String>,
+
+    /// Only search thoughts of this variation.
+    pub variation: Option<ThoughtVariation>,
+
+    /// Only search documents written by this stylo.
+    pub author: Option<Uuid>,
+
+    /// Maximum number of hits, 20 if not set.
+    pub limit: Option<usize>,
+
+    /// How the words of the query are matched.
+    #[serde(default)]
+    pub mode: SearchMode,
+
+    /// Minimum similarity of the words in fuzzy mode, the configured one if
+    /// not set.
+    pub similarity: Option<f64>,
+}
+
+/// Request payload for saving a search.
+/// The stylo is provided in the URL path.
+#[derive(Deserialize)]
+struct CreateSavedSearchRequest {
+    /// The name of the search, unique among the searches of the stylo.
+    pub name: String,
+
+    /// The query language expression.
+    pub query: String,
+}
+
+/// Query parameters for running a saved search.
+#[derive(Deserialize)]
+struct RunSavedSearchQuery {
+    /// Maximum number of matches, 20 if not set.
+    pub limit: Option<usize>,
+}
+
+/// Query parameters for streaming the model events.
+#[derive(Deserialize)]
+struct EventsQuery {
+    /// Only stream the events of the project with this slug.
+    pub project: Option<String>,
+
+    /// Only stream the events of the projects of this universe.
+    pub universe: Option<Uuid>,
+}
+
+/// Query parameters for querying notes and thoughts.
+#[derive(Deserialize)]
+struct QueryRequest {
+    /// The query language expression.
+    pub q: String,
+
+    /// Maximum number of matches, 20 if not set.
+    pub limit: Option<usize>,
+}
+
+/// Maximum size of a media upload request, in bytes.
+const MEDIA_UPLOAD_LIMIT: usize = 64 * 1024 * 1024;
+
+/// Default number of search hits.
+const SEARCH_DEFAULT_LIMIT: usize = 20;
+
+/// Maximum number of search hits.
+const SEARCH_MAX_LIMIT: usize = 100;
+
+/// Services shared by the handlers.
+/// Handlers extract the service they need from it.
+#[derive(Clone)]
+struct ApiState {
+    thought_service: Arc<ThoughtService>,
+    thought_search: Arc<ThoughtSearchService>,
+    saved_search: Arc<SavedSearchService>,
+    event_stream: Arc<EventStreamService>,
+    project_stats: Arc<ProjectStatsService>,
+    audit_log: Arc<AuditLogService>,
+    organizations: Arc<OrganizationService>,
+    stylos: Arc<StyloService>,
+}
+
+impl FromRef<ApiState> for Arc<ThoughtService> {
+    fn from_ref(state: &ApiState) -> Self {
+        state.thought_service.clone()
+    }
+}
+
+impl FromRef<ApiState> for Arc<ThoughtSearchService> {
+    fn from_ref(state: &ApiState) -> Self {
+        state.thought_search.clone()
+    }
+}
+
+impl FromRef<ApiState> for Arc<SavedSearchService> {
+    fn from_ref(state: &ApiState) -> Self {
+        state.saved_search.clone()
+    }
+}
+
+impl FromRef<ApiState> for Arc<EventStreamService> {
+    fn from_ref(state: &ApiState) -> Self {
+        state.event_stream.clone()
+    }
+}
+
+impl FromRef<ApiState> for Arc<ProjectStatsService> {
+    fn from_ref(state: &ApiState) -> Self {
+        state.project_stats.clone()
+    }
+}
+
+impl FromRef<ApiState> for Arc<AuditLogService> {
+    fn from_ref(state: &ApiState) -> Self {
+        state.audit_log.clone()
+    }
+}
+
+impl FromRef<ApiState> for Arc<OrganizationService> {
+    fn from_ref(state: &ApiState) -> Self {
+        state.organizations.clone()
+    }
+}
+
+impl FromRef<ApiState> for Arc<StyloService> {
+    fn from_ref(state: &ApiState) -> Self {
+        state.stylos.clone()
+    }
 }
 
 /// ApiApp is an actor that represents the API application.
 pub struct ApiApp {
     thought_service: Arc<ThoughtService>,
+    thought_search: Arc<ThoughtSearchService>,
+    saved_search: Arc<SavedSearchService>,
+    event_stream: Arc<EventStreamService>,
+    project_stats: Arc<ProjectStatsService>,
+    audit_log: Arc<AuditLogService>,
+    organizations: Arc<OrganizationService>,
+    stylos: Arc<StyloService>,
 }
 
 impl ApiApp {
     /// Create a new API application.
-    pub fn new(thought_service: Arc<ThoughtService>) -> Self {
-        Self { thought_service }
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        thought_service: Arc<ThoughtService>,
+        thought_search: Arc<ThoughtSearchService>,
+        saved_search: Arc<SavedSearchService>,
+        event_stream: Arc<EventStreamService>,
+        project_stats: Arc<ProjectStatsService>,
+        audit_log: Arc<AuditLogService>,
+        organizations: Arc<OrganizationService>,
+        stylos: Arc<StyloService>,
+    ) -> Self {
+        Self {
+            thought_service,
+            thought_search,
+            saved_search,
+            event_stream,
+            project_stats,
+            audit_log,
+            organizations,
+            stylos,
+        }
     }
 
     /// Get the router for the API application.
@@ -64,9 +320,107 @@ impl ApiApp {
         Router::new()
             .route("/project/{project_slug}/note", post(create_note))
             .route("/project/{project_slug}/thought", post(create_thought))
+            .route(
+                "/project/{project_slug}/thoughts",
+                get(list_thoughts).delete(trash_thoughts),
+            )
+            .route("/project/{project_slug}/notes", get(list_notes))
+            .route("/project/{project_slug}/tags", get(list_tags))
+            .route("/project/{project_slug}/categories", get(get_category_tree))
+            .route("/project/{project_slug}/stats", get(get_project_stats))
+            .route("/project/{project_slug}/audit", get(get_project_audit))
+            .route(
+                "/project/{project_slug}/links/dangling",
+                get(list_dangling_links),
+            )
+            .route("/project/{project_slug}/tags/rename", post(rename_tag))
+            .route("/project/{project_slug}/tags/merge", post(merge_tags))
+            .route(
+                "/project/{project_slug}/references",
+                get(list_references).post(create_reference),
+            )
+            .route(
+                "/project/{project_slug}/references.bib",
+                get(export_bibtex).post(import_bibtex),
+            )
             .route("/project/create", post(create_project))
-            .route("/notes/{note_id}", delete(scratch_note))
-            .with_state(self.thought_service.clone())
+            .route(
+                "/thought/{thought_id}",
+                get(get_thought).patch(modify_thought),
+            )
+            .route(
+                "/thought/{thought_id}/ancestors",
+                get(get_thought_ancestors),
+            )
+            .route(
+                "/thought/{thought_id}/descendants",
+                get(get_thought_descendants),
+            )
+            .route("/thought/{thought_id}/audit", get(get_model_audit))
+            .route("/thought/{thought_id}/answers", get(list_answers))
+            .route("/thought/{thought_id}/backlinks", get(list_backlinks))
+            .route("/thought/{thought_id}/reopen", post(reopen_question))
+            .route("/thought/{thought_id}/refute", post(refute_thought))
+            .route(
+                "/thought/{thought_id}/refutation/accept",
+                post(accept_refutation),
+            )
+            .route(
+                "/thought/{thought_id}/refutation/dismiss",
+                post(dismiss_refutation),
+            )
+            .route("/notes/{note_id}", get(get_note).delete(scratch_note))
+            .route("/notes/{note_id}/audit", get(get_model_audit))
+            .route("/reference/{reference_id}", get(get_reference))
+            .route(
+                "/media",
+                post(upload_media).layer(DefaultBodyLimit::max(MEDIA_UPLOAD_LIMIT)),
+            )
+            .route("/media/{digest}", get(download_media))
+            .route("/search", get(search))
+            .route("/query", get(query))
+            .route("/events", get(stream_events))
+            .route("/organization", post(create_organization))
+            .route("/organization/{organization_id}", get(get_organization))
+            .route(
+                "/organization/{organization_id}/universe",
+                get(get_universe),
+            )
+            .route("/organization/{organization_id}/stylos", get(list_stylos))
+            .route("/stylo", post(grant_stylo))
+            .route(
+                "/stylo/{stylo_id}",
+                get(get_stylo).patch(modify_stylo).delete(revoke_stylo),
+            )
+            .route("/stylo/{stylo_id}/lock", post(lock_stylo))
+            .route("/stylo/{stylo_id}/unlock", post(unlock_stylo))
+            .route("/stylo/{stylo_id}/audit", get(get_model_audit))
+            .route("/stylo/{stylo_id}/search", post(create_saved_search))
+            .route("/stylo/{stylo_id}/searches", get(list_saved_searches))
+            .route(
+                "/stylo/{stylo_id}/search/{saved_search_id}",
+                get(get_saved_search)
+                    .patch(modify_saved_search)
+                    .delete(delete_saved_search),
+            )
+            .route(
+                "/stylo/{stylo_id}/search/{saved_search_id}/run",
+                get(run_saved_search),
+            )
+            .route(
+                "/stylo/{stylo_id}/results",
+                get(list_saved_search_results).delete(clear_saved_search_results),
+            )
+            .with_state(ApiState {
+                thought_service: self.thought_service.clone(),
+                thought_search: self.thought_search.clone(),
+                saved_search: self.saved_search.clone(),
+                event_stream: self.event_stream.clone(),
+                project_stats: self.project_stats.clone(),
+                audit_log: self.audit_log.clone(),
+                organizations: self.organizations.clone(),
+                stylos: self.stylos.clone(),
+            })
     }
 }
 
@@ -81,6 +435,8 @@ async fn create_note(
         imported_at: payload.imported_at,
         stylo_id: payload.stylo_id,
         content: payload.content,
+        references: payload.references,
+        media: payload.media,
     };
 
     let note = service.create_note(command).await;
@@ -91,13 +447,90 @@ async fn create_note(
                 axum::http::header::LOCATION,
                 format!("/note/{}", note.note_id),
             )];
-            (StatusCode::CREATED, headers, Json(()))
+            (StatusCode::CREATED, headers, Json(json!(null)))
         }
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            [(axum::http::header::LOCATION, "".to_string())],
-            Json(()),
-        ),
+        Err(e) => match e.downcast_ref::<ThoughtServiceError>() {
+            Some(error @ ThoughtServiceError::ProjectNotFound(_, suggestions)) => (
+                StatusCode::NOT_FOUND,
+                [(axum::http::header::LOCATION, String::new())],
+                Json(json!({
+                    "error": error.to_string(),
+                    "suggestions": suggestions,
+                })),
+            ),
+            Some(error @ ThoughtServiceError::StyloNotFound(stylo_id)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                [(axum::http::header::LOCATION, String::new())],
+                Json(json!({
+                    "error": error.to_string(),
+                    "stylo": stylo_id,
+                })),
+            ),
+            Some(error @ ThoughtServiceError::StyloLocked(stylo_id)) => (
+                StatusCode::FORBIDDEN,
+                [(axum::http::header::LOCATION, String::new())],
+                Json(json!({
+                    "error": error.to_string(),
+                    "stylo": stylo_id,
+                })),
+            ),
+            Some(error @ ThoughtServiceError::InvalidReference(reference_id)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                [(axum::http::header::LOCATION, String::new())],
+                Json(json!({
+                    "error": error.to_string(),
+                    "reference": reference_id,
+                })),
+            ),
+            Some(error @ ThoughtServiceError::InvalidMedia(digest)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                [(axum::http::header::LOCATION, String::new())],
+                Json(json!({
+                    "error": error.to_string(),
+                    "media": digest,
+                })),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                [(axum::http::header::LOCATION, String::new())],
+                Json(json!(null)),
+            ),
+        },
+    }
+}
+
+/// Get a note by its ID
+async fn get_note(
+    State(service): State<Arc<ThoughtService>>,
+    Path(note_id): Path<Uuid>,
+) -> Response {
+    match service.get_note(note_id).await {
+        Ok(note) => (StatusCode::OK, Json(note)).into_response(),
+        Err(e)
+            if matches!(
+                e.downcast_ref::<ThoughtServiceError>(),
+                Some(ThoughtServiceError::NoteNotFound(_))
+            ) =>
+        {
+            (StatusCode::NOT_FOUND, Json(())).into_response()
+        }
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+    }
+}
+
+/// List the notes of a project
+async fn list_notes(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+) -> Response {
+    match service.list_notes_by_project(&project_slug).await {
+        Ok(notes) => (StatusCode::OK, Json(notes)).into_response(),
+        Err(e) => match e.downcast_ref::<ThoughtServiceError>() {
+            Some(error @ ThoughtServiceError::ProjectNotFound(_, suggestions)) => {
+                project_not_found(error, suggestions)
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+        },
     }
 }
 
@@ -106,30 +539,791 @@ async fn create_thought(
     State(service): State<Arc<ThoughtService>>,
     Path(project_slug): Path<String>,
     Json(payload): Json<CreateThoughtRequest>,
-) -> impl IntoResponse {
+) -> Response {
     let command = CreateThoughtCommand {
         project_slug,
         imported_at: payload.imported_at,
         stylo_id: payload.stylo_id,
         content: payload.content,
-        parent_id: None,
+        variation: payload.variation,
+        answers: payload.answers,
+        tags: payload.tags,
+        category: payload.category,
+        links: payload.links,
+        references: payload.references,
+        media: payload.media,
+        parent_id: payload.parent_id,
     };
 
     let thought = service.create_thought(command).await;
 
     match thought {
-        Ok(thought) => {
+        Ok(created) => {
             let headers = [(
                 axum::http::header::LOCATION,
-                format!("/thought/{}", thought.thought_id),
+                format!("/thought/{}", created.thought.thought_id),
             )];
-            (StatusCode::CREATED, headers, Json(()))
+            (StatusCode::CREATED, headers, Json(created)).into_response()
+        }
+        Err(e) => match e.downcast_ref::<ThoughtServiceError>() {
+            Some(error @ ThoughtServiceError::ProjectNotFound(_, suggestions)) => {
+                project_not_found(error, suggestions)
+            }
+            Some(error @ ThoughtServiceError::StyloNotFound(stylo_id)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "error": error.to_string(),
+                    "stylo": stylo_id,
+                })),
+            )
+                .into_response(),
+            Some(error @ ThoughtServiceError::StyloLocked(stylo_id)) => (
+                StatusCode::FORBIDDEN,
+                Json(json!({
+                    "error": error.to_string(),
+                    "stylo": stylo_id,
+                })),
+            )
+                .into_response(),
+            Some(
+                error @ (ThoughtServiceError::InvalidParentReference(parent_id)
+                | ThoughtServiceError::ParentInAnotherProject(parent_id)),
+            ) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "error": error.to_string(),
+                    "parent_id": parent_id,
+                })),
+            )
+                .into_response(),
+            Some(error @ ThoughtServiceError::InvalidQuestionReference(question_id)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "error": error.to_string(),
+                    "answers": question_id,
+                })),
+            )
+                .into_response(),
+            Some(error @ ThoughtServiceError::InvalidTag(tag)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "error": error.to_string(),
+                    "tag": tag,
+                })),
+            )
+                .into_response(),
+            Some(error @ ThoughtServiceError::InvalidLink(target_id)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "error": error.to_string(),
+                    "link": target_id,
+                })),
+            )
+                .into_response(),
+            Some(error @ ThoughtServiceError::InvalidReference(reference_id)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "error": error.to_string(),
+                    "reference": reference_id,
+                })),
+            )
+                .into_response(),
+            Some(error @ ThoughtServiceError::InvalidMedia(digest)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "error": error.to_string(),
+                    "media": digest,
+                })),
+            )
+                .into_response(),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                [(axum::http::header::LOCATION, String::new())],
+                Json(()),
+            )
+                .into_response(),
+        },
+    }
+}
+
+/// Get a thought by its ID
+async fn get_thought(
+    State(service): State<Arc<ThoughtService>>,
+    Path(thought_id): Path<Uuid>,
+) -> Response {
+    thought_response(service.get_thought(thought_id).await)
+}
+
+/// Modify the content, the tags, the category, the links, the references or
+/// the medias of a thought
+async fn modify_thought(
+    State(service): State<Arc<ThoughtService>>,
+    Path(thought_id): Path<Uuid>,
+    Json(payload): Json<ModifyThoughtCommand>,
+) -> Response {
+    thought_response(service.modify_thought(thought_id, payload).await)
+}
+
+/// Get the ancestors of a thought, from its parent up to the root
+async fn get_thought_ancestors(
+    State(service): State<Arc<ThoughtService>>,
+    Path(thought_id): Path<Uuid>,
+) -> Response {
+    thought_response(service.get_thought_ancestors(thought_id).await)
+}
+
+/// Get the tree of descendants of a thought
+async fn get_thought_descendants(
+    State(service): State<Arc<ThoughtService>>,
+    Path(thought_id): Path<Uuid>,
+    Query(query): Query<DescendantsQuery>,
+) -> Response {
+    thought_response(
+        service
+            .get_thought_descendants(thought_id, query.depth)
+            .await,
+    )
+}
+
+/// List the thoughts of a project, optionally filtered
+async fn list_thoughts(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+    Query(filter): Query<ThoughtFilter>,
+) -> Response {
+    match service
+        .list_thoughts_by_project(&project_slug, &filter)
+        .await
+    {
+        Ok(thoughts) => (StatusCode::OK, Json(thoughts)).into_response(),
+        Err(e) => match e.downcast_ref::<ThoughtServiceError>() {
+            Some(error @ ThoughtServiceError::ProjectNotFound(_, suggestions)) => {
+                project_not_found(error, suggestions)
+            }
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+        },
+    }
+}
+
+/// List the thoughts answering a question
+async fn list_answers(
+    State(service): State<Arc<ThoughtService>>,
+    Path(thought_id): Path<Uuid>,
+) -> Response {
+    thought_response(service.list_answers(thought_id).await)
+}
+
+/// List the thoughts linking to a thought
+async fn list_backlinks(
+    State(service): State<Arc<ThoughtService>>,
+    Path(thought_id): Path<Uuid>,
+) -> Response {
+    thought_response(service.list_backlinks(thought_id).await)
+}
+
+/// Reopen an answered question
+async fn reopen_question(
+    State(service): State<Arc<ThoughtService>>,
+    Path(thought_id): Path<Uuid>,
+) -> Response {
+    thought_response(service.reopen_question(thought_id).await)
+}
+
+/// Refute a thought by another thought
+async fn refute_thought(
+    State(service): State<Arc<ThoughtService>>,
+    Path(thought_id): Path<Uuid>,
+    Json(payload): Json<RefuteThoughtRequest>,
+) -> Response {
+    thought_response(service.refute(thought_id, payload.refuted_by).await)
+}
+
+/// Accept the refutation of a thought, invalidating it
+async fn accept_refutation(
+    State(service): State<Arc<ThoughtService>>,
+    Path(thought_id): Path<Uuid>,
+) -> Response {
+    thought_response(service.accept_refutation(thought_id).await)
+}
+
+/// Dismiss the refutation of a thought, it stands again
+async fn dismiss_refutation(
+    State(service): State<Arc<ThoughtService>>,
+    Path(thought_id): Path<Uuid>,
+) -> Response {
+    thought_response(service.dismiss_refutation(thought_id).await)
+}
+
+/// Turn the result of a thought query into a response.
+fn thought_response<T: serde::Serialize>(result: crate::Result<T>) -> Response {
+    match result {
+        Ok(body) => (StatusCode::OK, Json(body)).into_response(),
+        Err(e)
+            if matches!(
+                e.downcast_ref::<ThoughtServiceError>(),
+                Some(ThoughtServiceError::ThoughtNotFound(_))
+            ) =>
+        {
+            (StatusCode::NOT_FOUND, Json(())).into_response()
         }
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            [(axum::http::header::LOCATION, String::new())],
-            Json(()),
-        ),
+        Err(e) => match e.downcast_ref::<ThoughtServiceError>() {
+            Some(
+                error @ (ThoughtServiceError::NotAQuestion(_)
+                | ThoughtServiceError::InvalidRefutation(_)
+                | ThoughtServiceError::InvalidTag(_)
+                | ThoughtServiceError::InvalidLink(_)
+                | ThoughtServiceError::InvalidReference(_)
+                | ThoughtServiceError::InvalidMedia(_)),
+            ) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "error": error.to_string() })),
+            )
+                .into_response(),
+            Some(
+                error @ (ThoughtServiceError::NotDisputed(_)
+                | ThoughtServiceError::AlreadyInvalidated(_)),
+            ) => (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": error.to_string() })),
+            )
+                .into_response(),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+        },
+    }
+}
+
+/// List the tags of a project with their usage counts
+async fn list_tags(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+) -> Response {
+    project_response(service.list_tags(&project_slug).await)
+}
+
+/// Get the category tree of a project with thought counts
+async fn get_category_tree(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+) -> Response {
+    project_response(service.get_category_tree(&project_slug).await)
+}
+
+/// Get the counts of the changes made in a project
+async fn get_project_stats(
+    State(service): State<Arc<ThoughtService>>,
+    State(project_stats): State<Arc<ProjectStatsService>>,
+    Path(project_slug): Path<String>,
+) -> Response {
+    let stats = match service.get_project(&project_slug).await {
+        Ok(project) => Ok(project_stats.get(project.project_id).await),
+        Err(e) => Err(e),
+    };
+
+    project_response(stats)
+}
+
+/// List the audit entries of a project, its notes and its thoughts
+async fn get_project_audit(
+    State(service): State<Arc<ThoughtService>>,
+    State(audit_log): State<Arc<AuditLogService>>,
+    Path(project_slug): Path<String>,
+) -> Response {
+    let entries = match service.get_project(&project_slug).await {
+        Ok(project) => audit_log.entries(project.project_id).await,
+        Err(e) => Err(e),
+    };
+
+    project_response(entries)
+}
+
+/// List the audit entries of a note, a thought or a stylo
+/// The entries of a scratched note or of a trashed thought are still listed.
+async fn get_model_audit(
+    State(audit_log): State<Arc<AuditLogService>>,
+    Path(model_id): Path<Uuid>,
+) -> Response {
+    match audit_log.entries(model_id).await {
+        Ok(entries) => (StatusCode::OK, Json(entries)).into_response(),
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+    }
+}
+
+/// Trash all the thoughts of a project
+async fn trash_thoughts(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+) -> Response {
+    project_response(service.trash_thoughts(&project_slug).await)
+}
+
+/// List the links of a project whose target has been trashed
+async fn list_dangling_links(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+) -> Response {
+    project_response(service.list_dangling_links(&project_slug).await)
+}
+
+/// Rename a tag on all the thoughts of a project
+async fn rename_tag(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+    Json(payload): Json<RenameTagRequest>,
+) -> Response {
+    project_response(
+        service
+            .rename_tag(&project_slug, &payload.tag, &payload.new_tag)
+            .await,
+    )
+}
+
+/// Merge tags on all the thoughts of a project
+async fn merge_tags(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+    Json(payload): Json<MergeTagsRequest>,
+) -> Response {
+    project_response(
+        service
+            .merge_tags(&project_slug, &payload.tags, &payload.into)
+            .await,
+    )
+}
+
+/// Tell a project does not exist, suggesting the projects with close slugs.
+fn project_not_found(error: &impl std::fmt::Display, suggestions: &[String]) -> Response {
+    (
+        StatusCode::NOT_FOUND,
+        Json(json!({
+            "error": error.to_string(),
+            "suggestions": suggestions,
+        })),
+    )
+        .into_response()
+}
+
+/// Turn the result of an operation over a whole project into a response.
+fn project_response<T: serde::Serialize>(result: crate::Result<T>) -> Response {
+    match result {
+        Ok(body) => (StatusCode::OK, Json(body)).into_response(),
+        Err(e) => match e.downcast_ref::<ThoughtServiceError>() {
+            Some(error @ ThoughtServiceError::ProjectNotFound(_, suggestions)) => {
+                project_not_found(error, suggestions)
+            }
+            Some(error @ ThoughtServiceError::InvalidTag(_)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "error": error.to_string() })),
+            )
+                .into_response(),
+            Some(error @ ThoughtServiceError::TagAlreadyExists(_)) => (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": error.to_string() })),
+            )
+                .into_response(),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+        },
+    }
+}
+
+/// Create a reference in a project
+async fn create_reference(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+    Json(payload): Json<CreateReferenceCommand>,
+) -> Response {
+    match service.create_reference(&project_slug, payload).await {
+        Ok(reference) => {
+            let headers = [(
+                axum::http::header::LOCATION,
+                format!("/reference/{}", reference.reference_id),
+            )];
+            (StatusCode::CREATED, headers, Json(reference)).into_response()
+        }
+        Err(e) => reference_response::<()>(Err(e)),
+    }
+}
+
+/// Get a reference by its ID
+async fn get_reference(
+    State(service): State<Arc<ThoughtService>>,
+    Path(reference_id): Path<Uuid>,
+) -> Response {
+    reference_response(service.get_reference(reference_id).await)
+}
+
+/// List the references of a project
+async fn list_references(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+) -> Response {
+    reference_response(service.list_references(&project_slug).await)
+}
+
+/// Import a BibTeX document in a project
+async fn import_bibtex(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+    body: String,
+) -> Response {
+    reference_response(service.import_bibtex(&project_slug, &body).await)
+}
+
+/// Export the references of a project as a BibTeX document
+async fn export_bibtex(
+    State(service): State<Arc<ThoughtService>>,
+    Path(project_slug): Path<String>,
+) -> Response {
+    match service.export_bibtex(&project_slug).await {
+        Ok(bibtex) => (
+            StatusCode::OK,
+            [(axum::http::header::CONTENT_TYPE, "application/x-bibtex")],
+            bibtex,
+        )
+            .into_response(),
+        Err(e) => reference_response::<()>(Err(e)),
+    }
+}
+
+/// Turn the result of a reference operation into a response.
+fn reference_response<T: serde::Serialize>(result: crate::Result<T>) -> Response {
+    match result {
+        Ok(body) => (StatusCode::OK, Json(body)).into_response(),
+        Err(e) => match e.downcast_ref::<ThoughtServiceError>() {
+            Some(error @ ThoughtServiceError::ProjectNotFound(_, suggestions)) => {
+                project_not_found(error, suggestions)
+            }
+            Some(ThoughtServiceError::ReferenceNotFound(_)) => {
+                (StatusCode::NOT_FOUND, Json(())).into_response()
+            }
+            Some(error @ ThoughtServiceError::InvalidBibliography(_)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "error": error.to_string() })),
+            )
+                .into_response(),
+            Some(error @ ThoughtServiceError::CitationKeyAlreadyExists(_)) => (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": error.to_string() })),
+            )
+                .into_response(),
+            _ => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+        },
+    }
+}
+
+/// Upload the files of a multipart form to the media store
+async fn upload_media(
+    State(service): State<Arc<ThoughtService>>,
+    mut multipart: Multipart,
+) -> Response {
+    let mut uploaded = Vec::new();
+
+    loop {
+        let field = match multipart.next_field().await {
+            Ok(Some(field)) => field,
+            Ok(None) => break,
+            Err(e) => return (e.status(), Json(json!({ "error": e.body_text() }))).into_response(),
+        };
+        let mime_type = field.content_type().map(str::to_string);
+        let filename = field.file_name().map(str::to_string);
+        let content = match field.bytes().await {
+            Ok(content) => content,
+            Err(e) => return (e.status(), Json(json!({ "error": e.body_text() }))).into_response(),
+        };
+        match service
+            .upload_media(&content, mime_type.as_deref(), filename)
+            .await
+        {
+            Ok(media) => uploaded.push(media),
+            Err(_) => return (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+        }
+    }
+
+    if uploaded.is_empty() {
+        return (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "error": "The form does not contain any file." })),
+        )
+            .into_response();
+    }
+
+    (StatusCode::CREATED, Json(uploaded)).into_response()
+}
+
+/// Download the content of a media
+async fn download_media(
+    State(service): State<Arc<ThoughtService>>,
+    Path(digest): Path<String>,
+) -> Response {
+    match service.download_media(&digest).await {
+        Ok((content, mime_type)) => (
+            StatusCode::OK,
+            [
+                (axum::http::header::CONTENT_TYPE, mime_type),
+                (axum::http::header::ETAG, format!("\"{digest}\"")),
+                (
+                    axum::http::header::CACHE_CONTROL,
+                    "public, max-age=31536000, immutable".to_string(),
+                ),
+            ],
+            content,
+        )
+            .into_response(),
+        Err(e)
+            if matches!(
+                e.downcast_ref::<ThoughtServiceError>(),
+                Some(ThoughtServiceError::MediaNotFound(_))
+            ) =>
+        {
+            (StatusCode::NOT_FOUND, Json(())).into_response()
+        }
+        Err(_) => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+    }
+}
+
+/// Search notes and thoughts, most relevant first
+async fn search(
+    State(search): State<Arc<ThoughtSearchService>>,
+    Query(query): Query<SearchQuery>,
+) -> Response {
+    let filter = SearchFilter {
+        project: query.project,
+        tag: query.tag,
+        variation: query.variation,
+        author: query.author,
+    };
+    let limit = query
+        .limit
+        .unwrap_or(SEARCH_DEFAULT_LIMIT)
+        .min(SEARCH_MAX_LIMIT);
+
+    let hits = match query.mode {
+        SearchMode::Exact => search.search(&query.q, &filter, limit).await,
+        SearchMode::Fuzzy => {
+            search
+                .fuzzy_search(&query.q, &filter, query.similarity, limit)
+                .await
+        }
+    };
+
+    match hits {
+        Ok(hits) => (StatusCode::OK, Json(hits)).into_response(),
+        Err(e) => search_error(e),
+    }
+}
+
+/// Find the notes and thoughts matching a query language expression, newest
+/// first
+async fn query(
+    State(search): State<Arc<ThoughtSearchService>>,
+    Query(query): Query<QueryRequest>,
+) -> Response {
+    let limit = query
+        .limit
+        .unwrap_or(SEARCH_DEFAULT_LIMIT)
+        .min(SEARCH_MAX_LIMIT);
+
+    match search.query(&query.q, limit).await {
+        Ok(hits) => (StatusCode::OK, Json(hits)).into_response(),
+        Err(e) => search_error(e),
+    }
+}
+
+/// Response to an error of the search service.
+/// Syntax errors of queries are located so clients can underline them.
+fn search_error(e: anyhow::Error) -> Response {
+    match e.downcast_ref::<ThoughtSearchServiceError>() {
+        Some(error @ ThoughtSearchServiceError::ProjectNotFound(_, suggestions)) => {
+            project_not_found(error, suggestions)
+        }
+        Some(
+            error @ (ThoughtSearchServiceError::EmptyQuery
+            | ThoughtSearchServiceError::InvalidThreshold(_)),
+        ) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(json!({ "error": error.to_string() })),
+        )
+            .into_response(),
+        Some(ThoughtSearchServiceError::InvalidQuery(error)) => query_syntax_error(error),
+        None => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+    }
+}
+
+/// Response to a syntax error in a query, located so clients can underline it.
+fn query_syntax_error(error: &QuerySyntaxError) -> Response {
+    (
+        StatusCode::BAD_REQUEST,
+        Json(json!({
+            "error": error.message,
+            "kind": error.kind,
+            "start": error.start,
+            "end": error.end,
+        })),
+    )
+        .into_response()
+}
+
+/// Stream the model events as server-sent events
+///
+/// Each event carries its epoch and number as identifier, so a client
+/// reconnecting with the `Last-Event-ID` header receives the events it missed,
+/// as long as they are still kept, or all the kept events if the application
+/// restarted since. A client that does not keep up is disconnected and has to
+/// reconnect the same way.
+async fn stream_events(
+    State(events): State<Arc<EventStreamService>>,
+    State(service): State<Arc<ThoughtService>>,
+    Query(query): Query<EventsQuery>,
+    headers: HeaderMap,
+) -> Response {
+    let project_id = match query.project {
+        Some(slug) => match service.get_project(&slug).await {
+            Ok(project) => Some(project.project_id),
+            Err(e) => return project_response::<()>(Err(e)),
+        },
+        None => None,
+    };
+    let filter = EventStreamFilter {
+        project_id,
+        universe_id: query.universe,
+    };
+    let last_event_id = headers
+        .get("last-event-id")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok());
+
+    let (replay, receiver) = events.stream(last_event_id).await;
+    let live = stream::unfold(receiver, |mut receiver| async move {
+        match receiver.recv().await {
+            Ok(streamed) => Some((streamed, receiver)),
+            Err(broadcast::error::RecvError::Lagged(_) | broadcast::error::RecvError::Closed) => {
+                None
+            }
+        }
+    });
+    let stream = stream::iter(replay)
+        .chain(live)
+        .filter(move |streamed| std::future::ready(streamed.matches(&filter)))
+        .map(|streamed: StreamedEvent| {
+            Event::default()
+                .id(streamed.id.to_string())
+                .event("model")
+                .json_data(&streamed.event)
+        });
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+/// Save a search for a stylo
+async fn create_saved_search(
+    State(service): State<Arc<SavedSearchService>>,
+    Path(stylo_id): Path<Uuid>,
+    Json(payload): Json<CreateSavedSearchRequest>,
+) -> Response {
+    let command = CreateSavedSearchCommand {
+        stylo_id,
+        name: payload.name,
+        query: payload.query,
+    };
+
+    match service.create(command).await {
+        Ok(saved_search) => {
+            let headers = [(
+                axum::http::header::LOCATION,
+                format!("/stylo/{stylo_id}/search/{}", saved_search.saved_search_id),
+            )];
+            (StatusCode::CREATED, headers, Json(saved_search)).into_response()
+        }
+        Err(e) => saved_search_response::<()>(Err(e)),
+    }
+}
+
+/// List the saved searches of a stylo
+async fn list_saved_searches(
+    State(service): State<Arc<SavedSearchService>>,
+    Path(stylo_id): Path<Uuid>,
+) -> Response {
+    saved_search_response(service.list(stylo_id).await)
+}
+
+/// Get a saved search of a stylo
+async fn get_saved_search(
+    State(service): State<Arc<SavedSearchService>>,
+    Path((stylo_id, saved_search_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    saved_search_response(service.get(stylo_id, saved_search_id).await)
+}
+
+/// Rename a saved search or change its query
+async fn modify_saved_search(
+    State(service): State<Arc<SavedSearchService>>,
+    Path((stylo_id, saved_search_id)): Path<(Uuid, Uuid)>,
+    Json(payload): Json<ModifySavedSearchCommand>,
+) -> Response {
+    saved_search_response(service.modify(stylo_id, saved_search_id, payload).await)
+}
+
+/// Delete a saved search and its new results
+async fn delete_saved_search(
+    State(service): State<Arc<SavedSearchService>>,
+    Path((stylo_id, saved_search_id)): Path<(Uuid, Uuid)>,
+) -> Response {
+    saved_search_response(service.delete(stylo_id, saved_search_id).await)
+}
+
+/// Run a saved search, newest matches first
+async fn run_saved_search(
+    State(service): State<Arc<SavedSearchService>>,
+    Path((stylo_id, saved_search_id)): Path<(Uuid, Uuid)>,
+    Query(query): Query<RunSavedSearchQuery>,
+) -> Response {
+    let limit = query
+        .limit
+        .unwrap_or(SEARCH_DEFAULT_LIMIT)
+        .min(SEARCH_MAX_LIMIT);
+
+    saved_search_response(service.run(stylo_id, saved_search_id, limit).await)
+}
+
+/// List the documents that matched the saved searches of a stylo since they
+/// were last cleared
+async fn list_saved_search_results(
+    State(service): State<Arc<SavedSearchService>>,
+    Path(stylo_id): Path<Uuid>,
+) -> Response {
+    saved_search_response(service.list_results(stylo_id).await)
+}
+
+/// Clear the new results of a stylo, returning them
+async fn clear_saved_search_results(
+    State(service): State<Arc<SavedSearchService>>,
+    Path(stylo_id): Path<Uuid>,
+) -> Response {
+    saved_search_response(service.clear_results(stylo_id).await)
+}
+
+/// Turn the result of a saved search operation into a response.
+fn saved_search_response<T: serde::Serialize>(result: crate::Result<T>) -> Response {
+    match result {
+        Ok(body) => (StatusCode::OK, Json(body)).into_response(),
+        Err(e) => match e.downcast_ref::<SavedSearchServiceError>() {
+            Some(SavedSearchServiceError::SavedSearchNotFound(_)) => {
+                (StatusCode::NOT_FOUND, Json(())).into_response()
+            }
+            Some(error @ SavedSearchServiceError::EmptyName) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "error": error.to_string() })),
+            )
+                .into_response(),
+            Some(error @ SavedSearchServiceError::NameAlreadyUsed(_)) => (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": error.to_string() })),
+            )
+                .into_response(),
+            Some(SavedSearchServiceError::InvalidQuery(error)) => query_syntax_error(error),
+            None => search_error(e),
+        },
     }
 }
 
@@ -151,25 +1345,184 @@ async fn create_project(
                 axum::http::header::LOCATION,
                 format!("/project/{}", project.slug),
             )];
-            (StatusCode::CREATED, headers, Json(()))
+            (StatusCode::CREATED, headers, Json(json!(null)))
         }
-        Err(e)
-            if matches!(
-                e.downcast_ref::<ThoughtServiceError>(),
-                Some(ThoughtServiceError::ProjectAlreadyExists(_))
-            ) =>
-        {
-            (
+        Err(e) => match e.downcast_ref::<ThoughtServiceError>() {
+            Some(ThoughtServiceError::ProjectAlreadyExists(_)) => (
                 StatusCode::CONFLICT,
                 [(axum::http::header::LOCATION, String::new())],
-                Json(()),
+                Json(json!(null)),
+            ),
+            Some(error @ ThoughtServiceError::UniverseNotFound(universe_id)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                [(axum::http::header::LOCATION, String::new())],
+                Json(json!({
+                    "error": error.to_string(),
+                    "universe": universe_id,
+                })),
+            ),
+            _ => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                [(axum::http::header::LOCATION, String::new())],
+                Json(json!(null)),
+            ),
+        },
+    }
+}
+
+/// Create an organization and its universe
+async fn create_organization(
+    State(service): State<Arc<OrganizationService>>,
+    Json(payload): Json<CreateOrganizationCommand>,
+) -> Response {
+    match service.create(payload).await {
+        Ok(created) => {
+            let headers = [(
+                axum::http::header::LOCATION,
+                format!("/organization/{}", created.organization.organization_id),
+            )];
+            (StatusCode::CREATED, headers, Json(created)).into_response()
+        }
+        Err(e) => organization_response::<()>(Err(e)),
+    }
+}
+
+/// Get an organization by its ID
+async fn get_organization(
+    State(service): State<Arc<OrganizationService>>,
+    Path(organization_id): Path<Uuid>,
+) -> Response {
+    organization_response(service.get(organization_id).await)
+}
+
+/// Get the universe of an organization
+async fn get_universe(
+    State(service): State<Arc<OrganizationService>>,
+    Path(organization_id): Path<Uuid>,
+) -> Response {
+    organization_response(service.get_universe(organization_id).await)
+}
+
+/// Turn the result of an organization operation into a response.
+fn organization_response<T: serde::Serialize>(result: crate::Result<T>) -> Response {
+    match result {
+        Ok(body) => (StatusCode::OK, Json(body)).into_response(),
+        Err(e) => match e.downcast_ref::<OrganizationServiceError>() {
+            Some(OrganizationServiceError::OrganizationNotFound(_)) => {
+                (StatusCode::NOT_FOUND, Json(())).into_response()
+            }
+            Some(error @ OrganizationServiceError::InvalidOrganization(_)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "error": error.to_string() })),
             )
+                .into_response(),
+            None => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+        },
+    }
+}
+
+/// Grant a stylo
+async fn grant_stylo(
+    State(service): State<Arc<StyloService>>,
+    Json(payload): Json<CreateStyloCommand>,
+) -> Response {
+    match service.grant(payload).await {
+        Ok(stylo) => {
+            let headers = [(
+                axum::http::header::LOCATION,
+                format!("/stylo/{}", stylo.stylo_id),
+            )];
+            (StatusCode::CREATED, headers, Json(stylo)).into_response()
         }
-        Err(_) => (
-            StatusCode::INTERNAL_SERVER_ERROR,
-            [(axum::http::header::LOCATION, String::new())],
-            Json(()),
-        ),
+        // The organizations are given in the body, not in the path.
+        Err(e) => match e.downcast_ref::<StyloServiceError>() {
+            Some(error @ StyloServiceError::OrganizationNotFound(organization_id)) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({
+                    "error": error.to_string(),
+                    "organization": organization_id,
+                })),
+            )
+                .into_response(),
+            _ => stylo_response::<()>(Err(e)),
+        },
+    }
+}
+
+/// Get a stylo by its ID
+async fn get_stylo(
+    State(service): State<Arc<StyloService>>,
+    Path(stylo_id): Path<Uuid>,
+) -> Response {
+    stylo_response(service.get(stylo_id).await)
+}
+
+/// Modify the display name or the email of a stylo
+async fn modify_stylo(
+    State(service): State<Arc<StyloService>>,
+    Path(stylo_id): Path<Uuid>,
+    Json(payload): Json<ModifyStyloCommand>,
+) -> Response {
+    stylo_response(service.modify(stylo_id, payload).await)
+}
+
+/// Lock a stylo
+async fn lock_stylo(
+    State(service): State<Arc<StyloService>>,
+    Path(stylo_id): Path<Uuid>,
+) -> Response {
+    stylo_response(service.lock(stylo_id).await)
+}
+
+/// Unlock a stylo
+async fn unlock_stylo(
+    State(service): State<Arc<StyloService>>,
+    Path(stylo_id): Path<Uuid>,
+) -> Response {
+    stylo_response(service.unlock(stylo_id).await)
+}
+
+/// Revoke a stylo
+async fn revoke_stylo(
+    State(service): State<Arc<StyloService>>,
+    Path(stylo_id): Path<Uuid>,
+) -> Response {
+    match service.revoke(stylo_id).await {
+        Ok(_) => (StatusCode::NO_CONTENT, Json(())).into_response(),
+        Err(e) => stylo_response::<()>(Err(e)),
+    }
+}
+
+/// List the stylos owned by an organization
+async fn list_stylos(
+    State(service): State<Arc<StyloService>>,
+    Path(organization_id): Path<Uuid>,
+) -> Response {
+    stylo_response(service.list_by_owner(organization_id).await)
+}
+
+/// Turn the result of a stylo operation into a response.
+fn stylo_response<T: serde::Serialize>(result: crate::Result<T>) -> Response {
+    match result {
+        Ok(body) => (StatusCode::OK, Json(body)).into_response(),
+        Err(e) => match e.downcast_ref::<StyloServiceError>() {
+            Some(
+                StyloServiceError::StyloNotFound(_) | StyloServiceError::OrganizationNotFound(_),
+            ) => (StatusCode::NOT_FOUND, Json(())).into_response(),
+            Some(error @ StyloServiceError::EmptyDisplayName) => (
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(json!({ "error": error.to_string() })),
+            )
+                .into_response(),
+            Some(
+                error @ (StyloServiceError::AlreadyLocked(_) | StyloServiceError::NotLocked(_)),
+            ) => (
+                StatusCode::CONFLICT,
+                Json(json!({ "error": error.to_string() })),
+            )
+                .into_response(),
+            None => (StatusCode::INTERNAL_SERVER_ERROR, Json(())).into_response(),
+        },
     }
 }
 
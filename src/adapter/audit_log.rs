@@ -0,0 +1,67 @@
+use crate::models::{AuditEntry, ModelEvent};
+use crate::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// AuditLog is a trait that defines the methods that are required to record
+/// the model events in a tamper-evident log.
+/// Every entry holds the hash of the previous one, see [`AuditEntry`].
+#[async_trait]
+pub trait AuditLog: Sync + Send {
+    /// Records an event after the last entry and returns its entry.
+    /// An event already recorded, known by its identifier, is not recorded
+    /// again and its entry is returned.
+    async fn append(&self, event: ModelEvent) -> Result<AuditEntry>;
+
+    /// Lists the entries concerning a note, a thought or a project, oldest
+    /// first. The entries of a project include those of its notes and
+    /// thoughts.
+    async fn entries(&self, model_id: Uuid) -> Result<Vec<AuditEntry>>;
+}
+
+/// InMemoryAuditLog is an in-memory implementation of the AuditLog trait.
+/// Mostly used for testing purposes, the log is lost with the process.
+#[derive(Default)]
+pub struct InMemoryAuditLog {
+    entries: Arc<RwLock<Vec<AuditEntry>>>,
+}
+
+#[async_trait]
+impl AuditLog for InMemoryAuditLog {
+    async fn append(&self, event: ModelEvent) -> Result<AuditEntry> {
+        let mut entries = self.entries.write().await;
+        if let Some(entry) = entries
+            .iter()
+            .find(|entry| entry.event.event_id == event.event_id)
+        {
+            return Ok(entry.clone());
+        }
+        let entry = AuditEntry::new(entries.last(), event);
+        entries.push(entry.clone());
+
+        Ok(entry)
+    }
+
+    async fn entries(&self, model_id: Uuid) -> Result<Vec<AuditEntry>> {
+        Ok(self
+            .entries
+            .read()
+            .await
+            .iter()
+            .filter(|entry| entry.concerns(model_id))
+            .cloned()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        crate::testkit::check_audit_log(&InMemoryAuditLog::default()).await;
+    }
+}
@@ -0,0 +1,92 @@
+use crate::models::{ModelEvent, StoredEvent};
+use crate::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use synapps::EventMessage;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// EventStore is a trait that defines the methods that are required to record
+/// the event messages in an append-only history.
+/// Every recorded message gets a sequence number, increasing from 1 without
+/// gap, so the history can be read again from any position. An event is
+/// recorded once: the outbox relay may deliver it again after a restart.
+#[async_trait]
+pub trait EventStore: Sync + Send {
+    /// Records an event message after the last one and returns it with its
+    /// sequence number.
+    /// A message whose event is already recorded is not recorded again, the
+    /// recorded one is returned.
+    async fn append(&self, message: EventMessage<ModelEvent>) -> Result<StoredEvent>;
+
+    /// Reads at most `limit` events in order, starting at the given sequence
+    /// number. Nothing is returned past the last event.
+    async fn read(&self, from: u64, limit: usize) -> Result<Vec<StoredEvent>>;
+
+    /// Gets the sequence number of the last recorded event, 0 if there is none.
+    async fn last_sequence(&self) -> Result<u64>;
+}
+
+/// Position of the first event at or after a sequence number in a history
+/// sorted without gap.
+pub(crate) fn read_position(events: &[StoredEvent], from: u64) -> usize {
+    events
+        .first()
+        .map(|first| from.saturating_sub(first.sequence) as usize)
+        .unwrap_or_default()
+        .min(events.len())
+}
+
+/// InMemoryEventStore is an in-memory implementation of the EventStore trait.
+/// Mostly used for testing purposes, the history is lost with the process.
+#[derive(Default)]
+pub struct InMemoryEventStore {
+    events: Arc<RwLock<InMemoryEvents>>,
+}
+
+/// Recorded events and the position of every event identifier.
+#[derive(Default)]
+struct InMemoryEvents {
+    events: Vec<StoredEvent>,
+    positions: HashMap<Uuid, usize>,
+}
+
+#[async_trait]
+impl EventStore for InMemoryEventStore {
+    async fn append(&self, message: EventMessage<ModelEvent>) -> Result<StoredEvent> {
+        let mut events = self.events.write().await;
+        if let Some(position) = events.positions.get(&message.event.event_id) {
+            return Ok(events.events[*position].clone());
+        }
+        let position = events.events.len();
+        let stored = StoredEvent::new(position as u64 + 1, message);
+        events.positions.insert(stored.event.event_id, position);
+        events.events.push(stored.clone());
+
+        Ok(stored)
+    }
+
+    async fn read(&self, from: u64, limit: usize) -> Result<Vec<StoredEvent>> {
+        let events = &self.events.read().await.events;
+        let start = read_position(events, from);
+
+        Ok(events.iter().skip(start).take(limit).cloned().collect())
+    }
+
+    async fn last_sequence(&self) -> Result<u64> {
+        Ok(self.events.read().await.events.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let event_store = InMemoryEventStore::default();
+
+        crate::testkit::check_event_store(&event_store).await;
+    }
+}
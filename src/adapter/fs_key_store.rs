@@ -0,0 +1,160 @@
+use std::io::ErrorKind;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::adapter::key_store::check_key_id;
+use crate::adapter::{KeyStore, KeyStoreError};
+use crate::Result;
+
+/// FsKeyStore is a filesystem implementation of the KeyStore trait.
+/// Each key is kept in two files of the directory: `<key_id>.key` holds the
+/// private key and is only readable by its owner, `<key_id>.pub` holds the
+/// public key and can be handed to whoever verifies the events. Both are in
+/// lowercase hexadecimal.
+pub struct FsKeyStore {
+    root: PathBuf,
+    generation: Mutex<()>,
+}
+
+impl FsKeyStore {
+    /// Create a key store in the given directory, creating it if needed.
+    pub async fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        tokio::fs::create_dir_all(&root).await?;
+
+        Ok(Self {
+            root,
+            generation: Mutex::new(()),
+        })
+    }
+
+    fn path(&self, key_id: &str, extension: &str) -> PathBuf {
+        self.root.join(format!("{key_id}.{extension}"))
+    }
+
+    /// Read the 32 bytes of a key file, None if it does not exist.
+    async fn read(&self, key_id: &str, extension: &str) -> Result<Option<[u8; 32]>> {
+        let content = match tokio::fs::read_to_string(self.path(key_id, extension)).await {
+            Ok(content) => content,
+            Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+            Err(e) => return Err(e.into()),
+        };
+        let bytes = hex::decode(content.trim())
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .ok_or_else(|| KeyStoreError::InvalidKey {
+                key_id: key_id.to_string(),
+                message: format!("the .{extension} file does not hold 32 hexadecimal bytes"),
+            })?;
+
+        Ok(Some(bytes))
+    }
+
+    /// Write a key file through a temporary file so it is never seen half
+    /// written.
+    async fn write(
+        &self,
+        key_id: &str,
+        extension: &str,
+        bytes: &[u8],
+        private: bool,
+    ) -> Result<()> {
+        let temporary = self.root.join(format!(".{}.tmp", Uuid::new_v4()));
+        tokio::fs::write(&temporary, hex::encode(bytes)).await?;
+        #[cfg(unix)]
+        if private {
+            use std::os::unix::fs::PermissionsExt;
+            let permissions = std::fs::Permissions::from_mode(0o600);
+            tokio::fs::set_permissions(&temporary, permissions).await?;
+        }
+        #[cfg(not(unix))]
+        let _ = private;
+        if let Err(e) = tokio::fs::rename(&temporary, self.path(key_id, extension)).await {
+            let _ = tokio::fs::remove_file(&temporary).await;
+            return Err(e.into());
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KeyStore for FsKeyStore {
+    async fn signing_key(&self, key_id: &str) -> Result<SigningKey> {
+        check_key_id(key_id)?;
+
+        if let Some(bytes) = self.read(key_id, "key").await? {
+            return Ok(SigningKey::from_bytes(&bytes));
+        }
+        let _generation = self.generation.lock().await;
+        if let Some(bytes) = self.read(key_id, "key").await? {
+            return Ok(SigningKey::from_bytes(&bytes));
+        }
+        let key = SigningKey::generate(&mut rand_core::OsRng);
+        self.write(key_id, "pub", key.verifying_key().as_bytes(), false)
+            .await?;
+        self.write(key_id, "key", &key.to_bytes(), true).await?;
+
+        Ok(key)
+    }
+
+    async fn verifying_key(&self, key_id: &str) -> Result<Option<VerifyingKey>> {
+        check_key_id(key_id)?;
+
+        self.read(key_id, "pub")
+            .await?
+            .map(|bytes| {
+                VerifyingKey::from_bytes(&bytes).map_err(|e| {
+                    KeyStoreError::InvalidKey {
+                        key_id: key_id.to_string(),
+                        message: e.to_string(),
+                    }
+                    .into()
+                })
+            })
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let root = std::env::temp_dir().join(format!("kaku-keys-{}", Uuid::new_v4()));
+        let key_store = FsKeyStore::new(&root).await.unwrap();
+
+        crate::testkit::check_key_store(&key_store).await;
+
+        // The keys survive the store.
+        let key = key_store.signing_key("instance").await.unwrap();
+        let reopened = FsKeyStore::new(&root).await.unwrap();
+        assert_eq!(
+            reopened.signing_key("instance").await.unwrap().to_bytes(),
+            key.to_bytes()
+        );
+        assert!(root.join("instance.key").is_file());
+        assert!(root.join("instance.pub").is_file());
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let metadata = std::fs::metadata(root.join("instance.key")).unwrap();
+            assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+        }
+
+        tokio::fs::write(root.join("broken.pub"), "not a key")
+            .await
+            .unwrap();
+        let error = key_store.verifying_key("broken").await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<KeyStoreError>(),
+            Some(KeyStoreError::InvalidKey { .. })
+        ));
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
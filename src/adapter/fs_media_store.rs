@@ -0,0 +1,192 @@
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::adapter::media_store::check_digest;
+use crate::adapter::MediaStore;
+use crate::models::media_digest;
+use crate::Result;
+
+/// FsMediaStore is a filesystem implementation of the MediaStore trait.
+/// Blobs are stored in a directory named after the first two digits of their
+/// digest, `ab/cdef…`, so no directory grows too large. Blobs are written to a
+/// temporary file first and renamed so a blob is never seen half written.
+/// The storage date of a blob is the modification date of its file, its MIME
+/// type is kept next to it in a file with the `type` extension.
+pub struct FsMediaStore {
+    root: PathBuf,
+}
+
+impl FsMediaStore {
+    /// Create a media store in the given directory, creating it if needed.
+    pub async fn new(root: impl Into<PathBuf>) -> Result<Self> {
+        let root = root.into();
+        tokio::fs::create_dir_all(&root).await?;
+
+        Ok(Self { root })
+    }
+
+    fn path(&self, digest: &str) -> PathBuf {
+        self.root.join(&digest[..2]).join(&digest[2..])
+    }
+
+    fn mime_type_path(&self, digest: &str) -> PathBuf {
+        self.path(digest).with_extension("type")
+    }
+
+    /// List the digests of the stored blobs with their paths, sorted.
+    async fn blobs(&self) -> Result<Vec<(String, PathBuf)>> {
+        let mut blobs = Vec::new();
+        let mut directories = tokio::fs::read_dir(&self.root).await?;
+
+        while let Some(directory) = directories.next_entry().await? {
+            if !directory.file_type().await?.is_dir() {
+                continue;
+            }
+            let prefix = directory.file_name().to_string_lossy().into_owned();
+            let mut files = tokio::fs::read_dir(directory.path()).await?;
+            while let Some(file) = files.next_entry().await? {
+                let digest = format!("{prefix}{}", file.file_name().to_string_lossy());
+                if crate::models::is_media_digest(&digest) {
+                    blobs.push((digest, file.path()));
+                }
+            }
+        }
+        blobs.sort();
+
+        Ok(blobs)
+    }
+}
+
+/// Write a file through a temporary file renamed to the given path.
+async fn write_renamed(path: &Path, content: &[u8]) -> Result<()> {
+    let directory = path.parent().unwrap_or(Path::new("."));
+    tokio::fs::create_dir_all(directory).await?;
+    let temporary = directory.join(format!(".{}.tmp", Uuid::new_v4()));
+    tokio::fs::write(&temporary, content).await?;
+    if let Err(e) = tokio::fs::rename(&temporary, path).await {
+        let _ = tokio::fs::remove_file(&temporary).await;
+        return Err(e.into());
+    }
+
+    Ok(())
+}
+
+/// Turn a missing file into None.
+fn found<T>(result: std::io::Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(value) => Ok(Some(value)),
+        Err(e) if e.kind() == ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[async_trait]
+impl MediaStore for FsMediaStore {
+    async fn put(&self, content: &[u8], mime_type: &str) -> Result<String> {
+        let digest = media_digest(content);
+        let path = self.path(&digest);
+
+        // The type is written first so a stored blob always has one.
+        write_renamed(&self.mime_type_path(&digest), mime_type.as_bytes()).await?;
+        if tokio::fs::try_exists(&path).await? {
+            let file = tokio::fs::OpenOptions::new()
+                .write(true)
+                .open(&path)
+                .await?
+                .into_std()
+                .await;
+            tokio::task::spawn_blocking(move || file.set_modified(SystemTime::now())).await??;
+            return Ok(digest);
+        }
+        write_renamed(&path, content).await?;
+
+        Ok(digest)
+    }
+
+    async fn get(&self, digest: &str) -> Result<Option<Vec<u8>>> {
+        check_digest(digest)?;
+
+        found(tokio::fs::read(self.path(digest)).await)
+    }
+
+    async fn size(&self, digest: &str) -> Result<Option<u64>> {
+        check_digest(digest)?;
+
+        Ok(found(tokio::fs::metadata(self.path(digest)).await)?.map(|m| m.len()))
+    }
+
+    async fn mime_type(&self, digest: &str) -> Result<Option<String>> {
+        check_digest(digest)?;
+
+        // The blobs stored before their types were kept have none.
+        if !tokio::fs::try_exists(self.path(digest)).await? {
+            return Ok(None);
+        }
+        found(tokio::fs::read_to_string(self.mime_type_path(digest)).await)
+    }
+
+    async fn delete(&self, digest: &str) -> Result<bool> {
+        check_digest(digest)?;
+
+        found(tokio::fs::remove_file(self.mime_type_path(digest)).await)?;
+        Ok(found(tokio::fs::remove_file(self.path(digest)).await)?.is_some())
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        Ok(self
+            .blobs()
+            .await?
+            .into_iter()
+            .map(|(digest, _)| digest)
+            .collect())
+    }
+
+    async fn list_stored_before(&self, before: DateTime<Utc>) -> Result<Vec<String>> {
+        let before = SystemTime::from(before);
+        let mut digests = Vec::new();
+
+        for (digest, path) in self.blobs().await? {
+            // A blob deleted since it was listed is not stored anymore.
+            if let Some(metadata) = found(tokio::fs::metadata(&path).await)? {
+                if metadata.modified()? < before {
+                    digests.push(digest);
+                }
+            }
+        }
+
+        Ok(digests)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let root = std::env::temp_dir().join(format!("kaku-media-{}", Uuid::new_v4()));
+        let media_store = FsMediaStore::new(&root).await.unwrap();
+
+        crate::testkit::check_media_store(&media_store).await;
+
+        let digest = media_store
+            .put(b"Testkit media", "text/plain")
+            .await
+            .unwrap();
+        let blob = root.join(&digest[..2]).join(&digest[2..]);
+        assert!(blob.is_file());
+
+        // A blob stored before the types were kept has none.
+        tokio::fs::remove_file(blob.with_extension("type"))
+            .await
+            .unwrap();
+        assert!(media_store.mime_type(&digest).await.unwrap().is_none());
+        assert!(media_store.get(&digest).await.unwrap().is_some());
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
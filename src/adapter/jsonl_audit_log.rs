@@ -0,0 +1,256 @@
+use std::collections::HashMap;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::adapter::AuditLog;
+use crate::models::{AuditEntry, ModelEvent};
+use crate::Result;
+
+/// JsonlAuditLogError is an error type that is used to represent errors that
+/// occur when opening an audit log file.
+#[derive(Debug, thiserror::Error)]
+pub enum JsonlAuditLogError {
+    /// An error that occurs when the chain of the file is broken, nothing is
+    /// appended to a log that cannot be trusted anymore.
+    #[error("The audit log '{path}' is broken at line {}: {}", .broken_link.line, .broken_link.reason)]
+    BrokenChain {
+        /// The audit log file.
+        path: PathBuf,
+
+        /// The first broken link.
+        broken_link: BrokenLink,
+    },
+}
+
+/// First line of an audit log file that is not chained to the previous one.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BrokenLink {
+    /// Number of the line, from 1.
+    pub line: usize,
+
+    /// What is wrong with the line.
+    pub reason: String,
+}
+
+/// Result of the walk of an audit log file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditVerification {
+    /// Number of entries chained from the start of the file.
+    pub entries: u64,
+
+    /// The first broken link, None if the whole chain holds.
+    pub broken_link: Option<BrokenLink>,
+}
+
+/// The entries of a file, as found by a walk.
+#[derive(Default)]
+struct Walk {
+    last: Option<AuditEntry>,
+    offsets: Vec<u64>,
+    events: HashMap<Uuid, usize>,
+    subjects: HashMap<Uuid, Vec<usize>>,
+    length: u64,
+    broken_link: Option<BrokenLink>,
+}
+
+impl Walk {
+    /// Walk the complete lines of a file up to the first broken link.
+    fn new(content: &[u8]) -> Self {
+        let mut walk = Self::default();
+
+        for (index, line) in content.split_inclusive(|byte| *byte == b'\n').enumerate() {
+            if !line.ends_with(b"\n") {
+                break;
+            }
+            let checked = serde_json::from_slice::<AuditEntry>(line)
+                .map_err(|e| format!("not an audit entry: {e}"))
+                .and_then(|entry| {
+                    entry
+                        .check_link(walk.last.as_ref())
+                        .map(|_| entry)
+                        .map_err(|e| e.to_string())
+                });
+            match checked {
+                Ok(entry) => walk.push(entry, line.len() as u64),
+                Err(reason) => {
+                    walk.broken_link = Some(BrokenLink {
+                        line: index + 1,
+                        reason,
+                    });
+                    break;
+                }
+            }
+        }
+
+        walk
+    }
+
+    /// Index an entry written at the end of the file.
+    fn push(&mut self, entry: AuditEntry, line_length: u64) {
+        let position = self.offsets.len();
+        self.offsets.push(self.length);
+        self.length += line_length;
+        self.events.insert(entry.event.event_id, position);
+        for subject in entry.subjects() {
+            self.subjects.entry(subject).or_default().push(position);
+        }
+        self.last = Some(entry);
+    }
+}
+
+/// JsonlAuditLog is a file implementation of the AuditLog trait.
+/// Entries are appended to the file one JSON document per line and the file
+/// is synced after each of them. A last line left half written by a crash is
+/// dropped when the file is opened, a file whose chain is broken is refused.
+pub struct JsonlAuditLog {
+    path: PathBuf,
+    state: Mutex<(File, Walk)>,
+}
+
+impl JsonlAuditLog {
+    /// Open the audit log in the given file, creating it if needed.
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Some(directory) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(directory).await?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .read(true)
+            .open(&path)
+            .await?;
+
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).await?;
+        let mut walk = Walk::new(&content);
+        if let Some(broken_link) = walk.broken_link.take() {
+            return Err(JsonlAuditLogError::BrokenChain { path, broken_link }.into());
+        }
+        if walk.length < content.len() as u64 {
+            log::warn!(
+                "Dropping the half written last entry of the audit log '{}'.",
+                path.display()
+            );
+            file.set_len(walk.length).await?;
+            file.sync_data().await?;
+        }
+
+        Ok(Self {
+            path,
+            state: Mutex::new((file, walk)),
+        })
+    }
+
+    /// Walk the chain of an audit log file and report its first broken link.
+    /// A half written last line is not part of the chain.
+    pub async fn verify(path: impl AsRef<Path>) -> Result<AuditVerification> {
+        let content = tokio::fs::read(path).await?;
+        let walk = Walk::new(&content);
+
+        Ok(AuditVerification {
+            entries: walk.offsets.len() as u64,
+            broken_link: walk.broken_link,
+        })
+    }
+
+    /// Read the entry written at the given offset.
+    async fn read(&self, offset: u64) -> Result<AuditEntry> {
+        let mut file = File::open(&self.path).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut line = String::new();
+        BufReader::new(file).read_line(&mut line).await?;
+
+        Ok(serde_json::from_str(&line)?)
+    }
+}
+
+#[async_trait]
+impl AuditLog for JsonlAuditLog {
+    async fn append(&self, event: ModelEvent) -> Result<AuditEntry> {
+        let mut state = self.state.lock().await;
+        let (file, walk) = &mut *state;
+        if let Some(position) = walk.events.get(&event.event_id) {
+            return self.read(walk.offsets[*position]).await;
+        }
+        let entry = AuditEntry::new(walk.last.as_ref(), event);
+        let mut line = serde_json::to_vec(&entry)?;
+        line.push(b'\n');
+
+        file.write_all(&line).await?;
+        file.sync_data().await?;
+        walk.push(entry.clone(), line.len() as u64);
+
+        Ok(entry)
+    }
+
+    async fn entries(&self, model_id: Uuid) -> Result<Vec<AuditEntry>> {
+        let offsets = {
+            let state = self.state.lock().await;
+            let walk = &state.1;
+            walk.subjects
+                .get(&model_id)
+                .map(|positions| positions.iter().map(|p| walk.offsets[*p]).collect())
+                .unwrap_or_else(Vec::new)
+        };
+
+        let mut entries = Vec::with_capacity(offsets.len());
+        for offset in offsets {
+            entries.push(self.read(offset).await?);
+        }
+
+        Ok(entries)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let root = std::env::temp_dir().join(format!("kaku-audit-{}", Uuid::new_v4()));
+        let path = root.join("audit.jsonl");
+        let audit_log = JsonlAuditLog::open(&path).await.unwrap();
+
+        crate::testkit::check_audit_log(&audit_log).await;
+
+        let verification = JsonlAuditLog::verify(&path).await.unwrap();
+        assert!(verification.entries > 0);
+        assert_eq!(verification.broken_link, None);
+        drop(audit_log);
+        let mut file = OpenOptions::new().append(true).open(&path).await.unwrap();
+        file.write_all(br#"{"sequence":"#).await.unwrap();
+        file.flush().await.unwrap();
+        drop(file);
+        assert_eq!(JsonlAuditLog::verify(&path).await.unwrap(), verification);
+        let audit_log = JsonlAuditLog::open(&path).await.unwrap();
+        crate::testkit::check_audit_log(&audit_log).await;
+        drop(audit_log);
+
+        // Change the event of the second entry.
+        let content = tokio::fs::read_to_string(&path).await.unwrap();
+        let mut lines = content.lines().map(String::from).collect::<Vec<_>>();
+        let mut entry = serde_json::from_str::<AuditEntry>(&lines[1]).unwrap();
+        entry.event.timestamp += chrono::Duration::seconds(1);
+        lines[1] = serde_json::to_string(&entry).unwrap();
+        tokio::fs::write(&path, lines.join("\n") + "\n")
+            .await
+            .unwrap();
+        let verification = JsonlAuditLog::verify(&path).await.unwrap();
+        assert_eq!(verification.entries, 1);
+        assert_eq!(verification.broken_link.as_ref().unwrap().line, 2);
+        let error = JsonlAuditLog::open(&path).await.err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<JsonlAuditLogError>(),
+            Some(JsonlAuditLogError::BrokenChain { broken_link, .. }) if broken_link.line == 2
+        ));
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
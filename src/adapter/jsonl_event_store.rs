@@ -0,0 +1,227 @@
+use std::collections::HashMap;
+use std::io::SeekFrom;
+use std::path::{Path, PathBuf};
+
+use async_trait::async_trait;
+use synapps::EventMessage;
+use tokio::fs::{File, OpenOptions};
+use tokio::io::{AsyncBufReadExt, AsyncReadExt, AsyncSeekExt, AsyncWriteExt, BufReader};
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::adapter::EventStore;
+use crate::models::{ModelEvent, StoredEvent};
+use crate::Result;
+
+/// JsonlEventStoreError is an error type that is used to represent errors
+/// that occur when opening an event store file.
+#[derive(Debug, thiserror::Error)]
+pub enum JsonlEventStoreError {
+    /// An error that occurs when a line of the file is not a stored event.
+    #[error("Line {line} of the event store '{path}' is not an event: {message}")]
+    InvalidLine {
+        /// The event store file.
+        path: PathBuf,
+
+        /// Number of the line, from 1.
+        line: usize,
+
+        /// What is wrong with the line.
+        message: String,
+    },
+}
+
+/// Opened file, position of every event in it and sequence number of every
+/// event identifier.
+struct JsonlFile {
+    file: File,
+    offsets: Vec<u64>,
+    sequences: HashMap<Uuid, u64>,
+    length: u64,
+}
+
+/// JsonlEventStore is a file implementation of the EventStore trait.
+/// Events are appended to the file one JSON document per line and the file is
+/// synced after each of them. A last line left half written by a crash is
+/// dropped when the file is opened.
+pub struct JsonlEventStore {
+    path: PathBuf,
+    state: Mutex<JsonlFile>,
+}
+
+impl JsonlEventStore {
+    /// Open the event store in the given file, creating it if needed.
+    pub async fn open(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        if let Some(directory) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            tokio::fs::create_dir_all(directory).await?;
+        }
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .read(true)
+            .open(&path)
+            .await?;
+
+        let mut content = Vec::new();
+        file.read_to_end(&mut content).await?;
+        let (offsets, sequences) = Self::index(&path, &content)?;
+        let length = content
+            .iter()
+            .rposition(|byte| *byte == b'\n')
+            .map(|end| end as u64 + 1)
+            .unwrap_or_default();
+        if length < content.len() as u64 {
+            log::warn!(
+                "Dropping the half written last event of the event store '{}'.",
+                path.display()
+            );
+            file.set_len(length).await?;
+            file.sync_data().await?;
+        }
+
+        Ok(Self {
+            path,
+            state: Mutex::new(JsonlFile {
+                file,
+                offsets,
+                sequences,
+                length,
+            }),
+        })
+    }
+
+    /// Find the position of every complete line and check their sequence.
+    fn index(path: &Path, content: &[u8]) -> Result<(Vec<u64>, HashMap<Uuid, u64>)> {
+        let mut offsets = Vec::new();
+        let mut sequences = HashMap::new();
+        let mut start = 0;
+
+        for (index, line) in content.split_inclusive(|byte| *byte == b'\n').enumerate() {
+            if !line.ends_with(b"\n") {
+                break;
+            }
+            let invalid = |message: String| JsonlEventStoreError::InvalidLine {
+                path: path.to_path_buf(),
+                line: index + 1,
+                message,
+            };
+            let stored: StoredEvent =
+                serde_json::from_slice(line).map_err(|e| invalid(e.to_string()))?;
+            let expected = offsets.len() as u64 + 1;
+            if stored.sequence != expected {
+                return Err(invalid(format!(
+                    "sequence {} found where {expected} was expected",
+                    stored.sequence
+                ))
+                .into());
+            }
+            offsets.push(start);
+            sequences.insert(stored.event.event_id, stored.sequence);
+            start += line.len() as u64;
+        }
+
+        Ok((offsets, sequences))
+    }
+}
+
+#[async_trait]
+impl EventStore for JsonlEventStore {
+    async fn append(&self, message: EventMessage<ModelEvent>) -> Result<StoredEvent> {
+        let mut state = self.state.lock().await;
+        if let Some(sequence) = state.sequences.get(&message.event.event_id).copied() {
+            drop(state);
+            let mut recorded = self.read(sequence, 1).await?;
+            return recorded
+                .pop()
+                .ok_or_else(|| anyhow::anyhow!("The event {sequence} cannot be read again."));
+        }
+        let stored = StoredEvent::new(state.offsets.len() as u64 + 1, message);
+        let mut line = serde_json::to_vec(&stored)?;
+        line.push(b'\n');
+
+        state.file.write_all(&line).await?;
+        state.file.sync_data().await?;
+        let offset = state.length;
+        state.offsets.push(offset);
+        state
+            .sequences
+            .insert(stored.event.event_id, stored.sequence);
+        state.length += line.len() as u64;
+
+        Ok(stored)
+    }
+
+    async fn read(&self, from: u64, limit: usize) -> Result<Vec<StoredEvent>> {
+        let (offset, count) = {
+            let state = self.state.lock().await;
+            let start = (from.max(1) - 1).min(state.offsets.len() as u64) as usize;
+            let count = limit.min(state.offsets.len() - start);
+            match state.offsets.get(start) {
+                Some(offset) if count > 0 => (*offset, count),
+                _ => return Ok(Vec::new()),
+            }
+        };
+
+        let mut file = File::open(&self.path).await?;
+        file.seek(SeekFrom::Start(offset)).await?;
+        let mut lines = BufReader::new(file).lines();
+        let mut events = Vec::with_capacity(count);
+        while events.len() < count {
+            let Some(line) = lines.next_line().await? else {
+                break;
+            };
+            events.push(serde_json::from_str(&line)?);
+        }
+
+        Ok(events)
+    }
+
+    async fn last_sequence(&self) -> Result<u64> {
+        Ok(self.state.lock().await.offsets.len() as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let root = std::env::temp_dir().join(format!("kaku-events-{}", Uuid::new_v4()));
+        let path = root.join("events.jsonl");
+        let event_store = JsonlEventStore::open(&path).await.unwrap();
+
+        crate::testkit::check_event_store(&event_store).await;
+
+        let last_sequence = event_store.last_sequence().await.unwrap();
+        let events = event_store.read(1, usize::MAX).await.unwrap();
+        drop(event_store);
+        let mut file = OpenOptions::new().append(true).open(&path).await.unwrap();
+        file.write_all(br#"{"sequence":"#).await.unwrap();
+        file.flush().await.unwrap();
+        drop(file);
+        let event_store = JsonlEventStore::open(&path).await.unwrap();
+        assert_eq!(event_store.last_sequence().await.unwrap(), last_sequence);
+        assert_eq!(event_store.read(1, usize::MAX).await.unwrap(), events);
+        assert_eq!(
+            event_store.append(events[0].message()).await.unwrap(),
+            events[0]
+        );
+        let mut message = events[0].message();
+        message.event.event_id = Uuid::new_v4();
+        let appended = event_store.append(message).await.unwrap();
+        assert_eq!(appended.sequence, last_sequence + 1);
+        drop(event_store);
+
+        tokio::fs::write(&path, b"not an event\n").await.unwrap();
+        let error = JsonlEventStore::open(&path).await.err().unwrap();
+        assert!(matches!(
+            error.downcast_ref::<JsonlEventStoreError>(),
+            Some(JsonlEventStoreError::InvalidLine { line: 1, .. })
+        ));
+        tokio::fs::remove_dir_all(&root).await.unwrap();
+    }
+}
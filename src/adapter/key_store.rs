@@ -0,0 +1,116 @@
+use crate::models::ModelEvent;
+use crate::Result;
+use async_trait::async_trait;
+use ed25519_dalek::{SigningKey, VerifyingKey};
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// KeyStoreError is an error type that is used to represent errors that
+/// occur when interacting with the key store.
+#[derive(Debug, thiserror::Error)]
+pub enum KeyStoreError {
+    /// An error that occurs when a key identifier cannot name a key.
+    #[error("'{0}' is not a key identifier.")]
+    InvalidKeyId(String),
+
+    /// An error that occurs when no public key is known for a signer.
+    #[error("There is no public key for '{0}'.")]
+    UnknownKey(String),
+
+    /// An error that occurs when a stored key cannot be read.
+    #[error("The key '{key_id}' is not valid: {message}")]
+    InvalidKey {
+        /// The key identifier.
+        key_id: String,
+        /// What is wrong with the key.
+        message: String,
+    },
+}
+
+/// Check a key identifier before using it to address a key.
+/// Identifiers are organization identifiers or the instance key identifier,
+/// they are made of ASCII letters, digits and dashes.
+pub(crate) fn check_key_id(key_id: &str) -> Result<()> {
+    if !key_id.is_empty()
+        && key_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-')
+    {
+        Ok(())
+    } else {
+        Err(KeyStoreError::InvalidKeyId(key_id.to_string()).into())
+    }
+}
+
+/// KeyStore is a trait that defines the methods that are required to keep
+/// the Ed25519 keys signing the model events.
+/// Keys are addressed by the key identifier of the events, see
+/// [`ModelEvent::key_id`].
+#[async_trait]
+pub trait KeyStore: Sync + Send {
+    /// Gets the private key of a signer, it is generated on first use.
+    async fn signing_key(&self, key_id: &str) -> Result<SigningKey>;
+
+    /// Gets the public key of a signer.
+    /// If the key does not exist, None is returned.
+    async fn verifying_key(&self, key_id: &str) -> Result<Option<VerifyingKey>>;
+
+    /// Checks an event is signed by the signer of its responsibility chain.
+    /// An UnknownKey error is raised if the public key of the signer is not
+    /// in the store.
+    async fn verify(&self, event: &ModelEvent) -> Result<()> {
+        let key_id = event.key_id();
+        let key = self
+            .verifying_key(&key_id)
+            .await?
+            .ok_or(KeyStoreError::UnknownKey(key_id))?;
+        event.verify(&key)?;
+
+        Ok(())
+    }
+}
+
+/// InMemoryKeyStore is an in-memory implementation of the KeyStore trait.
+/// Keys are lost when the process stops, so are the means to verify the
+/// events they signed. Mostly used for testing purposes.
+#[derive(Default)]
+pub struct InMemoryKeyStore {
+    keys: Arc<RwLock<HashMap<String, SigningKey>>>,
+}
+
+#[async_trait]
+impl KeyStore for InMemoryKeyStore {
+    async fn signing_key(&self, key_id: &str) -> Result<SigningKey> {
+        check_key_id(key_id)?;
+
+        Ok(self
+            .keys
+            .write()
+            .await
+            .entry(key_id.to_string())
+            .or_insert_with(|| SigningKey::generate(&mut rand_core::OsRng))
+            .clone())
+    }
+
+    async fn verifying_key(&self, key_id: &str) -> Result<Option<VerifyingKey>> {
+        check_key_id(key_id)?;
+
+        Ok(self
+            .keys
+            .read()
+            .await
+            .get(key_id)
+            .map(SigningKey::verifying_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        crate::testkit::check_key_store(&InMemoryKeyStore::default()).await;
+    }
+}
@@ -0,0 +1,158 @@
+use crate::models::{is_media_digest, media_digest};
+use crate::Result;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// MediaStoreError is an error type that is used to represent errors that
+/// occur when interacting with the media store.
+#[derive(Debug, thiserror::Error)]
+pub enum MediaStoreError {
+    /// An error that occurs when a text is not a SHA-256 digest.
+    #[error("'{0}' is not a media digest.")]
+    InvalidDigest(String),
+}
+
+/// Check a digest before using it to address a blob.
+pub(crate) fn check_digest(digest: &str) -> Result<()> {
+    if is_media_digest(digest) {
+        Ok(())
+    } else {
+        Err(MediaStoreError::InvalidDigest(digest.to_string()).into())
+    }
+}
+
+/// MediaStore is a trait that defines the methods that are required to store
+/// media blobs.
+/// Blobs are addressed by the SHA-256 of their content, in lowercase
+/// hexadecimal. Using a text that is not a digest raises an InvalidDigest
+/// error.
+#[async_trait]
+pub trait MediaStore: Sync + Send {
+    /// Stores a blob with the MIME type of its content and returns its digest.
+    /// Storing a blob that is already stored only refreshes its storage date
+    /// and its MIME type.
+    async fn put(&self, content: &[u8], mime_type: &str) -> Result<String>;
+
+    /// Gets the content of a blob.
+    /// If the blob does not exist, None is returned.
+    async fn get(&self, digest: &str) -> Result<Option<Vec<u8>>>;
+
+    /// Gets the size in bytes of a blob.
+    /// If the blob does not exist, None is returned.
+    async fn size(&self, digest: &str) -> Result<Option<u64>>;
+
+    /// Gets the MIME type a blob was stored with.
+    /// If the blob does not exist or was stored without one, None is returned.
+    async fn mime_type(&self, digest: &str) -> Result<Option<String>>;
+
+    /// Deletes a blob.
+    /// Returns false if the blob does not exist.
+    async fn delete(&self, digest: &str) -> Result<bool>;
+
+    /// Lists the digests of all the blobs, sorted.
+    async fn list(&self) -> Result<Vec<String>>;
+
+    /// Lists the digests of the blobs last stored before the given date,
+    /// sorted.
+    async fn list_stored_before(&self, before: DateTime<Utc>) -> Result<Vec<String>>;
+}
+
+/// A blob of the in-memory media store.
+struct StoredBlob {
+    content: Vec<u8>,
+    mime_type: String,
+    stored_at: DateTime<Utc>,
+}
+
+/// InMemoryMediaStore is an in-memory implementation of the MediaStore trait.
+/// Mostly used for testing purposes.
+#[derive(Default)]
+pub struct InMemoryMediaStore {
+    blobs: Arc<RwLock<BTreeMap<String, StoredBlob>>>,
+}
+
+#[async_trait]
+impl MediaStore for InMemoryMediaStore {
+    async fn put(&self, content: &[u8], mime_type: &str) -> Result<String> {
+        let digest = media_digest(content);
+        let mut blobs = self.blobs.write().await;
+        let blob = blobs.entry(digest.clone()).or_insert_with(|| StoredBlob {
+            content: content.to_vec(),
+            mime_type: String::new(),
+            stored_at: Utc::now(),
+        });
+        blob.mime_type = mime_type.to_string();
+        blob.stored_at = Utc::now();
+
+        Ok(digest)
+    }
+
+    async fn get(&self, digest: &str) -> Result<Option<Vec<u8>>> {
+        check_digest(digest)?;
+
+        Ok(self
+            .blobs
+            .read()
+            .await
+            .get(digest)
+            .map(|blob| blob.content.clone()))
+    }
+
+    async fn size(&self, digest: &str) -> Result<Option<u64>> {
+        check_digest(digest)?;
+
+        Ok(self
+            .blobs
+            .read()
+            .await
+            .get(digest)
+            .map(|blob| blob.content.len() as u64))
+    }
+
+    async fn mime_type(&self, digest: &str) -> Result<Option<String>> {
+        check_digest(digest)?;
+
+        Ok(self
+            .blobs
+            .read()
+            .await
+            .get(digest)
+            .map(|blob| blob.mime_type.clone()))
+    }
+
+    async fn delete(&self, digest: &str) -> Result<bool> {
+        check_digest(digest)?;
+
+        Ok(self.blobs.write().await.remove(digest).is_some())
+    }
+
+    async fn list(&self) -> Result<Vec<String>> {
+        Ok(self.blobs.read().await.keys().cloned().collect())
+    }
+
+    async fn list_stored_before(&self, before: DateTime<Utc>) -> Result<Vec<String>> {
+        Ok(self
+            .blobs
+            .read()
+            .await
+            .iter()
+            .filter(|(_, blob)| blob.stored_at < before)
+            .map(|(digest, _)| digest.clone())
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let media_store = InMemoryMediaStore::default();
+
+        crate::testkit::check_media_store(&media_store).await;
+    }
+}
@@ -1,7 +1,43 @@
+mod audit_log;
+mod event_store;
+mod fs_key_store;
+mod fs_media_store;
+mod jsonl_audit_log;
+mod jsonl_event_store;
+mod key_store;
+mod media_store;
 mod note_book;
+mod organization_book;
+mod outbox;
 mod project_book;
+mod reference_book;
+mod saved_search_book;
+mod stylo_book;
 mod thought_book;
+mod universe_book;
 
+/// PostgreSQL storage backend.
+#[cfg(feature = "postgres")]
+pub mod postgres;
+
+/// SQLite storage backend.
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+pub use audit_log::*;
+pub use event_store::*;
+pub use fs_key_store::*;
+pub use fs_media_store::*;
+pub use jsonl_audit_log::*;
+pub use jsonl_event_store::*;
+pub use key_store::*;
+pub use media_store::*;
 pub use note_book::*;
+pub use organization_book::*;
+pub use outbox::*;
 pub use project_book::*;
+pub use reference_book::*;
+pub use saved_search_book::*;
+pub use stylo_book::*;
 pub use thought_book::*;
+pub use universe_book::*;
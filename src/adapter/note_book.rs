@@ -1,4 +1,5 @@
-use crate::models::{CreateNoteCommand, Note};
+use crate::adapter::InMemoryOutbox;
+use crate::models::{CreateNoteCommand, ModelEvent, Note, NoteChangeKind, ResponsibilityChain};
 use crate::Result;
 use async_trait::async_trait;
 use std::collections::HashMap;
@@ -8,10 +9,18 @@ use uuid::Uuid;
 
 /// NoteBook is a trait that defines the methods that are required to interact
 /// with a note database.
+/// Adding and deleting a note write its Created or Scratched event in the
+/// outbox in the same unit of work.
 #[async_trait]
 pub trait NoteBook: Sync + Send {
     /// Adds a new note to the note database.
-    async fn add(&self, command: CreateNoteCommand, project_id: Uuid) -> Result<Note>;
+    /// The Created event carries the given responsibility chain.
+    async fn add(
+        &self,
+        command: CreateNoteCommand,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Note>;
 
     /// Gets a note from the note database.
     /// If the note does not exist, None is returned.
@@ -23,10 +32,30 @@ pub trait NoteBook: Sync + Send {
     /// If the note does not exist, an error is returned.
     async fn sync(&self, note: Note) -> Result<Note>;
 
-    /// Deletes a note from the note database.
+    /// Deletes a note from the note database, the Scratched event carries the
+    /// given responsibility chain.
     /// If the note does not exist, None is returned.
     /// If the query could not be performed, an Error is raised.
-    async fn delete(&self, note_id: Uuid) -> Result<Option<Note>>;
+    async fn delete(&self, note_id: Uuid, chain: ResponsibilityChain) -> Result<Option<Note>>;
+
+    /// Lists all notes of a project.
+    /// Notes are sorted by their import date, oldest first.
+    async fn list_by_project(&self, project_id: Uuid) -> Result<Vec<Note>>;
+
+    /// Tells if a note is accompanied by the media with the given digest.
+    async fn is_media_referenced(&self, digest: &str) -> Result<bool>;
+
+    /// Lists the words of the notes whose trigram similarity with the given
+    /// word is at least the threshold, with their similarity.
+    /// None is returned when the book has no trigram index, the words are
+    /// then matched by the search index of the application.
+    async fn similar_words(
+        &self,
+        _word: &str,
+        _threshold: f64,
+    ) -> Result<Option<Vec<(String, f64)>>> {
+        Ok(None)
+    }
 }
 
 /// InMemoryNoteBook is an in-memory implementation of the NoteBook trait.
@@ -34,20 +63,40 @@ pub trait NoteBook: Sync + Send {
 #[derive(Default)]
 pub struct InMemoryNoteBook {
     notes: Arc<RwLock<HashMap<Uuid, Note>>>,
+    outbox: InMemoryOutbox,
+}
+
+impl InMemoryNoteBook {
+    /// Create a note book writing its events in the given outbox.
+    pub fn with_outbox(outbox: InMemoryOutbox) -> Self {
+        Self {
+            notes: Default::default(),
+            outbox,
+        }
+    }
 }
 
 #[async_trait]
 impl NoteBook for InMemoryNoteBook {
-    async fn add(&self, command: CreateNoteCommand, project_id: Uuid) -> Result<Note> {
+    async fn add(
+        &self,
+        command: CreateNoteCommand,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Note> {
         let note = Note {
             note_id: Uuid::new_v4(),
             imported_at: command.imported_at,
             stylo_id: command.stylo_id,
             project_id,
             content: command.content,
+            references: command.references,
+            media: command.media,
         };
         let mut notes = self.notes.write().await;
+        let mut outbox = self.outbox.write().await;
         notes.insert(note.note_id, note.clone());
+        outbox.push(ModelEvent::note(&note, NoteChangeKind::Created).with_chain(chain));
 
         Ok(note)
     }
@@ -58,13 +107,46 @@ impl NoteBook for InMemoryNoteBook {
 
     async fn sync(&self, note: Note) -> Result<Note> {
         let mut notes = self.notes.write().await;
-        notes.insert(note.note_id, note.clone());
+        let stored = notes
+            .get_mut(&note.note_id)
+            .ok_or_else(|| anyhow::anyhow!("Note does not exist: UUID='{}'.", note.note_id))?;
+        *stored = note.clone();
 
         Ok(note)
     }
 
-    async fn delete(&self, note_id: Uuid) -> Result<Option<Note>> {
-        Ok(self.notes.write().await.remove(&note_id))
+    async fn delete(&self, note_id: Uuid, chain: ResponsibilityChain) -> Result<Option<Note>> {
+        let mut notes = self.notes.write().await;
+        let mut outbox = self.outbox.write().await;
+        let note = notes.remove(&note_id);
+        if let Some(note) = &note {
+            outbox.push(ModelEvent::note(note, NoteChangeKind::Scratched).with_chain(chain));
+        }
+
+        Ok(note)
+    }
+
+    async fn list_by_project(&self, project_id: Uuid) -> Result<Vec<Note>> {
+        let mut notes: Vec<Note> = self
+            .notes
+            .read()
+            .await
+            .values()
+            .filter(|n| n.project_id == project_id)
+            .cloned()
+            .collect();
+        notes.sort_by_key(|n| n.imported_at);
+
+        Ok(notes)
+    }
+
+    async fn is_media_referenced(&self, digest: &str) -> Result<bool> {
+        Ok(self
+            .notes
+            .read()
+            .await
+            .values()
+            .any(|n| n.media.iter().any(|m| m.digest == digest)))
     }
 }
 
@@ -79,6 +161,8 @@ mod tests {
             stylo_id: Uuid::new_v4(),
             project_slug: "test-project".to_string(),
             content: "This is a test note.".to_string(),
+            references: Vec::new(),
+            media: Vec::new(),
         }
     }
 
@@ -91,6 +175,8 @@ mod tests {
             stylo_id: Uuid::new_v4(),
             project_id: Uuid::new_v4(),
             content: "This is a test note.".to_string(),
+            references: Vec::new(),
+            media: Vec::new(),
         }
     }
 
@@ -99,7 +185,10 @@ mod tests {
         let notebook = InMemoryNoteBook::default();
         let command = create_test_note_command();
         let project_id = Uuid::new_v4();
-        let note = notebook.add(command, project_id).await.unwrap();
+        let note = notebook
+            .add(command, project_id, Default::default())
+            .await
+            .unwrap();
 
         assert_eq!(note.content, "This is a test note.");
     }
@@ -145,7 +234,7 @@ mod tests {
         let note_id = note.note_id;
         notebook.notes.write().await.insert(note_id, note.clone());
         let deleted_note = notebook
-            .delete(note_id)
+            .delete(note_id, Default::default())
             .await
             .unwrap()
             .expect("There must be a note.");
@@ -153,4 +242,38 @@ mod tests {
         assert_eq!(deleted_note.content, "This is a test note.");
         assert!(notebook.notes.read().await.get(&note_id).is_none());
     }
+
+    #[tokio::test]
+    async fn test_list_by_project() {
+        let notebook = InMemoryNoteBook::default();
+        let project_id = Uuid::new_v4();
+        let _ = notebook
+            .add(create_test_note_command(), project_id, Default::default())
+            .await
+            .unwrap();
+        let _ = notebook
+            .add(create_test_note_command(), project_id, Default::default())
+            .await
+            .unwrap();
+        let _ = notebook
+            .add(
+                create_test_note_command(),
+                Uuid::new_v4(),
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        let notes = notebook.list_by_project(project_id).await.unwrap();
+
+        assert_eq!(notes.len(), 2);
+        assert!(notes.iter().all(|n| n.project_id == project_id));
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let notebook = InMemoryNoteBook::default();
+        let project_book = crate::adapter::InMemoryProjectBook::default();
+
+        crate::testkit::check_note_book(&notebook, &project_book).await;
+    }
 }
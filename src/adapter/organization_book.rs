@@ -0,0 +1,108 @@
+use crate::adapter::InMemoryUniverseBook;
+use crate::models::{
+    CreateOrganizationCommand, CreatedOrganization, Organization, OrganizationIdentifier, Universe,
+};
+use crate::Result;
+use async_trait::async_trait;
+use ed25519_dalek::VerifyingKey;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// OrganizationBook is a trait that defines the methods that are required to
+/// interact with an organization database.
+/// Creating an organization creates its universe in the same unit of work, so
+/// an organization is never stored without its universe nor the opposite.
+#[async_trait]
+pub trait OrganizationBook: Sync + Send {
+    /// Creates a new organization and its universe.
+    /// The organization is registered with the public key of the key
+    /// generated for it.
+    async fn create(
+        &self,
+        organization_id: OrganizationIdentifier,
+        command: CreateOrganizationCommand,
+        public_key: &VerifyingKey,
+    ) -> Result<CreatedOrganization>;
+
+    /// Gets an organization by its identifier.
+    /// If the organization does not exist, None is returned.
+    async fn get(&self, organization_id: OrganizationIdentifier) -> Result<Option<Organization>>;
+
+    /// Lists all the organizations, sorted by their creation date, oldest
+    /// first.
+    async fn list(&self) -> Result<Vec<Organization>>;
+}
+
+/// InMemoryOrganizationBook is an in-memory implementation of the
+/// OrganizationBook trait.
+/// Mostly used for testing purposes.
+#[derive(Default)]
+pub struct InMemoryOrganizationBook {
+    organizations: Arc<RwLock<HashMap<OrganizationIdentifier, Organization>>>,
+    universes: InMemoryUniverseBook,
+}
+
+impl InMemoryOrganizationBook {
+    /// Create an organization book writing the universes in the given book.
+    pub fn with_universe_book(universes: InMemoryUniverseBook) -> Self {
+        Self {
+            organizations: Default::default(),
+            universes,
+        }
+    }
+}
+
+#[async_trait]
+impl OrganizationBook for InMemoryOrganizationBook {
+    async fn create(
+        &self,
+        organization_id: OrganizationIdentifier,
+        command: CreateOrganizationCommand,
+        public_key: &VerifyingKey,
+    ) -> Result<CreatedOrganization> {
+        let organization = Organization::create(organization_id, command, public_key)?;
+        let universe = Universe::create(organization.organization_id);
+
+        let mut organizations = self.organizations.write().await;
+        let mut universes = self.universes.write().await;
+
+        organizations.insert(organization.organization_id, organization.clone());
+        universes.insert(universe.universe_id, universe.clone());
+
+        Ok(CreatedOrganization {
+            organization,
+            universe,
+        })
+    }
+
+    async fn get(&self, organization_id: OrganizationIdentifier) -> Result<Option<Organization>> {
+        Ok(self
+            .organizations
+            .read()
+            .await
+            .get(&organization_id)
+            .cloned())
+    }
+
+    async fn list(&self) -> Result<Vec<Organization>> {
+        let mut organizations: Vec<Organization> =
+            self.organizations.read().await.values().cloned().collect();
+        organizations.sort_by_key(|o| o.created_at);
+
+        Ok(organizations)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let universe_book = InMemoryUniverseBook::default();
+        let organization_book = InMemoryOrganizationBook::with_universe_book(universe_book.clone());
+
+        crate::testkit::check_organization_book(&organization_book, &universe_book).await;
+    }
+}
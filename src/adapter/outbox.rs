@@ -0,0 +1,80 @@
+use crate::models::ModelEvent;
+use crate::Result;
+use async_trait::async_trait;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// Outbox is a trait that defines the methods that are required to read the
+/// events waiting to be dispatched.
+/// The books write the events of their changes in the outbox in the same unit
+/// of work as the changes themselves, so a change is never stored without its
+/// event nor the opposite. An event stays in the outbox until it has been
+/// acknowledged as delivered.
+#[async_trait]
+pub trait Outbox: Sync + Send {
+    /// Lists at most `limit` events waiting to be dispatched, oldest first.
+    async fn pending(&self, limit: usize) -> Result<Vec<ModelEvent>>;
+
+    /// Removes delivered events from the outbox.
+    /// Unknown event identifiers are ignored.
+    async fn acknowledge(&self, event_ids: &[Uuid]) -> Result<()>;
+}
+
+/// InMemoryOutbox is an in-memory implementation of the Outbox trait.
+/// Clones share the same events, the in-memory books write in a clone of the
+/// outbox while holding the locks on their own data, the outbox lock is always
+/// taken last.
+#[derive(Default, Clone)]
+pub struct InMemoryOutbox {
+    events: Arc<RwLock<Vec<ModelEvent>>>,
+}
+
+impl InMemoryOutbox {
+    /// Get write access to the pending events.
+    pub(crate) async fn write(&self) -> tokio::sync::RwLockWriteGuard<'_, Vec<ModelEvent>> {
+        self.events.write().await
+    }
+}
+
+#[async_trait]
+impl Outbox for InMemoryOutbox {
+    async fn pending(&self, limit: usize) -> Result<Vec<ModelEvent>> {
+        Ok(self
+            .events
+            .read()
+            .await
+            .iter()
+            .take(limit)
+            .cloned()
+            .collect())
+    }
+
+    async fn acknowledge(&self, event_ids: &[Uuid]) -> Result<()> {
+        self.events
+            .write()
+            .await
+            .retain(|event| !event_ids.contains(&event.event_id));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapter::{InMemoryNoteBook, InMemoryProjectBook, InMemoryThoughtBook};
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let outbox = InMemoryOutbox::default();
+
+        crate::testkit::check_outbox(
+            &outbox,
+            &InMemoryProjectBook::with_outbox(outbox.clone()),
+            &InMemoryNoteBook::with_outbox(outbox.clone()),
+            &InMemoryThoughtBook::with_outbox(outbox.clone()),
+        )
+        .await;
+    }
+}
@@ -0,0 +1,35 @@
+//! PostgreSQL implementations of the books.
+//!
+//! The schema is embedded in the binary and migrated when connecting.
+mod note_book;
+mod organization_book;
+mod outbox;
+mod project_book;
+mod reference_book;
+mod saved_search_book;
+mod stylo_book;
+mod thought_book;
+mod trigram;
+mod universe_book;
+
+pub use note_book::*;
+pub use organization_book::*;
+pub use outbox::*;
+pub use project_book::*;
+pub use reference_book::*;
+pub use saved_search_book::*;
+pub use stylo_book::*;
+pub use thought_book::*;
+pub use universe_book::*;
+
+use sqlx::postgres::{PgPool, PgPoolOptions};
+
+use crate::Result;
+
+/// Connect to the PostgreSQL database and migrate its schema.
+pub async fn connect(database_url: &str) -> Result<PgPool> {
+    let pool = PgPoolOptions::new().connect(database_url).await?;
+    sqlx::migrate!("db/migrations/postgres").run(&pool).await?;
+
+    Ok(pool)
+}
@@ -0,0 +1,56 @@
+use async_trait::async_trait;
+use sqlx::postgres::{PgConnection, PgPool};
+use sqlx::types::Json;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::adapter::Outbox;
+use crate::models::ModelEvent;
+use crate::Result;
+
+/// PgOutbox is a PostgreSQL implementation of the Outbox trait.
+pub struct PgOutbox {
+    pool: PgPool,
+}
+
+impl PgOutbox {
+    /// Create a new outbox using the given connection pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Write events in the outbox, within the transaction of the change.
+pub(crate) async fn enqueue(connection: &mut PgConnection, events: &[ModelEvent]) -> Result<()> {
+    for event in events {
+        sqlx::query("insert into outbox (event_id, event) values ($1, $2)")
+            .bind(event.event_id)
+            .bind(Json(event))
+            .execute(&mut *connection)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl Outbox for PgOutbox {
+    async fn pending(&self, limit: usize) -> Result<Vec<ModelEvent>> {
+        sqlx::query("select event from outbox order by position limit $1")
+            .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(|row| Ok(row.try_get::<Json<ModelEvent>, _>("event")?.0))
+            .collect()
+    }
+
+    async fn acknowledge(&self, event_ids: &[Uuid]) -> Result<()> {
+        sqlx::query("delete from outbox where event_id = any($1)")
+            .bind(event_ids)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(())
+    }
+}
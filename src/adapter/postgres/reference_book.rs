@@ -0,0 +1,184 @@
+use async_trait::async_trait;
+use sqlx::postgres::{PgPool, PgRow};
+use sqlx::types::Json;
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::adapter::{ReferenceBook, ReferenceBookError};
+use crate::models::{CreateReferenceCommand, Reference, ReferenceIdentifier};
+use crate::Result;
+
+/// PgReferenceBook is a PostgreSQL implementation of the ReferenceBook trait.
+pub struct PgReferenceBook {
+    pool: PgPool,
+}
+
+impl PgReferenceBook {
+    /// Create a new reference book using the given connection pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    fn hydrate(row: &PgRow) -> Result<Reference> {
+        Ok(Reference {
+            reference_id: row.try_get("reference_id")?,
+            project_id: row.try_get("project_id")?,
+            entry_type: row.try_get("entry_type")?,
+            citation_key: row.try_get("citation_key")?,
+            authors: row.try_get("authors")?,
+            title: row.try_get("title")?,
+            year: row.try_get("year")?,
+            pages: row.try_get("pages")?,
+            extra_fields: row.try_get::<Json<_>, _>("extra_fields")?.0,
+        })
+    }
+
+    /// Turn a unique violation on the citation key into a
+    /// DuplicateCitationKey error.
+    fn map_error(error: sqlx::Error, citation_key: String) -> anyhow::Error {
+        match error {
+            sqlx::Error::Database(ref e) if e.is_unique_violation() => {
+                ReferenceBookError::DuplicateCitationKey(citation_key).into()
+            }
+            e => e.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ReferenceBook for PgReferenceBook {
+    async fn add(&self, command: CreateReferenceCommand, project_id: Uuid) -> Result<Reference> {
+        let reference = Reference::create(command, project_id)?;
+        let row = sqlx::query(
+            "insert into reference (reference_id, project_id, entry_type, citation_key, authors, \
+             title, year, pages, extra_fields) values ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+             returning *",
+        )
+        .bind(reference.reference_id)
+        .bind(reference.project_id)
+        .bind(&reference.entry_type)
+        .bind(&reference.citation_key)
+        .bind(&reference.authors)
+        .bind(&reference.title)
+        .bind(reference.year)
+        .bind(&reference.pages)
+        .bind(Json(&reference.extra_fields))
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Self::map_error(e, reference.citation_key.clone()))?;
+
+        Self::hydrate(&row)
+    }
+
+    async fn import(
+        &self,
+        commands: Vec<CreateReferenceCommand>,
+        project_id: Uuid,
+    ) -> Result<Vec<Reference>> {
+        let references = commands
+            .into_iter()
+            .map(|command| Reference::create(command, project_id))
+            .collect::<Result<Vec<Reference>>>()?;
+        let mut transaction = self.pool.begin().await?;
+        let mut imported = Vec::with_capacity(references.len());
+        for reference in references {
+            let row = sqlx::query(
+                "insert into reference (reference_id, project_id, entry_type, citation_key, \
+                 authors, title, year, pages, extra_fields) \
+                 values ($1, $2, $3, $4, $5, $6, $7, $8, $9) \
+                 on conflict (project_id, citation_key) do update set \
+                 entry_type = excluded.entry_type, authors = excluded.authors, \
+                 title = excluded.title, year = excluded.year, pages = excluded.pages, \
+                 extra_fields = excluded.extra_fields returning *",
+            )
+            .bind(reference.reference_id)
+            .bind(reference.project_id)
+            .bind(&reference.entry_type)
+            .bind(&reference.citation_key)
+            .bind(&reference.authors)
+            .bind(&reference.title)
+            .bind(reference.year)
+            .bind(&reference.pages)
+            .bind(Json(&reference.extra_fields))
+            .fetch_one(&mut *transaction)
+            .await?;
+            imported.push(Self::hydrate(&row)?);
+        }
+        transaction.commit().await?;
+
+        Ok(imported)
+    }
+
+    async fn get(&self, reference_id: ReferenceIdentifier) -> Result<Option<Reference>> {
+        sqlx::query("select * from reference where reference_id = $1")
+            .bind(reference_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn get_by_citation_key(
+        &self,
+        project_id: Uuid,
+        citation_key: &str,
+    ) -> Result<Option<Reference>> {
+        sqlx::query("select * from reference where project_id = $1 and citation_key = $2")
+            .bind(project_id)
+            .bind(citation_key)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn sync(&self, reference: Reference) -> Result<Reference> {
+        let row = sqlx::query(
+            "update reference set entry_type = $2, citation_key = $3, authors = $4, title = $5, \
+             year = $6, pages = $7, extra_fields = $8 where reference_id = $1 returning *",
+        )
+        .bind(reference.reference_id)
+        .bind(&reference.entry_type)
+        .bind(&reference.citation_key)
+        .bind(&reference.authors)
+        .bind(&reference.title)
+        .bind(reference.year)
+        .bind(&reference.pages)
+        .bind(Json(&reference.extra_fields))
+        .fetch_optional(&self.pool)
+        .await
+        .map_err(|e| Self::map_error(e, reference.citation_key.clone()))?
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Reference does not exist: UUID='{}'.",
+                reference.reference_id
+            )
+        })?;
+
+        Self::hydrate(&row)
+    }
+
+    async fn delete(&self, reference_id: ReferenceIdentifier) -> Result<Option<Reference>> {
+        sqlx::query("delete from reference where reference_id = $1 returning *")
+            .bind(reference_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn list_by_project(&self, project_id: Uuid) -> Result<Vec<Reference>> {
+        sqlx::query(
+            "select * from reference where project_id = $1 order by citation_key collate \"C\"",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(Self::hydrate)
+        .collect()
+    }
+}
@@ -0,0 +1,36 @@
+use sqlx::postgres::PgPool;
+
+use crate::Result;
+
+/// Lists the words of the contents of a table whose trigram similarity with
+/// the given word is at least the threshold, with their similarity.
+/// The contents holding such a word are found with the trigram index of the
+/// table: the word similarity of the word with a content is never lower than
+/// its similarity with the words of the content. Both thresholds are only set
+/// for the transaction of the query.
+pub(super) async fn similar_words(
+    pool: &PgPool,
+    table: &'static str,
+    word: &str,
+    threshold: f64,
+) -> Result<Vec<(String, f64)>> {
+    let mut transaction = pool.begin().await?;
+    sqlx::query(
+        "select set_config('pg_trgm.similarity_threshold', $1, true), \
+         set_config('pg_trgm.word_similarity_threshold', $1, true)",
+    )
+    .bind(threshold.to_string())
+    .execute(&mut *transaction)
+    .await?;
+    let words = sqlx::query_as(&format!(
+        "select word, similarity(word, $1)::float8 from (\
+         select distinct regexp_split_to_table(lower(content), '[^[:alnum:]]+') as word \
+         from {table} where $1 <% content) words where word % $1"
+    ))
+    .bind(word)
+    .fetch_all(&mut *transaction)
+    .await?;
+    transaction.commit().await?;
+
+    Ok(words)
+}
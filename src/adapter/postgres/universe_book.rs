@@ -0,0 +1,54 @@
+use async_trait::async_trait;
+use sqlx::postgres::{PgPool, PgRow};
+use sqlx::Row;
+
+use crate::adapter::UniverseBook;
+use crate::models::{OrganizationIdentifier, Universe, UniverseIdentifier};
+use crate::Result;
+
+/// PgUniverseBook is a PostgreSQL implementation of the UniverseBook trait.
+/// The universes are written by the [`super::PgOrganizationBook`].
+pub struct PgUniverseBook {
+    pool: PgPool,
+}
+
+impl PgUniverseBook {
+    /// Create a new universe book using the given connection pool.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub(super) fn hydrate(row: &PgRow) -> Result<Universe> {
+        Ok(Universe {
+            universe_id: row.try_get("universe_id")?,
+            organization_id: row.try_get("organization_id")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[async_trait]
+impl UniverseBook for PgUniverseBook {
+    async fn get(&self, universe_id: UniverseIdentifier) -> Result<Option<Universe>> {
+        sqlx::query("select * from universe where universe_id = $1")
+            .bind(universe_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn get_by_organization(
+        &self,
+        organization_id: OrganizationIdentifier,
+    ) -> Result<Option<Universe>> {
+        sqlx::query("select * from universe where organization_id = $1")
+            .bind(organization_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+}
@@ -1,4 +1,5 @@
-use crate::models::{CreateProjectCommand, Project};
+use crate::adapter::InMemoryOutbox;
+use crate::models::{most_similar, CreateProjectCommand, ModelEvent, Project, ProjectChangeKind};
 use crate::Result;
 use async_trait::async_trait;
 use std::collections::HashMap;
@@ -20,6 +21,8 @@ pub enum ProjectBookError {
 
 /// ProjectBook is a trait that defines the methods that are required to interact
 /// with a project database.
+/// Creating a project writes its Created event in the outbox in the same unit
+/// of work.
 #[async_trait]
 pub trait ProjectBook: Sync + Send {
     /// Creates a new project in the project database.
@@ -39,6 +42,15 @@ pub trait ProjectBook: Sync + Send {
 
     /// Lists all projects in a universe.
     async fn list_by_universe(&self, universe_id: &Uuid) -> Result<Vec<Project>>;
+
+    /// Lists all projects, whatever their universe.
+    /// Projects are sorted by their creation date, oldest first.
+    async fn list(&self) -> Result<Vec<Project>>;
+
+    /// Lists the slugs whose trigram similarity with the given slug is at
+    /// least the threshold, most similar first.
+    /// It is used to suggest projects when a slug is not found.
+    async fn suggest_slugs(&self, slug: &str, threshold: f64, limit: usize) -> Result<Vec<String>>;
 }
 
 /// InMemoryProjectBook is an in-memory implementation of the ProjectBook trait.
@@ -47,6 +59,18 @@ pub trait ProjectBook: Sync + Send {
 pub struct InMemoryProjectBook {
     projects: Arc<RwLock<HashMap<Uuid, Project>>>,
     slugs: Arc<RwLock<HashMap<String, Uuid>>>,
+    outbox: InMemoryOutbox,
+}
+
+impl InMemoryProjectBook {
+    /// Create a project book writing its events in the given outbox.
+    pub fn with_outbox(outbox: InMemoryOutbox) -> Self {
+        Self {
+            projects: Default::default(),
+            slugs: Default::default(),
+            outbox,
+        }
+    }
 }
 
 #[async_trait]
@@ -61,9 +85,11 @@ impl ProjectBook for InMemoryProjectBook {
 
         let mut projects = self.projects.write().await;
         let mut slugs = self.slugs.write().await;
+        let mut outbox = self.outbox.write().await;
 
         projects.insert(project.project_id, project.clone());
         slugs.insert(project.slug.clone(), project.project_id);
+        outbox.push(ModelEvent::project(&project, ProjectChangeKind::Created));
 
         Ok(project)
     }
@@ -91,11 +117,6 @@ impl ProjectBook for InMemoryProjectBook {
         let mut projects = self.projects.write().await;
         let mut slugs = self.slugs.write().await;
 
-        // Remove old slug mapping if it exists
-        if let Some(existing) = projects.get(&project.project_id) {
-            slugs.remove(&existing.slug);
-        }
-
         // Check for duplicate slug with other projects
         if let Some(existing_id) = slugs.get(&project.slug) {
             if existing_id != &project.project_id {
@@ -103,6 +124,11 @@ impl ProjectBook for InMemoryProjectBook {
             }
         }
 
+        // Remove old slug mapping if it exists
+        if let Some(existing) = projects.get(&project.project_id) {
+            slugs.remove(&existing.slug);
+        }
+
         slugs.insert(project.slug.clone(), project.project_id);
         projects.insert(project.project_id, project.clone());
 
@@ -131,6 +157,24 @@ impl ProjectBook for InMemoryProjectBook {
             .cloned()
             .collect())
     }
+
+    async fn list(&self) -> Result<Vec<Project>> {
+        let mut projects: Vec<Project> = self.projects.read().await.values().cloned().collect();
+        projects.sort_by_key(|p| p.created_at);
+
+        Ok(projects)
+    }
+
+    async fn suggest_slugs(&self, slug: &str, threshold: f64, limit: usize) -> Result<Vec<String>> {
+        let slugs = self.slugs.read().await;
+
+        Ok(most_similar(
+            slug,
+            slugs.keys().map(String::as_str),
+            threshold,
+            limit,
+        ))
+    }
 }
 
 #[cfg(test)]
@@ -214,4 +258,11 @@ mod tests {
         let projects = book.list_by_universe(&universe_id1).await.unwrap();
         assert_eq!(projects.len(), 2);
     }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let book = InMemoryProjectBook::default();
+
+        crate::testkit::check_project_book(&book).await;
+    }
 }
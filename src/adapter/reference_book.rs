@@ -0,0 +1,190 @@
+use crate::models::{CreateReferenceCommand, Reference, ReferenceIdentifier};
+use crate::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+/// ReferenceBookError is an error type that is used to represent errors that
+/// occur when interacting with the reference database.
+#[derive(Debug, thiserror::Error)]
+pub enum ReferenceBookError {
+    /// An error that occurs when a citation key is already used in the project.
+    #[error("Reference with citation key '{0}' already exists.")]
+    DuplicateCitationKey(String),
+}
+
+/// ReferenceBook is a trait that defines the methods that are required to
+/// interact with a reference database.
+#[async_trait]
+pub trait ReferenceBook: Sync + Send {
+    /// Adds a new reference to a project.
+    /// If the citation key is already used in the project, a
+    /// DuplicateCitationKey error is raised.
+    async fn add(&self, command: CreateReferenceCommand, project_id: Uuid) -> Result<Reference>;
+
+    /// Adds or updates references of a project in one unit of work.
+    /// A reference whose citation key is already used in the project updates
+    /// the existing one, keeping its identifier. Nothing is stored if one of
+    /// them is not valid. The imported references are returned in the order
+    /// of the commands.
+    async fn import(
+        &self,
+        commands: Vec<CreateReferenceCommand>,
+        project_id: Uuid,
+    ) -> Result<Vec<Reference>>;
+
+    /// Gets a reference from the reference database.
+    /// If the reference does not exist, None is returned.
+    async fn get(&self, reference_id: ReferenceIdentifier) -> Result<Option<Reference>>;
+
+    /// Gets a reference of a project by its citation key.
+    /// If the reference does not exist, None is returned.
+    async fn get_by_citation_key(
+        &self,
+        project_id: Uuid,
+        citation_key: &str,
+    ) -> Result<Option<Reference>>;
+
+    /// Syncs a reference in the reference database.
+    /// The identifier and the project cannot be updated.
+    /// If the reference does not exist, an error is returned.
+    async fn sync(&self, reference: Reference) -> Result<Reference>;
+
+    /// Deletes a reference from the reference database.
+    /// If the reference does not exist, None is returned.
+    async fn delete(&self, reference_id: ReferenceIdentifier) -> Result<Option<Reference>>;
+
+    /// Lists all references of a project.
+    /// References are sorted by their citation key.
+    async fn list_by_project(&self, project_id: Uuid) -> Result<Vec<Reference>>;
+}
+
+/// InMemoryReferenceBook is an in-memory implementation of the ReferenceBook trait.
+#[derive(Default)]
+pub struct InMemoryReferenceBook {
+    references: Arc<RwLock<HashMap<Uuid, Reference>>>,
+}
+
+#[async_trait]
+impl ReferenceBook for InMemoryReferenceBook {
+    async fn add(&self, command: CreateReferenceCommand, project_id: Uuid) -> Result<Reference> {
+        let reference = Reference::create(command, project_id)?;
+        let mut references = self.references.write().await;
+
+        if references
+            .values()
+            .any(|r| r.project_id == project_id && r.citation_key == reference.citation_key)
+        {
+            return Err(ReferenceBookError::DuplicateCitationKey(reference.citation_key).into());
+        }
+        references.insert(reference.reference_id, reference.clone());
+
+        Ok(reference)
+    }
+
+    async fn import(
+        &self,
+        commands: Vec<CreateReferenceCommand>,
+        project_id: Uuid,
+    ) -> Result<Vec<Reference>> {
+        let mut references = self.references.write().await;
+
+        let mut imported: Vec<Reference> = Vec::with_capacity(commands.len());
+        for command in commands {
+            let reference = Reference::create(command, project_id)?;
+            let reference_id = imported
+                .iter()
+                .chain(references.values())
+                .find(|r| r.project_id == project_id && r.citation_key == reference.citation_key)
+                .map_or(reference.reference_id, |r| r.reference_id);
+            imported.push(Reference {
+                reference_id,
+                ..reference
+            });
+        }
+        for reference in &imported {
+            references.insert(reference.reference_id, reference.clone());
+        }
+
+        Ok(imported)
+    }
+
+    async fn get(&self, reference_id: ReferenceIdentifier) -> Result<Option<Reference>> {
+        Ok(self.references.read().await.get(&reference_id).cloned())
+    }
+
+    async fn get_by_citation_key(
+        &self,
+        project_id: Uuid,
+        citation_key: &str,
+    ) -> Result<Option<Reference>> {
+        Ok(self
+            .references
+            .read()
+            .await
+            .values()
+            .find(|r| r.project_id == project_id && r.citation_key == citation_key)
+            .cloned())
+    }
+
+    async fn sync(&self, reference: Reference) -> Result<Reference> {
+        let mut references = self.references.write().await;
+
+        let project_id = references
+            .get(&reference.reference_id)
+            .map(|r| r.project_id)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Reference does not exist: UUID='{}'.",
+                    reference.reference_id
+                )
+            })?;
+        if references.values().any(|r| {
+            r.project_id == project_id
+                && r.reference_id != reference.reference_id
+                && r.citation_key == reference.citation_key
+        }) {
+            return Err(ReferenceBookError::DuplicateCitationKey(reference.citation_key).into());
+        }
+        let reference = Reference {
+            project_id,
+            ..reference
+        };
+        references.insert(reference.reference_id, reference.clone());
+
+        Ok(reference)
+    }
+
+    async fn delete(&self, reference_id: ReferenceIdentifier) -> Result<Option<Reference>> {
+        Ok(self.references.write().await.remove(&reference_id))
+    }
+
+    async fn list_by_project(&self, project_id: Uuid) -> Result<Vec<Reference>> {
+        let mut references: Vec<Reference> = self
+            .references
+            .read()
+            .await
+            .values()
+            .filter(|r| r.project_id == project_id)
+            .cloned()
+            .collect();
+        references.sort_by(|a, b| a.citation_key.cmp(&b.citation_key));
+
+        Ok(references)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let reference_book = InMemoryReferenceBook::default();
+        let project_book = crate::adapter::InMemoryProjectBook::default();
+
+        crate::testkit::check_reference_book(&reference_book, &project_book).await;
+    }
+}
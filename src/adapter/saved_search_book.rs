@@ -0,0 +1,202 @@
+use crate::models::{
+    CreateSavedSearchCommand, SavedSearch, SavedSearchIdentifier, SavedSearchResult,
+    StyloIdentifier,
+};
+use crate::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// SavedSearchBook is a trait that defines the methods that are required to
+/// interact with a saved search database, including the new results of the
+/// saved searches.
+#[async_trait]
+pub trait SavedSearchBook: Sync + Send {
+    /// Adds a new saved search.
+    async fn add(&self, command: CreateSavedSearchCommand) -> Result<SavedSearch>;
+
+    /// Gets a saved search from the saved search database.
+    /// If the saved search does not exist, None is returned.
+    async fn get(&self, saved_search_id: SavedSearchIdentifier) -> Result<Option<SavedSearch>>;
+
+    /// Gets a saved search of a stylo by its name.
+    /// If the saved search does not exist, None is returned.
+    async fn get_by_name(
+        &self,
+        stylo_id: StyloIdentifier,
+        name: &str,
+    ) -> Result<Option<SavedSearch>>;
+
+    /// Syncs a saved search in the saved search database.
+    /// The identifier and the stylo cannot be updated.
+    /// If the saved search does not exist, an error is returned.
+    async fn sync(&self, saved_search: SavedSearch) -> Result<SavedSearch>;
+
+    /// Deletes a saved search and its new results.
+    /// If the saved search does not exist, None is returned.
+    async fn delete(&self, saved_search_id: SavedSearchIdentifier) -> Result<Option<SavedSearch>>;
+
+    /// Lists all the saved searches of all the stylos.
+    async fn list(&self) -> Result<Vec<SavedSearch>>;
+
+    /// Lists the saved searches of a stylo, sorted by name.
+    async fn list_by_stylo(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearch>>;
+
+    /// Records a new result of a saved search.
+    /// A document already recorded for the saved search is not recorded twice.
+    async fn add_result(&self, result: SavedSearchResult) -> Result<()>;
+
+    /// Lists the new results of the saved searches of a stylo, newest first.
+    async fn list_results(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearchResult>>;
+
+    /// Removes the new results of the saved searches of a stylo, once seen.
+    /// The removed results are returned, newest first.
+    async fn clear_results(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearchResult>>;
+}
+
+/// InMemorySavedSearchBook is an in-memory implementation of the
+/// SavedSearchBook trait.
+#[derive(Default)]
+pub struct InMemorySavedSearchBook {
+    saved_searches: Arc<RwLock<HashMap<SavedSearchIdentifier, SavedSearch>>>,
+    results: Arc<RwLock<Vec<SavedSearchResult>>>,
+}
+
+/// Sort results newest first.
+fn newest_first(mut results: Vec<SavedSearchResult>) -> Vec<SavedSearchResult> {
+    results.sort_by_key(|r| std::cmp::Reverse(r.matched_at));
+
+    results
+}
+
+#[async_trait]
+impl SavedSearchBook for InMemorySavedSearchBook {
+    async fn add(&self, command: CreateSavedSearchCommand) -> Result<SavedSearch> {
+        let saved_search = SavedSearch::create(command);
+        self.saved_searches
+            .write()
+            .await
+            .insert(saved_search.saved_search_id, saved_search.clone());
+
+        Ok(saved_search)
+    }
+
+    async fn get(&self, saved_search_id: SavedSearchIdentifier) -> Result<Option<SavedSearch>> {
+        Ok(self
+            .saved_searches
+            .read()
+            .await
+            .get(&saved_search_id)
+            .cloned())
+    }
+
+    async fn get_by_name(
+        &self,
+        stylo_id: StyloIdentifier,
+        name: &str,
+    ) -> Result<Option<SavedSearch>> {
+        Ok(self
+            .saved_searches
+            .read()
+            .await
+            .values()
+            .find(|s| s.stylo_id == stylo_id && s.name == name)
+            .cloned())
+    }
+
+    async fn sync(&self, saved_search: SavedSearch) -> Result<SavedSearch> {
+        let mut saved_searches = self.saved_searches.write().await;
+
+        let stylo_id = saved_searches
+            .get(&saved_search.saved_search_id)
+            .map(|s| s.stylo_id)
+            .ok_or_else(|| {
+                anyhow::anyhow!(
+                    "Saved search does not exist: UUID='{}'.",
+                    saved_search.saved_search_id
+                )
+            })?;
+        let saved_search = SavedSearch {
+            stylo_id,
+            ..saved_search
+        };
+        saved_searches.insert(saved_search.saved_search_id, saved_search.clone());
+
+        Ok(saved_search)
+    }
+
+    async fn delete(&self, saved_search_id: SavedSearchIdentifier) -> Result<Option<SavedSearch>> {
+        let deleted = self.saved_searches.write().await.remove(&saved_search_id);
+        self.results
+            .write()
+            .await
+            .retain(|r| r.saved_search_id != saved_search_id);
+
+        Ok(deleted)
+    }
+
+    async fn list(&self) -> Result<Vec<SavedSearch>> {
+        Ok(self.saved_searches.read().await.values().cloned().collect())
+    }
+
+    async fn list_by_stylo(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearch>> {
+        let mut saved_searches: Vec<SavedSearch> = self
+            .saved_searches
+            .read()
+            .await
+            .values()
+            .filter(|s| s.stylo_id == stylo_id)
+            .cloned()
+            .collect();
+        saved_searches.sort_by(|a, b| a.name.cmp(&b.name));
+
+        Ok(saved_searches)
+    }
+
+    async fn add_result(&self, result: SavedSearchResult) -> Result<()> {
+        let mut results = self.results.write().await;
+
+        if !results
+            .iter()
+            .any(|r| r.saved_search_id == result.saved_search_id && r.id == result.id)
+        {
+            results.push(result);
+        }
+
+        Ok(())
+    }
+
+    async fn list_results(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearchResult>> {
+        let results = self
+            .results
+            .read()
+            .await
+            .iter()
+            .filter(|r| r.stylo_id == stylo_id)
+            .cloned()
+            .collect();
+
+        Ok(newest_first(results))
+    }
+
+    async fn clear_results(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearchResult>> {
+        let mut results = self.results.write().await;
+        let (cleared, kept) = results.drain(..).partition(|r| r.stylo_id == stylo_id);
+        *results = kept;
+
+        Ok(newest_first(cleared))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let saved_search_book = InMemorySavedSearchBook::default();
+
+        crate::testkit::check_saved_search_book(&saved_search_book).await;
+    }
+}
@@ -0,0 +1,44 @@
+//! SQLite implementations of the books.
+//!
+//! This backend stores everything in a single database file which makes it fit
+//! for single-user deployments. The schema is embedded in the binary and
+//! migrated when connecting.
+mod note_book;
+mod organization_book;
+mod outbox;
+mod project_book;
+mod reference_book;
+mod saved_search_book;
+mod stylo_book;
+mod thought_book;
+mod universe_book;
+
+pub use note_book::*;
+pub use organization_book::*;
+pub use outbox::*;
+pub use project_book::*;
+pub use reference_book::*;
+pub use saved_search_book::*;
+pub use stylo_book::*;
+pub use thought_book::*;
+pub use universe_book::*;
+
+use std::path::Path;
+
+use sqlx::sqlite::{SqliteConnectOptions, SqliteJournalMode, SqlitePool, SqlitePoolOptions};
+
+use crate::Result;
+
+/// Open the SQLite database file and migrate its schema.
+/// The file is created if it does not exist and the database is set in WAL mode.
+pub async fn connect(path: &Path) -> Result<SqlitePool> {
+    let options = SqliteConnectOptions::new()
+        .filename(path)
+        .create_if_missing(true)
+        .journal_mode(SqliteJournalMode::Wal)
+        .foreign_keys(true);
+    let pool = SqlitePoolOptions::new().connect_with(options).await?;
+    sqlx::migrate!("db/migrations/sqlite").run(&pool).await?;
+
+    Ok(pool)
+}
@@ -0,0 +1,139 @@
+use async_trait::async_trait;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+use uuid::Uuid;
+
+use super::outbox::enqueue;
+use crate::adapter::NoteBook;
+use crate::models::{CreateNoteCommand, ModelEvent, Note, NoteChangeKind, ResponsibilityChain};
+use crate::Result;
+
+/// SqliteNoteBook is a SQLite implementation of the NoteBook trait.
+pub struct SqliteNoteBook {
+    pool: SqlitePool,
+}
+
+impl SqliteNoteBook {
+    /// Create a new note book using the given connection pool.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn hydrate(row: &SqliteRow) -> Result<Note> {
+        Ok(Note {
+            note_id: row.try_get("note_id")?,
+            imported_at: row.try_get("imported_at")?,
+            stylo_id: row.try_get("stylo_id")?,
+            project_id: row.try_get("project_id")?,
+            content: row.try_get("content")?,
+            references: serde_json::from_str(row.try_get("reference_ids")?)?,
+            media: serde_json::from_str(row.try_get("media")?)?,
+        })
+    }
+}
+
+#[async_trait]
+impl NoteBook for SqliteNoteBook {
+    async fn add(
+        &self,
+        command: CreateNoteCommand,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Note> {
+        let mut transaction = self.pool.begin().await?;
+        let row = sqlx::query(
+            "insert into note (note_id, imported_at, stylo_id, project_id, content, reference_ids, \
+             media) values ($1, $2, $3, $4, $5, $6, $7) returning *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(command.imported_at)
+        .bind(command.stylo_id)
+        .bind(project_id)
+        .bind(command.content)
+        .bind(serde_json::to_string(&command.references)?)
+        .bind(serde_json::to_string(&command.media)?)
+        .fetch_one(&mut *transaction)
+        .await?;
+        let note = Self::hydrate(&row)?;
+        enqueue(
+            &mut transaction,
+            &[ModelEvent::note(&note, NoteChangeKind::Created).with_chain(chain)],
+        )
+        .await?;
+        transaction.commit().await?;
+
+        Ok(note)
+    }
+
+    async fn get(&self, note_id: Uuid) -> Result<Option<Note>> {
+        sqlx::query("select * from note where note_id = $1")
+            .bind(note_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn sync(&self, note: Note) -> Result<Note> {
+        let row = sqlx::query(
+            "update note set imported_at = $2, stylo_id = $3, project_id = $4, content = $5, \
+             reference_ids = $6, media = $7 where note_id = $1 returning *",
+        )
+        .bind(note.note_id)
+        .bind(note.imported_at)
+        .bind(note.stylo_id)
+        .bind(note.project_id)
+        .bind(note.content)
+        .bind(serde_json::to_string(&note.references)?)
+        .bind(serde_json::to_string(&note.media)?)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Note does not exist: UUID='{}'.", note.note_id))?;
+
+        Self::hydrate(&row)
+    }
+
+    async fn delete(&self, note_id: Uuid, chain: ResponsibilityChain) -> Result<Option<Note>> {
+        let mut transaction = self.pool.begin().await?;
+        let note = sqlx::query("delete from note where note_id = $1 returning *")
+            .bind(note_id)
+            .fetch_optional(&mut *transaction)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()?;
+        if let Some(note) = &note {
+            enqueue(
+                &mut transaction,
+                &[ModelEvent::note(note, NoteChangeKind::Scratched).with_chain(chain)],
+            )
+            .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(note)
+    }
+
+    async fn list_by_project(&self, project_id: Uuid) -> Result<Vec<Note>> {
+        sqlx::query("select * from note where project_id = $1 order by imported_at")
+            .bind(project_id)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(Self::hydrate)
+            .collect()
+    }
+
+    async fn is_media_referenced(&self, digest: &str) -> Result<bool> {
+        let row = sqlx::query(
+            "select exists (select 1 from note, json_each(note.media) \
+             where json_extract(json_each.value, '$.digest') = $1) as found",
+        )
+        .bind(digest)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.try_get("found")?)
+    }
+}
@@ -0,0 +1,96 @@
+use async_trait::async_trait;
+use ed25519_dalek::VerifyingKey;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use super::SqliteUniverseBook;
+use crate::adapter::OrganizationBook;
+use crate::models::{
+    CreateOrganizationCommand, CreatedOrganization, Organization, OrganizationIdentifier, Universe,
+};
+use crate::Result;
+
+/// SqliteOrganizationBook is a SQLite implementation of the OrganizationBook
+/// trait.
+/// The organization and its universe are inserted in the same transaction.
+pub struct SqliteOrganizationBook {
+    pool: SqlitePool,
+}
+
+impl SqliteOrganizationBook {
+    /// Create a new organization book using the given connection pool.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn hydrate(row: &SqliteRow) -> Result<Organization> {
+        Ok(Organization {
+            organization_id: row.try_get("organization_id")?,
+            name: row.try_get("name")?,
+            certificate: serde_json::from_str(row.try_get("certificate")?)?,
+            public_key: row.try_get("public_key")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+}
+
+#[async_trait]
+impl OrganizationBook for SqliteOrganizationBook {
+    async fn create(
+        &self,
+        organization_id: OrganizationIdentifier,
+        command: CreateOrganizationCommand,
+        public_key: &VerifyingKey,
+    ) -> Result<CreatedOrganization> {
+        let organization = Organization::create(organization_id, command, public_key)?;
+        let universe = Universe::create(organization.organization_id);
+        let mut transaction = self.pool.begin().await?;
+        let row = sqlx::query(
+            "insert into organization (organization_id, name, certificate, public_key, \
+             created_at) values ($1, $2, $3, $4, $5) returning *",
+        )
+        .bind(organization.organization_id)
+        .bind(organization.name)
+        .bind(serde_json::to_string(&organization.certificate)?)
+        .bind(organization.public_key)
+        .bind(organization.created_at)
+        .fetch_one(&mut *transaction)
+        .await?;
+        let organization = Self::hydrate(&row)?;
+        let row = sqlx::query(
+            "insert into universe (universe_id, organization_id, created_at) \
+             values ($1, $2, $3) returning *",
+        )
+        .bind(universe.universe_id)
+        .bind(universe.organization_id)
+        .bind(universe.created_at)
+        .fetch_one(&mut *transaction)
+        .await?;
+        let universe = SqliteUniverseBook::hydrate(&row)?;
+        transaction.commit().await?;
+
+        Ok(CreatedOrganization {
+            organization,
+            universe,
+        })
+    }
+
+    async fn get(&self, organization_id: OrganizationIdentifier) -> Result<Option<Organization>> {
+        sqlx::query("select * from organization where organization_id = $1")
+            .bind(organization_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn list(&self) -> Result<Vec<Organization>> {
+        sqlx::query("select * from organization order by created_at")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(Self::hydrate)
+            .collect()
+    }
+}
@@ -0,0 +1,62 @@
+use async_trait::async_trait;
+use sqlx::sqlite::{SqliteConnection, SqlitePool};
+use sqlx::Row;
+use uuid::Uuid;
+
+use crate::adapter::Outbox;
+use crate::models::ModelEvent;
+use crate::Result;
+
+/// SqliteOutbox is a SQLite implementation of the Outbox trait.
+pub struct SqliteOutbox {
+    pool: SqlitePool,
+}
+
+impl SqliteOutbox {
+    /// Create a new outbox using the given connection pool.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+}
+
+/// Write events in the outbox, within the transaction of the change.
+pub(crate) async fn enqueue(
+    connection: &mut SqliteConnection,
+    events: &[ModelEvent],
+) -> Result<()> {
+    for event in events {
+        sqlx::query("insert into outbox (event_id, event) values ($1, $2)")
+            .bind(event.event_id)
+            .bind(serde_json::to_string(event)?)
+            .execute(&mut *connection)
+            .await?;
+    }
+
+    Ok(())
+}
+
+#[async_trait]
+impl Outbox for SqliteOutbox {
+    async fn pending(&self, limit: usize) -> Result<Vec<ModelEvent>> {
+        sqlx::query("select event from outbox order by position limit $1")
+            .bind(i64::try_from(limit).unwrap_or(i64::MAX))
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(|row| Ok(serde_json::from_str(row.try_get("event")?)?))
+            .collect()
+    }
+
+    async fn acknowledge(&self, event_ids: &[Uuid]) -> Result<()> {
+        let mut transaction = self.pool.begin().await?;
+        for event_id in event_ids {
+            sqlx::query("delete from outbox where event_id = $1")
+                .bind(event_id)
+                .execute(&mut *transaction)
+                .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(())
+    }
+}
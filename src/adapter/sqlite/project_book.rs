@@ -0,0 +1,160 @@
+use async_trait::async_trait;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+use uuid::Uuid;
+
+use super::outbox::enqueue;
+use crate::adapter::{ProjectBook, ProjectBookError};
+use crate::models::{most_similar, CreateProjectCommand, ModelEvent, Project, ProjectChangeKind};
+use crate::Result;
+
+/// SqliteProjectBook is a SQLite implementation of the ProjectBook trait.
+pub struct SqliteProjectBook {
+    pool: SqlitePool,
+}
+
+impl SqliteProjectBook {
+    /// Create a new project book using the given connection pool.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn hydrate(row: &SqliteRow) -> Result<Project> {
+        Ok(Project {
+            project_id: row.try_get("project_id")?,
+            universe_id: row.try_get("universe_id")?,
+            created_at: row.try_get("created_at")?,
+            project_name: row.try_get("project_name")?,
+            slug: row.try_get("slug")?,
+            locked: row.try_get("locked")?,
+        })
+    }
+
+    /// Turn a unique violation on the slug into a DuplicateSlug error.
+    fn map_error(error: sqlx::Error, slug: String) -> anyhow::Error {
+        match error {
+            sqlx::Error::Database(ref e) if e.is_unique_violation() => {
+                ProjectBookError::DuplicateSlug(slug).into()
+            }
+            e => e.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl ProjectBook for SqliteProjectBook {
+    async fn create(&self, command: CreateProjectCommand) -> Result<Project> {
+        let project = Project::create(command)?;
+        let mut transaction = self.pool.begin().await?;
+        let row = sqlx::query(
+            "insert into project (project_id, universe_id, created_at, project_name, slug, locked) \
+             values ($1, $2, $3, $4, $5, $6) returning *",
+        )
+        .bind(project.project_id)
+        .bind(project.universe_id)
+        .bind(project.created_at)
+        .bind(&project.project_name)
+        .bind(&project.slug)
+        .bind(project.locked)
+        .fetch_one(&mut *transaction)
+        .await
+        .map_err(|e| Self::map_error(e, project.slug.clone()))?;
+        let project = Self::hydrate(&row)?;
+        enqueue(
+            &mut transaction,
+            &[ModelEvent::project(&project, ProjectChangeKind::Created)],
+        )
+        .await?;
+        transaction.commit().await?;
+
+        Ok(project)
+    }
+
+    async fn get(&self, project_id: &Uuid) -> Result<Option<Project>> {
+        sqlx::query("select * from project where project_id = $1")
+            .bind(project_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn get_by_slug(&self, slug: &str) -> Result<Option<Project>> {
+        sqlx::query("select * from project where slug = $1")
+            .bind(slug)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn update(&self, project: Project) -> Result<Project> {
+        let row = sqlx::query(
+            "insert into project (project_id, universe_id, created_at, project_name, slug, locked) \
+             values ($1, $2, $3, $4, $5, $6) \
+             on conflict (project_id) do update set universe_id = excluded.universe_id, \
+             created_at = excluded.created_at, project_name = excluded.project_name, \
+             slug = excluded.slug, locked = excluded.locked returning *",
+        )
+        .bind(project.project_id)
+        .bind(project.universe_id)
+        .bind(project.created_at)
+        .bind(&project.project_name)
+        .bind(&project.slug)
+        .bind(project.locked)
+        .fetch_one(&self.pool)
+        .await
+        .map_err(|e| Self::map_error(e, project.slug.clone()))?;
+
+        Self::hydrate(&row)
+    }
+
+    async fn delete(&self, project_id: &Uuid) -> Result<()> {
+        let result = sqlx::query("delete from project where project_id = $1")
+            .bind(project_id)
+            .execute(&self.pool)
+            .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(ProjectBookError::ProjectNotFound(*project_id).into());
+        }
+
+        Ok(())
+    }
+
+    async fn list_by_universe(&self, universe_id: &Uuid) -> Result<Vec<Project>> {
+        sqlx::query("select * from project where universe_id = $1 order by created_at")
+            .bind(universe_id)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(Self::hydrate)
+            .collect()
+    }
+
+    async fn list(&self) -> Result<Vec<Project>> {
+        sqlx::query("select * from project order by created_at")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(Self::hydrate)
+            .collect()
+    }
+
+    /// SQLite has no trigram support, the similarity is computed over all the
+    /// slugs.
+    async fn suggest_slugs(&self, slug: &str, threshold: f64, limit: usize) -> Result<Vec<String>> {
+        let slugs: Vec<String> = sqlx::query_scalar("select slug from project")
+            .fetch_all(&self.pool)
+            .await?;
+
+        Ok(most_similar(
+            slug,
+            slugs.iter().map(String::as_str),
+            threshold,
+            limit,
+        ))
+    }
+}
@@ -0,0 +1,182 @@
+use async_trait::async_trait;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use crate::adapter::SavedSearchBook;
+use crate::models::{
+    CreateSavedSearchCommand, SavedSearch, SavedSearchIdentifier, SavedSearchResult,
+    StyloIdentifier,
+};
+use crate::Result;
+
+/// SqliteSavedSearchBook is a SQLite implementation of the SavedSearchBook
+/// trait.
+/// The new results are deleted with their saved search by the database.
+pub struct SqliteSavedSearchBook {
+    pool: SqlitePool,
+}
+
+impl SqliteSavedSearchBook {
+    /// Create a new saved search book using the given connection pool.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn hydrate(row: &SqliteRow) -> Result<SavedSearch> {
+        Ok(SavedSearch {
+            saved_search_id: row.try_get("saved_search_id")?,
+            stylo_id: row.try_get("stylo_id")?,
+            name: row.try_get("name")?,
+            query: row.try_get("query")?,
+            created_at: row.try_get("created_at")?,
+        })
+    }
+
+    fn hydrate_result(row: &SqliteRow) -> Result<SavedSearchResult> {
+        Ok(SavedSearchResult {
+            saved_search_id: row.try_get("saved_search_id")?,
+            stylo_id: row.try_get("stylo_id")?,
+            kind: row.try_get::<String, _>("kind")?.parse()?,
+            id: row.try_get("id")?,
+            project_id: row.try_get("project_id")?,
+            matched_at: row.try_get("matched_at")?,
+        })
+    }
+}
+
+#[async_trait]
+impl SavedSearchBook for SqliteSavedSearchBook {
+    async fn add(&self, command: CreateSavedSearchCommand) -> Result<SavedSearch> {
+        let saved_search = SavedSearch::create(command);
+        let row = sqlx::query(
+            "insert into saved_search (saved_search_id, stylo_id, name, query, created_at) \
+             values ($1, $2, $3, $4, $5) returning *",
+        )
+        .bind(saved_search.saved_search_id)
+        .bind(saved_search.stylo_id)
+        .bind(&saved_search.name)
+        .bind(&saved_search.query)
+        .bind(saved_search.created_at)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Self::hydrate(&row)
+    }
+
+    async fn get(&self, saved_search_id: SavedSearchIdentifier) -> Result<Option<SavedSearch>> {
+        sqlx::query("select * from saved_search where saved_search_id = $1")
+            .bind(saved_search_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn get_by_name(
+        &self,
+        stylo_id: StyloIdentifier,
+        name: &str,
+    ) -> Result<Option<SavedSearch>> {
+        sqlx::query("select * from saved_search where stylo_id = $1 and name = $2")
+            .bind(stylo_id)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn sync(&self, saved_search: SavedSearch) -> Result<SavedSearch> {
+        let row = sqlx::query(
+            "update saved_search set name = $2, query = $3 where saved_search_id = $1 \
+             returning *",
+        )
+        .bind(saved_search.saved_search_id)
+        .bind(&saved_search.name)
+        .bind(&saved_search.query)
+        .fetch_optional(&self.pool)
+        .await?
+        .ok_or_else(|| {
+            anyhow::anyhow!(
+                "Saved search does not exist: UUID='{}'.",
+                saved_search.saved_search_id
+            )
+        })?;
+
+        Self::hydrate(&row)
+    }
+
+    async fn delete(&self, saved_search_id: SavedSearchIdentifier) -> Result<Option<SavedSearch>> {
+        sqlx::query("delete from saved_search where saved_search_id = $1 returning *")
+            .bind(saved_search_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn list(&self) -> Result<Vec<SavedSearch>> {
+        sqlx::query("select * from saved_search")
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(Self::hydrate)
+            .collect()
+    }
+
+    async fn list_by_stylo(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearch>> {
+        sqlx::query("select * from saved_search where stylo_id = $1 order by name")
+            .bind(stylo_id)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(Self::hydrate)
+            .collect()
+    }
+
+    async fn add_result(&self, result: SavedSearchResult) -> Result<()> {
+        sqlx::query(
+            "insert into saved_search_result (saved_search_id, stylo_id, kind, id, project_id, \
+             matched_at) values ($1, $2, $3, $4, $5, $6) on conflict do nothing",
+        )
+        .bind(result.saved_search_id)
+        .bind(result.stylo_id)
+        .bind(result.kind.as_str())
+        .bind(result.id)
+        .bind(result.project_id)
+        .bind(result.matched_at)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn list_results(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearchResult>> {
+        sqlx::query(
+            "select * from saved_search_result where stylo_id = $1 order by matched_at desc",
+        )
+        .bind(stylo_id)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(Self::hydrate_result)
+        .collect()
+    }
+
+    async fn clear_results(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearchResult>> {
+        let mut results =
+            sqlx::query("delete from saved_search_result where stylo_id = $1 returning *")
+                .bind(stylo_id)
+                .fetch_all(&self.pool)
+                .await?
+                .iter()
+                .map(Self::hydrate_result)
+                .collect::<Result<Vec<_>>>()?;
+        results.sort_by_key(|r| std::cmp::Reverse(r.matched_at));
+
+        Ok(results)
+    }
+}
@@ -0,0 +1,126 @@
+use async_trait::async_trait;
+use sqlx::sqlite::{SqlitePool, SqliteRow};
+use sqlx::Row;
+
+use super::outbox::enqueue;
+use crate::adapter::StyloBook;
+use crate::models::{
+    CreateStyloCommand, ModelEvent, OrganizationIdentifier, Stylo, StyloChangeKind, StyloIdentifier,
+};
+use crate::Result;
+
+/// SqliteStyloBook is a SQLite implementation of the StyloBook trait.
+pub struct SqliteStyloBook {
+    pool: SqlitePool,
+}
+
+impl SqliteStyloBook {
+    /// Create a new stylo book using the given connection pool.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn hydrate(row: &SqliteRow) -> Result<Stylo> {
+        Ok(Stylo {
+            stylo_id: row.try_get("stylo_id")?,
+            owner_organization_id: row.try_get("owner_organization_id")?,
+            actor_organization_id: row.try_get("actor_organization_id")?,
+            created_at: row.try_get("created_at")?,
+            display_name: row.try_get("display_name")?,
+            is_locked: row.try_get("is_locked")?,
+            email: row.try_get("email")?,
+        })
+    }
+}
+
+#[async_trait]
+impl StyloBook for SqliteStyloBook {
+    async fn add(&self, command: CreateStyloCommand) -> Result<Stylo> {
+        let stylo = Stylo::create(command)?;
+        let mut transaction = self.pool.begin().await?;
+        let row = sqlx::query(
+            "insert into stylo (stylo_id, owner_organization_id, actor_organization_id, \
+             created_at, display_name, is_locked, email) values ($1, $2, $3, $4, $5, $6, $7) \
+             returning *",
+        )
+        .bind(stylo.stylo_id)
+        .bind(stylo.owner_organization_id)
+        .bind(stylo.actor_organization_id)
+        .bind(stylo.created_at)
+        .bind(stylo.display_name)
+        .bind(stylo.is_locked)
+        .bind(stylo.email)
+        .fetch_one(&mut *transaction)
+        .await?;
+        let stylo = Self::hydrate(&row)?;
+        enqueue(
+            &mut transaction,
+            &[ModelEvent::stylo(&stylo, StyloChangeKind::Granted)],
+        )
+        .await?;
+        transaction.commit().await?;
+
+        Ok(stylo)
+    }
+
+    async fn get(&self, stylo_id: StyloIdentifier) -> Result<Option<Stylo>> {
+        sqlx::query("select * from stylo where stylo_id = $1")
+            .bind(stylo_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn sync(&self, stylo: Stylo, change_kind: StyloChangeKind) -> Result<Stylo> {
+        let mut transaction = self.pool.begin().await?;
+        let row = sqlx::query(
+            "update stylo set display_name = $2, is_locked = $3, email = $4 where stylo_id = $1 \
+             returning *",
+        )
+        .bind(stylo.stylo_id)
+        .bind(stylo.display_name)
+        .bind(stylo.is_locked)
+        .bind(stylo.email)
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Stylo does not exist: UUID='{}'.", stylo.stylo_id))?;
+        let stylo = Self::hydrate(&row)?;
+        enqueue(&mut transaction, &[ModelEvent::stylo(&stylo, change_kind)]).await?;
+        transaction.commit().await?;
+
+        Ok(stylo)
+    }
+
+    async fn delete(&self, stylo_id: StyloIdentifier) -> Result<Option<Stylo>> {
+        let mut transaction = self.pool.begin().await?;
+        let stylo = sqlx::query("delete from stylo where stylo_id = $1 returning *")
+            .bind(stylo_id)
+            .fetch_optional(&mut *transaction)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()?;
+        if let Some(stylo) = &stylo {
+            enqueue(
+                &mut transaction,
+                &[ModelEvent::stylo(stylo, StyloChangeKind::Revoked)],
+            )
+            .await?;
+        }
+        transaction.commit().await?;
+
+        Ok(stylo)
+    }
+
+    async fn list_by_owner(&self, organization_id: OrganizationIdentifier) -> Result<Vec<Stylo>> {
+        sqlx::query("select * from stylo where owner_organization_id = $1 order by created_at")
+            .bind(organization_id)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(Self::hydrate)
+            .collect()
+    }
+}
@@ -0,0 +1,436 @@
+use async_trait::async_trait;
+use sqlx::sqlite::{SqliteConnection, SqlitePool, SqliteRow};
+use sqlx::Row;
+use uuid::Uuid;
+
+use super::outbox::enqueue;
+use crate::adapter::thought_book::replace_tags;
+use crate::adapter::ThoughtBook;
+use crate::models::{
+    CategoryCount, CreateThoughtCommand, DanglingLink, ModelEvent, ResponsibilityChain, TagCount,
+    Thought, ThoughtChangeKind, ThoughtFilter, ThoughtIdentifier, ThoughtLink,
+};
+use crate::Result;
+
+/// SqliteThoughtBook is a SQLite implementation of the ThoughtBook trait.
+pub struct SqliteThoughtBook {
+    pool: SqlitePool,
+}
+
+impl SqliteThoughtBook {
+    /// Create a new thought book using the given connection pool.
+    pub fn new(pool: SqlitePool) -> Self {
+        Self { pool }
+    }
+
+    fn hydrate(row: &SqliteRow) -> Result<Thought> {
+        Ok(Thought {
+            thought_id: row.try_get("thought_id")?,
+            parent_id: row.try_get("parent_id")?,
+            imported_at: row.try_get("imported_at")?,
+            stylo_id: row.try_get("stylo_id")?,
+            project_id: row.try_get("project_id")?,
+            content: row.try_get("content")?,
+            variation: row.try_get::<String, _>("variation")?.parse()?,
+            answers: row.try_get("answers")?,
+            answered: row.try_get("answered")?,
+            refuted_by: row.try_get("refuted_by")?,
+            status: row.try_get::<String, _>("status")?.parse()?,
+            tags: serde_json::from_str(row.try_get("tags")?)?,
+            category: row
+                .try_get::<Option<String>, _>("category")?
+                .map(|category| category.parse())
+                .transpose()?,
+            links: serde_json::from_str(row.try_get("links")?)?,
+            references: serde_json::from_str(row.try_get("reference_ids")?)?,
+            media: serde_json::from_str(row.try_get("media")?)?,
+        })
+    }
+
+    /// Rebuild the reverse index entries of the links held by a thought.
+    async fn index_links(connection: &mut SqliteConnection, thought: &Thought) -> Result<()> {
+        sqlx::query("delete from thought_backlink where source_id = $1")
+            .bind(thought.thought_id)
+            .execute(&mut *connection)
+            .await?;
+        for link in &thought.links {
+            sqlx::query(
+                "insert into thought_backlink (target_id, source_id, kind) values ($1, $2, $3) \
+                 on conflict do nothing",
+            )
+            .bind(link.target_id)
+            .bind(thought.thought_id)
+            .bind(link.kind.as_str())
+            .execute(&mut *connection)
+            .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Check the parent of a new thought exists.
+    async fn check_parent(&self, parent_id: Option<ThoughtIdentifier>) -> Result<()> {
+        if let Some(parent_id) = parent_id {
+            if self.get(parent_id).await?.is_none() {
+                return Err(anyhow::anyhow!("Parent thought does not exist"));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Insert a new thought and index its links.
+    async fn insert(
+        connection: &mut SqliteConnection,
+        command: CreateThoughtCommand,
+        project_id: Uuid,
+    ) -> Result<Thought> {
+        let row = sqlx::query(
+            "insert into thought (thought_id, parent_id, imported_at, stylo_id, project_id, \
+             content, variation, answers, tags, category, links, reference_ids, media) \
+             values ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13) returning *",
+        )
+        .bind(Uuid::new_v4())
+        .bind(command.parent_id)
+        .bind(command.imported_at)
+        .bind(command.stylo_id)
+        .bind(project_id)
+        .bind(command.content)
+        .bind(command.variation.as_str())
+        .bind(command.answers)
+        .bind(serde_json::to_string(&command.tags)?)
+        .bind(command.category.as_ref().map(|c| c.as_str()))
+        .bind(serde_json::to_string(&command.links)?)
+        .bind(serde_json::to_string(&command.references)?)
+        .bind(serde_json::to_string(&command.media)?)
+        .fetch_one(&mut *connection)
+        .await?;
+        let thought = Self::hydrate(&row)?;
+        Self::index_links(connection, &thought).await?;
+
+        Ok(thought)
+    }
+}
+
+#[async_trait]
+impl ThoughtBook for SqliteThoughtBook {
+    async fn add(
+        &self,
+        command: CreateThoughtCommand,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Thought> {
+        self.check_parent(command.parent_id).await?;
+
+        let mut transaction = self.pool.begin().await?;
+        let thought = Self::insert(&mut transaction, command, project_id).await?;
+        enqueue(
+            &mut transaction,
+            &[ModelEvent::thought(&thought, ThoughtChangeKind::Created).with_chain(chain)],
+        )
+        .await?;
+        transaction.commit().await?;
+
+        Ok(thought)
+    }
+
+    async fn add_answer(
+        &self,
+        command: CreateThoughtCommand,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Thought> {
+        let question_id = command
+            .answers
+            .ok_or_else(|| anyhow::anyhow!("The thought does not answer a question"))?;
+        self.check_parent(command.parent_id).await?;
+        if self.get(question_id).await?.is_none() {
+            return Err(anyhow::anyhow!("Question thought does not exist"));
+        }
+
+        let mut transaction = self.pool.begin().await?;
+        let thought = Self::insert(&mut transaction, command, project_id).await?;
+        let mut events = vec![
+            ModelEvent::thought(&thought, ThoughtChangeKind::Created).with_chain(chain.clone())
+        ];
+        let question = sqlx::query(
+            "update thought set answered = true where thought_id = $1 and not answered \
+             returning *",
+        )
+        .bind(question_id)
+        .fetch_optional(&mut *transaction)
+        .await?;
+        if let Some(row) = &question {
+            let question = Self::hydrate(row)?;
+            events.push(
+                ModelEvent::thought(&question, ThoughtChangeKind::Modified)
+                    .with_chain(chain.clone()),
+            );
+        }
+        enqueue(&mut transaction, &events).await?;
+        transaction.commit().await?;
+
+        Ok(thought)
+    }
+
+    async fn get(&self, thought_id: ThoughtIdentifier) -> Result<Option<Thought>> {
+        sqlx::query("select * from thought where thought_id = $1")
+            .bind(thought_id)
+            .fetch_optional(&self.pool)
+            .await?
+            .as_ref()
+            .map(Self::hydrate)
+            .transpose()
+    }
+
+    async fn sync(
+        &self,
+        thought: Thought,
+        change_kind: ThoughtChangeKind,
+        chain: ResponsibilityChain,
+    ) -> Result<Thought> {
+        let mut transaction = self.pool.begin().await?;
+        let row = sqlx::query(
+            "update thought set parent_id = $2, imported_at = $3, stylo_id = $4, \
+             project_id = $5, content = $6, variation = $7, answers = $8, answered = $9, \
+             refuted_by = $10, status = $11, tags = $12, \
+             category = $13, links = $14, reference_ids = $15, \
+             media = $16 where thought_id = $1 returning *",
+        )
+        .bind(thought.thought_id)
+        .bind(thought.parent_id)
+        .bind(thought.imported_at)
+        .bind(thought.stylo_id)
+        .bind(thought.project_id)
+        .bind(thought.content)
+        .bind(thought.variation.as_str())
+        .bind(thought.answers)
+        .bind(thought.answered)
+        .bind(thought.refuted_by)
+        .bind(thought.status.as_str())
+        .bind(serde_json::to_string(&thought.tags)?)
+        .bind(thought.category.as_ref().map(|c| c.as_str()))
+        .bind(serde_json::to_string(&thought.links)?)
+        .bind(serde_json::to_string(&thought.references)?)
+        .bind(serde_json::to_string(&thought.media)?)
+        .fetch_optional(&mut *transaction)
+        .await?
+        .ok_or_else(|| anyhow::anyhow!("Thought does not exist: UUID='{}'.", thought.thought_id))?;
+        let thought = Self::hydrate(&row)?;
+        Self::index_links(&mut transaction, &thought).await?;
+        enqueue(
+            &mut transaction,
+            &[ModelEvent::thought(&thought, change_kind).with_chain(chain)],
+        )
+        .await?;
+        transaction.commit().await?;
+
+        Ok(thought)
+    }
+
+    async fn list_children(&self, parent_id: ThoughtIdentifier) -> Result<Vec<Thought>> {
+        sqlx::query("select * from thought where parent_id = $1 order by imported_at")
+            .bind(parent_id)
+            .fetch_all(&self.pool)
+            .await?
+            .iter()
+            .map(Self::hydrate)
+            .collect()
+    }
+
+    async fn list_by_project(
+        &self,
+        project_id: Uuid,
+        filter: &ThoughtFilter,
+    ) -> Result<Vec<Thought>> {
+        sqlx::query(
+            "select * from thought where project_id = $1 \
+             and ($2 is null or variation = $2) \
+             and ($3 is null or answered = $3) \
+             and ($4 is null or answers = $4) \
+             and ($5 is null or status = $5) \
+             and ($6 is null or exists (select 1 from json_each(tags) where value = $6)) \
+             and ($7 is null or category = $7 or substr(category, 1, length($7) + 1) = $7 || '.') \
+             order by imported_at",
+        )
+        .bind(project_id)
+        .bind(filter.variation.map(|v| v.as_str()))
+        .bind(filter.answered)
+        .bind(filter.answers)
+        .bind(filter.status.map(|s| s.as_str()))
+        .bind(filter.tag.as_deref())
+        .bind(filter.category.as_ref().map(|c| c.as_str()))
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(Self::hydrate)
+        .collect()
+    }
+
+    async fn list_tags(&self, project_id: Uuid) -> Result<Vec<TagCount>> {
+        sqlx::query(
+            "select tag.value as tag, count(*) as count from thought, json_each(thought.tags) as tag \
+             where project_id = $1 group by tag.value order by tag.value",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(TagCount {
+                tag: row.try_get("tag")?,
+                count: row.try_get::<i64, _>("count")?.try_into()?,
+            })
+        })
+        .collect()
+    }
+
+    async fn list_categories(&self, project_id: Uuid) -> Result<Vec<CategoryCount>> {
+        sqlx::query(
+            "select category as category, count(*) as count from thought \
+             where project_id = $1 and category is not null group by category order by category",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(CategoryCount {
+                category: row.try_get::<String, _>("category")?.parse()?,
+                count: row.try_get::<i64, _>("count")?.try_into()?,
+            })
+        })
+        .collect()
+    }
+
+    async fn retag(
+        &self,
+        project_id: Uuid,
+        from: &[String],
+        to: &str,
+        chain: ResponsibilityChain,
+    ) -> Result<Vec<Thought>> {
+        let mut transaction = self.pool.begin().await?;
+        let rows = sqlx::query(
+            "select * from thought where project_id = $1 \
+             and exists (select 1 from json_each(tags) where value in (select value from json_each($2))) \
+             order by imported_at",
+        )
+        .bind(project_id)
+        .bind(serde_json::to_string(from)?)
+        .fetch_all(&mut *transaction)
+        .await?;
+
+        let mut modified = Vec::with_capacity(rows.len());
+        for row in &rows {
+            let mut thought = Self::hydrate(row)?;
+            let Some(tags) = replace_tags(&thought.tags, from, to) else {
+                continue;
+            };
+            sqlx::query("update thought set tags = $2 where thought_id = $1")
+                .bind(thought.thought_id)
+                .bind(serde_json::to_string(&tags)?)
+                .execute(&mut *transaction)
+                .await?;
+            thought.tags = tags;
+            modified.push(thought);
+        }
+        let events: Vec<ModelEvent> = modified
+            .iter()
+            .map(|t| ModelEvent::thought(t, ThoughtChangeKind::Modified).with_chain(chain.clone()))
+            .collect();
+        enqueue(&mut transaction, &events).await?;
+        transaction.commit().await?;
+
+        Ok(modified)
+    }
+
+    async fn list_by_short_id(&self, project_id: Uuid, short_id: &str) -> Result<Vec<Thought>> {
+        sqlx::query(
+            "select * from thought where project_id = $1 \
+             and substr(lower(hex(thought_id)), 1, length($2)) = $2 \
+             order by imported_at",
+        )
+        .bind(project_id)
+        .bind(short_id)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(Self::hydrate)
+        .collect()
+    }
+
+    async fn list_backlinks(&self, target_id: ThoughtIdentifier) -> Result<Vec<Thought>> {
+        sqlx::query(
+            "select * from thought where thought_id in \
+             (select source_id from thought_backlink where target_id = $1) \
+             order by imported_at",
+        )
+        .bind(target_id)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(Self::hydrate)
+        .collect()
+    }
+
+    async fn list_dangling_links(&self, project_id: Uuid) -> Result<Vec<DanglingLink>> {
+        sqlx::query(
+            "select b.source_id, b.target_id, b.kind from thought_backlink b \
+             join thought s on s.thought_id = b.source_id \
+             where s.project_id = $1 \
+             and not exists (select 1 from thought t where t.thought_id = b.target_id) \
+             order by s.imported_at, b.target_id",
+        )
+        .bind(project_id)
+        .fetch_all(&self.pool)
+        .await?
+        .iter()
+        .map(|row| {
+            Ok(DanglingLink {
+                thought_id: row.try_get("source_id")?,
+                link: ThoughtLink {
+                    target_id: row.try_get("target_id")?,
+                    kind: row.try_get::<String, _>("kind")?.parse()?,
+                },
+            })
+        })
+        .collect()
+    }
+
+    async fn trash_by_project(
+        &self,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Vec<Thought>> {
+        let mut transaction = self.pool.begin().await?;
+        let mut trashed = sqlx::query("delete from thought where project_id = $1 returning *")
+            .bind(project_id)
+            .fetch_all(&mut *transaction)
+            .await?
+            .iter()
+            .map(Self::hydrate)
+            .collect::<Result<Vec<Thought>>>()?;
+        trashed.sort_by_key(|t| t.imported_at);
+        let events: Vec<ModelEvent> = trashed
+            .iter()
+            .map(|t| ModelEvent::thought(t, ThoughtChangeKind::Trashed).with_chain(chain.clone()))
+            .collect();
+        enqueue(&mut transaction, &events).await?;
+        transaction.commit().await?;
+
+        Ok(trashed)
+    }
+
+    async fn is_media_referenced(&self, digest: &str) -> Result<bool> {
+        let row = sqlx::query(
+            "select exists (select 1 from thought, json_each(thought.media) \
+             where json_extract(json_each.value, '$.digest') = $1) as found",
+        )
+        .bind(digest)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(row.try_get("found")?)
+    }
+}
@@ -0,0 +1,129 @@
+use crate::adapter::InMemoryOutbox;
+use crate::models::{
+    CreateStyloCommand, ModelEvent, OrganizationIdentifier, Stylo, StyloChangeKind, StyloIdentifier,
+};
+use crate::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// StyloBook is a trait that defines the methods that are required to
+/// interact with a stylo database.
+/// Every change of a stylo writes its event in the outbox in the same unit of
+/// work.
+#[async_trait]
+pub trait StyloBook: Sync + Send {
+    /// Grants a new stylo.
+    async fn add(&self, command: CreateStyloCommand) -> Result<Stylo>;
+
+    /// Gets a stylo from the stylo database.
+    /// If the stylo does not exist, None is returned.
+    async fn get(&self, stylo_id: StyloIdentifier) -> Result<Option<Stylo>>;
+
+    /// Syncs a stylo in the stylo database, the change kind tells how it has
+    /// changed.
+    /// The identifier and the organizations cannot be updated.
+    /// If the stylo does not exist, an error is returned.
+    async fn sync(&self, stylo: Stylo, change_kind: StyloChangeKind) -> Result<Stylo>;
+
+    /// Revokes a stylo, it is removed from the stylo database.
+    /// If the stylo does not exist, None is returned.
+    async fn delete(&self, stylo_id: StyloIdentifier) -> Result<Option<Stylo>>;
+
+    /// Lists the stylos owned by an organization.
+    /// Stylos are sorted by their creation date, oldest first.
+    async fn list_by_owner(&self, organization_id: OrganizationIdentifier) -> Result<Vec<Stylo>>;
+}
+
+/// InMemoryStyloBook is an in-memory implementation of the StyloBook trait.
+/// Mostly used for testing purposes.
+#[derive(Default)]
+pub struct InMemoryStyloBook {
+    stylos: Arc<RwLock<HashMap<StyloIdentifier, Stylo>>>,
+    outbox: InMemoryOutbox,
+}
+
+impl InMemoryStyloBook {
+    /// Create a stylo book writing its events in the given outbox.
+    pub fn with_outbox(outbox: InMemoryOutbox) -> Self {
+        Self {
+            stylos: Default::default(),
+            outbox,
+        }
+    }
+}
+
+#[async_trait]
+impl StyloBook for InMemoryStyloBook {
+    async fn add(&self, command: CreateStyloCommand) -> Result<Stylo> {
+        let stylo = Stylo::create(command)?;
+        let mut stylos = self.stylos.write().await;
+        let mut outbox = self.outbox.write().await;
+        stylos.insert(stylo.stylo_id, stylo.clone());
+        outbox.push(ModelEvent::stylo(&stylo, StyloChangeKind::Granted));
+
+        Ok(stylo)
+    }
+
+    async fn get(&self, stylo_id: StyloIdentifier) -> Result<Option<Stylo>> {
+        Ok(self.stylos.read().await.get(&stylo_id).cloned())
+    }
+
+    async fn sync(&self, stylo: Stylo, change_kind: StyloChangeKind) -> Result<Stylo> {
+        let mut stylos = self.stylos.write().await;
+        let stored = stylos
+            .get_mut(&stylo.stylo_id)
+            .ok_or_else(|| anyhow::anyhow!("Stylo does not exist: UUID='{}'.", stylo.stylo_id))?;
+        let mut outbox = self.outbox.write().await;
+        let stylo = Stylo {
+            stylo_id: stored.stylo_id,
+            owner_organization_id: stored.owner_organization_id,
+            actor_organization_id: stored.actor_organization_id,
+            created_at: stored.created_at,
+            ..stylo
+        };
+        *stored = stylo.clone();
+        outbox.push(ModelEvent::stylo(&stylo, change_kind));
+
+        Ok(stylo)
+    }
+
+    async fn delete(&self, stylo_id: StyloIdentifier) -> Result<Option<Stylo>> {
+        let mut stylos = self.stylos.write().await;
+        let mut outbox = self.outbox.write().await;
+        let stylo = stylos.remove(&stylo_id);
+        if let Some(stylo) = &stylo {
+            outbox.push(ModelEvent::stylo(stylo, StyloChangeKind::Revoked));
+        }
+
+        Ok(stylo)
+    }
+
+    async fn list_by_owner(&self, organization_id: OrganizationIdentifier) -> Result<Vec<Stylo>> {
+        let mut stylos: Vec<Stylo> = self
+            .stylos
+            .read()
+            .await
+            .values()
+            .filter(|s| s.owner_organization_id == organization_id)
+            .cloned()
+            .collect();
+        stylos.sort_by_key(|s| s.created_at);
+
+        Ok(stylos)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let outbox = InMemoryOutbox::default();
+
+        crate::testkit::check_stylo_book(&InMemoryStyloBook::with_outbox(outbox.clone()), &outbox)
+            .await;
+    }
+}
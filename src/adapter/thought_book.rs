@@ -1,55 +1,286 @@
-use crate::models::{CreateThoughtCommand, Thought, ThoughtIdentifier};
+use crate::adapter::InMemoryOutbox;
+use crate::models::{
+    Category, CategoryCount, CreateThoughtCommand, DanglingLink, ModelEvent, ResponsibilityChain,
+    TagCount, Thought, ThoughtChangeKind, ThoughtFilter, ThoughtIdentifier, ThoughtLink,
+    ThoughtStatus,
+};
 use crate::Result;
 use async_trait::async_trait;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 use uuid::Uuid;
 
 /// ThoughtBook is a trait that defines the methods that are required to interact
 /// with a thought database.
+/// Adding, syncing, retagging and trashing thoughts write the events of the
+/// changed thoughts in the outbox in the same unit of work.
 #[async_trait]
 pub trait ThoughtBook: Sync + Send {
     /// Adds a new thought to the thought database.
-    async fn add(&self, command: CreateThoughtCommand, project_id: Uuid) -> Result<Thought>;
+    /// The Created event carries the given responsibility chain.
+    async fn add(
+        &self,
+        command: CreateThoughtCommand,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Thought>;
+
+    /// Adds a new thought answering a question and marks the question as
+    /// answered, in the same unit of work.
+    /// The Created event of the answer is followed by the Modified event of the
+    /// question if the question was not answered yet, both carry the given
+    /// responsibility chain. An error is raised if the command answers no
+    /// question or if the question does not exist.
+    async fn add_answer(
+        &self,
+        command: CreateThoughtCommand,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Thought>;
 
     /// Gets a thought from the thought database.
     /// If the thought does not exist, None is returned.
     /// If the query could not be performed, an Error is raised.
     async fn get(&self, thought_id: ThoughtIdentifier) -> Result<Option<Thought>>;
 
-    /// Syncs a thought in the thought database.
+    /// Syncs a thought in the thought database, the change kind tells how it
+    /// has changed and the event carries the given responsibility chain.
     /// The identifier cannot be updated.
     /// If the thought does not exist, an error is returned.
-    async fn sync(&self, thought: Thought) -> Result<Thought>;
+    async fn sync(
+        &self,
+        thought: Thought,
+        change_kind: ThoughtChangeKind,
+        chain: ResponsibilityChain,
+    ) -> Result<Thought>;
+
+    /// Lists the direct children of a thought.
+    /// Children are sorted by their import date, oldest first.
+    async fn list_children(&self, parent_id: ThoughtIdentifier) -> Result<Vec<Thought>>;
+
+    /// Lists the thoughts of a project matching the given filter.
+    /// Thoughts are sorted by their import date, oldest first.
+    async fn list_by_project(
+        &self,
+        project_id: Uuid,
+        filter: &ThoughtFilter,
+    ) -> Result<Vec<Thought>>;
+
+    /// Lists the tags used by the thoughts of a project with their usage counts.
+    /// Tags are sorted alphabetically.
+    async fn list_tags(&self, project_id: Uuid) -> Result<Vec<TagCount>>;
+
+    /// Lists the categories used by the thoughts of a project with the number
+    /// of thoughts filed exactly in each of them.
+    /// Categories are sorted alphabetically.
+    async fn list_categories(&self, project_id: Uuid) -> Result<Vec<CategoryCount>>;
+
+    /// Replaces the given tags by the target tag on all the thoughts of a
+    /// project, in one operation.
+    /// Thoughts keep their tags sorted and free of duplicates, their events
+    /// carry the given responsibility chain.
+    /// The modified thoughts are returned, sorted by their import date.
+    async fn retag(
+        &self,
+        project_id: Uuid,
+        from: &[String],
+        to: &str,
+        chain: ResponsibilityChain,
+    ) -> Result<Vec<Thought>>;
+
+    /// Lists the thoughts of a project whose identifier starts with the given
+    /// short identifier, made of lowercase hexadecimal digits without hyphens.
+    /// Thoughts are sorted by their import date, oldest first.
+    async fn list_by_short_id(&self, project_id: Uuid, short_id: &str) -> Result<Vec<Thought>>;
+
+    /// Lists the thoughts linking to the given thought, whatever their project.
+    /// Thoughts are sorted by their import date, oldest first.
+    async fn list_backlinks(&self, target_id: ThoughtIdentifier) -> Result<Vec<Thought>>;
+
+    /// Lists the links of the thoughts of a project whose target does not exist
+    /// anymore.
+    /// Links are sorted by the import date of the thought holding them.
+    async fn list_dangling_links(&self, project_id: Uuid) -> Result<Vec<DanglingLink>>;
+
+    /// Trashes all the thoughts of a project, each one is told as trashed with
+    /// the given responsibility chain.
+    /// Links held by thoughts of other projects to the trashed thoughts are
+    /// kept and become dangling.
+    /// The trashed thoughts are returned, sorted by their import date.
+    async fn trash_by_project(
+        &self,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Vec<Thought>>;
+
+    /// Tells if a thought is accompanied by the media with the given digest.
+    async fn is_media_referenced(&self, digest: &str) -> Result<bool>;
+
+    /// Lists the words of the thoughts whose trigram similarity with the given
+    /// word is at least the threshold, with their similarity.
+    /// None is returned when the book has no trigram index, the words are
+    /// then matched by the search index of the application.
+    async fn similar_words(
+        &self,
+        _word: &str,
+        _threshold: f64,
+    ) -> Result<Option<Vec<(String, f64)>>> {
+        Ok(None)
+    }
+}
+
+/// Replace the given tags by the target tag in a list of tags.
+/// Returns None when the tags are left unchanged.
+pub(crate) fn replace_tags(tags: &[String], from: &[String], to: &str) -> Option<Vec<String>> {
+    let mut replaced: Vec<String> = tags
+        .iter()
+        .map(|t| {
+            if from.contains(t) {
+                to.to_string()
+            } else {
+                t.clone()
+            }
+        })
+        .collect();
+    replaced.sort();
+    replaced.dedup();
+
+    (replaced != tags).then_some(replaced)
 }
 
 /// InMemoryThoughtBook is an in-memory implementation of the ThoughtBook trait.
 /// Mostly used for testing purposes.
+/// The thoughts linking to a thought are kept in a reverse index. The thoughts
+/// lock must always be taken before the backlinks lock, and the outbox lock
+/// last.
 #[derive(Default)]
 pub struct InMemoryThoughtBook {
     thoughts: Arc<RwLock<HashMap<Uuid, Thought>>>,
+    backlinks: Arc<RwLock<HashMap<ThoughtIdentifier, HashSet<ThoughtIdentifier>>>>,
+    outbox: InMemoryOutbox,
 }
 
-#[async_trait]
-impl ThoughtBook for InMemoryThoughtBook {
-    async fn add(&self, command: CreateThoughtCommand, project_id: Uuid) -> Result<Thought> {
-        if let Some(parent_id) = command.parent_id {
+impl InMemoryThoughtBook {
+    /// Create a thought book writing its events in the given outbox.
+    pub fn with_outbox(outbox: InMemoryOutbox) -> Self {
+        Self {
+            thoughts: Default::default(),
+            backlinks: Default::default(),
+            outbox,
+        }
+    }
+
+    /// Update the reverse index when the links of a thought change.
+    fn index_links(
+        backlinks: &mut HashMap<ThoughtIdentifier, HashSet<ThoughtIdentifier>>,
+        source_id: ThoughtIdentifier,
+        old_links: &[ThoughtLink],
+        new_links: &[ThoughtLink],
+    ) {
+        for link in old_links {
+            if let Some(sources) = backlinks.get_mut(&link.target_id) {
+                sources.remove(&source_id);
+                if sources.is_empty() {
+                    backlinks.remove(&link.target_id);
+                }
+            }
+        }
+        for link in new_links {
+            backlinks
+                .entry(link.target_id)
+                .or_default()
+                .insert(source_id);
+        }
+    }
+
+    /// Check the parent of a new thought exists.
+    async fn check_parent(&self, parent_id: Option<ThoughtIdentifier>) -> Result<()> {
+        if let Some(parent_id) = parent_id {
             if !self.thoughts.read().await.contains_key(&parent_id) {
                 return Err(anyhow::anyhow!("Parent thought does not exist"));
             }
         }
 
-        let thought = Thought {
+        Ok(())
+    }
+
+    /// Build a new thought from its command.
+    fn create(command: CreateThoughtCommand, project_id: Uuid) -> Thought {
+        Thought {
             thought_id: Uuid::new_v4(),
             parent_id: command.parent_id,
             imported_at: command.imported_at,
             stylo_id: command.stylo_id,
             project_id,
             content: command.content,
-        };
+            variation: command.variation,
+            answers: command.answers,
+            answered: false,
+            refuted_by: None,
+            status: ThoughtStatus::Standing,
+            tags: command.tags,
+            category: command.category,
+            links: command.links,
+            references: command.references,
+            media: command.media,
+        }
+    }
+}
+
+#[async_trait]
+impl ThoughtBook for InMemoryThoughtBook {
+    async fn add(
+        &self,
+        command: CreateThoughtCommand,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Thought> {
+        self.check_parent(command.parent_id).await?;
+
+        let thought = Self::create(command, project_id);
         let mut thoughts = self.thoughts.write().await;
+        let mut backlinks = self.backlinks.write().await;
+        let mut outbox = self.outbox.write().await;
         thoughts.insert(thought.thought_id, thought.clone());
+        Self::index_links(&mut backlinks, thought.thought_id, &[], &thought.links);
+        outbox.push(ModelEvent::thought(&thought, ThoughtChangeKind::Created).with_chain(chain));
+
+        Ok(thought)
+    }
+
+    async fn add_answer(
+        &self,
+        command: CreateThoughtCommand,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Thought> {
+        let question_id = command
+            .answers
+            .ok_or_else(|| anyhow::anyhow!("The thought does not answer a question"))?;
+        self.check_parent(command.parent_id).await?;
+
+        let thought = Self::create(command, project_id);
+        let mut thoughts = self.thoughts.write().await;
+        let mut backlinks = self.backlinks.write().await;
+        let mut outbox = self.outbox.write().await;
+        let question = thoughts
+            .get_mut(&question_id)
+            .ok_or_else(|| anyhow::anyhow!("Question thought does not exist"))?;
+        let answered = !question.answered;
+        question.answered = true;
+        let question = question.clone();
+        thoughts.insert(thought.thought_id, thought.clone());
+        Self::index_links(&mut backlinks, thought.thought_id, &[], &thought.links);
+        outbox.push(
+            ModelEvent::thought(&thought, ThoughtChangeKind::Created).with_chain(chain.clone()),
+        );
+        if answered {
+            outbox.push(
+                ModelEvent::thought(&question, ThoughtChangeKind::Modified)
+                    .with_chain(chain.clone()),
+            );
+        }
 
         Ok(thought)
     }
@@ -58,17 +289,219 @@ impl ThoughtBook for InMemoryThoughtBook {
         Ok(self.thoughts.read().await.get(&thought_id).cloned())
     }
 
-    async fn sync(&self, thought: Thought) -> Result<Thought> {
+    async fn sync(
+        &self,
+        thought: Thought,
+        change_kind: ThoughtChangeKind,
+        chain: ResponsibilityChain,
+    ) -> Result<Thought> {
         let mut thoughts = self.thoughts.write().await;
-        thoughts.insert(thought.thought_id, thought.clone());
+        let stored = thoughts.get_mut(&thought.thought_id).ok_or_else(|| {
+            anyhow::anyhow!("Thought does not exist: UUID='{}'.", thought.thought_id)
+        })?;
+        let mut backlinks = self.backlinks.write().await;
+        let mut outbox = self.outbox.write().await;
+        Self::index_links(
+            &mut backlinks,
+            thought.thought_id,
+            &stored.links,
+            &thought.links,
+        );
+        *stored = thought.clone();
+        outbox.push(ModelEvent::thought(&thought, change_kind).with_chain(chain));
 
         Ok(thought)
     }
+
+    async fn list_children(&self, parent_id: ThoughtIdentifier) -> Result<Vec<Thought>> {
+        let mut children: Vec<Thought> = self
+            .thoughts
+            .read()
+            .await
+            .values()
+            .filter(|t| t.parent_id == Some(parent_id))
+            .cloned()
+            .collect();
+        children.sort_by_key(|t| t.imported_at);
+
+        Ok(children)
+    }
+
+    async fn list_by_project(
+        &self,
+        project_id: Uuid,
+        filter: &ThoughtFilter,
+    ) -> Result<Vec<Thought>> {
+        let mut thoughts: Vec<Thought> = self
+            .thoughts
+            .read()
+            .await
+            .values()
+            .filter(|t| t.project_id == project_id && filter.matches(t))
+            .cloned()
+            .collect();
+        thoughts.sort_by_key(|t| t.imported_at);
+
+        Ok(thoughts)
+    }
+
+    async fn list_tags(&self, project_id: Uuid) -> Result<Vec<TagCount>> {
+        let mut counts: BTreeMap<String, u64> = BTreeMap::new();
+        for thought in self.thoughts.read().await.values() {
+            if thought.project_id == project_id {
+                for tag in &thought.tags {
+                    *counts.entry(tag.clone()).or_default() += 1;
+                }
+            }
+        }
+
+        Ok(counts
+            .into_iter()
+            .map(|(tag, count)| TagCount { tag, count })
+            .collect())
+    }
+
+    async fn list_categories(&self, project_id: Uuid) -> Result<Vec<CategoryCount>> {
+        let mut counts: BTreeMap<Category, u64> = BTreeMap::new();
+        for thought in self.thoughts.read().await.values() {
+            if thought.project_id == project_id {
+                if let Some(category) = &thought.category {
+                    *counts.entry(category.clone()).or_default() += 1;
+                }
+            }
+        }
+
+        Ok(counts
+            .into_iter()
+            .map(|(category, count)| CategoryCount { category, count })
+            .collect())
+    }
+
+    async fn retag(
+        &self,
+        project_id: Uuid,
+        from: &[String],
+        to: &str,
+        chain: ResponsibilityChain,
+    ) -> Result<Vec<Thought>> {
+        let mut modified = Vec::new();
+        let mut thoughts = self.thoughts.write().await;
+        let mut outbox = self.outbox.write().await;
+        for thought in thoughts.values_mut() {
+            if thought.project_id != project_id {
+                continue;
+            }
+            if let Some(tags) = replace_tags(&thought.tags, from, to) {
+                thought.tags = tags;
+                modified.push(thought.clone());
+            }
+        }
+        modified.sort_by_key(|t| t.imported_at);
+        outbox.extend(modified.iter().map(|t| {
+            ModelEvent::thought(t, ThoughtChangeKind::Modified).with_chain(chain.clone())
+        }));
+
+        Ok(modified)
+    }
+
+    async fn list_by_short_id(&self, project_id: Uuid, short_id: &str) -> Result<Vec<Thought>> {
+        let mut thoughts: Vec<Thought> = self
+            .thoughts
+            .read()
+            .await
+            .values()
+            .filter(|t| {
+                t.project_id == project_id
+                    && t.thought_id.simple().to_string().starts_with(short_id)
+            })
+            .cloned()
+            .collect();
+        thoughts.sort_by_key(|t| t.imported_at);
+
+        Ok(thoughts)
+    }
+
+    async fn list_backlinks(&self, target_id: ThoughtIdentifier) -> Result<Vec<Thought>> {
+        let thoughts = self.thoughts.read().await;
+        let backlinks = self.backlinks.read().await;
+        let mut sources: Vec<Thought> = backlinks
+            .get(&target_id)
+            .into_iter()
+            .flatten()
+            .filter_map(|source_id| thoughts.get(source_id))
+            .cloned()
+            .collect();
+        sources.sort_by_key(|t| t.imported_at);
+
+        Ok(sources)
+    }
+
+    async fn list_dangling_links(&self, project_id: Uuid) -> Result<Vec<DanglingLink>> {
+        let thoughts = self.thoughts.read().await;
+        let mut sources: Vec<&Thought> = thoughts
+            .values()
+            .filter(|t| t.project_id == project_id)
+            .collect();
+        sources.sort_by_key(|t| t.imported_at);
+
+        Ok(sources
+            .into_iter()
+            .flat_map(|t| {
+                t.links
+                    .iter()
+                    .filter(|link| !thoughts.contains_key(&link.target_id))
+                    .map(|link| DanglingLink {
+                        thought_id: t.thought_id,
+                        link: *link,
+                    })
+            })
+            .collect())
+    }
+
+    async fn trash_by_project(
+        &self,
+        project_id: Uuid,
+        chain: ResponsibilityChain,
+    ) -> Result<Vec<Thought>> {
+        let mut thoughts = self.thoughts.write().await;
+        let mut backlinks = self.backlinks.write().await;
+        let mut outbox = self.outbox.write().await;
+        let trashed_ids: Vec<Uuid> = thoughts
+            .values()
+            .filter(|t| t.project_id == project_id)
+            .map(|t| t.thought_id)
+            .collect();
+        let mut trashed = Vec::with_capacity(trashed_ids.len());
+        for thought_id in trashed_ids {
+            if let Some(thought) = thoughts.remove(&thought_id) {
+                Self::index_links(&mut backlinks, thought_id, &thought.links, &[]);
+                trashed.push(thought);
+            }
+        }
+        trashed.sort_by_key(|t| t.imported_at);
+        outbox.extend(
+            trashed.iter().map(|t| {
+                ModelEvent::thought(t, ThoughtChangeKind::Trashed).with_chain(chain.clone())
+            }),
+        );
+
+        Ok(trashed)
+    }
+
+    async fn is_media_referenced(&self, digest: &str) -> Result<bool> {
+        Ok(self
+            .thoughts
+            .read()
+            .await
+            .values()
+            .any(|t| t.media.iter().any(|m| m.digest == digest)))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::models::ThoughtVariation;
     use chrono::Utc;
 
     fn create_test_thought_command() -> CreateThoughtCommand {
@@ -78,6 +511,13 @@ mod tests {
             stylo_id: Uuid::new_v4(),
             project_slug: "test-project".to_string(),
             content: "This is a test thought.".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
         }
     }
 
@@ -91,6 +531,16 @@ mod tests {
             stylo_id: Uuid::new_v4(),
             project_id: Uuid::new_v4(),
             content: "This is a test thought.".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            answered: false,
+            refuted_by: None,
+            status: ThoughtStatus::Standing,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
         }
     }
 
@@ -99,7 +549,10 @@ mod tests {
         let thought_book = InMemoryThoughtBook::default();
         let command = create_test_thought_command();
         let project_id = Uuid::new_v4();
-        let thought = thought_book.add(command, project_id).await.unwrap();
+        let thought = thought_book
+            .add(command, project_id, Default::default())
+            .await
+            .unwrap();
 
         assert_eq!(thought.content, "This is a test thought.");
     }
@@ -133,7 +586,14 @@ mod tests {
             .await
             .insert(thought_id, thought.clone());
         thought.content = "Updated Test Thought".to_string();
-        let updated_thought = thought_book.sync(thought.clone()).await.unwrap();
+        let updated_thought = thought_book
+            .sync(
+                thought.clone(),
+                ThoughtChangeKind::Modified,
+                Default::default(),
+            )
+            .await
+            .unwrap();
 
         assert_eq!(updated_thought.content, "Updated Test Thought");
         assert_eq!(
@@ -164,9 +624,19 @@ mod tests {
             stylo_id: Uuid::new_v4(),
             project_slug: "test-project".to_string(),
             content: "This is a child thought.".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
         };
         let project_id = Uuid::new_v4();
-        let thought = thought_book.add(command, project_id).await.unwrap();
+        let thought = thought_book
+            .add(command, project_id, Default::default())
+            .await
+            .unwrap();
 
         assert_eq!(thought.content, "This is a child thought.");
         assert_eq!(thought.parent_id, Some(parent_thought.thought_id));
@@ -183,10 +653,59 @@ mod tests {
             stylo_id: Uuid::new_v4(),
             project_slug: "test-project".to_string(),
             content: "This is a child thought.".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
         };
         let project_id = Uuid::new_v4();
-        let result = thought_book.add(command, project_id).await;
+        let result = thought_book
+            .add(command, project_id, Default::default())
+            .await;
 
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn test_list_children() {
+        let thought_book = InMemoryThoughtBook::default();
+        let project_id = Uuid::new_v4();
+        let parent = thought_book
+            .add(
+                create_test_thought_command(),
+                project_id,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        let mut command = create_test_thought_command();
+        command.parent_id = Some(parent.thought_id);
+        let child = thought_book
+            .add(command, project_id, Default::default())
+            .await
+            .unwrap();
+        let _ = thought_book
+            .add(
+                create_test_thought_command(),
+                project_id,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        let children = thought_book.list_children(parent.thought_id).await.unwrap();
+
+        assert_eq!(children.len(), 1);
+        assert_eq!(children[0].thought_id, child.thought_id);
+    }
+
+    #[tokio::test]
+    async fn test_conformance() {
+        let thought_book = InMemoryThoughtBook::default();
+        let project_book = crate::adapter::InMemoryProjectBook::default();
+
+        crate::testkit::check_thought_book(&thought_book, &project_book).await;
+    }
 }
@@ -0,0 +1,62 @@
+use crate::models::{OrganizationIdentifier, Universe, UniverseIdentifier};
+use crate::Result;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+/// UniverseBook is a trait that defines the methods that are required to read
+/// the universes.
+/// Universes are created with their organization by the
+/// [`crate::adapter::OrganizationBook`], in the same unit of work.
+#[async_trait]
+pub trait UniverseBook: Sync + Send {
+    /// Gets a universe by its identifier.
+    /// If the universe does not exist, None is returned.
+    async fn get(&self, universe_id: UniverseIdentifier) -> Result<Option<Universe>>;
+
+    /// Gets the universe of an organization.
+    /// If the organization does not exist, None is returned.
+    async fn get_by_organization(
+        &self,
+        organization_id: OrganizationIdentifier,
+    ) -> Result<Option<Universe>>;
+}
+
+/// InMemoryUniverseBook is an in-memory implementation of the UniverseBook
+/// trait.
+/// Clones share the same universes, the in-memory organization book writes in
+/// a clone of the universe book while holding the lock on the organizations.
+#[derive(Default, Clone)]
+pub struct InMemoryUniverseBook {
+    universes: Arc<RwLock<HashMap<UniverseIdentifier, Universe>>>,
+}
+
+impl InMemoryUniverseBook {
+    /// Get write access to the universes.
+    pub(crate) async fn write(
+        &self,
+    ) -> tokio::sync::RwLockWriteGuard<'_, HashMap<UniverseIdentifier, Universe>> {
+        self.universes.write().await
+    }
+}
+
+#[async_trait]
+impl UniverseBook for InMemoryUniverseBook {
+    async fn get(&self, universe_id: UniverseIdentifier) -> Result<Option<Universe>> {
+        Ok(self.universes.read().await.get(&universe_id).cloned())
+    }
+
+    async fn get_by_organization(
+        &self,
+        organization_id: OrganizationIdentifier,
+    ) -> Result<Option<Universe>> {
+        Ok(self
+            .universes
+            .read()
+            .await
+            .values()
+            .find(|u| u.organization_id == organization_id)
+            .cloned())
+    }
+}
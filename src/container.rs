@@ -16,12 +16,39 @@ pub type UnboundedEventMessageSender = UnboundedSender<EventMessage<ModelEvent>>
 pub struct Container {
     note_book: OnceCell<Arc<dyn crate::adapter::NoteBook>>,
     project_book: OnceCell<Arc<dyn crate::adapter::ProjectBook>>,
+    in_memory_universe_book: OnceCell<crate::adapter::InMemoryUniverseBook>,
+    universe_book: OnceCell<Arc<dyn crate::adapter::UniverseBook>>,
+    organization_book: OnceCell<Arc<dyn crate::adapter::OrganizationBook>>,
+    stylo_book: OnceCell<Arc<dyn crate::adapter::StyloBook>>,
     thought_book: OnceCell<Arc<dyn crate::adapter::ThoughtBook>>,
+    reference_book: OnceCell<Arc<dyn crate::adapter::ReferenceBook>>,
+    saved_search_book: OnceCell<Arc<dyn crate::adapter::SavedSearchBook>>,
+    media_store: OnceCell<Arc<dyn crate::adapter::MediaStore>>,
+    event_store: OnceCell<Arc<dyn crate::adapter::EventStore>>,
+    audit_log: OnceCell<Arc<dyn crate::adapter::AuditLog>>,
+    key_store: OnceCell<Arc<dyn crate::adapter::KeyStore>>,
+    in_memory_outbox: OnceCell<crate::adapter::InMemoryOutbox>,
+    outbox: OnceCell<Arc<dyn crate::adapter::Outbox>>,
+    similarity_thresholds: OnceCell<crate::models::SimilarityThresholds>,
+    event_replay_capacity: OnceCell<usize>,
+    outbox_relay: OnceCell<Arc<crate::service::OutboxRelay>>,
     thought_service: OnceCell<Arc<crate::service::ThoughtService>>,
+    organization_service: OnceCell<Arc<crate::service::OrganizationService>>,
+    stylo_service: OnceCell<Arc<crate::service::StyloService>>,
+    thought_search: OnceCell<Arc<crate::service::ThoughtSearchService>>,
+    saved_search_service: OnceCell<Arc<crate::service::SavedSearchService>>,
+    event_stream: OnceCell<Arc<crate::service::EventStreamService>>,
+    event_history: OnceCell<Arc<crate::service::EventHistoryService>>,
+    audit_log_service: OnceCell<Arc<crate::service::AuditLogService>>,
+    project_stats: OnceCell<Arc<crate::service::ProjectStatsService>>,
     event_publisher: OnceCell<(
         UnboundedSender<EventMessage<ModelEvent>>,
         UnboundedEventMessageReceiver,
     )>,
+    #[cfg(feature = "postgres")]
+    pg_pool: OnceCell<sqlx::PgPool>,
+    #[cfg(feature = "sqlite")]
+    sqlite_pool: OnceCell<sqlx::SqlitePool>,
 }
 
 impl Container {
@@ -29,6 +56,109 @@ impl Container {
     /// This allows to drop the different Arc instances stored in the container.
     pub fn destroy(self) {}
 
+    /// Set the PostgreSQL connection pool
+    /// When set, the books are stored in PostgreSQL instead of memory. It must
+    /// be set before any book is requested from the container.
+    #[cfg(feature = "postgres")]
+    pub fn set_pg_pool(&mut self, pool: sqlx::PgPool) -> Result<()> {
+        self.pg_pool
+            .set(pool)
+            .map_err(|_| anyhow::anyhow!("The PostgreSQL pool is already set."))
+    }
+
+    /// Set the SQLite connection pool
+    /// When set, the books are stored in SQLite instead of memory. It must be
+    /// set before any book is requested from the container.
+    #[cfg(feature = "sqlite")]
+    pub fn set_sqlite_pool(&mut self, pool: sqlx::SqlitePool) -> Result<()> {
+        self.sqlite_pool
+            .set(pool)
+            .map_err(|_| anyhow::anyhow!("The SQLite pool is already set."))
+    }
+
+    /// Set the media store
+    /// When not set, medias are kept in memory. It must be set before the
+    /// thought service is requested from the container.
+    pub fn set_media_store(
+        &mut self,
+        media_store: Arc<dyn crate::adapter::MediaStore>,
+    ) -> Result<()> {
+        self.media_store
+            .set(media_store)
+            .map_err(|_| anyhow::anyhow!("The media store is already set."))
+    }
+
+    /// Set the event store
+    /// When not set, the event history is kept in memory. It must be set before
+    /// the event history is requested from the container.
+    pub fn set_event_store(
+        &mut self,
+        event_store: Arc<dyn crate::adapter::EventStore>,
+    ) -> Result<()> {
+        self.event_store
+            .set(event_store)
+            .map_err(|_| anyhow::anyhow!("The event store is already set."))
+    }
+
+    /// Set the audit log
+    /// When not set, the audit log is kept in memory. It must be set before
+    /// the audit log service is requested from the container.
+    pub fn set_audit_log(&mut self, audit_log: Arc<dyn crate::adapter::AuditLog>) -> Result<()> {
+        self.audit_log
+            .set(audit_log)
+            .map_err(|_| anyhow::anyhow!("The audit log is already set."))
+    }
+
+    /// Set the key store
+    /// When not set, the keys signing the events are kept in memory. It must
+    /// be set before the outbox relay is requested from the container.
+    pub fn set_key_store(&mut self, key_store: Arc<dyn crate::adapter::KeyStore>) -> Result<()> {
+        self.key_store
+            .set(key_store)
+            .map_err(|_| anyhow::anyhow!("The key store is already set."))
+    }
+
+    /// Set the similarity thresholds
+    /// When not set, the default thresholds are used. It must be set before the
+    /// services are requested from the container. Thresholds must be between 0
+    /// and 1.
+    pub fn set_similarity_thresholds(
+        &mut self,
+        thresholds: crate::models::SimilarityThresholds,
+    ) -> Result<()> {
+        for threshold in [thresholds.word, thresholds.slug] {
+            if !(0.0..=1.0).contains(&threshold) {
+                return Err(anyhow::anyhow!(
+                    "The similarity threshold {threshold} is not between 0 and 1."
+                ));
+            }
+        }
+
+        self.similarity_thresholds
+            .set(thresholds)
+            .map_err(|_| anyhow::anyhow!("The similarity thresholds are already set."))
+    }
+
+    /// Get the similarity thresholds
+    pub fn similarity_thresholds(&mut self) -> Result<crate::models::SimilarityThresholds> {
+        Ok(*self.similarity_thresholds.get_or_init(Default::default))
+    }
+
+    /// Set the number of events kept to resume interrupted event streams
+    /// When not set, the last 1000 events are kept. It must be set before the
+    /// event stream is requested from the container.
+    pub fn set_event_replay_capacity(&mut self, capacity: usize) -> Result<()> {
+        if capacity == 0 {
+            return Err(anyhow::anyhow!(
+                "The event replay capacity must be at least 1."
+            ));
+        }
+
+        self.event_replay_capacity
+            .set(capacity)
+            .map_err(|_| anyhow::anyhow!("The event replay capacity is already set."))
+    }
+
     /// Get or iniitalize the channels for the event
     pub fn event_publisher(
         &mut self,
@@ -58,7 +188,21 @@ impl Container {
     pub fn note_book(&mut self) -> Result<Arc<dyn crate::adapter::NoteBook>> {
         Ok(self
             .note_book
-            .get_or_init(|| Arc::new(crate::adapter::InMemoryNoteBook::default()))
+            .get_or_init(|| {
+                #[cfg(feature = "postgres")]
+                if let Some(pool) = self.pg_pool.get() {
+                    return Arc::new(crate::adapter::postgres::PgNoteBook::new(pool.clone()));
+                }
+
+                #[cfg(feature = "sqlite")]
+                if let Some(pool) = self.sqlite_pool.get() {
+                    return Arc::new(crate::adapter::sqlite::SqliteNoteBook::new(pool.clone()));
+                }
+
+                Arc::new(crate::adapter::InMemoryNoteBook::with_outbox(
+                    self.in_memory_outbox(),
+                ))
+            })
             .clone())
     }
 
@@ -66,7 +210,100 @@ impl Container {
     pub fn project_book(&mut self) -> Result<Arc<dyn crate::adapter::ProjectBook>> {
         Ok(self
             .project_book
-            .get_or_init(|| Arc::new(crate::adapter::InMemoryProjectBook::default()))
+            .get_or_init(|| {
+                #[cfg(feature = "postgres")]
+                if let Some(pool) = self.pg_pool.get() {
+                    return Arc::new(crate::adapter::postgres::PgProjectBook::new(pool.clone()));
+                }
+
+                #[cfg(feature = "sqlite")]
+                if let Some(pool) = self.sqlite_pool.get() {
+                    return Arc::new(crate::adapter::sqlite::SqliteProjectBook::new(pool.clone()));
+                }
+
+                Arc::new(crate::adapter::InMemoryProjectBook::with_outbox(
+                    self.in_memory_outbox(),
+                ))
+            })
+            .clone())
+    }
+
+    /// The universe book shared with the in-memory organization book.
+    fn in_memory_universe_book(&self) -> crate::adapter::InMemoryUniverseBook {
+        self.in_memory_universe_book
+            .get_or_init(Default::default)
+            .clone()
+    }
+
+    /// Get the organization book
+    pub fn organization_book(&mut self) -> Result<Arc<dyn crate::adapter::OrganizationBook>> {
+        Ok(self
+            .organization_book
+            .get_or_init(|| {
+                #[cfg(feature = "postgres")]
+                if let Some(pool) = self.pg_pool.get() {
+                    return Arc::new(crate::adapter::postgres::PgOrganizationBook::new(
+                        pool.clone(),
+                    ));
+                }
+
+                #[cfg(feature = "sqlite")]
+                if let Some(pool) = self.sqlite_pool.get() {
+                    return Arc::new(crate::adapter::sqlite::SqliteOrganizationBook::new(
+                        pool.clone(),
+                    ));
+                }
+
+                Arc::new(
+                    crate::adapter::InMemoryOrganizationBook::with_universe_book(
+                        self.in_memory_universe_book(),
+                    ),
+                )
+            })
+            .clone())
+    }
+
+    /// Get the universe book
+    pub fn universe_book(&mut self) -> Result<Arc<dyn crate::adapter::UniverseBook>> {
+        Ok(self
+            .universe_book
+            .get_or_init(|| {
+                #[cfg(feature = "postgres")]
+                if let Some(pool) = self.pg_pool.get() {
+                    return Arc::new(crate::adapter::postgres::PgUniverseBook::new(pool.clone()));
+                }
+
+                #[cfg(feature = "sqlite")]
+                if let Some(pool) = self.sqlite_pool.get() {
+                    return Arc::new(crate::adapter::sqlite::SqliteUniverseBook::new(
+                        pool.clone(),
+                    ));
+                }
+
+                Arc::new(self.in_memory_universe_book())
+            })
+            .clone())
+    }
+
+    /// Get the stylo book
+    pub fn stylo_book(&mut self) -> Result<Arc<dyn crate::adapter::StyloBook>> {
+        Ok(self
+            .stylo_book
+            .get_or_init(|| {
+                #[cfg(feature = "postgres")]
+                if let Some(pool) = self.pg_pool.get() {
+                    return Arc::new(crate::adapter::postgres::PgStyloBook::new(pool.clone()));
+                }
+
+                #[cfg(feature = "sqlite")]
+                if let Some(pool) = self.sqlite_pool.get() {
+                    return Arc::new(crate::adapter::sqlite::SqliteStyloBook::new(pool.clone()));
+                }
+
+                Arc::new(crate::adapter::InMemoryStyloBook::with_outbox(
+                    self.in_memory_outbox(),
+                ))
+            })
             .clone())
     }
 
@@ -74,7 +311,138 @@ impl Container {
     pub fn thought_book(&mut self) -> Result<Arc<dyn crate::adapter::ThoughtBook>> {
         Ok(self
             .thought_book
-            .get_or_init(|| Arc::new(crate::adapter::InMemoryThoughtBook::default()))
+            .get_or_init(|| {
+                #[cfg(feature = "postgres")]
+                if let Some(pool) = self.pg_pool.get() {
+                    return Arc::new(crate::adapter::postgres::PgThoughtBook::new(pool.clone()));
+                }
+
+                #[cfg(feature = "sqlite")]
+                if let Some(pool) = self.sqlite_pool.get() {
+                    return Arc::new(crate::adapter::sqlite::SqliteThoughtBook::new(pool.clone()));
+                }
+
+                Arc::new(crate::adapter::InMemoryThoughtBook::with_outbox(
+                    self.in_memory_outbox(),
+                ))
+            })
+            .clone())
+    }
+
+    /// Get the reference book
+    pub fn reference_book(&mut self) -> Result<Arc<dyn crate::adapter::ReferenceBook>> {
+        Ok(self
+            .reference_book
+            .get_or_init(|| {
+                #[cfg(feature = "postgres")]
+                if let Some(pool) = self.pg_pool.get() {
+                    return Arc::new(crate::adapter::postgres::PgReferenceBook::new(pool.clone()));
+                }
+
+                #[cfg(feature = "sqlite")]
+                if let Some(pool) = self.sqlite_pool.get() {
+                    return Arc::new(crate::adapter::sqlite::SqliteReferenceBook::new(
+                        pool.clone(),
+                    ));
+                }
+
+                Arc::new(crate::adapter::InMemoryReferenceBook::default())
+            })
+            .clone())
+    }
+
+    /// Get the saved search book
+    pub fn saved_search_book(&mut self) -> Result<Arc<dyn crate::adapter::SavedSearchBook>> {
+        Ok(self
+            .saved_search_book
+            .get_or_init(|| {
+                #[cfg(feature = "postgres")]
+                if let Some(pool) = self.pg_pool.get() {
+                    return Arc::new(crate::adapter::postgres::PgSavedSearchBook::new(
+                        pool.clone(),
+                    ));
+                }
+
+                #[cfg(feature = "sqlite")]
+                if let Some(pool) = self.sqlite_pool.get() {
+                    return Arc::new(crate::adapter::sqlite::SqliteSavedSearchBook::new(
+                        pool.clone(),
+                    ));
+                }
+
+                Arc::new(crate::adapter::InMemorySavedSearchBook::default())
+            })
+            .clone())
+    }
+
+    /// Get the media store
+    pub fn media_store(&mut self) -> Result<Arc<dyn crate::adapter::MediaStore>> {
+        Ok(self
+            .media_store
+            .get_or_init(|| Arc::new(crate::adapter::InMemoryMediaStore::default()))
+            .clone())
+    }
+
+    /// Get the event store
+    pub fn event_store(&mut self) -> Result<Arc<dyn crate::adapter::EventStore>> {
+        Ok(self
+            .event_store
+            .get_or_init(|| Arc::new(crate::adapter::InMemoryEventStore::default()))
+            .clone())
+    }
+
+    /// Get the audit log
+    pub fn audit_log(&mut self) -> Result<Arc<dyn crate::adapter::AuditLog>> {
+        Ok(self
+            .audit_log
+            .get_or_init(|| Arc::new(crate::adapter::InMemoryAuditLog::default()))
+            .clone())
+    }
+
+    /// Get the key store
+    pub fn key_store(&mut self) -> Result<Arc<dyn crate::adapter::KeyStore>> {
+        Ok(self
+            .key_store
+            .get_or_init(|| Arc::new(crate::adapter::InMemoryKeyStore::default()))
+            .clone())
+    }
+
+    /// The outbox shared by the in-memory books.
+    fn in_memory_outbox(&self) -> crate::adapter::InMemoryOutbox {
+        self.in_memory_outbox.get_or_init(Default::default).clone()
+    }
+
+    /// Get the outbox
+    /// The books write their events in it, in the same storage as their data.
+    pub fn outbox(&mut self) -> Result<Arc<dyn crate::adapter::Outbox>> {
+        Ok(self
+            .outbox
+            .get_or_init(|| {
+                #[cfg(feature = "postgres")]
+                if let Some(pool) = self.pg_pool.get() {
+                    return Arc::new(crate::adapter::postgres::PgOutbox::new(pool.clone()));
+                }
+
+                #[cfg(feature = "sqlite")]
+                if let Some(pool) = self.sqlite_pool.get() {
+                    return Arc::new(crate::adapter::sqlite::SqliteOutbox::new(pool.clone()));
+                }
+
+                Arc::new(self.in_memory_outbox())
+            })
+            .clone())
+    }
+
+    /// Get the outbox relay
+    /// It must be requested before the event dispatcher.
+    pub fn outbox_relay(&mut self) -> Result<Arc<crate::service::OutboxRelay>> {
+        let outbox = self.outbox()?;
+        let key_store = self.key_store()?;
+        let sender = self.event_publisher_sender()?;
+
+        Ok(self
+            .outbox_relay
+            .get_or_init(|| Arc::new(crate::service::OutboxRelay::new(outbox, key_store, sender)))
             .clone())
     }
 
@@ -82,22 +450,155 @@ impl Container {
     pub fn thought_service(&mut self) -> Result<Arc<crate::service::ThoughtService>> {
         let note_book = self.note_book()?;
         let project_book = self.project_book()?;
+        let universe_book = self.universe_book()?;
+        let stylo_book = self.stylo_book()?;
         let thought_book = self.thought_book()?;
-        let sender = self.event_publisher_sender()?;
+        let reference_book = self.reference_book()?;
+        let media_store = self.media_store()?;
+        let relay = self.outbox_relay()?;
+        let similarity = self.similarity_thresholds()?;
 
         Ok(self
             .thought_service
             .get_or_init(|| {
                 Arc::new(crate::service::ThoughtService::new(
+                    note_book,
+                    project_book,
+                    universe_book,
+                    stylo_book,
+                    thought_book,
+                    reference_book,
+                    media_store,
+                    relay,
+                    similarity,
+                ))
+            })
+            .clone())
+    }
+
+    /// Get the organization service
+    pub fn organization_service(&mut self) -> Result<Arc<crate::service::OrganizationService>> {
+        let organization_book = self.organization_book()?;
+        let universe_book = self.universe_book()?;
+        let key_store = self.key_store()?;
+
+        Ok(self
+            .organization_service
+            .get_or_init(|| {
+                Arc::new(crate::service::OrganizationService::new(
+                    organization_book,
+                    universe_book,
+                    key_store,
+                ))
+            })
+            .clone())
+    }
+
+    /// Get the stylo service
+    pub fn stylo_service(&mut self) -> Result<Arc<crate::service::StyloService>> {
+        let stylo_book = self.stylo_book()?;
+        let organization_book = self.organization_book()?;
+        let relay = self.outbox_relay()?;
+
+        Ok(self
+            .stylo_service
+            .get_or_init(|| {
+                Arc::new(crate::service::StyloService::new(
+                    stylo_book,
+                    organization_book,
+                    relay,
+                ))
+            })
+            .clone())
+    }
+
+    /// Get the thought search service
+    /// Its index is empty until it is rebuilt or fed with events.
+    pub fn thought_search(&mut self) -> Result<Arc<crate::service::ThoughtSearchService>> {
+        let note_book = self.note_book()?;
+        let project_book = self.project_book()?;
+        let thought_book = self.thought_book()?;
+        let similarity = self.similarity_thresholds()?;
+
+        Ok(self
+            .thought_search
+            .get_or_init(|| {
+                Arc::new(crate::service::ThoughtSearchService::new(
+                    note_book,
+                    project_book,
+                    thought_book,
+                    similarity,
+                ))
+            })
+            .clone())
+    }
+
+    /// Get the saved search service
+    pub fn saved_search_service(&mut self) -> Result<Arc<crate::service::SavedSearchService>> {
+        let saved_search_book = self.saved_search_book()?;
+        let note_book = self.note_book()?;
+        let project_book = self.project_book()?;
+        let thought_book = self.thought_book()?;
+        let thought_search = self.thought_search()?;
+
+        Ok(self
+            .saved_search_service
+            .get_or_init(|| {
+                Arc::new(crate::service::SavedSearchService::new(
+                    saved_search_book,
                     note_book,
                     project_book,
                     thought_book,
-                    sender,
+                    thought_search,
                 ))
             })
             .clone())
     }
 
+    /// Get the event stream service
+    pub fn event_stream(&mut self) -> Result<Arc<crate::service::EventStreamService>> {
+        let project_book = self.project_book()?;
+        let capacity = *self
+            .event_replay_capacity
+            .get_or_init(|| crate::service::EVENT_REPLAY_CAPACITY);
+
+        Ok(self
+            .event_stream
+            .get_or_init(|| {
+                Arc::new(crate::service::EventStreamService::new(
+                    project_book,
+                    capacity,
+                ))
+            })
+            .clone())
+    }
+
+    /// Get the event history service
+    pub fn event_history(&mut self) -> Result<Arc<crate::service::EventHistoryService>> {
+        let event_store = self.event_store()?;
+
+        Ok(self
+            .event_history
+            .get_or_init(|| Arc::new(crate::service::EventHistoryService::new(event_store)))
+            .clone())
+    }
+
+    /// Get the audit log service
+    pub fn audit_log_service(&mut self) -> Result<Arc<crate::service::AuditLogService>> {
+        let audit_log = self.audit_log()?;
+
+        Ok(self
+            .audit_log_service
+            .get_or_init(|| Arc::new(crate::service::AuditLogService::new(audit_log)))
+            .clone())
+    }
+
+    /// Get the project stats service
+    /// Its counts are empty until they are rebuilt or fed with events.
+    pub fn project_stats(&mut self) -> Result<Arc<crate::service::ProjectStatsService>> {
+        Ok(self.project_stats.get_or_init(Default::default).clone())
+    }
+
     /// Get the event dispatcher
     pub fn event_dispatcher(&mut self) -> Result<synapps::EventDispatcher<ModelEvent>> {
         let receiver = self.event_publisher_receiver()?;
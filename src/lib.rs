@@ -22,5 +22,9 @@ pub mod models;
 /// Service module.
 pub mod service;
 
+/// Conformance test kit for the storage backends.
+#[cfg(any(test, feature = "testkit"))]
+pub mod testkit;
+
 /// Result type used in the application.
 pub type Result<T> = anyhow::Result<T>;
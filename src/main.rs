@@ -1,11 +1,17 @@
 use anyhow::anyhow;
-use clap::Parser;
+use clap::{Parser, Subcommand};
 use log::warn;
 use log::{debug, error, info};
 use tokio::signal;
 use tokio::task::JoinHandle;
 
 use kaku::actor::ApiApp;
+use kaku::models::SimilarityThresholds;
+use kaku::service::{
+    AuditLogService, EventHistoryService, EventStreamService, ProjectStatsService,
+    SavedSearchService, ThoughtSearchService, MEDIA_ORPHAN_AGE, MEDIA_SWEEP_INTERVAL,
+    OUTBOX_POLL_INTERVAL,
+};
 use kaku::{Container, Result};
 
 /// Application configuration
@@ -19,6 +25,66 @@ pub struct Config {
     /// API server port
     #[arg(long, default_value = "8080")]
     pub port: u16,
+
+    /// PostgreSQL database URL, data are kept in memory if not set
+    #[cfg(feature = "postgres")]
+    #[arg(long, env = "KAKU_DATABASE_URL")]
+    pub database_url: Option<String>,
+
+    /// SQLite database file, data are kept in memory if not set
+    #[cfg(feature = "sqlite")]
+    #[arg(long, env = "KAKU_SQLITE_PATH")]
+    pub sqlite_path: Option<std::path::PathBuf>,
+
+    /// Directory of the media store, medias are kept in memory if not set
+    #[arg(long, env = "KAKU_MEDIA_PATH")]
+    pub media_path: Option<std::path::PathBuf>,
+
+    /// JSON Lines file recording the model events, events are kept in memory if not set
+    #[arg(long, env = "KAKU_EVENT_STORE_PATH")]
+    pub event_store_path: Option<std::path::PathBuf>,
+
+    /// JSON Lines file of the hash-chained audit log, the audit log is kept in memory if not set
+    #[arg(long, env = "KAKU_AUDIT_LOG_PATH")]
+    pub audit_log_path: Option<std::path::PathBuf>,
+
+    /// Directory of the keys signing the model events, keys are kept in memory if not set
+    #[arg(long, env = "KAKU_KEY_PATH")]
+    pub key_path: Option<std::path::PathBuf>,
+
+    /// Minimum trigram similarity of the words matched by fuzzy searches
+    #[arg(long, env = "KAKU_WORD_SIMILARITY")]
+    pub word_similarity: Option<f64>,
+
+    /// Minimum trigram similarity of the project slugs suggested for unknown ones
+    #[arg(long, env = "KAKU_SLUG_SIMILARITY")]
+    pub slug_similarity: Option<f64>,
+
+    /// Number of events kept so interrupted event streams can be resumed
+    #[arg(long, env = "KAKU_EVENT_REPLAY_CAPACITY")]
+    pub event_replay_capacity: Option<usize>,
+
+    /// Command to run instead of the API server
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+/// Commands
+#[derive(Subcommand, Debug, Clone)]
+pub enum Command {
+    /// Check the audit log
+    Audit {
+        /// Audit command
+        #[command(subcommand)]
+        command: AuditCommand,
+    },
+}
+
+/// Audit log commands
+#[derive(Subcommand, Debug, Clone)]
+pub enum AuditCommand {
+    /// Walk the chain of the audit log and report its first broken link
+    Verify,
 }
 
 /// Application
@@ -36,8 +102,93 @@ impl Application {
     /// It launches the API server and waits for a signal to stop the application.
     pub async fn run(self) -> Result<()> {
         let mut container = Container::default();
+
+        #[cfg(feature = "postgres")]
+        if let Some(database_url) = &self.config.database_url {
+            let pool = kaku::adapter::postgres::connect(database_url).await?;
+            container.set_pg_pool(pool)?;
+            debug!("Using PostgreSQL storage.");
+        }
+
+        #[cfg(feature = "sqlite")]
+        if let Some(sqlite_path) = &self.config.sqlite_path {
+            #[cfg(feature = "postgres")]
+            if self.config.database_url.is_some() {
+                return Err(anyhow!(
+                    "Only one of database URL or SQLite path can be set."
+                ));
+            }
+
+            let pool = kaku::adapter::sqlite::connect(sqlite_path).await?;
+            container.set_sqlite_pool(pool)?;
+            debug!("Using SQLite storage in '{}'.", sqlite_path.display());
+        }
+
+        if let Some(media_path) = &self.config.media_path {
+            let media_store = kaku::adapter::FsMediaStore::new(media_path).await?;
+            container.set_media_store(std::sync::Arc::new(media_store))?;
+            debug!("Storing medias in '{}'.", media_path.display());
+        }
+
+        if let Some(event_store_path) = &self.config.event_store_path {
+            let event_store = kaku::adapter::JsonlEventStore::open(event_store_path).await?;
+            container.set_event_store(std::sync::Arc::new(event_store))?;
+            debug!("Recording events in '{}'.", event_store_path.display());
+        }
+
+        if let Some(audit_log_path) = &self.config.audit_log_path {
+            let audit_log = kaku::adapter::JsonlAuditLog::open(audit_log_path).await?;
+            container.set_audit_log(std::sync::Arc::new(audit_log))?;
+            debug!("Auditing events in '{}'.", audit_log_path.display());
+        }
+
+        if let Some(key_path) = &self.config.key_path {
+            let key_store = kaku::adapter::FsKeyStore::new(key_path).await?;
+            container.set_key_store(std::sync::Arc::new(key_store))?;
+            debug!("Signing events with the keys of '{}'.", key_path.display());
+        }
+
+        let defaults = SimilarityThresholds::default();
+        container.set_similarity_thresholds(SimilarityThresholds {
+            word: self.config.word_similarity.unwrap_or(defaults.word),
+            slug: self.config.slug_similarity.unwrap_or(defaults.slug),
+        })?;
+
+        if let Some(capacity) = self.config.event_replay_capacity {
+            container.set_event_replay_capacity(capacity)?;
+        }
+
         let thought_service = container.thought_service()?;
-        let api_app = ApiApp::new(thought_service.clone());
+        let outbox_relay = container.outbox_relay()?;
+        let thought_search = container.thought_search()?;
+        thought_search.rebuild().await?;
+        let saved_search = container.saved_search_service()?;
+        let event_stream = container.event_stream()?;
+        let event_history = container.event_history()?;
+        let project_stats = container.project_stats()?;
+        let audit_log = container.audit_log_service()?;
+        let organizations = container.organization_service()?;
+        let stylos = container.stylo_service()?;
+        // The subscribers skip the events the relay delivers again after a
+        // restart, the history tells them which events they already handled.
+        let replayed = event_history
+            .rebuild(&[
+                project_stats.as_ref(),
+                event_stream.delivered(),
+                saved_search.delivered(),
+            ])
+            .await?;
+        debug!("Replayed {replayed} events to count the changes of the projects.");
+        let api_app = ApiApp::new(
+            thought_service.clone(),
+            thought_search.clone(),
+            saved_search.clone(),
+            event_stream.clone(),
+            project_stats.clone(),
+            audit_log.clone(),
+            organizations.clone(),
+            stylos.clone(),
+        );
 
         let joinhandle: JoinHandle<Result<()>> = tokio::spawn(async move {
             let addr = format!("{}:{}", self.config.host, self.config.port);
@@ -49,25 +200,102 @@ impl Application {
             Ok(())
         });
 
-        let event_dispatcher = container.event_dispatcher()?;
+        let mut event_dispatcher = container.event_dispatcher()?;
+        let search_receiver = ThoughtSearchService::subscribe(&mut event_dispatcher);
+        let saved_search_receiver = SavedSearchService::subscribe(&mut event_dispatcher);
+        let event_stream_receiver = EventStreamService::subscribe(&mut event_dispatcher);
+        let event_history_receiver = EventHistoryService::subscribe(&mut event_dispatcher);
+        let project_stats_receiver = ProjectStatsService::subscribe(&mut event_dispatcher);
+        let audit_log_receiver = AuditLogService::subscribe(&mut event_dispatcher);
         let event_handle = tokio::spawn(async move { event_dispatcher.execute().await });
+        // Send the events left in the outbox by a previous run, then the ones
+        // whose dispatch failed.
+        let outbox_handle =
+            tokio::spawn(async move { outbox_relay.run(OUTBOX_POLL_INTERVAL).await });
+        let search_handle =
+            tokio::spawn(async move { thought_search.listen(search_receiver).await });
+        let saved_search_handle =
+            tokio::spawn(async move { saved_search.listen(saved_search_receiver).await });
+        let event_stream_handle =
+            tokio::spawn(async move { event_stream.listen(event_stream_receiver).await });
+        let event_history_handle =
+            tokio::spawn(async move { event_history.listen(event_history_receiver).await });
+        let project_stats_handle =
+            tokio::spawn(async move { project_stats.listen(project_stats_receiver).await });
+        let audit_log_handle =
+            tokio::spawn(async move { audit_log.listen(audit_log_receiver).await });
+        // Delete the uploads never attached and the medias left behind.
+        let media_sweep_handle = tokio::spawn(async move {
+            thought_service
+                .run_media_sweep(MEDIA_SWEEP_INTERVAL, MEDIA_ORPHAN_AGE)
+                .await
+        });
 
         tokio::select! {
             r = joinhandle => {r?},
             _ = event_handle => { Err( anyhow!("The event dispatcher has quit."))},
+            _ = outbox_handle => { Err( anyhow!("The outbox relay has quit."))},
+            _ = search_handle => { Err( anyhow!("The search indexer has quit."))},
+            _ = saved_search_handle => { Err( anyhow!("The saved search matcher has quit."))},
+            _ = event_stream_handle => { Err( anyhow!("The event stream has quit."))},
+            _ = event_history_handle => { Err( anyhow!("The event recorder has quit."))},
+            _ = project_stats_handle => { Err( anyhow!("The project stats counter has quit."))},
+            _ = audit_log_handle => { Err( anyhow!("The audit log has quit."))},
+            _ = media_sweep_handle => { Err( anyhow!("The media sweeper has quit."))},
             _ = signal::ctrl_c() => {
                 warn!("Received Ctrl+C, shutting down...");
                 Ok(())
             },
         }
     }
+
+    /// Verify the audit log
+    /// It walks the chain of the audit log file and fails at its first broken
+    /// link.
+    pub async fn verify_audit(self) -> Result<()> {
+        let Some(audit_log_path) = &self.config.audit_log_path else {
+            return Err(anyhow!("The audit log path is not set."));
+        };
+        let verification = kaku::adapter::JsonlAuditLog::verify(audit_log_path)
+            .await
+            .map_err(|e| {
+                anyhow!(
+                    "Could not read the audit log '{}': {e}",
+                    audit_log_path.display()
+                )
+            })?;
+
+        match verification.broken_link {
+            None => {
+                println!(
+                    "The audit log '{}' is intact: {} entries.",
+                    audit_log_path.display(),
+                    verification.entries
+                );
+                Ok(())
+            }
+            Some(broken_link) => Err(anyhow!(
+                "The audit log '{}' is broken at line {} after {} intact entries: {}.",
+                audit_log_path.display(),
+                broken_link.line,
+                verification.entries,
+                broken_link.reason
+            )),
+        }
+    }
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
     env_logger::init();
-    info!("Starting Kaku.");
     let config = Config::parse();
+    if let Some(Command::Audit {
+        command: AuditCommand::Verify,
+    }) = config.command
+    {
+        return Application::new(config).verify_audit().await;
+    }
+    info!("Starting Kaku.");
     let app = Application::new(config);
 
     match app.run().await {
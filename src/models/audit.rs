@@ -0,0 +1,206 @@
+use chrono::{DateTime, SecondsFormat, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{ModelEvent, ModelKind};
+
+/// Hash the first entry of an audit log is chained to.
+pub const AUDIT_GENESIS_HASH: &str =
+    "0000000000000000000000000000000000000000000000000000000000000000";
+
+/// AuditLinkError is an error type that is used to represent the reasons an
+/// audit entry is not chained to the previous one.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AuditLinkError {
+    /// An entry is missing or repeated.
+    #[error("sequence {found} found where {expected} was expected")]
+    Sequence {
+        /// The sequence following the previous entry.
+        expected: u64,
+        /// The sequence of the entry.
+        found: u64,
+    },
+
+    /// The entry is not chained to the previous one.
+    #[error("the previous hash does not match the hash of the previous entry")]
+    PreviousHash,
+
+    /// The entry has been modified after it was recorded.
+    #[error("the hash does not match the content of the entry")]
+    Hash,
+}
+
+/// Audit entry
+/// A model event recorded in the audit log. Each entry holds the hash of the
+/// previous one, so modifying, removing or inserting an entry breaks the
+/// chain from there.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct AuditEntry {
+    /// Position of the entry in the log, increasing from 1 without gap.
+    pub sequence: u64,
+
+    /// The time the entry was recorded.
+    pub recorded_at: DateTime<Utc>,
+
+    /// The recorded event.
+    pub event: ModelEvent,
+
+    /// Hash of the previous entry, [`AUDIT_GENESIS_HASH`] for the first one.
+    pub previous_hash: String,
+
+    /// SHA-256 of the entry and of the previous hash, in lowercase
+    /// hexadecimal.
+    pub hash: String,
+}
+
+/// The hashed parts of an entry, in the order they are serialized.
+#[derive(Serialize)]
+struct HashedEntry<'a> {
+    sequence: u64,
+    recorded_at: String,
+    event: &'a ModelEvent,
+    previous_hash: &'a str,
+}
+
+impl AuditEntry {
+    /// Record an event after the given entry, or as the first entry.
+    pub fn new(previous: Option<&AuditEntry>, event: ModelEvent) -> Self {
+        let mut entry = Self {
+            sequence: previous.map(|p| p.sequence + 1).unwrap_or(1),
+            recorded_at: Utc::now(),
+            event,
+            previous_hash: previous
+                .map(|p| p.hash.clone())
+                .unwrap_or_else(|| AUDIT_GENESIS_HASH.to_string()),
+            hash: String::new(),
+        };
+        entry.hash = entry.compute_hash();
+
+        entry
+    }
+
+    /// Compute the hash of the entry from its content.
+    pub fn compute_hash(&self) -> String {
+        let hashed = HashedEntry {
+            sequence: self.sequence,
+            recorded_at: self.recorded_at.to_rfc3339_opts(SecondsFormat::Nanos, true),
+            event: &self.event,
+            previous_hash: &self.previous_hash,
+        };
+        let json = serde_json::to_vec(&hashed).expect("An entry can always be serialized.");
+
+        hex::encode(Sha256::digest(json))
+    }
+
+    /// Check the entry follows the given entry, or is the first entry, and
+    /// has not been modified.
+    pub fn check_link(&self, previous: Option<&AuditEntry>) -> Result<(), AuditLinkError> {
+        let expected = previous.map(|p| p.sequence + 1).unwrap_or(1);
+        if self.sequence != expected {
+            return Err(AuditLinkError::Sequence {
+                expected,
+                found: self.sequence,
+            });
+        }
+        let previous_hash = previous
+            .map(|p| p.hash.as_str())
+            .unwrap_or(AUDIT_GENESIS_HASH);
+        if self.previous_hash != previous_hash {
+            return Err(AuditLinkError::PreviousHash);
+        }
+        if self.hash != self.compute_hash() {
+            return Err(AuditLinkError::Hash);
+        }
+
+        Ok(())
+    }
+
+    /// Identifiers of the models the entry concerns.
+    /// The changes of the notes and thoughts of a project concern the project
+    /// too.
+    pub fn subjects(&self) -> Vec<Uuid> {
+        match self.event.model {
+            ModelKind::Note {
+                note_id,
+                project_id,
+                ..
+            } => vec![note_id, project_id],
+            ModelKind::Thought {
+                thought_id,
+                project_id,
+                ..
+            } => vec![thought_id, project_id],
+            ModelKind::Project { project_id, .. } => vec![project_id],
+            ModelKind::Stylo { stylo_id, .. } => vec![stylo_id],
+        }
+    }
+
+    /// Tell if the entry concerns the model with the given identifier.
+    pub fn concerns(&self, model_id: Uuid) -> bool {
+        self.subjects().contains(&model_id)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::NoteChangeKind;
+
+    use super::*;
+
+    fn note_event(note_id: Uuid, project_id: Uuid) -> ModelEvent {
+        ModelEvent::new(ModelKind::Note {
+            note_id,
+            project_id,
+            change_kind: NoteChangeKind::Created,
+        })
+    }
+
+    #[test]
+    fn test_chain() {
+        let (note_id, project_id) = (Uuid::new_v4(), Uuid::new_v4());
+        let first = AuditEntry::new(None, note_event(note_id, project_id));
+        let second = AuditEntry::new(Some(&first), note_event(Uuid::new_v4(), project_id));
+        assert_eq!(first.sequence, 1);
+        assert_eq!(first.previous_hash, AUDIT_GENESIS_HASH);
+        assert_eq!(second.sequence, 2);
+        assert_eq!(second.previous_hash, first.hash);
+        assert_eq!(first.check_link(None), Ok(()));
+        assert_eq!(second.check_link(Some(&first)), Ok(()));
+
+        let json = serde_json::to_string(&second).unwrap();
+        let parsed = serde_json::from_str::<AuditEntry>(&json).unwrap();
+        assert_eq!(parsed.check_link(Some(&first)), Ok(()));
+
+        assert!(first.concerns(note_id));
+        assert!(first.concerns(project_id));
+        assert!(!second.concerns(note_id));
+    }
+
+    #[test]
+    fn test_broken_links() {
+        let first = AuditEntry::new(None, note_event(Uuid::new_v4(), Uuid::new_v4()));
+        let second = AuditEntry::new(Some(&first), note_event(Uuid::new_v4(), Uuid::new_v4()));
+        let third = AuditEntry::new(Some(&second), note_event(Uuid::new_v4(), Uuid::new_v4()));
+
+        assert_eq!(
+            third.check_link(Some(&first)),
+            Err(AuditLinkError::Sequence {
+                expected: 2,
+                found: 3
+            })
+        );
+
+        let mut forged = first.clone();
+        forged.event.timestamp += chrono::Duration::seconds(1);
+        assert_eq!(forged.check_link(None), Err(AuditLinkError::Hash));
+
+        // Rehashing the forged entry does not hide it, the next link breaks.
+        forged.hash = forged.compute_hash();
+        assert_eq!(forged.check_link(None), Ok(()));
+        assert_eq!(
+            second.check_link(Some(&forged)),
+            Err(AuditLinkError::PreviousHash)
+        );
+    }
+}
@@ -0,0 +1,226 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+
+/// Maximum length of a category label.
+const MAX_LABEL_LENGTH: usize = 255;
+
+/// Category is a hierarchical path of labels separated by dots, like
+/// `philosophy.ethics.kant`.
+/// Labels are made of ASCII letters, digits and underscores, so categories are
+/// compatible with the PostgreSQL ltree type.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[serde(try_from = "String", into = "String")]
+pub struct Category(String);
+
+impl Category {
+    /// Textual representation of the category, as stored in databases.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// The labels of the category, from the root down.
+    pub fn labels(&self) -> impl Iterator<Item = &str> {
+        self.0.split('.')
+    }
+
+    /// The parent category, None for a root category.
+    pub fn parent(&self) -> Option<Category> {
+        self.0
+            .rsplit_once('.')
+            .map(|(parent, _)| Self(parent.to_string()))
+    }
+
+    /// Tell if this category is the given category or lies under it.
+    /// This behaves like the ltree `<@` operator.
+    pub fn is_descendant_of(&self, ancestor: &Category) -> bool {
+        self.0
+            .strip_prefix(ancestor.as_str())
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('.'))
+    }
+}
+
+impl fmt::Display for Category {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl FromStr for Category {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        for label in s.split('.') {
+            if label.is_empty() {
+                return Err(anyhow!("Category '{s}' contains an empty label."));
+            }
+            if label.len() > MAX_LABEL_LENGTH {
+                return Err(anyhow!("Category '{s}' contains a label too long."));
+            }
+            if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(anyhow!(
+                    "Category label '{label}' must only contain letters, digits and underscores."
+                ));
+            }
+        }
+
+        Ok(Self(s.to_string()))
+    }
+}
+
+impl TryFrom<String> for Category {
+    type Error = anyhow::Error;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Category> for String {
+    fn from(value: Category) -> Self {
+        value.0
+    }
+}
+
+/// CategoryCount tells how many thoughts of a project are filed in a category.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CategoryCount {
+    /// The category.
+    pub category: Category,
+
+    /// The number of thoughts filed exactly in this category.
+    pub count: u64,
+}
+
+/// CategoryTree is a category with its sub categories and thought counts.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CategoryTree {
+    /// The category at the root of this tree.
+    pub category: Category,
+
+    /// The number of thoughts filed exactly in this category.
+    pub count: u64,
+
+    /// The number of thoughts filed in this category or under it.
+    pub total: u64,
+
+    /// The sub categories, sorted by name.
+    pub children: Vec<CategoryTree>,
+}
+
+impl CategoryTree {
+    /// Build the category trees from the thought counts per category.
+    /// Intermediate categories holding no thought directly are created with a
+    /// count of zero. The root categories are returned sorted by name.
+    pub fn build(counts: &[CategoryCount]) -> Vec<CategoryTree> {
+        let mut roots: Vec<CategoryTree> = Vec::new();
+
+        for category_count in counts {
+            let mut path: Option<Category> = None;
+            let mut level = &mut roots;
+
+            for label in category_count.category.labels() {
+                let category = Category(match &path {
+                    Some(parent) => format!("{parent}.{label}"),
+                    None => label.to_string(),
+                });
+                let index = match level.iter().position(|t| t.category == category) {
+                    Some(index) => index,
+                    None => {
+                        level.push(CategoryTree {
+                            category: category.clone(),
+                            count: 0,
+                            total: 0,
+                            children: Vec::new(),
+                        });
+                        level.len() - 1
+                    }
+                };
+                let tree = &mut level[index];
+                tree.total += category_count.count;
+                if category == category_count.category {
+                    tree.count += category_count.count;
+                }
+                path = Some(category);
+                level = &mut tree.children;
+            }
+        }
+        Self::sort(&mut roots);
+
+        roots
+    }
+
+    fn sort(trees: &mut [CategoryTree]) {
+        trees.sort_by(|a, b| a.category.cmp(&b.category));
+        for tree in trees {
+            Self::sort(&mut tree.children);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn category(path: &str) -> Category {
+        path.parse().unwrap()
+    }
+
+    #[test]
+    fn test_parse_category() {
+        assert_eq!(
+            category("philosophy.ethics.kant").as_str(),
+            "philosophy.ethics.kant"
+        );
+        assert!("".parse::<Category>().is_err());
+        assert!("philosophy..kant".parse::<Category>().is_err());
+        assert!("philosophy.ethics.".parse::<Category>().is_err());
+        assert!("philosophy.moral ethics".parse::<Category>().is_err());
+        assert!(serde_json::from_str::<Category>("\"a.b-c\"").is_err());
+    }
+
+    #[test]
+    fn test_is_descendant_of() {
+        let kant = category("philosophy.ethics.kant");
+
+        assert!(kant.is_descendant_of(&category("philosophy")));
+        assert!(kant.is_descendant_of(&category("philosophy.ethics")));
+        assert!(kant.is_descendant_of(&kant));
+        assert!(!kant.is_descendant_of(&category("philosophy.eth")));
+        assert!(!category("philosophy").is_descendant_of(&kant));
+        assert_eq!(kant.parent(), Some(category("philosophy.ethics")));
+        assert_eq!(category("philosophy").parent(), None);
+    }
+
+    #[test]
+    fn test_build_tree() {
+        let counts = vec![
+            CategoryCount {
+                category: category("philosophy.ethics.kant"),
+                count: 2,
+            },
+            CategoryCount {
+                category: category("history"),
+                count: 1,
+            },
+            CategoryCount {
+                category: category("philosophy"),
+                count: 1,
+            },
+        ];
+        let trees = CategoryTree::build(&counts);
+
+        assert_eq!(trees.len(), 2);
+        assert_eq!(trees[0].category, category("history"));
+        let philosophy = &trees[1];
+        assert_eq!((philosophy.count, philosophy.total), (1, 3));
+        let ethics = &philosophy.children[0];
+        assert_eq!((ethics.count, ethics.total), (0, 2));
+        assert_eq!(
+            ethics.children[0].category,
+            category("philosophy.ethics.kant")
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use uuid::Uuid;
+
+use super::{normalize_tag, Note, Thought};
+
+/// Minimum number of hexadecimal digits of a short identifier.
+pub const SHORT_ID_MIN_LENGTH: usize = 8;
+
+/// LinkReference is a `[[...]]` reference found in a content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkReference {
+    /// A full thought identifier.
+    Id(Uuid),
+
+    /// The first hexadecimal digits of a thought identifier, lowercased and
+    /// without hyphens.
+    ShortId(String),
+
+    /// Anything else, it cannot be resolved to a thought.
+    Unknown(String),
+}
+
+impl LinkReference {
+    /// Classify the text found between double brackets.
+    fn parse(text: &str) -> Self {
+        let text = text.trim();
+
+        if let Ok(id) = Uuid::parse_str(text) {
+            return Self::Id(id);
+        }
+        let digits: String = text.chars().filter(|c| *c != '-').collect();
+        if digits.len() >= SHORT_ID_MIN_LENGTH && digits.chars().all(|c| c.is_ascii_hexdigit()) {
+            return Self::ShortId(digits.to_ascii_lowercase());
+        }
+
+        Self::Unknown(text.to_string())
+    }
+
+    /// Textual form of the reference, as reported when it cannot be resolved.
+    pub fn as_text(&self) -> String {
+        match self {
+            Self::Id(id) => id.to_string(),
+            Self::ShortId(short_id) | Self::Unknown(short_id) => short_id.clone(),
+        }
+    }
+}
+
+/// ParsedContent holds the links and tags written inline in a content.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ParsedContent {
+    /// The `[[...]]` references, in order of appearance and without duplicates.
+    pub links: Vec<LinkReference>,
+
+    /// The normalized `#tag` tokens, in order of appearance and without
+    /// duplicates.
+    pub tags: Vec<String>,
+}
+
+impl ParsedContent {
+    /// Parse a content.
+    ///
+    /// Links are written `[[thought-id]]` or `[[short-id]]`. A tag starts with a
+    /// `#` at the beginning of a word followed by a letter, it is made of
+    /// letters, digits, `_`, `-` and `/`, so `C#` or `# Title` are not tags.
+    pub fn parse(content: &str) -> Self {
+        let mut parsed = Self::default();
+        let mut rest = content;
+
+        while let Some(start) = rest.find("[[") {
+            parsed.parse_tags(&rest[..start]);
+            let after = &rest[start + 2..];
+            match after.find("]]") {
+                Some(end) if !after[..end].contains('\n') => {
+                    let link = LinkReference::parse(&after[..end]);
+                    if !parsed.links.contains(&link) {
+                        parsed.links.push(link);
+                    }
+                    rest = &after[end + 2..];
+                }
+                _ => rest = after,
+            }
+        }
+        parsed.parse_tags(rest);
+
+        parsed
+    }
+
+    fn parse_tags(&mut self, text: &str) {
+        let mut previous: Option<char> = None;
+        let mut chars = text.char_indices().peekable();
+
+        while let Some((index, c)) = chars.next() {
+            let at_word_start = previous.is_none_or(|p| p.is_whitespace() || p == '(');
+            previous = Some(c);
+            if c != '#' || !at_word_start {
+                continue;
+            }
+            if !chars.peek().is_some_and(|(_, next)| next.is_alphabetic()) {
+                continue;
+            }
+            let token_start = index + 1;
+            let mut token_end = token_start;
+            while let Some((next_index, next)) = chars.peek().copied() {
+                if !(next.is_alphanumeric() || matches!(next, '_' | '-' | '/')) {
+                    break;
+                }
+                token_end = next_index + next.len_utf8();
+                previous = Some(next);
+                chars.next();
+            }
+            if let Some(tag) = normalize_tag(&text[token_start..token_end]) {
+                if !self.tags.contains(&tag) {
+                    self.tags.push(tag);
+                }
+            }
+        }
+    }
+}
+
+impl Thought {
+    /// Parse the links and tags written inline in the content of the thought.
+    pub fn parse_content(&self) -> ParsedContent {
+        ParsedContent::parse(&self.content)
+    }
+}
+
+impl Note {
+    /// Parse the links and tags written inline in the content of the note.
+    pub fn parse_content(&self) -> ParsedContent {
+        ParsedContent::parse(&self.content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_tags() {
+        let parsed = ParsedContent::parse(
+            "#Ethics according to Kant (#kant), not C# nor # Title, see #moral/duty. #ethics",
+        );
+
+        assert_eq!(parsed.tags, vec!["ethics", "kant", "moral/duty"]);
+        assert!(parsed.links.is_empty());
+    }
+
+    #[test]
+    fn test_parse_links() {
+        let id = Uuid::new_v4();
+        let content = format!(
+            "As said in [[{id}]] and [[1A2B3C4D]], see [[a title]] or [[1234]]. [[{id}]] [[ unclosed"
+        );
+        let parsed = ParsedContent::parse(&content);
+
+        assert_eq!(
+            parsed.links,
+            vec![
+                LinkReference::Id(id),
+                LinkReference::ShortId("1a2b3c4d".to_string()),
+                LinkReference::Unknown("a title".to_string()),
+                LinkReference::Unknown("1234".to_string()),
+            ]
+        );
+        assert!(parsed.tags.is_empty());
+    }
+
+    #[test]
+    fn test_parse_tags_around_links() {
+        let parsed = ParsedContent::parse("#first [[#not-a-tag]] #second");
+
+        assert_eq!(parsed.tags, vec!["first", "second"]);
+        assert_eq!(
+            parsed.links,
+            vec![LinkReference::Unknown("#not-a-tag".to_string())]
+        );
+    }
+}
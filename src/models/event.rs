@@ -1,10 +1,16 @@
-use synapps::Event;
+use serde::{Deserialize, Serialize};
+use synapps::{Event, EventMessage};
 use uuid::Uuid;
 
-use super::{NoteChangeKind, ProjectChangeKind, ThoughtChangeKind};
+use super::{
+    EventSignature, Note, NoteChangeKind, Project, ProjectChangeKind, Stylo, StyloChangeKind,
+    Thought, ThoughtChangeKind,
+};
 
 /// Type of model
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// It is serialized with its kind in a `kind` field, like `"kind": "note"`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "lowercase")]
 pub enum ModelKind {
     /// a note model
     Note {
@@ -43,16 +49,208 @@ pub enum ModelKind {
         /// change kind
         change_kind: ThoughtChangeKind,
     },
+
+    /// a stylo model
+    Stylo {
+        /// stylo identifier
+        stylo_id: Uuid,
+
+        /// change kind
+        change_kind: StyloChangeKind,
+    },
 }
+
+impl ModelKind {
+    /// The project the changed model belongs to, or the changed project.
+    /// Stylos do not belong to a project.
+    pub fn project_id(&self) -> Option<Uuid> {
+        match self {
+            ModelKind::Note { project_id, .. }
+            | ModelKind::Project { project_id, .. }
+            | ModelKind::Thought { project_id, .. } => Some(*project_id),
+            ModelKind::Stylo { .. } => None,
+        }
+    }
+}
+
 /// Model event structure
 /// This sprays model changes to all actors.
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ModelEvent {
+    /// event identifier
+    /// An event may be delivered more than once, subscribers use this
+    /// identifier to recognize the events they have already seen.
+    pub event_id: Uuid,
+
     /// type of model
     pub model: ModelKind,
 
+    /// responsibility chain
+    /// Who requested the change, it is empty for the events recorded before
+    /// the chain existed.
+    #[serde(default)]
+    pub chain: ResponsibilityChain,
+
     /// model modification timestamp
     pub timestamp: chrono::DateTime<chrono::Utc>,
+
+    /// signature of the event checksum
+    /// Events are signed when they are dispatched, an event read from the
+    /// outbox is not signed yet.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub signature: Option<EventSignature>,
+}
+
+/// Responsibility chain of a change
+/// The identifiers that do not apply to the changed model, or that are not
+/// known yet, are left empty.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ResponsibilityChain {
+    /// stylo used to write the change
+    pub stylo_id: Option<Uuid>,
+
+    /// organization owning the changed model
+    pub owner_organization_id: Option<Uuid>,
+
+    /// organization acting on the behalf of the owner
+    pub actor_organization_id: Option<Uuid>,
+
+    /// authentication token the change was requested with
+    pub authentication_token_id: Option<Uuid>,
+}
+
+impl ResponsibilityChain {
+    /// The chain of a change written with a stylo.
+    pub fn stylo(stylo_id: Uuid) -> Self {
+        Self {
+            stylo_id: Some(stylo_id),
+            ..Default::default()
+        }
+    }
+
+    /// The chain of a change written with a stylo, on the behalf of the
+    /// organizations of the stylo.
+    pub fn written_with(stylo: &Stylo) -> Self {
+        Self {
+            stylo_id: Some(stylo.stylo_id),
+            owner_organization_id: Some(stylo.owner_organization_id),
+            actor_organization_id: Some(stylo.actor_organization_id),
+            ..Default::default()
+        }
+    }
+
+    /// The chain of a change made by an organization on its own behalf,
+    /// without a stylo.
+    pub fn organization(organization_id: Uuid) -> Self {
+        Self {
+            owner_organization_id: Some(organization_id),
+            actor_organization_id: Some(organization_id),
+            ..Default::default()
+        }
+    }
+}
+
+impl ModelEvent {
+    /// Create a new event for a change happening now.
+    pub fn new(model: ModelKind) -> Self {
+        Self {
+            event_id: Uuid::new_v4(),
+            model,
+            chain: ResponsibilityChain::default(),
+            timestamp: chrono::Utc::now(),
+            signature: None,
+        }
+    }
+
+    /// Set the responsibility chain of the event.
+    pub fn with_chain(mut self, chain: ResponsibilityChain) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Create a new event for a change of a note.
+    pub fn note(note: &Note, change_kind: NoteChangeKind) -> Self {
+        Self::new(ModelKind::Note {
+            note_id: note.note_id,
+            project_id: note.project_id,
+            change_kind,
+        })
+        .with_chain(ResponsibilityChain::stylo(note.stylo_id))
+    }
+
+    /// Create a new event for a change of a project.
+    pub fn project(project: &Project, change_kind: ProjectChangeKind) -> Self {
+        Self::new(ModelKind::Project {
+            project_id: project.project_id,
+            universe_id: project.universe_id,
+            change_kind,
+        })
+    }
+
+    /// Create a new event for a change of a stylo.
+    pub fn stylo(stylo: &Stylo, change_kind: StyloChangeKind) -> Self {
+        Self::new(ModelKind::Stylo {
+            stylo_id: stylo.stylo_id,
+            change_kind,
+        })
+        .with_chain(ResponsibilityChain {
+            owner_organization_id: Some(stylo.owner_organization_id),
+            actor_organization_id: Some(stylo.actor_organization_id),
+            ..Default::default()
+        })
+    }
+
+    /// Create a new event for a change of a thought.
+    pub fn thought(thought: &Thought, change_kind: ThoughtChangeKind) -> Self {
+        Self::new(ModelKind::Thought {
+            thought_id: thought.thought_id,
+            project_id: thought.project_id,
+            change_kind,
+        })
+        .with_chain(ResponsibilityChain::stylo(thought.stylo_id))
+    }
 }
 
 impl Event for ModelEvent {}
+
+/// StoredEvent is an event message recorded in the event store.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StoredEvent {
+    /// Position of the event in the store, increasing from 1 without gap.
+    pub sequence: u64,
+
+    /// The sender identifier of the message.
+    pub sender: String,
+
+    /// The topic of the message.
+    pub topic: String,
+
+    /// The time the message was built.
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+
+    /// The model event.
+    pub event: ModelEvent,
+}
+
+impl StoredEvent {
+    /// Record an event message at the given position.
+    pub fn new(sequence: u64, message: EventMessage<ModelEvent>) -> Self {
+        Self {
+            sequence,
+            sender: message.sender,
+            topic: message.topic,
+            timestamp: message.timestamp,
+            event: message.event,
+        }
+    }
+
+    /// Get back the event message, as it was dispatched.
+    pub fn message(&self) -> EventMessage<ModelEvent> {
+        EventMessage {
+            sender: self.sender.clone(),
+            topic: self.topic.clone(),
+            timestamp: self.timestamp,
+            event: self.event.clone(),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+/// Media is a reference to a blob of the media store attached to a thought or
+/// a note, like a picture or a recording.
+/// The blob is addressed by the SHA-256 of its content so the same file
+/// attached several times is stored once.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Media {
+    /// The SHA-256 of the content, in lowercase hexadecimal.
+    pub digest: String,
+
+    /// The MIME type of the content, like `image/png`.
+    pub mime_type: String,
+
+    /// The size of the content in bytes.
+    pub size: u64,
+
+    /// The original file name, if known.
+    #[serde(default)]
+    pub filename: Option<String>,
+}
+
+impl Media {
+    /// Describe a content, computing its digest and its size.
+    pub fn describe(content: &[u8], mime_type: &str, filename: Option<String>) -> Self {
+        Self {
+            digest: media_digest(content),
+            mime_type: mime_type.to_string(),
+            size: content.len() as u64,
+            filename,
+        }
+    }
+}
+
+/// Compute the digest a content is stored under.
+pub fn media_digest(content: &[u8]) -> String {
+    hex::encode(Sha256::digest(content))
+}
+
+/// Check a text is a digest as computed by [`media_digest`].
+pub fn is_media_digest(digest: &str) -> bool {
+    digest.len() == 64
+        && digest
+            .chars()
+            .all(|c| c.is_ascii_digit() || ('a'..='f').contains(&c))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_describe() {
+        let media = Media::describe(b"hello", "text/plain", None);
+
+        assert_eq!(
+            media.digest,
+            "2cf24dba5fb0a30e26e83b2ac5b9e29e1b161e5c1fa7425e73043362938b9824"
+        );
+        assert_eq!(media.size, 5);
+        assert!(is_media_digest(&media.digest));
+        assert!(!is_media_digest(&media.digest.to_uppercase()));
+        assert!(!is_media_digest("../../etc/passwd"));
+    }
+}
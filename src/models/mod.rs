@@ -1,11 +1,35 @@
+mod audit;
+mod category;
+mod content;
 mod event;
+mod media;
 mod note;
+mod organization;
 mod project;
+mod query;
+mod reference;
+mod saved_search;
+mod search;
+mod signature;
+mod stats;
 mod stylo;
 mod thought;
+mod universe;
 
+pub use audit::*;
+pub use category::*;
+pub use content::*;
 pub use event::*;
+pub use media::*;
 pub use note::*;
+pub use organization::*;
 pub use project::*;
+pub use query::*;
+pub use reference::*;
+pub use saved_search::*;
+pub use search::*;
+pub use signature::*;
+pub use stats::*;
 pub use stylo::*;
 pub use thought::*;
+pub use universe::*;
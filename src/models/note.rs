@@ -2,6 +2,8 @@ use chrono::DateTime;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::{Media, ReferenceIdentifier};
+
 /// NoteIdentifier is a type alias for a UUID that represents a note identifier.
 pub type NoteIdentifier = Uuid;
 
@@ -25,6 +27,14 @@ pub struct Note {
 
     /// The content of the note.
     pub content: String,
+
+    /// The references cited by the note, in the same project.
+    #[serde(default)]
+    pub references: Vec<ReferenceIdentifier>,
+
+    /// The medias accompanying the note.
+    #[serde(default)]
+    pub media: Vec<Media>,
 }
 
 /// CreateNoteCommand is a command that is used to create a new note.
@@ -41,10 +51,17 @@ pub struct CreateNoteCommand {
 
     /// The content of the note.
     pub content: String,
+
+    /// The references cited by the note.
+    pub references: Vec<ReferenceIdentifier>,
+
+    /// The medias accompanying the note, they must be in the media store.
+    pub media: Vec<Media>,
 }
 
 /// Business changes on the Note model
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum NoteChangeKind {
     /// Note created
     Created,
@@ -0,0 +1,153 @@
+use chrono::{DateTime, Utc};
+use ed25519_dalek::VerifyingKey;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::Universe;
+
+/// OrganizationIdentifier is a type alias for a UUID that represents an
+/// organization identifier.
+pub type OrganizationIdentifier = Uuid;
+
+/// OrganizationError is an error type that is used to represent the reasons
+/// an organization cannot be created.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum OrganizationError {
+    /// The name of the organization is empty.
+    #[error("The name of an organization cannot be empty.")]
+    EmptyName,
+
+    /// The public key of the organization is not an Ed25519 public key.
+    #[error("The public key of an organization must be a hexadecimal Ed25519 public key.")]
+    InvalidPublicKey,
+}
+
+/// Organization represents a legally accountable entity.
+/// It owns the stylos and the universe its projects are created in.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Organization {
+    /// The unique identifier of the organization
+    pub organization_id: OrganizationIdentifier,
+
+    /// The name of the organization
+    pub name: String,
+
+    /// The certificate attesting the identity of the organization
+    pub certificate: serde_json::Value,
+
+    /// The Ed25519 public key verifying the model events acting for the
+    /// organization, in lowercase hexadecimal.
+    /// Its private key is generated in the key store of the instance when the
+    /// organization is created.
+    pub public_key: String,
+
+    /// Timestamp when the organization was created
+    pub created_at: DateTime<Utc>,
+}
+
+/// Command to create a new Organization
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateOrganizationCommand {
+    /// The name of the organization
+    pub name: String,
+
+    /// The certificate of the organization
+    #[serde(default)]
+    pub certificate: serde_json::Value,
+}
+
+/// CreatedOrganization is the outcome of the creation of an organization,
+/// both are created together.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct CreatedOrganization {
+    /// The created organization.
+    pub organization: Organization,
+
+    /// The universe of the organization.
+    pub universe: Universe,
+}
+
+impl Organization {
+    /// Create a new organization from a command, registered with the public
+    /// key of the key generated for it.
+    /// The name cannot be empty.
+    pub fn create(
+        organization_id: OrganizationIdentifier,
+        command: CreateOrganizationCommand,
+        public_key: &VerifyingKey,
+    ) -> Result<Self, OrganizationError> {
+        if command.name.trim().is_empty() {
+            return Err(OrganizationError::EmptyName);
+        }
+
+        Ok(Self {
+            organization_id,
+            name: command.name.trim().to_string(),
+            certificate: command.certificate,
+            public_key: hex::encode(public_key.as_bytes()),
+            created_at: Utc::now(),
+        })
+    }
+
+    /// Get the public key verifying the model events acting for the
+    /// organization.
+    pub fn verifying_key(&self) -> Result<VerifyingKey, OrganizationError> {
+        hex::decode(&self.public_key)
+            .ok()
+            .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+            .and_then(|bytes| VerifyingKey::from_bytes(&bytes).ok())
+            .ok_or(OrganizationError::InvalidPublicKey)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ed25519_dalek::SigningKey;
+
+    use super::*;
+
+    fn command(name: &str) -> CreateOrganizationCommand {
+        CreateOrganizationCommand {
+            name: name.to_string(),
+            certificate: serde_json::json!({ "issuer": "test" }),
+        }
+    }
+
+    fn public_key() -> VerifyingKey {
+        SigningKey::from_bytes(&[7; 32]).verifying_key()
+    }
+
+    #[test]
+    fn test_organization_creation() {
+        let organization_id = Uuid::new_v4();
+        let organization =
+            Organization::create(organization_id, command(" Acme "), &public_key()).unwrap();
+
+        assert_eq!(organization.organization_id, organization_id);
+        assert_eq!(organization.name, "Acme");
+        assert_eq!(
+            organization.public_key,
+            hex::encode(public_key().as_bytes())
+        );
+        assert_eq!(organization.certificate["issuer"], "test");
+        assert_eq!(organization.verifying_key(), Ok(public_key()));
+    }
+
+    #[test]
+    fn test_invalid_organization() {
+        assert_eq!(
+            Organization::create(Uuid::new_v4(), command("  "), &public_key()),
+            Err(OrganizationError::EmptyName)
+        );
+
+        let mut organization =
+            Organization::create(Uuid::new_v4(), command("Acme"), &public_key()).unwrap();
+        for public_key in ["c0ffee", "not hexadecimal"] {
+            organization.public_key = public_key.to_string();
+            assert_eq!(
+                organization.verifying_key(),
+                Err(OrganizationError::InvalidPublicKey)
+            );
+        }
+    }
+}
@@ -32,7 +32,8 @@ pub struct Project {
 }
 
 /// Project change kind
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ProjectChangeKind {
     /// Project created
     Created,
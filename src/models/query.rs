@@ -0,0 +1,854 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{tokenize, Note, SearchDocumentKind, Thought, ThoughtStatus, ThoughtVariation};
+
+/// QueryErrorKind tells what is wrong in a query.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum QueryErrorKind {
+    /// The query has no criterion.
+    EmptyQuery,
+
+    /// A token cannot be used where it is.
+    UnexpectedToken,
+
+    /// The query ends while an expression is expected.
+    UnexpectedEnd,
+
+    /// A double quote is not closed.
+    UnclosedPhrase,
+
+    /// A parenthesis is not closed.
+    UnclosedParenthesis,
+
+    /// The field before a colon is not known.
+    UnknownField,
+
+    /// The value of a field is not valid for this field.
+    InvalidValue,
+}
+
+/// QuerySyntaxError is a mistake in a query, located so it can be underlined.
+/// Positions are counted in characters from the start of the query, the end
+/// being excluded.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq, thiserror::Error)]
+#[error("{message}")]
+pub struct QuerySyntaxError {
+    /// What is wrong.
+    pub kind: QueryErrorKind,
+
+    /// Human readable description of the mistake.
+    pub message: String,
+
+    /// Position of the first character of the mistake.
+    pub start: usize,
+
+    /// Position right after the last character of the mistake.
+    pub end: usize,
+}
+
+impl QuerySyntaxError {
+    fn new(kind: QueryErrorKind, message: impl Into<String>, start: usize, end: usize) -> Self {
+        Self {
+            kind,
+            message: message.into(),
+            start,
+            end,
+        }
+    }
+}
+
+/// DateRange is a span of import dates, both bounds being optional.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DateRange {
+    /// First instant of the range, included.
+    pub from: Option<DateTime<Utc>>,
+
+    /// Instant the range ends at, excluded.
+    pub until: Option<DateTime<Utc>>,
+}
+
+impl DateRange {
+    /// Tell if an instant is in the range.
+    pub fn contains(&self, instant: &DateTime<Utc>) -> bool {
+        self.from.is_none_or(|from| *instant >= from)
+            && self.until.is_none_or(|until| *instant < until)
+    }
+}
+
+/// QueryField is a criterion on a property of the documents.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryField {
+    /// `tag:ethics`, thoughts carrying the tag.
+    Tag(String),
+
+    /// `project:reading-list`, documents of the project with this slug.
+    Project(String),
+
+    /// `author:<stylo>`, documents written by the stylo.
+    Author(Uuid),
+
+    /// `created:>2026-01-01`, documents imported in the range.
+    Created(DateRange),
+
+    /// `variation:question`, thoughts of the variation.
+    Variation(ThoughtVariation),
+
+    /// `status:disputed`, thoughts with the status.
+    Status(ThoughtStatus),
+
+    /// `kind:note`, documents of the kind.
+    Kind(SearchDocumentKind),
+
+    /// `refuted`, thoughts refuted by another thought, whether the
+    /// refutation has been accepted or not.
+    Refuted,
+}
+
+/// QueryNode is a node of the syntax tree of a query.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QueryNode {
+    /// A word the content must contain, normalized as the search does.
+    Word(String),
+
+    /// Words the content must contain in this order, from a quoted phrase.
+    Phrase(Vec<String>),
+
+    /// A criterion on a property of the documents.
+    Field(QueryField),
+
+    /// `-x` or `NOT x`, documents not matching the node.
+    Not(Box<QueryNode>),
+
+    /// `x AND y` or `x y`, documents matching all the nodes.
+    And(Vec<QueryNode>),
+
+    /// `x OR y`, documents matching any of the nodes.
+    Or(Vec<QueryNode>),
+}
+
+/// QueryHit is a document matching a query.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "kind", rename_all = "lowercase")]
+pub enum QueryHit {
+    /// A matching note.
+    Note(Note),
+
+    /// A matching thought.
+    Thought(Thought),
+}
+
+impl QueryHit {
+    /// The import date of the document.
+    pub fn imported_at(&self) -> DateTime<Utc> {
+        match self {
+            Self::Note(note) => note.imported_at,
+            Self::Thought(thought) => thought.imported_at,
+        }
+    }
+}
+
+/// QueryDocument is the view of a note or a thought a query is evaluated on.
+#[derive(Debug, Clone)]
+pub struct QueryDocument<'a> {
+    /// Whether the document is a note or a thought.
+    pub kind: SearchDocumentKind,
+
+    /// The project of the document.
+    pub project_id: Uuid,
+
+    /// The stylo that wrote the document.
+    pub stylo_id: Uuid,
+
+    /// The import date of the document.
+    pub imported_at: DateTime<Utc>,
+
+    /// The tags of the document, empty for notes.
+    pub tags: &'a [String],
+
+    /// The variation of thoughts.
+    pub variation: Option<ThoughtVariation>,
+
+    /// The status of thoughts.
+    pub status: Option<ThoughtStatus>,
+
+    /// Whether the document is a refuted thought.
+    pub refuted: bool,
+
+    /// The normalized words of the content, in order.
+    pub terms: Vec<String>,
+}
+
+impl<'a> QueryDocument<'a> {
+    /// View of a note.
+    pub fn from_note(note: &'a Note) -> Self {
+        Self {
+            kind: SearchDocumentKind::Note,
+            project_id: note.project_id,
+            stylo_id: note.stylo_id,
+            imported_at: note.imported_at,
+            tags: &[],
+            variation: None,
+            status: None,
+            refuted: false,
+            terms: tokenize(&note.content)
+                .into_iter()
+                .map(|t| t.term)
+                .collect(),
+        }
+    }
+
+    /// View of a thought.
+    pub fn from_thought(thought: &'a Thought) -> Self {
+        Self {
+            kind: SearchDocumentKind::Thought,
+            project_id: thought.project_id,
+            stylo_id: thought.stylo_id,
+            imported_at: thought.imported_at,
+            tags: &thought.tags,
+            variation: Some(thought.variation),
+            status: Some(thought.status),
+            refuted: thought.refuted_by.is_some(),
+            terms: tokenize(&thought.content)
+                .into_iter()
+                .map(|t| t.term)
+                .collect(),
+        }
+    }
+}
+
+impl QueryNode {
+    /// Tell if a document matches the node.
+    /// Project slugs are resolved with the given identifiers, a project
+    /// missing from them matches nothing.
+    pub fn matches(&self, document: &QueryDocument, projects: &HashMap<String, Uuid>) -> bool {
+        match self {
+            Self::Word(word) => document.terms.contains(word),
+            Self::Phrase(words) => document
+                .terms
+                .windows(words.len())
+                .any(|window| window == words.as_slice()),
+            Self::Field(field) => field.matches(document, projects),
+            Self::Not(node) => !node.matches(document, projects),
+            Self::And(nodes) => nodes.iter().all(|node| node.matches(document, projects)),
+            Self::Or(nodes) => nodes.iter().any(|node| node.matches(document, projects)),
+        }
+    }
+
+    /// The project slugs the node refers to, wherever they are.
+    pub fn project_slugs(&self) -> Vec<&str> {
+        match self {
+            Self::Field(QueryField::Project(slug)) => vec![slug.as_str()],
+            Self::Not(node) => node.project_slugs(),
+            Self::And(nodes) | Self::Or(nodes) => {
+                nodes.iter().flat_map(|node| node.project_slugs()).collect()
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    /// The project slugs a matching document must belong to one of, None if
+    /// the node does not restrict the projects.
+    pub fn required_projects(&self) -> Option<Vec<&str>> {
+        match self {
+            Self::Field(QueryField::Project(slug)) => Some(vec![slug.as_str()]),
+            Self::And(nodes) => nodes.iter().find_map(|node| node.required_projects()),
+            Self::Or(nodes) => {
+                let mut slugs = Vec::new();
+                for node in nodes {
+                    slugs.extend(node.required_projects()?);
+                }
+                Some(slugs)
+            }
+            _ => None,
+        }
+    }
+}
+
+impl QueryField {
+    fn matches(&self, document: &QueryDocument, projects: &HashMap<String, Uuid>) -> bool {
+        match self {
+            Self::Tag(tag) => document.tags.contains(tag),
+            Self::Project(slug) => projects.get(slug) == Some(&document.project_id),
+            Self::Author(stylo_id) => document.stylo_id == *stylo_id,
+            Self::Created(range) => range.contains(&document.imported_at),
+            Self::Variation(variation) => document.variation == Some(*variation),
+            Self::Status(status) => document.status == Some(*status),
+            Self::Kind(kind) => document.kind == *kind,
+            Self::Refuted => document.refuted,
+        }
+    }
+}
+
+/// Query is a parsed search query.
+///
+/// Words must all be found in the content, accents and case aside, and
+/// quoted phrases must be found as is. Fields restrict the documents on their
+/// properties:
+/// * `tag:ethics`
+/// * `project:reading-list`
+/// * `author:<stylo identifier>`
+/// * `created:2026-01-01`, `created:>2026-01-01`, also with `>=`, `<` and `<=`
+/// * `variation:question`
+/// * `status:disputed`
+/// * `kind:note`
+///
+/// The `refuted` keyword matches the refuted thoughts, quote it to search the
+/// word. Criteria are combined with `AND`, which may be omitted, `OR` and
+/// `NOT`, which may be written `-`, and grouped with parentheses.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Query {
+    /// The root of the syntax tree.
+    pub root: QueryNode,
+}
+
+impl Query {
+    /// Parse a query.
+    pub fn parse(text: &str) -> Result<Self, QuerySyntaxError> {
+        let tokens = lex(text)?;
+        let mut parser = Parser {
+            tokens,
+            position: 0,
+            length: text.chars().count(),
+        };
+
+        if parser.tokens.is_empty() {
+            return Err(QuerySyntaxError::new(
+                QueryErrorKind::EmptyQuery,
+                "The query is empty.",
+                0,
+                parser.length,
+            ));
+        }
+        let root = parser.parse_or()?;
+        if let Some(token) = parser.peek() {
+            return Err(parser.unexpected(token));
+        }
+
+        Ok(Self { root })
+    }
+
+    /// Tell if a document matches the query.
+    pub fn matches(&self, document: &QueryDocument, projects: &HashMap<String, Uuid>) -> bool {
+        self.root.matches(document, projects)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum TokenKind {
+    OpenParenthesis,
+    CloseParenthesis,
+    Minus,
+    And,
+    Or,
+    Not,
+    Word(String),
+    Phrase(String),
+    Field {
+        name: String,
+        value: String,
+        value_start: usize,
+    },
+}
+
+#[derive(Debug, Clone)]
+struct Token {
+    kind: TokenKind,
+    start: usize,
+    end: usize,
+}
+
+/// Read a quoted text starting at the given double quote, returns the text
+/// and the position after the closing quote.
+fn lex_phrase(chars: &[char], start: usize) -> Result<(String, usize), QuerySyntaxError> {
+    match chars[start + 1..].iter().position(|c| *c == '"') {
+        Some(length) => Ok((
+            chars[start + 1..start + 1 + length].iter().collect(),
+            start + length + 2,
+        )),
+        None => Err(QuerySyntaxError::new(
+            QueryErrorKind::UnclosedPhrase,
+            "The quoted phrase is not closed.",
+            start,
+            chars.len(),
+        )),
+    }
+}
+
+fn lex(text: &str) -> Result<Vec<Token>, QuerySyntaxError> {
+    let chars: Vec<char> = text.chars().collect();
+    let is_delimiter = |c: char| c.is_whitespace() || c == '(' || c == ')' || c == '"';
+    let mut tokens = Vec::new();
+    let mut position = 0;
+
+    while position < chars.len() {
+        let start = position;
+        let c = chars[position];
+        let kind = match c {
+            c if c.is_whitespace() => {
+                position += 1;
+                continue;
+            }
+            '(' => {
+                position += 1;
+                TokenKind::OpenParenthesis
+            }
+            ')' => {
+                position += 1;
+                TokenKind::CloseParenthesis
+            }
+            '-' if chars.get(position + 1).is_some_and(|c| !c.is_whitespace()) => {
+                position += 1;
+                TokenKind::Minus
+            }
+            '"' => {
+                let (phrase, end) = lex_phrase(&chars, position)?;
+                position = end;
+                TokenKind::Phrase(phrase)
+            }
+            _ => {
+                while position < chars.len() && !is_delimiter(chars[position]) {
+                    if chars[position] == ':' && position > start {
+                        break;
+                    }
+                    position += 1;
+                }
+                let word: String = chars[start..position].iter().collect();
+                if chars.get(position) == Some(&':') {
+                    position += 1;
+                    let value_start = position;
+                    let value = if chars.get(position) == Some(&'"') {
+                        let (value, end) = lex_phrase(&chars, position)?;
+                        position = end;
+                        value
+                    } else {
+                        while position < chars.len() && !is_delimiter(chars[position]) {
+                            position += 1;
+                        }
+                        chars[value_start..position].iter().collect()
+                    };
+                    TokenKind::Field {
+                        name: word,
+                        value,
+                        value_start,
+                    }
+                } else {
+                    match word.as_str() {
+                        "AND" => TokenKind::And,
+                        "OR" => TokenKind::Or,
+                        "NOT" => TokenKind::Not,
+                        _ => TokenKind::Word(word),
+                    }
+                }
+            }
+        };
+        tokens.push(Token {
+            kind,
+            start,
+            end: position,
+        });
+    }
+
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+    length: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<Token> {
+        self.tokens.get(self.position).cloned()
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.peek();
+        self.position += 1;
+        token
+    }
+
+    fn unexpected(&self, token: Token) -> QuerySyntaxError {
+        let message = match token.kind {
+            TokenKind::CloseParenthesis => "This parenthesis has not been opened.",
+            TokenKind::And | TokenKind::Or => "An expression is expected before this operator.",
+            _ => "This is not expected here.",
+        };
+
+        QuerySyntaxError::new(
+            QueryErrorKind::UnexpectedToken,
+            message,
+            token.start,
+            token.end,
+        )
+    }
+
+    /// Error when the query ends after the given operator.
+    fn missing_operand(&self, operator: &Token) -> QuerySyntaxError {
+        QuerySyntaxError::new(
+            QueryErrorKind::UnexpectedEnd,
+            "An expression is expected after this operator.",
+            operator.start,
+            self.length,
+        )
+    }
+
+    fn starts_operand(token: &Token) -> bool {
+        !matches!(
+            token.kind,
+            TokenKind::And | TokenKind::Or | TokenKind::CloseParenthesis
+        )
+    }
+
+    fn parse_or(&mut self) -> Result<QueryNode, QuerySyntaxError> {
+        let mut nodes = vec![self.parse_and()?];
+
+        while let Some(token) = self.peek().filter(|t| t.kind == TokenKind::Or) {
+            self.position += 1;
+            match self.peek() {
+                Some(next) if Self::starts_operand(&next) => nodes.push(self.parse_and()?),
+                Some(next) => return Err(self.unexpected(next)),
+                None => return Err(self.missing_operand(&token)),
+            }
+        }
+
+        Ok(if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            QueryNode::Or(nodes)
+        })
+    }
+
+    fn parse_and(&mut self) -> Result<QueryNode, QuerySyntaxError> {
+        let mut nodes = vec![self.parse_unary()?];
+
+        while let Some(token) = self.peek() {
+            if token.kind == TokenKind::And {
+                self.position += 1;
+                match self.peek() {
+                    Some(next) if Self::starts_operand(&next) => nodes.push(self.parse_unary()?),
+                    Some(next) => return Err(self.unexpected(next)),
+                    None => return Err(self.missing_operand(&token)),
+                }
+            } else if Self::starts_operand(&token) {
+                nodes.push(self.parse_unary()?);
+            } else {
+                break;
+            }
+        }
+
+        Ok(if nodes.len() == 1 {
+            nodes.remove(0)
+        } else {
+            QueryNode::And(nodes)
+        })
+    }
+
+    fn parse_unary(&mut self) -> Result<QueryNode, QuerySyntaxError> {
+        let Some(token) = self.next() else {
+            return Err(QuerySyntaxError::new(
+                QueryErrorKind::UnexpectedEnd,
+                "An expression is expected.",
+                self.length,
+                self.length,
+            ));
+        };
+
+        match token.kind {
+            TokenKind::Minus | TokenKind::Not => match self.peek() {
+                Some(next) if Self::starts_operand(&next) => {
+                    Ok(QueryNode::Not(Box::new(self.parse_unary()?)))
+                }
+                Some(next) => Err(self.unexpected(next)),
+                None => Err(self.missing_operand(&token)),
+            },
+            TokenKind::OpenParenthesis => {
+                let node = self.parse_or()?;
+                match self.next() {
+                    Some(Token {
+                        kind: TokenKind::CloseParenthesis,
+                        ..
+                    }) => Ok(node),
+                    _ => Err(QuerySyntaxError::new(
+                        QueryErrorKind::UnclosedParenthesis,
+                        "This parenthesis is not closed.",
+                        token.start,
+                        token.end,
+                    )),
+                }
+            }
+            TokenKind::Word(ref word) if word == "refuted" => {
+                Ok(QueryNode::Field(QueryField::Refuted))
+            }
+            TokenKind::Word(ref word) => words_node(word, &token),
+            TokenKind::Phrase(ref phrase) => words_node(phrase, &token),
+            TokenKind::Field {
+                ref name,
+                ref value,
+                value_start,
+            } => parse_field(name, value, &token, value_start).map(QueryNode::Field),
+            _ => Err(self.unexpected(token)),
+        }
+    }
+}
+
+/// Node matching the words of a text, a phrase if there are several.
+fn words_node(text: &str, token: &Token) -> Result<QueryNode, QuerySyntaxError> {
+    let mut words: Vec<String> = tokenize(text).into_iter().map(|t| t.term).collect();
+
+    match words.len() {
+        0 => Err(QuerySyntaxError::new(
+            QueryErrorKind::InvalidValue,
+            "There is no word to search here.",
+            token.start,
+            token.end,
+        )),
+        1 => Ok(QueryNode::Word(words.remove(0))),
+        _ => Ok(QueryNode::Phrase(words)),
+    }
+}
+
+fn parse_field(
+    name: &str,
+    value: &str,
+    token: &Token,
+    value_start: usize,
+) -> Result<QueryField, QuerySyntaxError> {
+    let invalid = |message: String| {
+        QuerySyntaxError::new(
+            QueryErrorKind::InvalidValue,
+            message,
+            value_start.min(token.end),
+            token.end,
+        )
+    };
+    if value.is_empty() {
+        return Err(invalid(format!("A value is expected after '{name}:'.")));
+    }
+
+    match name {
+        "tag" => super::normalize_tag(value)
+            .map(QueryField::Tag)
+            .ok_or_else(|| invalid(format!("'{value}' is not a valid tag."))),
+        "project" => Ok(QueryField::Project(value.to_string())),
+        "author" => Uuid::parse_str(value)
+            .map(QueryField::Author)
+            .map_err(|_| invalid(format!("'{value}' is not a stylo identifier."))),
+        "created" => parse_date_range(value)
+            .map(QueryField::Created)
+            .ok_or_else(|| {
+                invalid(format!(
+                    "'{value}' is not a date like 2026-01-01, optionally preceded by >, >=, < or <=."
+                ))
+            }),
+        "variation" => value
+            .parse()
+            .map(QueryField::Variation)
+            .map_err(|e: anyhow::Error| invalid(e.to_string())),
+        "status" => value
+            .parse()
+            .map(QueryField::Status)
+            .map_err(|e: anyhow::Error| invalid(e.to_string())),
+        "kind" => match value {
+            "note" => Ok(QueryField::Kind(SearchDocumentKind::Note)),
+            "thought" => Ok(QueryField::Kind(SearchDocumentKind::Thought)),
+            _ => Err(invalid(format!("Unknown document kind '{value}'."))),
+        },
+        _ => Err(QuerySyntaxError::new(
+            QueryErrorKind::UnknownField,
+            format!("Unknown field '{name}'."),
+            token.start,
+            token.start + name.chars().count(),
+        )),
+    }
+}
+
+/// Parse a date preceded by an optional comparison operator.
+fn parse_date_range(value: &str) -> Option<DateRange> {
+    let (operator, date) = [">=", "<=", ">", "<", "="]
+        .into_iter()
+        .find_map(|operator| value.strip_prefix(operator).map(|date| (operator, date)))
+        .unwrap_or(("=", value));
+    let date = NaiveDate::parse_from_str(date, "%Y-%m-%d").ok()?;
+    let day = date.and_hms_opt(0, 0, 0)?.and_utc();
+    let next_day = day.checked_add_days(Days::new(1))?;
+
+    let (from, until) = match operator {
+        ">" => (Some(next_day), None),
+        ">=" => (Some(day), None),
+        "<" => (None, Some(day)),
+        "<=" => (None, Some(next_day)),
+        _ => (Some(day), Some(next_day)),
+    };
+
+    Some(DateRange { from, until })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn word(word: &str) -> QueryNode {
+        QueryNode::Word(word.to_string())
+    }
+
+    #[test]
+    fn test_parse() {
+        let stylo_id = Uuid::new_v4();
+        let query = Query::parse(&format!(
+            "tag:ethics AND project:reading-list -refuted author:{stylo_id} \
+             created:>2026-01-01 \"Exact phrase\""
+        ))
+        .unwrap();
+
+        assert_eq!(
+            query.root,
+            QueryNode::And(vec![
+                QueryNode::Field(QueryField::Tag("ethics".to_string())),
+                QueryNode::Field(QueryField::Project("reading-list".to_string())),
+                QueryNode::Not(Box::new(QueryNode::Field(QueryField::Refuted))),
+                QueryNode::Field(QueryField::Author(stylo_id)),
+                QueryNode::Field(QueryField::Created(DateRange {
+                    from: Some("2026-01-02T00:00:00Z".parse().unwrap()),
+                    until: None,
+                })),
+                QueryNode::Phrase(vec!["exact".to_string(), "phrase".to_string()]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_precedence() {
+        let query = Query::parse("a b OR NOT (c OR d) e").unwrap();
+
+        assert_eq!(
+            query.root,
+            QueryNode::Or(vec![
+                QueryNode::And(vec![word("a"), word("b")]),
+                QueryNode::And(vec![
+                    QueryNode::Not(Box::new(QueryNode::Or(vec![word("c"), word("d")]))),
+                    word("e"),
+                ]),
+            ])
+        );
+        assert_eq!(
+            Query::parse("\"refuted\" self-aware").unwrap().root,
+            QueryNode::And(vec![
+                word("refuted"),
+                QueryNode::Phrase(vec!["self".to_string(), "aware".to_string()]),
+            ])
+        );
+    }
+
+    #[test]
+    fn test_syntax_errors() {
+        let error = |text: &str| Query::parse(text).unwrap_err();
+
+        assert_eq!(error("  ").kind, QueryErrorKind::EmptyQuery);
+        let e = error("ethics \"open phrase");
+        assert_eq!(
+            (e.kind, e.start, e.end),
+            (QueryErrorKind::UnclosedPhrase, 7, 19)
+        );
+        let e = error("(a OR b");
+        assert_eq!(
+            (e.kind, e.start, e.end),
+            (QueryErrorKind::UnclosedParenthesis, 0, 1)
+        );
+        let e = error("a)");
+        assert_eq!(
+            (e.kind, e.start, e.end),
+            (QueryErrorKind::UnexpectedToken, 1, 2)
+        );
+        let e = error("OR a");
+        assert_eq!(
+            (e.kind, e.start, e.end),
+            (QueryErrorKind::UnexpectedToken, 0, 2)
+        );
+        let e = error("a AND");
+        assert_eq!(
+            (e.kind, e.start, e.end),
+            (QueryErrorKind::UnexpectedEnd, 2, 5)
+        );
+        let e = error("a colour:blue");
+        assert_eq!(
+            (e.kind, e.start, e.end),
+            (QueryErrorKind::UnknownField, 2, 8)
+        );
+        let e = error("éé created:>yesterday");
+        assert_eq!(
+            (e.kind, e.start, e.end),
+            (QueryErrorKind::InvalidValue, 11, 21)
+        );
+        let e = error("author:nobody");
+        assert_eq!(
+            (e.kind, e.start, e.end),
+            (QueryErrorKind::InvalidValue, 7, 13)
+        );
+        assert_eq!(error("tag:").kind, QueryErrorKind::InvalidValue);
+        assert_eq!(error("variation:answer").kind, QueryErrorKind::InvalidValue);
+    }
+
+    #[test]
+    fn test_matches() {
+        let project_id = Uuid::new_v4();
+        let projects = HashMap::from([("reading-list".to_string(), project_id)]);
+        let tags = vec!["ethics".to_string()];
+        let document = QueryDocument {
+            kind: SearchDocumentKind::Thought,
+            project_id,
+            stylo_id: Uuid::new_v4(),
+            imported_at: "2026-03-01T10:00:00Z".parse().unwrap(),
+            tags: &tags,
+            variation: Some(ThoughtVariation::Thought),
+            status: Some(ThoughtStatus::Standing),
+            refuted: false,
+            terms: tokenize("Virtue is an exact phrase.")
+                .into_iter()
+                .map(|t| t.term)
+                .collect(),
+        };
+        let matches = |text: &str| Query::parse(text).unwrap().matches(&document, &projects);
+
+        assert!(matches(
+            "tag:ethics AND project:reading-list -refuted created:>2026-01-01 \"exact phrase\""
+        ));
+        assert!(!matches("\"phrase exact\""));
+        assert!(!matches("project:garden"));
+        assert!(matches("vice OR virtue"));
+        assert!(!matches("virtue -(ethics OR tag:ethics)"));
+        assert!(matches("created:2026-03-01 created:<=2026-03-01"));
+        assert!(!matches("created:<2026-03-01"));
+        assert!(matches("kind:thought status:standing variation:thought"));
+        assert!(!matches("kind:note"));
+    }
+
+    #[test]
+    fn test_required_projects() {
+        let slugs = |text: &str| {
+            let query = Query::parse(text).unwrap();
+            let slugs = query.root.required_projects();
+            slugs.map(|slugs| slugs.join(" "))
+        };
+
+        assert_eq!(slugs("a project:x"), Some("x".to_string()));
+        assert_eq!(
+            slugs("project:x OR (project:y tag:z)"),
+            Some("x y".to_string())
+        );
+        assert_eq!(slugs("project:x OR a"), None);
+        assert_eq!(slugs("-project:x"), None);
+        assert_eq!(
+            Query::parse("-project:x OR project:y")
+                .unwrap()
+                .root
+                .project_slugs(),
+            vec!["x", "y"]
+        );
+    }
+}
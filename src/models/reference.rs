@@ -0,0 +1,439 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::Result;
+
+/// ReferenceIdentifier is a type alias for a UUID that represents a reference identifier.
+pub type ReferenceIdentifier = Uuid;
+
+/// Reference is a bibliographic reference, books mostly, that thoughts and
+/// notes of a project may cite.
+/// Its citation key is unique in the project.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Reference {
+    /// The unique identifier of the reference.
+    pub reference_id: ReferenceIdentifier,
+
+    /// The unique identifier of the project the reference belongs to.
+    pub project_id: Uuid,
+
+    /// The BibTeX entry type, like `book` or `article`.
+    pub entry_type: String,
+
+    /// The citation key, like `kant1785`.
+    pub citation_key: String,
+
+    /// The authors, in order.
+    pub authors: Vec<String>,
+
+    /// The title of the work.
+    pub title: String,
+
+    /// The publication year, if known.
+    pub year: Option<i32>,
+
+    /// The pages cited, like `12--15`, if any.
+    pub pages: Option<String>,
+
+    /// The other BibTeX fields, kept so references round-trip.
+    #[serde(default)]
+    pub extra_fields: BTreeMap<String, String>,
+}
+
+/// CreateReferenceCommand is a command that is used to create a new reference.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateReferenceCommand {
+    /// The BibTeX entry type, like `book` or `article`.
+    #[serde(default = "default_entry_type")]
+    pub entry_type: String,
+
+    /// The citation key, like `kant1785`.
+    pub citation_key: String,
+
+    /// The authors, in order.
+    #[serde(default)]
+    pub authors: Vec<String>,
+
+    /// The title of the work.
+    pub title: String,
+
+    /// The publication year, if known.
+    #[serde(default)]
+    pub year: Option<i32>,
+
+    /// The pages cited, like `12--15`, if any.
+    #[serde(default)]
+    pub pages: Option<String>,
+
+    /// The other BibTeX fields.
+    #[serde(default)]
+    pub extra_fields: BTreeMap<String, String>,
+}
+
+fn default_entry_type() -> String {
+    "book".to_string()
+}
+
+impl CreateReferenceCommand {
+    /// Check the reference can be created.
+    /// The citation key must be a single BibTeX word and the title must not be
+    /// empty.
+    pub fn validate(&self) -> Result<()> {
+        let citation_key = self.citation_key.trim();
+
+        if citation_key.is_empty()
+            || citation_key
+                .chars()
+                .any(|c| c.is_whitespace() || matches!(c, ',' | '{' | '}' | '"' | '@'))
+        {
+            return Err(anyhow!("Invalid citation key '{citation_key}'."));
+        }
+        if self.entry_type.trim().is_empty()
+            || !self.entry_type.trim().chars().all(char::is_alphanumeric)
+        {
+            return Err(anyhow!("Invalid entry type '{}'.", self.entry_type));
+        }
+        if self.title.trim().is_empty() {
+            return Err(anyhow!("The title of '{citation_key}' cannot be empty."));
+        }
+
+        Ok(())
+    }
+
+    /// Parse the entries of a BibTeX document.
+    ///
+    /// `@comment`, `@preamble` and `@string` entries are ignored. An error is
+    /// raised with the position of the faulty entry if the document cannot be
+    /// parsed.
+    pub fn from_bibtex(input: &str) -> Result<Vec<Self>> {
+        let mut commands = Vec::new();
+        let mut parser = BibParser { input, position: 0 };
+
+        while let Some(entry_type) = parser.next_entry_type() {
+            let start = parser.position;
+            let entry_type = entry_type.to_lowercase();
+            if matches!(entry_type.as_str(), "comment" | "preamble" | "string") {
+                parser.skip_block()?;
+                continue;
+            }
+            let command = parser
+                .parse_entry(entry_type)
+                .map_err(|e| anyhow!("Invalid BibTeX entry at byte {start}: {e}"))?;
+            commands.push(command);
+        }
+
+        Ok(commands)
+    }
+}
+
+impl Reference {
+    /// Create a new reference in a project.
+    /// An error is raised if the command is not valid.
+    pub fn create(command: CreateReferenceCommand, project_id: Uuid) -> Result<Self> {
+        command.validate()?;
+
+        Ok(Self {
+            reference_id: Uuid::new_v4(),
+            project_id,
+            entry_type: command.entry_type.trim().to_lowercase(),
+            citation_key: command.citation_key.trim().to_string(),
+            authors: command.authors,
+            title: command.title.trim().to_string(),
+            year: command.year,
+            pages: command.pages,
+            extra_fields: command.extra_fields,
+        })
+    }
+
+    /// Format the reference as a BibTeX entry.
+    pub fn to_bibtex(&self) -> String {
+        let mut fields: Vec<(&str, String)> = Vec::new();
+        if !self.authors.is_empty() {
+            fields.push(("author", self.authors.join(" and ")));
+        }
+        fields.push(("title", self.title.clone()));
+        if let Some(year) = self.year {
+            fields.push(("year", year.to_string()));
+        }
+        if let Some(pages) = &self.pages {
+            fields.push(("pages", pages.clone()));
+        }
+        for (name, value) in &self.extra_fields {
+            fields.push((name, value.clone()));
+        }
+
+        let mut entry = format!("@{}{{{},\n", self.entry_type, self.citation_key);
+        for (name, value) in fields {
+            let _ = writeln!(entry, "  {name} = {{{value}}},");
+        }
+        entry.push_str("}\n");
+
+        entry
+    }
+}
+
+/// Minimal BibTeX parser, it reads the document entry by entry.
+struct BibParser<'a> {
+    input: &'a str,
+    position: usize,
+}
+
+impl BibParser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.position..]
+    }
+
+    fn skip_whitespaces(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn expect(&mut self, expected: char) -> Result<()> {
+        self.skip_whitespaces();
+        match self.peek() {
+            Some(c) if c == expected => {
+                self.position += c.len_utf8();
+                Ok(())
+            }
+            Some(c) => Err(anyhow!("expected '{expected}', found '{c}'")),
+            None => Err(anyhow!(
+                "expected '{expected}', found the end of the document"
+            )),
+        }
+    }
+
+    /// Move to the next `@type` and return the type, text outside entries is
+    /// a comment in BibTeX.
+    fn next_entry_type(&mut self) -> Option<String> {
+        let at = self.rest().find('@')?;
+        self.position += at + 1;
+        let name = self.read_name();
+
+        Some(name)
+    }
+
+    fn read_name(&mut self) -> String {
+        self.skip_whitespaces();
+        let name: String = self
+            .rest()
+            .chars()
+            .take_while(|c| c.is_alphanumeric() || matches!(c, '_' | '-' | ':' | '.' | '/'))
+            .collect();
+        self.position += name.len();
+
+        name
+    }
+
+    /// Skip a braced or parenthesized block.
+    fn skip_block(&mut self) -> Result<()> {
+        self.skip_whitespaces();
+        let closing = match self.peek() {
+            Some('{') => '}',
+            Some('(') => ')',
+            _ => return Ok(()),
+        };
+        self.position += 1;
+        let mut depth = 0;
+        for (index, c) in self.rest().char_indices() {
+            match c {
+                '{' => depth += 1,
+                '}' if depth > 0 => depth -= 1,
+                c if c == closing && depth == 0 => {
+                    self.position += index + 1;
+                    return Ok(());
+                }
+                _ => {}
+            }
+        }
+
+        Err(anyhow!("unterminated block"))
+    }
+
+    fn parse_entry(&mut self, entry_type: String) -> Result<CreateReferenceCommand> {
+        self.expect('{')?;
+        let citation_key = self.read_name();
+        if citation_key.is_empty() {
+            return Err(anyhow!("missing citation key"));
+        }
+        let mut fields: BTreeMap<String, String> = BTreeMap::new();
+
+        loop {
+            self.skip_whitespaces();
+            match self.peek() {
+                Some('}') => {
+                    self.position += 1;
+                    break;
+                }
+                Some(',') => {
+                    self.position += 1;
+                    continue;
+                }
+                None => return Err(anyhow!("unterminated entry '{citation_key}'")),
+                _ => {}
+            }
+            let name = self.read_name().to_lowercase();
+            if name.is_empty() {
+                return Err(anyhow!("invalid field in entry '{citation_key}'"));
+            }
+            self.expect('=')?;
+            let value = self.read_value()?;
+            fields.insert(name, value);
+        }
+
+        let title = fields.remove("title").unwrap_or_default();
+        let authors = fields
+            .remove("author")
+            .map(|authors| {
+                authors
+                    .split(" and ")
+                    .map(|author| author.trim().to_string())
+                    .filter(|author| !author.is_empty())
+                    .collect()
+            })
+            .unwrap_or_default();
+        let year = match fields.remove("year") {
+            Some(year) => Some(
+                year.trim()
+                    .parse()
+                    .map_err(|_| anyhow!("invalid year '{year}'"))?,
+            ),
+            None => None,
+        };
+        let pages = fields.remove("pages");
+
+        Ok(CreateReferenceCommand {
+            entry_type,
+            citation_key,
+            authors,
+            title,
+            year,
+            pages,
+            extra_fields: fields,
+        })
+    }
+
+    /// Read a field value, braced, quoted or bare, possibly concatenated with `#`.
+    fn read_value(&mut self) -> Result<String> {
+        let mut value = String::new();
+
+        loop {
+            self.skip_whitespaces();
+            match self.peek() {
+                Some('{') => {
+                    self.position += 1;
+                    let mut depth = 0;
+                    let mut end = None;
+                    for (index, c) in self.rest().char_indices() {
+                        match c {
+                            '{' => depth += 1,
+                            '}' if depth > 0 => depth -= 1,
+                            '}' => {
+                                end = Some(index);
+                                break;
+                            }
+                            _ => {}
+                        }
+                    }
+                    let end = end.ok_or_else(|| anyhow!("unterminated braced value"))?;
+                    value.push_str(&self.rest()[..end]);
+                    self.position += end + 1;
+                }
+                Some('"') => {
+                    self.position += 1;
+                    let end = self
+                        .rest()
+                        .find('"')
+                        .ok_or_else(|| anyhow!("unterminated quoted value"))?;
+                    value.push_str(&self.rest()[..end]);
+                    self.position += end + 1;
+                }
+                _ => {
+                    let bare = self.read_name();
+                    if bare.is_empty() {
+                        return Err(anyhow!("missing field value"));
+                    }
+                    value.push_str(&bare);
+                }
+            }
+            self.skip_whitespaces();
+            if self.peek() != Some('#') {
+                break;
+            }
+            self.position += 1;
+        }
+
+        Ok(value.split_whitespace().collect::<Vec<_>>().join(" "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const BIBTEX: &str = r#"
+This text is a comment.
+@comment{ignored}
+@Book{kant1785,
+  Author = {Immanuel Kant and Mary {Gregor}},
+  title = "Groundwork of the Metaphysics of Morals",
+  year = 1785,
+  pages = {12--15},
+  publisher = {Hartknoch},
+}
+@article{mill1861, title = {Utilitarianism}, journal = "Fraser's" # { Magazine}}
+"#;
+
+    #[test]
+    fn test_parse_bibtex() {
+        let commands = CreateReferenceCommand::from_bibtex(BIBTEX).unwrap();
+
+        assert_eq!(commands.len(), 2);
+        let kant = &commands[0];
+        assert_eq!(kant.entry_type, "book");
+        assert_eq!(kant.citation_key, "kant1785");
+        assert_eq!(kant.authors, vec!["Immanuel Kant", "Mary {Gregor}"]);
+        assert_eq!(kant.title, "Groundwork of the Metaphysics of Morals");
+        assert_eq!(kant.year, Some(1785));
+        assert_eq!(kant.pages.as_deref(), Some("12--15"));
+        assert_eq!(kant.extra_fields["publisher"], "Hartknoch");
+        assert_eq!(commands[1].extra_fields["journal"], "Fraser's Magazine");
+    }
+
+    #[test]
+    fn test_parse_invalid_bibtex() {
+        assert!(CreateReferenceCommand::from_bibtex("@book{key, title = {Open").is_err());
+        assert!(CreateReferenceCommand::from_bibtex("@book{key, year = {soon}}").is_err());
+    }
+
+    #[test]
+    fn test_bibtex_round_trip() {
+        let project_id = Uuid::new_v4();
+        let references: Vec<Reference> = CreateReferenceCommand::from_bibtex(BIBTEX)
+            .unwrap()
+            .into_iter()
+            .map(|command| Reference::create(command, project_id).unwrap())
+            .collect();
+        let exported: String = references.iter().map(Reference::to_bibtex).collect();
+        let commands = CreateReferenceCommand::from_bibtex(&exported).unwrap();
+
+        assert_eq!(commands.len(), references.len());
+        for (reference, command) in references.iter().zip(commands) {
+            let imported = Reference::create(command, project_id).unwrap();
+            assert_eq!(
+                Reference {
+                    reference_id: reference.reference_id,
+                    ..imported
+                },
+                *reference
+            );
+        }
+    }
+}
@@ -0,0 +1,92 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{SearchDocumentKind, StyloIdentifier};
+
+/// SavedSearchIdentifier is a type alias for a UUID that represents a saved
+/// search identifier.
+pub type SavedSearchIdentifier = Uuid;
+
+/// SavedSearch is a named query a stylo keeps to run it again.
+/// Its name is unique among the saved searches of the stylo.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SavedSearch {
+    /// The unique identifier of the saved search.
+    pub saved_search_id: SavedSearchIdentifier,
+
+    /// The stylo that saved the search.
+    pub stylo_id: StyloIdentifier,
+
+    /// The name of the search, given by the stylo.
+    pub name: String,
+
+    /// The query language expression, see [`super::Query`].
+    pub query: String,
+
+    /// The date and time the search was saved.
+    pub created_at: DateTime<Utc>,
+}
+
+/// CreateSavedSearchCommand is a command that is used to save a search.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreateSavedSearchCommand {
+    /// The stylo saving the search.
+    pub stylo_id: StyloIdentifier,
+
+    /// The name of the search.
+    pub name: String,
+
+    /// The query language expression.
+    pub query: String,
+}
+
+/// ModifySavedSearchCommand is a command that is used to modify a saved
+/// search. Unset fields are left unchanged.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ModifySavedSearchCommand {
+    /// The new name of the search.
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// The new query language expression.
+    #[serde(default)]
+    pub query: Option<String>,
+}
+
+/// SavedSearchResult is a document created after a search was saved and
+/// matching it, that the stylo has not seen yet.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct SavedSearchResult {
+    /// The saved search the document matches.
+    pub saved_search_id: SavedSearchIdentifier,
+
+    /// The stylo that saved the search.
+    pub stylo_id: StyloIdentifier,
+
+    /// Whether the document is a note or a thought.
+    pub kind: SearchDocumentKind,
+
+    /// The identifier of the note or the thought.
+    pub id: Uuid,
+
+    /// The project of the document.
+    pub project_id: Uuid,
+
+    /// The date and time the document was found to match.
+    pub matched_at: DateTime<Utc>,
+}
+
+impl SavedSearch {
+    /// Create a new saved search, the name is trimmed.
+    /// The command is expected to be validated by the caller.
+    pub fn create(command: CreateSavedSearchCommand) -> Self {
+        Self {
+            saved_search_id: Uuid::new_v4(),
+            stylo_id: command.stylo_id,
+            name: command.name.trim().to_string(),
+            query: command.query,
+            created_at: Utc::now(),
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+
+use anyhow::anyhow;
+use serde::{Deserialize, Serialize};
+use unidecode::unidecode;
+use uuid::Uuid;
+
+use super::ThoughtVariation;
+
+/// SearchMode tells how the words of a query are matched.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchMode {
+    /// Words must be found as is, accents and case aside.
+    #[default]
+    Exact,
+
+    /// Words only need to be similar enough, so typos and variants match.
+    Fuzzy,
+}
+
+/// SimilarityThresholds are the minimum trigram similarities, between 0 and
+/// 1, for two texts to be considered close.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct SimilarityThresholds {
+    /// Minimum similarity between a word of a query and a word of a document
+    /// in fuzzy searches.
+    pub word: f64,
+
+    /// Minimum similarity between an unknown project slug and the slugs
+    /// suggested instead.
+    pub slug: f64,
+}
+
+impl Default for SimilarityThresholds {
+    /// The default similarity threshold of the PostgreSQL trigram extension.
+    fn default() -> Self {
+        Self {
+            word: 0.3,
+            slug: 0.3,
+        }
+    }
+}
+
+/// SearchDocumentKind tells whether a search hit is a note or a thought.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum SearchDocumentKind {
+    /// A note.
+    Note,
+
+    /// A thought or a question.
+    Thought,
+}
+
+impl SearchDocumentKind {
+    /// Textual representation of the kind, as stored in databases.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Note => "note",
+            Self::Thought => "thought",
+        }
+    }
+}
+
+impl FromStr for SearchDocumentKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "note" => Ok(Self::Note),
+            "thought" => Ok(Self::Thought),
+            _ => Err(anyhow!("Unknown search document kind '{s}'.")),
+        }
+    }
+}
+
+/// SearchFilter restricts the documents returned by a search.
+/// Unset criteria do not filter anything. Notes have neither tags nor
+/// variation, filtering on one of these only returns thoughts.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct SearchFilter {
+    /// Only search in the project with this slug.
+    pub project: Option<String>,
+
+    /// Only search thoughts carrying this tag.
+    pub tag: Option<String>,
+
+    /// Only search thoughts of this variation.
+    pub variation: Option<ThoughtVariation>,
+
+    /// Only search documents written by this stylo.
+    pub author: Option<Uuid>,
+}
+
+/// SearchHit is a document matching a search.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct SearchHit {
+    /// Whether the document is a note or a thought.
+    pub kind: SearchDocumentKind,
+
+    /// The identifier of the note or the thought.
+    pub id: Uuid,
+
+    /// The project of the document.
+    pub project_id: Uuid,
+
+    /// The stylo that wrote the document.
+    pub stylo_id: Uuid,
+
+    /// The relevance of the document, higher is better.
+    pub score: f64,
+
+    /// An excerpt of the content around the matches, the matching words being
+    /// surrounded by `<mark>` and `</mark>`.
+    pub highlight: String,
+}
+
+/// SearchToken is a word of a text, as indexed by the search.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchToken {
+    /// The normalized word: transliterated to ASCII and lowercased.
+    pub term: String,
+
+    /// Byte offset of the word in the text.
+    pub start: usize,
+
+    /// Byte offset right after the word in the text.
+    pub end: usize,
+}
+
+/// Split a text in words.
+/// Words are runs of alphanumeric characters, they are normalized so accents
+/// and case do not matter when searching.
+pub fn tokenize(text: &str) -> Vec<SearchToken> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+
+    for (index, c) in text.char_indices().chain([(text.len(), ' ')]) {
+        match (c.is_alphanumeric(), start) {
+            (true, None) => start = Some(index),
+            (false, Some(from)) => {
+                let term: String = unidecode(&text[from..index])
+                    .to_lowercase()
+                    .chars()
+                    .filter(|c| c.is_ascii_alphanumeric())
+                    .collect();
+                if !term.is_empty() {
+                    tokens.push(SearchToken {
+                        term,
+                        start: from,
+                        end: index,
+                    });
+                }
+                start = None;
+            }
+            _ => {}
+        }
+    }
+
+    tokens
+}
+
+/// Trigrams of a text, as computed by the PostgreSQL trigram extension.
+/// Each word is prefixed with two spaces and suffixed with one before being
+/// split in sequences of three characters.
+pub fn trigrams(text: &str) -> HashSet<String> {
+    let mut trigrams = HashSet::new();
+
+    for token in tokenize(text) {
+        let padded: Vec<char> = format!("  {} ", token.term).chars().collect();
+        trigrams.extend(padded.windows(3).map(|w| w.iter().collect::<String>()));
+    }
+
+    trigrams
+}
+
+/// Similarity of two texts: the number of trigrams they share over the
+/// number of distinct trigrams of both, from 0 to 1.
+pub fn trigram_similarity(a: &str, b: &str) -> f64 {
+    similarity_of(&trigrams(a), &trigrams(b))
+}
+
+/// Similarity of two sets of trigrams, see [`trigram_similarity`].
+pub fn similarity_of(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    let shared = a.intersection(b).count();
+    let all = a.len() + b.len() - shared;
+
+    if all == 0 {
+        0.0
+    } else {
+        shared as f64 / all as f64
+    }
+}
+
+/// Texts of the candidates similar enough to a text, most similar first.
+/// Candidates equally similar are sorted alphabetically.
+pub fn most_similar<'a>(
+    text: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+    threshold: f64,
+    limit: usize,
+) -> Vec<String> {
+    let reference = trigrams(text);
+    let mut similar: Vec<(f64, &str)> = candidates
+        .into_iter()
+        .map(|candidate| (similarity_of(&reference, &trigrams(candidate)), candidate))
+        .filter(|(similarity, _)| *similarity >= threshold)
+        .collect();
+    similar.sort_by(|(a_similarity, a), (b_similarity, b)| {
+        b_similarity.total_cmp(a_similarity).then(a.cmp(b))
+    });
+
+    similar
+        .into_iter()
+        .take(limit)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tokenize() {
+        let text = "L'Éthique, à Nicomaque (#ethics)";
+        let tokens = tokenize(text);
+        let terms: Vec<&str> = tokens.iter().map(|t| t.term.as_str()).collect();
+
+        assert_eq!(terms, vec!["l", "ethique", "a", "nicomaque", "ethics"]);
+        assert_eq!(&text[tokens[1].start..tokens[1].end], "Éthique");
+        assert!(tokenize(" -- ").is_empty());
+    }
+
+    #[test]
+    fn test_trigram_similarity() {
+        assert_eq!(trigrams("cat").len(), 4);
+        assert!(trigrams("Cat").contains("  c"));
+        assert_eq!(trigram_similarity("reading-list", "reading-list"), 1.0);
+        assert_eq!(trigram_similarity("virtue", "virtu"), 5.0 / 8.0);
+        assert_eq!(trigram_similarity("abc", "xyz"), 0.0);
+        assert_eq!(trigram_similarity("", ""), 0.0);
+
+        let similar = most_similar(
+            "reading-lst",
+            ["garden", "reading-list", "reading-lists"],
+            0.3,
+            5,
+        );
+        assert_eq!(similar, vec!["reading-list", "reading-lists"]);
+        assert!(most_similar("garden", ["reading-list"], 0.3, 5).is_empty());
+    }
+}
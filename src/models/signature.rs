@@ -0,0 +1,220 @@
+use chrono::SecondsFormat;
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use uuid::Uuid;
+
+use super::{ModelEvent, ModelKind, ResponsibilityChain};
+
+/// Identifier of the key signing the events of no organization.
+pub const INSTANCE_KEY_ID: &str = "instance";
+
+/// EventSignatureError is an error type that is used to represent the reasons
+/// an event signature is refused.
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum EventSignatureError {
+    /// The event has no signature.
+    #[error("The event {0} is not signed.")]
+    Unsigned(Uuid),
+
+    /// The event is signed with the key of another signer than its chain's.
+    #[error("The event {event_id} is signed by '{signed_by}' instead of '{expected}'.")]
+    WrongSigner {
+        /// The event identifier.
+        event_id: Uuid,
+        /// The key the event is signed with.
+        signed_by: String,
+        /// The key of the responsibility chain.
+        expected: String,
+    },
+
+    /// The event has been modified after it was signed.
+    #[error("The checksum of the event {0} does not match its content.")]
+    ChecksumMismatch(Uuid),
+
+    /// The signature was not made with the key of the signer.
+    #[error("The signature of the event {0} is not valid.")]
+    InvalidSignature(Uuid),
+}
+
+/// Signature of a model event
+/// The checksum is the SHA-256 of the canonical form of the event, the
+/// signature is the Ed25519 signature of the checksum bytes. Both are in
+/// lowercase hexadecimal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventSignature {
+    /// Identifier of the signing key, see [`ModelEvent::key_id`].
+    pub key_id: String,
+
+    /// SHA-256 of the canonical form of the event.
+    pub checksum: String,
+
+    /// Ed25519 signature of the checksum.
+    pub signature: String,
+}
+
+/// The signed parts of an event, in the order they are serialized.
+#[derive(Serialize)]
+struct CanonicalEvent<'a> {
+    model: &'a ModelKind,
+    chain: &'a ResponsibilityChain,
+    timestamp: String,
+}
+
+impl ModelEvent {
+    /// Serialize the model reference, the responsibility chain and the date
+    /// of the event, always the same way, as compact JSON.
+    pub fn canonical(&self) -> String {
+        let canonical = CanonicalEvent {
+            model: &self.model,
+            chain: &self.chain,
+            timestamp: self.timestamp.to_rfc3339_opts(SecondsFormat::Nanos, true),
+        };
+
+        serde_json::to_string(&canonical).expect("An event can always be serialized.")
+    }
+
+    /// Compute the SHA-256 of the canonical form of the event.
+    pub fn checksum(&self) -> String {
+        hex::encode(Sha256::digest(self.canonical()))
+    }
+
+    /// Identifier of the key the event must be signed with: the key of the
+    /// acting organization, of the owning organization when no other
+    /// organization acts, or the instance key when the change belongs to no
+    /// organization.
+    pub fn key_id(&self) -> String {
+        self.chain
+            .actor_organization_id
+            .or(self.chain.owner_organization_id)
+            .map(|organization_id| organization_id.to_string())
+            .unwrap_or_else(|| INSTANCE_KEY_ID.to_string())
+    }
+
+    /// Sign the event with the key of its signer, replacing any previous
+    /// signature.
+    pub fn sign(&mut self, key: &SigningKey) {
+        let checksum = Sha256::digest(self.canonical());
+        self.signature = Some(EventSignature {
+            key_id: self.key_id(),
+            checksum: hex::encode(checksum),
+            signature: hex::encode(key.sign(&checksum).to_bytes()),
+        });
+    }
+
+    /// Check the event is signed by its signer and has not been modified
+    /// since.
+    pub fn verify(&self, key: &VerifyingKey) -> Result<(), EventSignatureError> {
+        let signature = self
+            .signature
+            .as_ref()
+            .ok_or(EventSignatureError::Unsigned(self.event_id))?;
+        let expected = self.key_id();
+        if signature.key_id != expected {
+            return Err(EventSignatureError::WrongSigner {
+                event_id: self.event_id,
+                signed_by: signature.key_id.clone(),
+                expected,
+            });
+        }
+
+        let checksum = Sha256::digest(self.canonical());
+        if hex::encode(checksum) != signature.checksum {
+            return Err(EventSignatureError::ChecksumMismatch(self.event_id));
+        }
+        let bytes = hex::decode(&signature.signature)
+            .ok()
+            .and_then(|bytes| <[u8; 64]>::try_from(bytes).ok())
+            .ok_or(EventSignatureError::InvalidSignature(self.event_id))?;
+        key.verify(&checksum, &Signature::from_bytes(&bytes))
+            .map_err(|_| EventSignatureError::InvalidSignature(self.event_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::NoteChangeKind;
+
+    use super::*;
+
+    fn note_event() -> ModelEvent {
+        ModelEvent::new(ModelKind::Note {
+            note_id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            change_kind: NoteChangeKind::Created,
+        })
+        .with_chain(ResponsibilityChain::stylo(Uuid::new_v4()))
+    }
+
+    fn key() -> SigningKey {
+        SigningKey::generate(&mut rand_core::OsRng)
+    }
+
+    #[test]
+    fn test_checksum() {
+        let event = note_event();
+        let checksum = event.checksum();
+        assert_eq!(checksum.len(), 64);
+
+        // The identifier and the signature are not part of the checksum.
+        let mut copy = event.clone();
+        copy.event_id = Uuid::new_v4();
+        copy.sign(&key());
+        assert_eq!(copy.checksum(), checksum);
+
+        let mut copy = event.clone();
+        copy.chain.authentication_token_id = Some(Uuid::new_v4());
+        assert_ne!(copy.checksum(), checksum);
+        let mut copy = event.clone();
+        copy.timestamp += chrono::Duration::nanoseconds(1);
+        assert_ne!(copy.checksum(), checksum);
+
+        let json = serde_json::to_string(&event).unwrap();
+        let parsed = serde_json::from_str::<ModelEvent>(&json).unwrap();
+        assert_eq!(parsed.canonical(), event.canonical());
+    }
+
+    #[test]
+    fn test_key_id() {
+        let mut event = note_event();
+        assert_eq!(event.key_id(), INSTANCE_KEY_ID);
+
+        let owner = Uuid::new_v4();
+        event.chain.owner_organization_id = Some(owner);
+        assert_eq!(event.key_id(), owner.to_string());
+        let actor = Uuid::new_v4();
+        event.chain.actor_organization_id = Some(actor);
+        assert_eq!(event.key_id(), actor.to_string());
+    }
+
+    #[test]
+    fn test_sign_and_verify() {
+        let key = key();
+        let mut event = note_event();
+        assert_eq!(
+            event.verify(&key.verifying_key()),
+            Err(EventSignatureError::Unsigned(event.event_id))
+        );
+
+        event.sign(&key);
+        assert_eq!(event.verify(&key.verifying_key()), Ok(()));
+        assert_eq!(
+            event.verify(&self::key().verifying_key()),
+            Err(EventSignatureError::InvalidSignature(event.event_id))
+        );
+
+        let mut tampered = event.clone();
+        tampered.chain.stylo_id = Some(Uuid::new_v4());
+        assert_eq!(
+            tampered.verify(&key.verifying_key()),
+            Err(EventSignatureError::ChecksumMismatch(event.event_id))
+        );
+
+        let mut tampered = event.clone();
+        tampered.chain.owner_organization_id = Some(Uuid::new_v4());
+        assert!(matches!(
+            tampered.verify(&key.verifying_key()),
+            Err(EventSignatureError::WrongSigner { .. })
+        ));
+    }
+}
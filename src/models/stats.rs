@@ -0,0 +1,102 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::{ModelEvent, ModelKind, NoteChangeKind, ThoughtChangeKind};
+
+/// ProjectStats counts the changes made in a project.
+/// They are computed from the model events only, so they can be rebuilt by
+/// replaying the event history.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProjectStats {
+    /// Project identifier
+    pub project_id: Uuid,
+
+    /// Number of notes, scratched ones aside
+    pub notes: u64,
+
+    /// Number of scratched notes
+    pub scratched_notes: u64,
+
+    /// Number of thoughts, trashed ones aside
+    pub thoughts: u64,
+
+    /// Number of trashed thoughts
+    pub trashed_thoughts: u64,
+
+    /// Number of thought modifications
+    pub modifications: u64,
+
+    /// Number of times a thought was disputed
+    pub disputes: u64,
+
+    /// Number of thoughts invalidated by a refutation
+    pub invalidations: u64,
+
+    /// Time of the last change in the project
+    pub last_change_at: Option<DateTime<Utc>>,
+}
+
+impl ProjectStats {
+    /// Count a change of the project.
+    /// Events of other projects are ignored.
+    pub fn apply(&mut self, event: &ModelEvent) {
+        if event.model.project_id() != Some(self.project_id) {
+            return;
+        }
+
+        match &event.model {
+            ModelKind::Note { change_kind, .. } => match change_kind {
+                NoteChangeKind::Created => self.notes += 1,
+                NoteChangeKind::Scratched => {
+                    self.notes = self.notes.saturating_sub(1);
+                    self.scratched_notes += 1;
+                }
+            },
+            ModelKind::Thought { change_kind, .. } => match change_kind {
+                ThoughtChangeKind::Created => self.thoughts += 1,
+                ThoughtChangeKind::Modified => self.modifications += 1,
+                ThoughtChangeKind::Disputed(_) => self.disputes += 1,
+                ThoughtChangeKind::Invalidated(_) => self.invalidations += 1,
+                ThoughtChangeKind::Trashed => {
+                    self.thoughts = self.thoughts.saturating_sub(1);
+                    self.trashed_thoughts += 1;
+                }
+            },
+            ModelKind::Project { .. } | ModelKind::Stylo { .. } => {}
+        }
+        self.last_change_at = self.last_change_at.max(Some(event.timestamp));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let project_id = Uuid::new_v4();
+        let mut stats = ProjectStats {
+            project_id,
+            ..Default::default()
+        };
+        let event = |project_id, change_kind| {
+            ModelEvent::new(ModelKind::Note {
+                note_id: Uuid::new_v4(),
+                project_id,
+                change_kind,
+            })
+        };
+
+        stats.apply(&event(project_id, NoteChangeKind::Created));
+        stats.apply(&event(project_id, NoteChangeKind::Created));
+        stats.apply(&event(project_id, NoteChangeKind::Scratched));
+        let last = event(project_id, NoteChangeKind::Created);
+        stats.apply(&last);
+        stats.apply(&event(Uuid::new_v4(), NoteChangeKind::Created));
+
+        assert_eq!(stats.notes, 2);
+        assert_eq!(stats.scratched_notes, 1);
+        assert_eq!(stats.last_change_at, Some(last.timestamp));
+    }
+}
@@ -8,7 +8,7 @@ pub type StyloIdentifier = Uuid;
 
 /// Stylo represents a right to write in the behalf of an organization given to
 /// an organization member (both organizations may be the same or different).
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 pub struct Stylo {
     /// The unique identifier of the stylo
     pub stylo_id: StyloIdentifier,
@@ -48,11 +48,28 @@ pub struct CreateStyloCommand {
     pub email: String,
 }
 
+/// Command to modify a Stylo
+/// Unset fields are left untouched.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ModifyStyloCommand {
+    /// The new display name of the stylo
+    #[serde(default)]
+    pub display_name: Option<String>,
+
+    /// The new email address associated with the stylo
+    #[serde(default)]
+    pub email: Option<String>,
+}
+
 /// Different kinds of changes that can happen to a Stylo
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum StyloChangeKind {
-    /// Stylo was created
-    Created,
+    /// Stylo was granted
+    Granted,
+
+    /// Stylo display name or email was modified
+    Modified,
 
     /// Stylo was locked
     Locked,
@@ -81,6 +98,21 @@ impl Stylo {
             email: command.email,
         })
     }
+
+    /// Apply a modification to the stylo
+    pub fn modify(&mut self, command: ModifyStyloCommand) -> Result<()> {
+        if let Some(display_name) = command.display_name {
+            if display_name.trim().is_empty() {
+                return Err(anyhow!("Display name cannot be empty"));
+            }
+            self.display_name = display_name.trim().to_string();
+        }
+        if let Some(email) = command.email {
+            self.email = email;
+        }
+
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -117,6 +149,33 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_modify() {
+        let mut stylo = Stylo::create(CreateStyloCommand {
+            owner_organization_id: Uuid::new_v4(),
+            actor_organization_id: Uuid::new_v4(),
+            display_name: "Test Stylo".to_string(),
+            email: "whoever@internet.com".to_string(),
+        })
+        .unwrap();
+
+        let command = ModifyStyloCommand {
+            display_name: Some(" Renamed ".to_string()),
+            ..Default::default()
+        };
+        stylo.modify(command).unwrap();
+        assert_eq!(stylo.display_name, "Renamed");
+        assert_eq!(stylo.email, "whoever@internet.com");
+
+        let command = ModifyStyloCommand {
+            display_name: Some(" ".to_string()),
+            email: Some("someone@internet.com".to_string()),
+        };
+        assert!(stylo.modify(command).is_err());
+        assert_eq!(stylo.display_name, "Renamed");
+        assert_eq!(stylo.email, "whoever@internet.com");
+    }
+
     #[test]
     fn test_same_organization() {
         let org_id = Uuid::new_v4();
@@ -1,10 +1,177 @@
+use std::fmt;
+use std::str::FromStr;
+
+use anyhow::anyhow;
 use chrono::DateTime;
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use super::{Category, Media, ReferenceIdentifier};
+
 /// ThoughtIdentifier is a type alias for a UUID that represents a thought identifier.
 pub type ThoughtIdentifier = Uuid;
 
+/// ThoughtVariation tells if a thought is a plain thought or a question.
+/// A question opens a new search field, thoughts may then answer it.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThoughtVariation {
+    /// A plain thought.
+    #[default]
+    Thought,
+
+    /// A question.
+    Question,
+}
+
+impl ThoughtVariation {
+    /// Textual representation of the variation, as stored in databases.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Thought => "thought",
+            Self::Question => "question",
+        }
+    }
+}
+
+impl fmt::Display for ThoughtVariation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ThoughtVariation {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "thought" => Ok(Self::Thought),
+            "question" => Ok(Self::Question),
+            _ => Err(anyhow!("Unknown thought variation '{s}'.")),
+        }
+    }
+}
+
+/// ThoughtStatus tells whether a thought currently stands.
+/// A thought is disputed once another thought refutes it and invalidated once
+/// this refutation has been accepted.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ThoughtStatus {
+    /// The thought stands.
+    #[default]
+    Standing,
+
+    /// The thought is refuted by another thought.
+    Disputed,
+
+    /// The refutation of the thought has been accepted.
+    Invalidated,
+}
+
+impl ThoughtStatus {
+    /// Textual representation of the status, as stored in databases.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Standing => "standing",
+            Self::Disputed => "disputed",
+            Self::Invalidated => "invalidated",
+        }
+    }
+}
+
+impl fmt::Display for ThoughtStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for ThoughtStatus {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "standing" => Ok(Self::Standing),
+            "disputed" => Ok(Self::Disputed),
+            "invalidated" => Ok(Self::Invalidated),
+            _ => Err(anyhow!("Unknown thought status '{s}'.")),
+        }
+    }
+}
+
+/// LinkKind tells how a thought relates to the thought it links to.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum LinkKind {
+    /// The thought simply refers to the linked thought.
+    #[default]
+    References,
+
+    /// The thought supports the linked thought.
+    Supports,
+
+    /// The thought contradicts the linked thought.
+    Contradicts,
+
+    /// The thought extends the linked thought.
+    Extends,
+}
+
+impl LinkKind {
+    /// Textual representation of the link kind, as stored in databases.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::References => "references",
+            Self::Supports => "supports",
+            Self::Contradicts => "contradicts",
+            Self::Extends => "extends",
+        }
+    }
+}
+
+impl fmt::Display for LinkKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LinkKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "references" => Ok(Self::References),
+            "supports" => Ok(Self::Supports),
+            "contradicts" => Ok(Self::Contradicts),
+            "extends" => Ok(Self::Extends),
+            _ => Err(anyhow!("Unknown link kind '{s}'.")),
+        }
+    }
+}
+
+/// ThoughtLink is an outgoing link from a thought to another thought.
+/// The linked thought may belong to another project.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ThoughtLink {
+    /// The linked thought.
+    pub target_id: ThoughtIdentifier,
+
+    /// How the thought relates to the linked thought.
+    #[serde(default)]
+    pub kind: LinkKind,
+}
+
+/// DanglingLink is a link whose target thought does not exist anymore,
+/// because it has been trashed with its project.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct DanglingLink {
+    /// The thought holding the link.
+    pub thought_id: ThoughtIdentifier,
+
+    /// The dangling link.
+    pub link: ThoughtLink,
+}
+
 /// Thought is a domain model that represents a thought.
 /// A thought is a piece of information that is written by a stylo.
 /// Thoughts are intended to be long term and are used to capture information.
@@ -28,6 +195,42 @@ pub struct Thought {
 
     /// The content of the thought.
     pub content: String,
+
+    /// Whether the thought is a plain thought or a question.
+    pub variation: ThoughtVariation,
+
+    /// The question this thought answers, if any.
+    pub answers: Option<ThoughtIdentifier>,
+
+    /// For questions, whether the question has been answered.
+    /// This is always false for plain thoughts.
+    pub answered: bool,
+
+    /// The thought refuting this thought, if any.
+    pub refuted_by: Option<ThoughtIdentifier>,
+
+    /// Whether the thought stands, is disputed or has been invalidated.
+    pub status: ThoughtStatus,
+
+    /// The tags of the thought, normalized and sorted.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// The category the thought is filed in, if any.
+    #[serde(default)]
+    pub category: Option<Category>,
+
+    /// The outgoing links of the thought.
+    #[serde(default)]
+    pub links: Vec<ThoughtLink>,
+
+    /// The references cited by the thought, in the same project.
+    #[serde(default)]
+    pub references: Vec<ReferenceIdentifier>,
+
+    /// The medias accompanying the thought.
+    #[serde(default)]
+    pub media: Vec<Media>,
 }
 
 /// CreateThoughtCommand is a command that is used to create a new thought.
@@ -47,14 +250,154 @@ pub struct CreateThoughtCommand {
 
     /// The content of the thought.
     pub content: String,
+
+    /// Whether the thought is a plain thought or a question.
+    pub variation: ThoughtVariation,
+
+    /// The question this thought answers, if any.
+    pub answers: Option<ThoughtIdentifier>,
+
+    /// The tags of the thought.
+    pub tags: Vec<String>,
+
+    /// The category the thought is filed in, if any.
+    pub category: Option<Category>,
+
+    /// The outgoing links of the thought.
+    pub links: Vec<ThoughtLink>,
+
+    /// The references cited by the thought.
+    pub references: Vec<ReferenceIdentifier>,
+
+    /// The medias accompanying the thought, they must be in the media store.
+    pub media: Vec<Media>,
+}
+
+/// CreatedThought is the outcome of the creation of a thought.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CreatedThought {
+    /// The created thought.
+    pub thought: Thought,
+
+    /// The links written in the content that could not be resolved to a
+    /// thought.
+    pub unresolved_links: Vec<String>,
+}
+
+/// ModifyThoughtCommand is a command that is used to modify an existing thought.
+/// Unset fields are left untouched.
+#[derive(Serialize, Deserialize, Default)]
+pub struct ModifyThoughtCommand {
+    /// The new content of the thought.
+    pub content: Option<String>,
+
+    /// The new tags of the thought, replacing the current ones.
+    pub tags: Option<Vec<String>>,
+
+    /// The new category of the thought.
+    pub category: Option<Category>,
+
+    /// The new outgoing links of the thought, replacing the current ones.
+    pub links: Option<Vec<ThoughtLink>>,
+
+    /// The new references cited by the thought, replacing the current ones.
+    pub references: Option<Vec<ReferenceIdentifier>>,
+
+    /// The new medias accompanying the thought, replacing the current ones.
+    pub media: Option<Vec<Media>>,
+}
+
+/// ThoughtFilter restricts the thoughts listed in a project.
+/// Unset criteria do not filter anything.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub struct ThoughtFilter {
+    /// Only list thoughts of this variation.
+    pub variation: Option<ThoughtVariation>,
+
+    /// Only list thoughts with this answered status.
+    pub answered: Option<bool>,
+
+    /// Only list thoughts answering this question.
+    pub answers: Option<ThoughtIdentifier>,
+
+    /// Only list thoughts with this status.
+    pub status: Option<ThoughtStatus>,
+
+    /// Only list thoughts carrying this tag.
+    pub tag: Option<String>,
+
+    /// Only list thoughts filed in this category or under it.
+    pub category: Option<Category>,
+}
+
+impl ThoughtFilter {
+    /// Tell if a thought matches this filter.
+    pub fn matches(&self, thought: &Thought) -> bool {
+        self.variation.is_none_or(|v| thought.variation == v)
+            && self.answered.is_none_or(|a| thought.answered == a)
+            && self.answers.is_none_or(|q| thought.answers == Some(q))
+            && self.status.is_none_or(|s| thought.status == s)
+            && self.tag.as_ref().is_none_or(|t| thought.tags.contains(t))
+            && self.category.as_ref().is_none_or(|c| {
+                thought
+                    .category
+                    .as_ref()
+                    .is_some_and(|category| category.is_descendant_of(c))
+            })
+    }
+}
+
+/// Normalize a tag: surrounding spaces and a leading `#` are removed and the
+/// tag is lowercased.
+/// None is returned when the tag is empty or contains whitespaces.
+pub fn normalize_tag(tag: &str) -> Option<String> {
+    let tag = tag.trim();
+    let tag = tag.strip_prefix('#').unwrap_or(tag);
+
+    if tag.is_empty() || tag.contains(char::is_whitespace) {
+        return None;
+    }
+
+    Some(tag.to_lowercase())
+}
+
+/// TagCount tells how many thoughts of a project carry a tag.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct TagCount {
+    /// The tag.
+    pub tag: String,
+
+    /// The number of thoughts carrying the tag.
+    pub count: u64,
+}
+
+/// ThoughtTree is a thought with its descendants.
+/// It is used to navigate the trees of thoughts built from their parents.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThoughtTree {
+    /// The thought at the root of this tree.
+    pub thought: Thought,
+
+    /// The children of the thought, each with their own descendants.
+    pub children: Vec<ThoughtTree>,
 }
 
 /// Business changes on the Thought model
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum ThoughtChangeKind {
     /// Thought created
     Created,
 
-    /// Thought disputed
+    /// Thought modified
+    Modified,
+
+    /// Thought disputed by another thought
     Disputed(ThoughtIdentifier),
+
+    /// Thought invalidated, the refutation by another thought is accepted
+    Invalidated(ThoughtIdentifier),
+
+    /// Thought trashed with the other thoughts of its project
+    Trashed,
 }
@@ -0,0 +1,34 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use super::OrganizationIdentifier;
+
+/// UniverseIdentifier is a type alias for a UUID that represents a universe
+/// identifier.
+pub type UniverseIdentifier = Uuid;
+
+/// Universe is the group of projects of an organization.
+/// Each organization has exactly one universe, created with it.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct Universe {
+    /// The unique identifier of the universe
+    pub universe_id: UniverseIdentifier,
+
+    /// The organization the universe belongs to
+    pub organization_id: OrganizationIdentifier,
+
+    /// Timestamp when the universe was created
+    pub created_at: DateTime<Utc>,
+}
+
+impl Universe {
+    /// Create the universe of an organization
+    pub fn create(organization_id: OrganizationIdentifier) -> Self {
+        Self {
+            universe_id: Uuid::new_v4(),
+            organization_id,
+            created_at: Utc::now(),
+        }
+    }
+}
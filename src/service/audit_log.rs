@@ -0,0 +1,93 @@
+use std::sync::Arc;
+
+use synapps::{EventDispatcher, EventMessage, EventSubscription, TopicPatternValidator};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use uuid::Uuid;
+
+use crate::adapter::AuditLog;
+use crate::models::{AuditEntry, ModelEvent};
+use crate::Result;
+
+/// Audit log service
+/// It chains every model event in the audit log, so the log tells who changed
+/// what and when, and any later modification of it can be detected.
+pub struct AuditLogService {
+    audit_log: Arc<dyn AuditLog>,
+}
+
+impl AuditLogService {
+    /// Create a new audit log service
+    pub fn new(audit_log: Arc<dyn AuditLog>) -> Self {
+        Self { audit_log }
+    }
+
+    /// Register the service to the event dispatcher.
+    /// The returned receiver must be given to [`AuditLogService::listen`].
+    pub fn subscribe(
+        dispatcher: &mut EventDispatcher<ModelEvent>,
+    ) -> UnboundedReceiver<EventMessage<ModelEvent>> {
+        let (sender, receiver) = unbounded_channel();
+        let validator = Arc::new(TopicPatternValidator::new("model"));
+        dispatcher.register("audit_log", EventSubscription::new(sender, validator));
+
+        receiver
+    }
+
+    /// Record the events of the receiver until it is closed.
+    /// An event that cannot be recorded is logged and skipped.
+    pub async fn listen(
+        &self,
+        mut receiver: UnboundedReceiver<EventMessage<ModelEvent>>,
+    ) -> Result<()> {
+        while let Some(message) = receiver.recv().await {
+            if let Err(e) = self.audit_log.append(message.event.clone()).await {
+                log::error!("Could not audit {:?}: {e}", message.event.model);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// List the audit entries of a note, a thought or a project, oldest
+    /// first. The entries of a project include those of its notes and
+    /// thoughts.
+    pub async fn entries(&self, model_id: Uuid) -> Result<Vec<AuditEntry>> {
+        self.audit_log.entries(model_id).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::adapter::InMemoryAuditLog;
+    use crate::models::{ModelKind, NoteChangeKind};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_record_duplicates_once() {
+        let service = AuditLogService::new(Arc::new(InMemoryAuditLog::default()));
+        let project_id = Uuid::new_v4();
+        let event = ModelEvent::new(ModelKind::Note {
+            note_id: Uuid::new_v4(),
+            project_id,
+            change_kind: NoteChangeKind::Created,
+        });
+        let (sender, receiver) = unbounded_channel();
+        for _ in 0..2 {
+            sender
+                .send(EventMessage {
+                    sender: "test".to_string(),
+                    topic: "model".to_string(),
+                    timestamp: chrono::Utc::now(),
+                    event: event.clone(),
+                })
+                .unwrap();
+        }
+        drop(sender);
+        service.listen(receiver).await.unwrap();
+
+        let entries = service.entries(project_id).await.unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].event, event);
+    }
+}
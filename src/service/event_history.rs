@@ -0,0 +1,231 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use synapps::{EventDispatcher, EventMessage, EventSubscription, TopicPatternValidator};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::adapter::EventStore;
+use crate::models::{ModelEvent, StoredEvent};
+use crate::Result;
+
+/// Number of events read at once from the store during a replay.
+pub const EVENT_REPLAY_BATCH_SIZE: usize = 100;
+
+/// ReadModel is a view built from the model events only, so it can be rebuilt
+/// by replaying the event history.
+#[async_trait]
+pub trait ReadModel: Sync + Send {
+    /// Forget everything, as before the first event.
+    async fn reset(&self) -> Result<()>;
+
+    /// Update the view after a model change.
+    async fn apply(&self, event: &ModelEvent) -> Result<()>;
+}
+
+/// Delivered events
+/// The identifiers of the events a subscriber already handled. The outbox
+/// relay delivers an event at least once, so a subscriber skips the events it
+/// was given before. Replaying the event history into it when the application
+/// starts remembers the events delivered before a restart.
+#[derive(Default)]
+pub struct DeliveredEvents {
+    event_ids: RwLock<HashSet<Uuid>>,
+}
+
+impl DeliveredEvents {
+    /// Remember an event and tell if it is delivered for the first time.
+    pub async fn first_delivery(&self, event: &ModelEvent) -> bool {
+        self.event_ids.write().await.insert(event.event_id)
+    }
+}
+
+#[async_trait]
+impl ReadModel for DeliveredEvents {
+    async fn reset(&self) -> Result<()> {
+        self.event_ids.write().await.clear();
+
+        Ok(())
+    }
+
+    async fn apply(&self, event: &ModelEvent) -> Result<()> {
+        self.first_delivery(event).await;
+
+        Ok(())
+    }
+}
+
+/// Event history service
+/// It records every model event in the event store and replays the recorded
+/// events to rebuild the read models.
+pub struct EventHistoryService {
+    event_store: Arc<dyn EventStore>,
+}
+
+impl EventHistoryService {
+    /// Create a new event history service
+    pub fn new(event_store: Arc<dyn EventStore>) -> Self {
+        Self { event_store }
+    }
+
+    /// Register the service to the event dispatcher.
+    /// The returned receiver must be given to [`EventHistoryService::listen`].
+    pub fn subscribe(
+        dispatcher: &mut EventDispatcher<ModelEvent>,
+    ) -> UnboundedReceiver<EventMessage<ModelEvent>> {
+        let (sender, receiver) = unbounded_channel();
+        let validator = Arc::new(TopicPatternValidator::new("model"));
+        dispatcher.register("event_history", EventSubscription::new(sender, validator));
+
+        receiver
+    }
+
+    /// Record the events of the receiver until it is closed.
+    /// An event that cannot be recorded is logged and skipped, an event
+    /// delivered again is recorded once.
+    pub async fn listen(
+        &self,
+        mut receiver: UnboundedReceiver<EventMessage<ModelEvent>>,
+    ) -> Result<()> {
+        while let Some(message) = receiver.recv().await {
+            if let Err(e) = self.event_store.append(message.clone()).await {
+                log::error!("Could not record {:?}: {e}", message.event.model);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Read at most `limit` recorded events, starting at the given sequence
+    /// number.
+    pub async fn events(&self, from: u64, limit: usize) -> Result<Vec<StoredEvent>> {
+        self.event_store.read(from, limit).await
+    }
+
+    /// Apply the recorded events to the read models in order, starting at the
+    /// given sequence number, and return the number of replayed events.
+    /// The replay stops at the first event a read model cannot apply.
+    pub async fn replay(&self, from: u64, read_models: &[&dyn ReadModel]) -> Result<u64> {
+        let mut next = from.max(1);
+        let mut replayed = 0;
+
+        loop {
+            let events = self.event_store.read(next, EVENT_REPLAY_BATCH_SIZE).await?;
+            let Some(last) = events.last() else {
+                break;
+            };
+            next = last.sequence + 1;
+            for stored in &events {
+                for read_model in read_models {
+                    if let Err(e) = read_model.apply(&stored.event).await {
+                        return Err(anyhow::anyhow!(
+                            "Could not replay the event {}: {e}",
+                            stored.sequence
+                        ));
+                    }
+                }
+                replayed += 1;
+            }
+        }
+
+        Ok(replayed)
+    }
+
+    /// Reset the read models and replay the whole history.
+    pub async fn rebuild(&self, read_models: &[&dyn ReadModel]) -> Result<u64> {
+        for read_model in read_models {
+            read_model.reset().await?;
+        }
+
+        self.replay(1, read_models).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::adapter::InMemoryEventStore;
+    use crate::models::{ModelKind, NoteChangeKind};
+    use crate::service::ProjectStatsService;
+
+    use super::*;
+
+    fn note_message(project_id: Uuid, change_kind: NoteChangeKind) -> EventMessage<ModelEvent> {
+        EventMessage {
+            sender: "test".to_string(),
+            topic: "model".to_string(),
+            timestamp: Utc::now(),
+            event: ModelEvent::new(ModelKind::Note {
+                note_id: Uuid::new_v4(),
+                project_id,
+                change_kind,
+            }),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_record_and_replay() {
+        let service = EventHistoryService::new(Arc::new(InMemoryEventStore::default()));
+        let project_id = Uuid::new_v4();
+        let (sender, receiver) = unbounded_channel();
+        for _ in 0..EVENT_REPLAY_BATCH_SIZE + 1 {
+            sender
+                .send(note_message(project_id, NoteChangeKind::Created))
+                .unwrap();
+        }
+        sender
+            .send(note_message(project_id, NoteChangeKind::Scratched))
+            .unwrap();
+        drop(sender);
+        service.listen(receiver).await.unwrap();
+        let recorded = service.events(1, usize::MAX).await.unwrap();
+        assert_eq!(recorded.len(), EVENT_REPLAY_BATCH_SIZE + 2);
+
+        let last = recorded.len() as u64;
+        let stats = ProjectStatsService::default();
+        assert_eq!(service.rebuild(&[&stats]).await.unwrap(), last);
+        assert_eq!(stats.get(project_id).await.notes, last - 2);
+        assert_eq!(service.rebuild(&[&stats]).await.unwrap(), last);
+        assert_eq!(stats.get(project_id).await.notes, last - 2);
+
+        stats.reset().await.unwrap();
+        assert_eq!(service.replay(last - 1, &[&stats]).await.unwrap(), 2);
+        let replayed = stats.get(project_id).await;
+        assert_eq!(replayed.notes, 0);
+        assert_eq!(replayed.scratched_notes, 1);
+        assert_eq!(service.replay(last + 1, &[&stats]).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_deliver_again() {
+        let service = EventHistoryService::new(Arc::new(InMemoryEventStore::default()));
+        let stats = ProjectStatsService::default();
+        let project_id = Uuid::new_v4();
+        let message = note_message(project_id, NoteChangeKind::Created);
+        let (sender, receiver) = unbounded_channel();
+        let (stats_sender, stats_receiver) = unbounded_channel();
+        for _ in 0..2 {
+            sender.send(message.clone()).unwrap();
+            stats_sender.send(message.clone()).unwrap();
+        }
+        drop(sender);
+        drop(stats_sender);
+        service.listen(receiver).await.unwrap();
+        stats.listen(stats_receiver).await.unwrap();
+
+        assert_eq!(service.events(1, usize::MAX).await.unwrap().len(), 1);
+        assert_eq!(stats.get(project_id).await.notes, 1);
+
+        // After a restart, the history tells the event was already counted.
+        let stats = ProjectStatsService::default();
+        let delivered = DeliveredEvents::default();
+        assert_eq!(service.rebuild(&[&stats, &delivered]).await.unwrap(), 1);
+        stats.apply(&message.event).await.unwrap();
+        assert_eq!(stats.get(project_id).await.notes, 1);
+        assert!(!delivered.first_delivery(&message.event).await);
+    }
+}
@@ -0,0 +1,376 @@
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+
+use anyhow::anyhow;
+use synapps::{EventDispatcher, EventMessage, EventSubscription, TopicPatternValidator};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::sync::{broadcast, Mutex};
+use uuid::Uuid;
+
+use crate::adapter::ProjectBook;
+use crate::models::{ModelEvent, ModelKind};
+use crate::service::DeliveredEvents;
+use crate::Result;
+
+/// Default number of events kept to resume interrupted streams.
+pub const EVENT_REPLAY_CAPACITY: usize = 1000;
+
+/// StreamedEventId identifies a streamed event across the restarts of the
+/// application.
+/// It is written `<epoch>-<sequence>`, the epoch being drawn when the
+/// application starts and the sequence increasing from 1 within an epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct StreamedEventId {
+    /// The epoch of the application which streamed the event.
+    pub epoch: Uuid,
+
+    /// Number of the event within its epoch.
+    pub sequence: u64,
+}
+
+impl fmt::Display for StreamedEventId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.epoch.simple(), self.sequence)
+    }
+}
+
+impl FromStr for StreamedEventId {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let invalid = || anyhow!("Invalid streamed event identifier '{s}'.");
+        let (epoch, sequence) = s.split_once('-').ok_or_else(invalid)?;
+
+        Ok(Self {
+            epoch: Uuid::try_parse(epoch).map_err(|_| invalid())?,
+            sequence: sequence.parse().map_err(|_| invalid())?,
+        })
+    }
+}
+
+/// StreamedEvent is a model event numbered for the clients of the stream.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StreamedEvent {
+    /// Identifier of the event.
+    pub id: StreamedEventId,
+
+    /// The project the changed model belongs to, or the changed project.
+    /// Stylos do not belong to a project.
+    pub project_id: Option<Uuid>,
+
+    /// The universe of the project, if the project is still known.
+    pub universe_id: Option<Uuid>,
+
+    /// The model event.
+    pub event: ModelEvent,
+}
+
+/// EventStreamFilter restricts the events sent to a client.
+/// Unset criteria do not filter anything.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct EventStreamFilter {
+    /// Only stream the events of this project.
+    pub project_id: Option<Uuid>,
+
+    /// Only stream the events of the projects of this universe.
+    pub universe_id: Option<Uuid>,
+}
+
+impl StreamedEvent {
+    /// Tell if the event passes a filter.
+    /// The events of the models outside of the projects only pass the filters
+    /// without criteria.
+    pub fn matches(&self, filter: &EventStreamFilter) -> bool {
+        filter.project_id.is_none_or(|p| self.project_id == Some(p))
+            && filter
+                .universe_id
+                .is_none_or(|u| self.universe_id == Some(u))
+    }
+}
+
+/// Numbering of the events and recent events kept for replay.
+#[derive(Default)]
+struct EventLog {
+    last_id: u64,
+    recent: VecDeque<StreamedEvent>,
+    universes: HashMap<Uuid, Uuid>,
+}
+
+/// Event stream service
+/// It numbers the model events and broadcasts them to the clients of the
+/// stream. The last events are kept in a bounded buffer so clients can resume
+/// a stream after a disconnection without missing events. An event delivered
+/// again is streamed once.
+pub struct EventStreamService {
+    project_book: Arc<dyn ProjectBook>,
+    epoch: Uuid,
+    capacity: usize,
+    log: Mutex<EventLog>,
+    sender: broadcast::Sender<StreamedEvent>,
+    delivered: DeliveredEvents,
+}
+
+impl EventStreamService {
+    /// Create a new event stream service keeping the given number of events
+    /// for replay, at least one.
+    pub fn new(project_book: Arc<dyn ProjectBook>, capacity: usize) -> Self {
+        let capacity = capacity.max(1);
+        let (sender, _) = broadcast::channel(capacity);
+
+        Self {
+            project_book,
+            epoch: Uuid::new_v4(),
+            capacity,
+            log: Mutex::new(EventLog::default()),
+            sender,
+            delivered: DeliveredEvents::default(),
+        }
+    }
+
+    /// The epoch of the identifiers of the streamed events, drawn when the
+    /// service is created.
+    pub fn epoch(&self) -> Uuid {
+        self.epoch
+    }
+
+    /// The events already streamed, to be rebuilt from the event history
+    /// when the application starts.
+    pub fn delivered(&self) -> &DeliveredEvents {
+        &self.delivered
+    }
+
+    /// Register the service to the event dispatcher.
+    /// The returned receiver must be given to [`EventStreamService::listen`].
+    pub fn subscribe(
+        dispatcher: &mut EventDispatcher<ModelEvent>,
+    ) -> UnboundedReceiver<EventMessage<ModelEvent>> {
+        let (sender, receiver) = unbounded_channel();
+        let validator = Arc::new(TopicPatternValidator::new("model"));
+        dispatcher.register("event_stream", EventSubscription::new(sender, validator));
+
+        receiver
+    }
+
+    /// Publish the events of the receiver until it is closed.
+    /// An event that cannot be published is logged and skipped.
+    pub async fn listen(
+        &self,
+        mut receiver: UnboundedReceiver<EventMessage<ModelEvent>>,
+    ) -> Result<()> {
+        while let Some(message) = receiver.recv().await {
+            if let Err(e) = self.publish(message.event).await {
+                log::error!("Could not stream an event: {e}");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Number an event, keep it for replay and send it to the clients.
+    /// An event already streamed is skipped and None is returned.
+    pub async fn publish(&self, event: ModelEvent) -> Result<Option<StreamedEvent>> {
+        if !self.delivered.first_delivery(&event).await {
+            return Ok(None);
+        }
+
+        let project_id = event.model.project_id();
+        let universe_id = match (&event.model, project_id) {
+            (ModelKind::Project { universe_id, .. }, _) => Some(*universe_id),
+            (_, Some(project_id)) => self.universe_of(project_id).await?,
+            (_, None) => None,
+        };
+
+        let mut log = self.log.lock().await;
+        if let (Some(project_id), Some(universe_id)) = (project_id, universe_id) {
+            log.universes.insert(project_id, universe_id);
+        }
+        log.last_id += 1;
+        let streamed = StreamedEvent {
+            id: StreamedEventId {
+                epoch: self.epoch,
+                sequence: log.last_id,
+            },
+            project_id,
+            universe_id,
+            event,
+        };
+        if log.recent.len() == self.capacity {
+            log.recent.pop_front();
+        }
+        log.recent.push_back(streamed.clone());
+        // Nobody may be listening, which is fine.
+        let _ = self.sender.send(streamed.clone());
+
+        Ok(Some(streamed))
+    }
+
+    /// Find the universe of a project, if the project is still known.
+    async fn universe_of(&self, project_id: Uuid) -> Result<Option<Uuid>> {
+        let cached = self.log.lock().await.universes.get(&project_id).copied();
+        if cached.is_some() {
+            return Ok(cached);
+        }
+
+        Ok(self
+            .project_book
+            .get(&project_id)
+            .await?
+            .map(|project| project.universe_id))
+    }
+
+    /// Start streaming the events.
+    ///
+    /// The kept events following the last event a client received are
+    /// returned along with a receiver of the next events, without gap nor
+    /// duplicate between them. A last event identifier of another epoch,
+    /// streamed before the application started, replays all the kept events.
+    /// The receiver lags, and should be dropped, if the client does not keep
+    /// up.
+    pub async fn stream(
+        &self,
+        last_event_id: Option<StreamedEventId>,
+    ) -> (Vec<StreamedEvent>, broadcast::Receiver<StreamedEvent>) {
+        let log = self.log.lock().await;
+        let receiver = self.sender.subscribe();
+        let after = match last_event_id {
+            Some(id) if id.epoch == self.epoch && id.sequence <= log.last_id => id.sequence,
+            _ => 0,
+        };
+        let replay = log
+            .recent
+            .iter()
+            .filter(|streamed| streamed.id.sequence > after)
+            .cloned()
+            .collect();
+
+        (replay, receiver)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+
+    use crate::adapter::InMemoryProjectBook;
+    use crate::models::{CreateProjectCommand, NoteChangeKind};
+    use crate::service::ReadModel;
+
+    use super::*;
+
+    fn note_event(project_id: Uuid) -> ModelEvent {
+        ModelEvent::new(ModelKind::Note {
+            note_id: Uuid::new_v4(),
+            project_id,
+            change_kind: NoteChangeKind::Created,
+        })
+    }
+
+    #[tokio::test]
+    async fn test_publish_and_replay() {
+        let project_book = Arc::new(InMemoryProjectBook::default());
+        let project = project_book
+            .create(CreateProjectCommand {
+                project_name: "Reading list".to_string(),
+                universe_id: Uuid::new_v4(),
+            })
+            .await
+            .unwrap();
+        let service = EventStreamService::new(project_book, 2);
+
+        let (replay, mut receiver) = service.stream(None).await;
+        assert!(replay.is_empty());
+        let first = service
+            .publish(note_event(project.project_id))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(first.id.epoch, service.epoch());
+        assert_eq!(first.id.sequence, 1);
+        assert_eq!(first.universe_id, Some(project.universe_id));
+        assert_eq!(receiver.recv().await.unwrap(), first);
+        let unknown = service
+            .publish(note_event(Uuid::new_v4()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(unknown.universe_id, None);
+        let third = service
+            .publish(note_event(project.project_id))
+            .await
+            .unwrap()
+            .unwrap();
+
+        let ids = |events: Vec<StreamedEvent>| -> Vec<u64> {
+            events.into_iter().map(|e| e.id.sequence).collect()
+        };
+        let id = |sequence| {
+            Some(StreamedEventId {
+                epoch: service.epoch(),
+                sequence,
+            })
+        };
+        assert_eq!(ids(service.stream(None).await.0), vec![2, 3]);
+        assert_eq!(ids(service.stream(id(2)).await.0), vec![3]);
+        assert!(service.stream(id(3)).await.0.is_empty());
+        assert_eq!(ids(service.stream(id(42)).await.0), vec![2, 3]);
+
+        let filter = EventStreamFilter {
+            universe_id: Some(project.universe_id),
+            ..Default::default()
+        };
+        assert!(third.matches(&filter));
+        assert!(!unknown.matches(&filter));
+        let filter = EventStreamFilter {
+            project_id: Some(project.project_id),
+            ..Default::default()
+        };
+        assert!(third.matches(&filter));
+        assert!(!unknown.matches(&filter));
+    }
+
+    #[tokio::test]
+    async fn test_publish_again() {
+        let service = EventStreamService::new(Arc::new(InMemoryProjectBook::default()), 10);
+        let event = note_event(Uuid::new_v4());
+
+        let (_, mut receiver) = service.stream(None).await;
+        let streamed = service.publish(event.clone()).await.unwrap().unwrap();
+        assert!(service.publish(event.clone()).await.unwrap().is_none());
+        assert_eq!(receiver.recv().await.unwrap(), streamed);
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(service.stream(None).await.0, vec![streamed]);
+
+        // The events streamed before a restart are known from the history.
+        let service = EventStreamService::new(Arc::new(InMemoryProjectBook::default()), 10);
+        service.delivered().apply(&event).await.unwrap();
+        assert!(service.publish(event).await.unwrap().is_none());
+        assert!(service.stream(None).await.0.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stream_after_restart() {
+        let project_book = Arc::new(InMemoryProjectBook::default());
+        let service = EventStreamService::new(project_book.clone(), 10);
+        let before = service
+            .publish(note_event(Uuid::new_v4()))
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(
+            before.id.to_string().parse::<StreamedEventId>().unwrap(),
+            before.id
+        );
+        assert!("42".parse::<StreamedEventId>().is_err());
+
+        // The numbering starts again, in another epoch.
+        let service = EventStreamService::new(project_book, 10);
+        for _ in 0..2 {
+            service.publish(note_event(Uuid::new_v4())).await.unwrap();
+        }
+        let (replay, _) = service.stream(Some(before.id)).await;
+        assert_ne!(replay[0].id.epoch, before.id.epoch);
+        let sequences: Vec<u64> = replay.iter().map(|e| e.id.sequence).collect();
+        assert_eq!(sequences, vec![1, 2]);
+    }
+}
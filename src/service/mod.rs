@@ -1,3 +1,21 @@
+mod audit_log;
+mod event_history;
+mod event_stream;
+mod organization;
+mod outbox_relay;
+mod project_stats;
+mod saved_search;
+mod stylo;
 mod thought;
+mod thought_search;
 
+pub use audit_log::*;
+pub use event_history::*;
+pub use event_stream::*;
+pub use organization::*;
+pub use outbox_relay::*;
+pub use project_stats::*;
+pub use saved_search::*;
+pub use stylo::*;
 pub use thought::*;
+pub use thought_search::*;
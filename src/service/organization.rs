@@ -0,0 +1,169 @@
+use std::sync::Arc;
+
+use thiserror::Error;
+use uuid::Uuid;
+
+use crate::adapter::{KeyStore, OrganizationBook, UniverseBook};
+use crate::models::{
+    CreateOrganizationCommand, CreatedOrganization, Organization, OrganizationError,
+    OrganizationIdentifier, Universe,
+};
+use crate::Result;
+
+/// OrganizationServiceError
+/// Different errors returned by the OrganizationService.
+#[derive(Debug, Error)]
+pub enum OrganizationServiceError {
+    /// Organization not found
+    #[error("There is no organization with organization_id='{0}'.")]
+    OrganizationNotFound(OrganizationIdentifier),
+
+    /// The organization is not valid
+    #[error(transparent)]
+    InvalidOrganization(#[from] OrganizationError),
+}
+
+/// Organization service
+/// It registers the organizations, each with the universe its projects are
+/// created in and the key signing the model events acting for it.
+pub struct OrganizationService {
+    organization_book: Arc<dyn OrganizationBook>,
+    universe_book: Arc<dyn UniverseBook>,
+    key_store: Arc<dyn KeyStore>,
+}
+
+impl OrganizationService {
+    /// Create a new organization service
+    pub fn new(
+        organization_book: Arc<dyn OrganizationBook>,
+        universe_book: Arc<dyn UniverseBook>,
+        key_store: Arc<dyn KeyStore>,
+    ) -> Self {
+        Self {
+            organization_book,
+            universe_book,
+            key_store,
+        }
+    }
+
+    /// Create an organization and its universe.
+    /// Both are stored at once, or none of them if the creation fails. The
+    /// key of the organization is generated in the key store beforehand, the
+    /// organization is registered with its public key.
+    ///
+    /// An error is raised if the organization is not valid, see
+    /// [`Organization::create`].
+    pub async fn create(&self, command: CreateOrganizationCommand) -> Result<CreatedOrganization> {
+        let organization_id = Uuid::new_v4();
+        let key = self
+            .key_store
+            .signing_key(&organization_id.to_string())
+            .await?;
+
+        self.organization_book
+            .create(organization_id, command, &key.verifying_key())
+            .await
+            .map_err(|e| match e.downcast::<OrganizationError>() {
+                Ok(error) => OrganizationServiceError::InvalidOrganization(error).into(),
+                Err(e) => e,
+            })
+    }
+
+    /// Get an organization.
+    ///
+    /// An error is raised if the organization does not exist.
+    pub async fn get(&self, organization_id: OrganizationIdentifier) -> Result<Organization> {
+        self.organization_book
+            .get(organization_id)
+            .await?
+            .ok_or_else(|| OrganizationServiceError::OrganizationNotFound(organization_id).into())
+    }
+
+    /// Get the universe of an organization.
+    ///
+    /// An error is raised if the organization does not exist.
+    pub async fn get_universe(&self, organization_id: OrganizationIdentifier) -> Result<Universe> {
+        self.universe_book
+            .get_by_organization(organization_id)
+            .await?
+            .ok_or_else(|| OrganizationServiceError::OrganizationNotFound(organization_id).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::adapter::{InMemoryKeyStore, InMemoryOrganizationBook, InMemoryUniverseBook};
+
+    use super::*;
+
+    fn organization_service(key_store: Arc<InMemoryKeyStore>) -> OrganizationService {
+        let universe_book = InMemoryUniverseBook::default();
+        let organization_book = InMemoryOrganizationBook::with_universe_book(universe_book.clone());
+
+        OrganizationService::new(
+            Arc::new(organization_book),
+            Arc::new(universe_book),
+            key_store,
+        )
+    }
+
+    fn command(name: &str) -> CreateOrganizationCommand {
+        CreateOrganizationCommand {
+            name: name.to_string(),
+            certificate: serde_json::Value::Null,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_organization() {
+        let key_store = Arc::new(InMemoryKeyStore::default());
+        let service = organization_service(key_store.clone());
+
+        let created = service.create(command("Acme")).await.unwrap();
+        let organization_id = created.organization.organization_id;
+
+        assert_eq!(service.get(organization_id).await.unwrap().name, "Acme");
+        assert_eq!(
+            service.get_universe(organization_id).await.unwrap(),
+            created.universe
+        );
+        assert_eq!(
+            key_store
+                .verifying_key(&organization_id.to_string())
+                .await
+                .unwrap(),
+            Some(created.organization.verifying_key().unwrap())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_invalid_organization() {
+        let service = organization_service(Default::default());
+
+        let error = service.create(command(" ")).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<OrganizationServiceError>(),
+            Some(OrganizationServiceError::InvalidOrganization(
+                OrganizationError::EmptyName
+            ))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_organization_not_found() {
+        let service = organization_service(Default::default());
+        let organization_id = Uuid::new_v4();
+
+        for error in [
+            service.get(organization_id).await.unwrap_err(),
+            service.get_universe(organization_id).await.unwrap_err(),
+        ] {
+            assert!(matches!(
+                error.downcast_ref::<OrganizationServiceError>(),
+                Some(OrganizationServiceError::OrganizationNotFound(id)) if *id == organization_id
+            ));
+        }
+    }
+}
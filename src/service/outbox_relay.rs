@@ -0,0 +1,244 @@
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+use std::time::Duration;
+
+use synapps::EventMessage;
+use tokio::sync::mpsc::UnboundedSender;
+use tokio::sync::Mutex;
+use uuid::Uuid;
+
+use crate::adapter::{KeyStore, Outbox};
+use crate::models::ModelEvent;
+use crate::Result;
+
+/// Number of events read at once from the outbox.
+pub const OUTBOX_BATCH_SIZE: usize = 100;
+
+/// Number of delivered event identifiers remembered to avoid sending an event
+/// twice when its acknowledgement failed.
+pub const OUTBOX_DEDUP_WINDOW: usize = 1000;
+
+/// Delay between two polls of the outbox by [`OutboxRelay::run`].
+pub const OUTBOX_POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Identifiers of the events already sent to the dispatcher.
+#[derive(Default)]
+struct Delivered {
+    ids: HashSet<Uuid>,
+    order: VecDeque<Uuid>,
+}
+
+impl Delivered {
+    fn contains(&self, event_id: &Uuid) -> bool {
+        self.ids.contains(event_id)
+    }
+
+    fn insert(&mut self, event_id: Uuid) {
+        if !self.ids.insert(event_id) {
+            return;
+        }
+        self.order.push_back(event_id);
+        if self.order.len() > OUTBOX_DEDUP_WINDOW {
+            if let Some(oldest) = self.order.pop_front() {
+                self.ids.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Outbox relay
+/// It signs the events written in the outbox with the key of their signer and
+/// forwards them to the event dispatcher.
+/// An event is removed from the outbox only once sent, so it is delivered at
+/// least once; the event identifier lets the relay and the subscribers drop
+/// the duplicates.
+pub struct OutboxRelay {
+    outbox: Arc<dyn Outbox>,
+    key_store: Arc<dyn KeyStore>,
+    sender: UnboundedSender<EventMessage<ModelEvent>>,
+    delivered: Mutex<Delivered>,
+}
+
+impl OutboxRelay {
+    /// Create a new outbox relay
+    pub fn new(
+        outbox: Arc<dyn Outbox>,
+        key_store: Arc<dyn KeyStore>,
+        sender: UnboundedSender<EventMessage<ModelEvent>>,
+    ) -> Self {
+        Self {
+            outbox,
+            key_store,
+            sender,
+            delivered: Mutex::new(Delivered::default()),
+        }
+    }
+
+    /// Send the pending events to the dispatcher, oldest first, and return the
+    /// number of sent events.
+    /// The flush stops at the first event that cannot be signed or sent, it
+    /// stays in the outbox with the following ones.
+    pub async fn flush(&self) -> Result<usize> {
+        // Holding the lock for the whole flush keeps the events in order when
+        // several flushes run at once.
+        let mut delivered = self.delivered.lock().await;
+        let mut sent = 0;
+
+        loop {
+            let events = self.outbox.pending(OUTBOX_BATCH_SIZE).await?;
+            if events.is_empty() {
+                return Ok(sent);
+            }
+
+            let mut acknowledged = Vec::with_capacity(events.len());
+            let mut failure = None;
+            for event in events {
+                let event_id = event.event_id;
+                if !delivered.contains(&event_id) {
+                    if let Err(e) = self.send(event).await {
+                        failure = Some(e);
+                        break;
+                    }
+                    delivered.insert(event_id);
+                    sent += 1;
+                }
+                acknowledged.push(event_id);
+            }
+            self.outbox.acknowledge(&acknowledged).await?;
+
+            if let Some(e) = failure {
+                return Err(e);
+            }
+        }
+    }
+
+    /// Flush the outbox after a change stored by a book.
+    /// The change is already stored, so a failure is only logged: the events
+    /// stay in the outbox and [`OutboxRelay::run`] sends them again later.
+    pub async fn flush_or_log(&self) {
+        if let Err(e) = self.flush().await {
+            log::warn!("Could not dispatch the model events, they will be retried: {e}");
+        }
+    }
+
+    /// Sign an event and send it to the dispatcher.
+    async fn send(&self, mut event: ModelEvent) -> Result<()> {
+        let key = self.key_store.signing_key(&event.key_id()).await?;
+        event.sign(&key);
+        self.sender.send(EventMessage {
+            sender: "outbox".to_string(),
+            topic: "model".to_string(),
+            timestamp: chrono::Utc::now(),
+            event,
+        })?;
+
+        Ok(())
+    }
+
+    /// Flush the outbox at every interval until the dispatcher is gone.
+    /// It sends the events whose flush failed or was interrupted by a crash.
+    pub async fn run(&self, interval: Duration) -> Result<()> {
+        loop {
+            if let Err(e) = self.flush().await {
+                if self.sender.is_closed() {
+                    return Err(e);
+                }
+                log::warn!("Could not flush the outbox: {e}");
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use async_trait::async_trait;
+    use tokio::sync::mpsc::unbounded_channel;
+
+    use crate::adapter::{InMemoryKeyStore, InMemoryOutbox};
+    use crate::models::{ModelKind, NoteChangeKind};
+
+    use super::*;
+
+    fn note_event() -> ModelEvent {
+        ModelEvent::new(ModelKind::Note {
+            note_id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            change_kind: NoteChangeKind::Created,
+        })
+    }
+
+    /// An outbox that cannot acknowledge, as when the database is down.
+    struct StickyOutbox(InMemoryOutbox);
+
+    #[async_trait]
+    impl Outbox for StickyOutbox {
+        async fn pending(&self, limit: usize) -> Result<Vec<ModelEvent>> {
+            self.0.pending(limit).await
+        }
+
+        async fn acknowledge(&self, _event_ids: &[Uuid]) -> Result<()> {
+            Err(anyhow::anyhow!("The outbox is unavailable."))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flush() {
+        let outbox = InMemoryOutbox::default();
+        let (sender, mut receiver) = unbounded_channel();
+        let key_store = Arc::new(InMemoryKeyStore::default());
+        let relay = OutboxRelay::new(Arc::new(outbox.clone()), key_store.clone(), sender);
+        let events = vec![note_event(), note_event()];
+        outbox.write().await.extend(events.clone());
+
+        assert_eq!(relay.flush().await.unwrap(), 2);
+        for event in &events {
+            let message = receiver.try_recv().unwrap();
+            assert_eq!(message.event.event_id, event.event_id);
+            assert_eq!(message.topic, "model");
+            key_store.verify(&message.event).await.unwrap();
+        }
+        assert!(outbox.pending(usize::MAX).await.unwrap().is_empty());
+        assert_eq!(relay.flush().await.unwrap(), 0);
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_flush_closed_dispatcher() {
+        let outbox = InMemoryOutbox::default();
+        let (sender, receiver) = unbounded_channel();
+        let relay = OutboxRelay::new(
+            Arc::new(outbox.clone()),
+            Arc::new(InMemoryKeyStore::default()),
+            sender,
+        );
+        let event = note_event();
+        outbox.write().await.push(event.clone());
+        drop(receiver);
+
+        assert!(relay.flush().await.is_err());
+        relay.flush_or_log().await;
+        let pending = outbox.pending(usize::MAX).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(pending[0].event_id, event.event_id);
+    }
+
+    #[tokio::test]
+    async fn test_flush_deduplicates() {
+        let outbox = InMemoryOutbox::default();
+        let (sender, mut receiver) = unbounded_channel();
+        let relay = OutboxRelay::new(
+            Arc::new(StickyOutbox(outbox.clone())),
+            Arc::new(InMemoryKeyStore::default()),
+            sender,
+        );
+        outbox.write().await.push(note_event());
+
+        // The event was sent but is still pending, it must not be sent again.
+        assert!(relay.flush().await.is_err());
+        assert!(receiver.try_recv().is_ok());
+        assert!(relay.flush().await.is_err());
+        assert!(receiver.try_recv().is_err());
+        assert_eq!(outbox.pending(usize::MAX).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use synapps::{EventDispatcher, EventMessage, EventSubscription, TopicPatternValidator};
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::models::{ModelEvent, ProjectStats};
+use crate::service::{DeliveredEvents, ReadModel};
+use crate::Result;
+
+/// Project stats service
+/// It counts the changes made in every project by listening to the model
+/// events. The counts are only kept in memory and rebuilt from the event
+/// history when the application starts. An event delivered again is counted
+/// once.
+#[derive(Default)]
+pub struct ProjectStatsService {
+    stats: RwLock<HashMap<Uuid, ProjectStats>>,
+    delivered: DeliveredEvents,
+}
+
+impl ProjectStatsService {
+    /// Register the service to the event dispatcher.
+    /// The returned receiver must be given to [`ProjectStatsService::listen`].
+    pub fn subscribe(
+        dispatcher: &mut EventDispatcher<ModelEvent>,
+    ) -> UnboundedReceiver<EventMessage<ModelEvent>> {
+        let (sender, receiver) = unbounded_channel();
+        let validator = Arc::new(TopicPatternValidator::new("model"));
+        dispatcher.register("project_stats", EventSubscription::new(sender, validator));
+
+        receiver
+    }
+
+    /// Count the events of the receiver until it is closed.
+    pub async fn listen(
+        &self,
+        mut receiver: UnboundedReceiver<EventMessage<ModelEvent>>,
+    ) -> Result<()> {
+        while let Some(message) = receiver.recv().await {
+            self.count(&message.event).await;
+        }
+
+        Ok(())
+    }
+
+    /// Get the stats of a project, all zero if nothing happened in it.
+    pub async fn get(&self, project_id: Uuid) -> ProjectStats {
+        self.stats
+            .read()
+            .await
+            .get(&project_id)
+            .cloned()
+            .unwrap_or(ProjectStats {
+                project_id,
+                ..Default::default()
+            })
+    }
+
+    async fn count(&self, event: &ModelEvent) {
+        let Some(project_id) = event.model.project_id() else {
+            return;
+        };
+        if !self.delivered.first_delivery(event).await {
+            return;
+        }
+
+        self.stats
+            .write()
+            .await
+            .entry(project_id)
+            .or_insert_with(|| ProjectStats {
+                project_id,
+                ..Default::default()
+            })
+            .apply(event);
+    }
+}
+
+#[async_trait]
+impl ReadModel for ProjectStatsService {
+    async fn reset(&self) -> Result<()> {
+        self.stats.write().await.clear();
+        self.delivered.reset().await
+    }
+
+    async fn apply(&self, event: &ModelEvent) -> Result<()> {
+        self.count(event).await;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,499 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use chrono::Utc;
+use synapps::{EventDispatcher, EventMessage, EventSubscription, TopicPatternValidator};
+use thiserror::Error;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use uuid::Uuid;
+
+use crate::adapter::{NoteBook, ProjectBook, SavedSearchBook, ThoughtBook};
+use crate::models::{
+    CreateSavedSearchCommand, ModelEvent, ModelKind, ModifySavedSearchCommand, NoteChangeKind,
+    Query, QueryDocument, QueryHit, QuerySyntaxError, SavedSearch, SavedSearchIdentifier,
+    SavedSearchResult, SearchDocumentKind, StyloIdentifier, ThoughtChangeKind,
+};
+use crate::service::{DeliveredEvents, ThoughtSearchService};
+use crate::Result;
+
+/// SavedSearchServiceError
+/// Different errors returned by the SavedSearchService.
+#[derive(Debug, Error)]
+pub enum SavedSearchServiceError {
+    /// Saved search not found
+    #[error("There is no saved search with saved_search_id='{0}'.")]
+    SavedSearchNotFound(SavedSearchIdentifier),
+
+    /// The name of the saved search is empty
+    #[error("The name of a saved search cannot be empty.")]
+    EmptyName,
+
+    /// The name is already used by another saved search of the stylo
+    #[error("The name '{0}' is already used by another saved search.")]
+    NameAlreadyUsed(String),
+
+    /// The query of the saved search is not valid
+    #[error("The query is not valid: {0}")]
+    InvalidQuery(#[from] QuerySyntaxError),
+}
+
+/// Saved search service
+/// It keeps the searches of the stylos and, by listening to the model events,
+/// records the notes and thoughts created since that match them.
+pub struct SavedSearchService {
+    saved_search_book: Arc<dyn SavedSearchBook>,
+    note_book: Arc<dyn NoteBook>,
+    project_book: Arc<dyn ProjectBook>,
+    thought_book: Arc<dyn ThoughtBook>,
+    thought_search: Arc<ThoughtSearchService>,
+    delivered: DeliveredEvents,
+}
+
+impl SavedSearchService {
+    /// Create a new saved search service
+    pub fn new(
+        saved_search_book: Arc<dyn SavedSearchBook>,
+        note_book: Arc<dyn NoteBook>,
+        project_book: Arc<dyn ProjectBook>,
+        thought_book: Arc<dyn ThoughtBook>,
+        thought_search: Arc<ThoughtSearchService>,
+    ) -> Self {
+        Self {
+            saved_search_book,
+            note_book,
+            project_book,
+            thought_book,
+            thought_search,
+            delivered: DeliveredEvents::default(),
+        }
+    }
+
+    /// The events already matched, to be rebuilt from the event history when
+    /// the application starts.
+    pub fn delivered(&self) -> &DeliveredEvents {
+        &self.delivered
+    }
+
+    /// Register the service to the event dispatcher.
+    /// The returned receiver must be given to [`SavedSearchService::listen`].
+    pub fn subscribe(
+        dispatcher: &mut EventDispatcher<ModelEvent>,
+    ) -> UnboundedReceiver<EventMessage<ModelEvent>> {
+        let (sender, receiver) = unbounded_channel();
+        let validator = Arc::new(TopicPatternValidator::new("model"));
+        dispatcher.register("saved_search", EventSubscription::new(sender, validator));
+
+        receiver
+    }
+
+    /// Match the events of the receiver against the saved searches until it
+    /// is closed. An event that cannot be matched is logged and skipped.
+    pub async fn listen(
+        &self,
+        mut receiver: UnboundedReceiver<EventMessage<ModelEvent>>,
+    ) -> Result<()> {
+        while let Some(message) = receiver.recv().await {
+            if let Err(e) = self.apply(&message.event).await {
+                log::error!(
+                    "Could not match {:?} against the saved searches: {e}",
+                    message.event.model
+                );
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Record the created note or thought of an event as a new result of the
+    /// saved searches it matches. Other events, and events already matched,
+    /// are ignored.
+    pub async fn apply(&self, event: &ModelEvent) -> Result<()> {
+        if !self.delivered.first_delivery(event).await {
+            return Ok(());
+        }
+
+        let (kind, id) = match &event.model {
+            ModelKind::Note {
+                note_id,
+                change_kind: NoteChangeKind::Created,
+                ..
+            } => (SearchDocumentKind::Note, *note_id),
+            ModelKind::Thought {
+                thought_id,
+                change_kind: ThoughtChangeKind::Created,
+                ..
+            } => (SearchDocumentKind::Thought, *thought_id),
+            _ => return Ok(()),
+        };
+        let (note, thought) = match kind {
+            SearchDocumentKind::Note => (self.note_book.get(id).await?, None),
+            SearchDocumentKind::Thought => (None, self.thought_book.get(id).await?),
+        };
+        let document = match (&note, &thought) {
+            (Some(note), _) => QueryDocument::from_note(note),
+            (_, Some(thought)) => QueryDocument::from_thought(thought),
+            _ => return Ok(()),
+        };
+
+        let mut projects = HashMap::new();
+        for saved_search in self.saved_search_book.list().await? {
+            let query = match Query::parse(&saved_search.query) {
+                Ok(query) => query,
+                Err(e) => {
+                    log::error!(
+                        "The saved search {} is not valid: {e}",
+                        saved_search.saved_search_id
+                    );
+                    continue;
+                }
+            };
+            for slug in query.root.project_slugs() {
+                if !projects.contains_key(slug) {
+                    if let Some(project) = self.project_book.get_by_slug(slug).await? {
+                        projects.insert(slug.to_string(), project.project_id);
+                    }
+                }
+            }
+
+            if query.matches(&document, &projects) {
+                self.saved_search_book
+                    .add_result(SavedSearchResult {
+                        saved_search_id: saved_search.saved_search_id,
+                        stylo_id: saved_search.stylo_id,
+                        kind,
+                        id,
+                        project_id: document.project_id,
+                        matched_at: Utc::now(),
+                    })
+                    .await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Save a search for a stylo.
+    ///
+    /// This returns an error if:
+    /// - The name is empty or already used by another search of the stylo
+    /// - The query is not valid
+    pub async fn create(&self, command: CreateSavedSearchCommand) -> Result<SavedSearch> {
+        self.validate(command.stylo_id, None, &command.name, &command.query)
+            .await?;
+
+        self.saved_search_book.add(command).await
+    }
+
+    /// Get a saved search of a stylo.
+    ///
+    /// An error is raised if the saved search does not exist or belongs to
+    /// another stylo.
+    pub async fn get(
+        &self,
+        stylo_id: StyloIdentifier,
+        saved_search_id: SavedSearchIdentifier,
+    ) -> Result<SavedSearch> {
+        self.saved_search_book
+            .get(saved_search_id)
+            .await?
+            .filter(|s| s.stylo_id == stylo_id)
+            .ok_or_else(|| SavedSearchServiceError::SavedSearchNotFound(saved_search_id).into())
+    }
+
+    /// List the saved searches of a stylo, sorted by name.
+    pub async fn list(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearch>> {
+        self.saved_search_book.list_by_stylo(stylo_id).await
+    }
+
+    /// Rename a saved search of a stylo or change its query.
+    ///
+    /// Results already recorded are kept. This returns an error if:
+    /// - The saved search does not exist or belongs to another stylo
+    /// - The new name is empty or already used by another search of the stylo
+    /// - The new query is not valid
+    pub async fn modify(
+        &self,
+        stylo_id: StyloIdentifier,
+        saved_search_id: SavedSearchIdentifier,
+        command: ModifySavedSearchCommand,
+    ) -> Result<SavedSearch> {
+        let mut saved_search = self.get(stylo_id, saved_search_id).await?;
+        if let Some(name) = command.name {
+            saved_search.name = name.trim().to_string();
+        }
+        if let Some(query) = command.query {
+            saved_search.query = query;
+        }
+        self.validate(
+            stylo_id,
+            Some(saved_search_id),
+            &saved_search.name,
+            &saved_search.query,
+        )
+        .await?;
+
+        self.saved_search_book.sync(saved_search).await
+    }
+
+    /// Delete a saved search of a stylo and its new results.
+    ///
+    /// An error is raised if the saved search does not exist or belongs to
+    /// another stylo.
+    pub async fn delete(
+        &self,
+        stylo_id: StyloIdentifier,
+        saved_search_id: SavedSearchIdentifier,
+    ) -> Result<SavedSearch> {
+        self.get(stylo_id, saved_search_id).await?;
+
+        self.saved_search_book
+            .delete(saved_search_id)
+            .await?
+            .ok_or_else(|| SavedSearchServiceError::SavedSearchNotFound(saved_search_id).into())
+    }
+
+    /// Run a saved search of a stylo over all the notes and thoughts, see
+    /// [`ThoughtSearchService::query`].
+    pub async fn run(
+        &self,
+        stylo_id: StyloIdentifier,
+        saved_search_id: SavedSearchIdentifier,
+        limit: usize,
+    ) -> Result<Vec<QueryHit>> {
+        let saved_search = self.get(stylo_id, saved_search_id).await?;
+
+        self.thought_search.query(&saved_search.query, limit).await
+    }
+
+    /// List the documents matching the saved searches of a stylo since they
+    /// were last cleared, newest first.
+    pub async fn list_results(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearchResult>> {
+        self.saved_search_book.list_results(stylo_id).await
+    }
+
+    /// Clear the new results of a stylo once it has seen them.
+    /// The cleared results are returned, newest first.
+    pub async fn clear_results(&self, stylo_id: StyloIdentifier) -> Result<Vec<SavedSearchResult>> {
+        self.saved_search_book.clear_results(stylo_id).await
+    }
+
+    /// Check the name and the query of a saved search.
+    async fn validate(
+        &self,
+        stylo_id: StyloIdentifier,
+        saved_search_id: Option<Uuid>,
+        name: &str,
+        query: &str,
+    ) -> Result<()> {
+        let name = name.trim();
+        if name.is_empty() {
+            return Err(SavedSearchServiceError::EmptyName.into());
+        }
+        Query::parse(query).map_err(SavedSearchServiceError::from)?;
+
+        match self.saved_search_book.get_by_name(stylo_id, name).await? {
+            Some(other) if Some(other.saved_search_id) != saved_search_id => {
+                Err(SavedSearchServiceError::NameAlreadyUsed(name.to_string()).into())
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::models::{
+        CreateNoteCommand, CreateProjectCommand, CreateThoughtCommand, ThoughtVariation,
+    };
+    use crate::testkit::{create_organization, grant_stylo};
+    use crate::Container;
+
+    use super::*;
+
+    fn command(stylo_id: Uuid, name: &str, query: &str) -> CreateSavedSearchCommand {
+        CreateSavedSearchCommand {
+            stylo_id,
+            name: name.to_string(),
+            query: query.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_saved_search_crud() {
+        let mut container = Container::default();
+        let service = container.saved_search_service().unwrap();
+        let stylo_id = Uuid::new_v4();
+
+        let saved_search = service
+            .create(command(stylo_id, "Ethics", "tag:ethics"))
+            .await
+            .unwrap();
+        service
+            .create(command(stylo_id, "Garden", "project:garden"))
+            .await
+            .unwrap();
+        let error = service
+            .create(command(stylo_id, " Ethics", "virtue"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<SavedSearchServiceError>(),
+            Some(SavedSearchServiceError::NameAlreadyUsed(_))
+        ));
+        let error = service
+            .create(command(stylo_id, "Broken", "tag:ethics AND"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<SavedSearchServiceError>(),
+            Some(SavedSearchServiceError::InvalidQuery(_))
+        ));
+        let error = service
+            .create(command(stylo_id, "  ", "virtue"))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<SavedSearchServiceError>(),
+            Some(SavedSearchServiceError::EmptyName)
+        ));
+
+        let modified = service
+            .modify(
+                stylo_id,
+                saved_search.saved_search_id,
+                ModifySavedSearchCommand {
+                    name: Some("Virtue ethics".to_string()),
+                    query: Some("tag:ethics virtue".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(modified.name, "Virtue ethics");
+        let names: Vec<String> = service
+            .list(stylo_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|s| s.name)
+            .collect();
+        assert_eq!(names, vec!["Garden", "Virtue ethics"]);
+
+        let error = service
+            .get(Uuid::new_v4(), saved_search.saved_search_id)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<SavedSearchServiceError>(),
+            Some(SavedSearchServiceError::SavedSearchNotFound(_))
+        ));
+        service
+            .delete(stylo_id, saved_search.saved_search_id)
+            .await
+            .unwrap();
+        assert!(service
+            .get(stylo_id, saved_search.saved_search_id)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_new_results() {
+        let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
+        let thought_service = container.thought_service().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        let service = container.saved_search_service().unwrap();
+        let stylo_id = Uuid::new_v4();
+        let author_id = grant_stylo(container.stylo_book().unwrap().as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+        let project = thought_service
+            .create_project(CreateProjectCommand {
+                project_name: "Reading list".to_string(),
+                universe_id,
+            })
+            .await
+            .unwrap();
+        let ethics = service
+            .create(command(
+                stylo_id,
+                "Ethics",
+                "project:reading-list tag:ethics",
+            ))
+            .await
+            .unwrap();
+        let notes = service
+            .create(command(stylo_id, "Notes", "kind:note virtue"))
+            .await
+            .unwrap();
+        service
+            .create(command(Uuid::new_v4(), "Garden", "project:garden"))
+            .await
+            .unwrap();
+
+        let thought = thought_service
+            .create_thought(CreateThoughtCommand {
+                imported_at: Utc::now(),
+                parent_id: None,
+                stylo_id: author_id,
+                project_slug: project.slug.clone(),
+                content: "Virtue is a habit.".to_string(),
+                variation: ThoughtVariation::Thought,
+                answers: None,
+                tags: vec!["ethics".to_string()],
+                category: None,
+                links: Vec::new(),
+                references: Vec::new(),
+                media: Vec::new(),
+            })
+            .await
+            .unwrap()
+            .thought;
+        let note = thought_service
+            .create_note(CreateNoteCommand {
+                imported_at: Utc::now(),
+                stylo_id: author_id,
+                project_slug: project.slug.clone(),
+                content: "Aristotle on virtue.".to_string(),
+                references: Vec::new(),
+                media: Vec::new(),
+            })
+            .await
+            .unwrap();
+        let mut events = Vec::new();
+        while let Ok(message) = receiver.try_recv() {
+            service.apply(&message.event).await.unwrap();
+            events.push(message.event);
+        }
+
+        let results = service.list_results(stylo_id).await.unwrap();
+        let matched: Vec<(Uuid, Uuid)> =
+            results.iter().map(|r| (r.saved_search_id, r.id)).collect();
+        assert_eq!(
+            matched,
+            vec![
+                (notes.saved_search_id, note.note_id),
+                (ethics.saved_search_id, thought.thought_id),
+            ]
+        );
+
+        let hits = service
+            .run(stylo_id, ethics.saved_search_id, 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+
+        let cleared = service.clear_results(stylo_id).await.unwrap();
+        assert_eq!(cleared, results);
+        assert!(service.list_results(stylo_id).await.unwrap().is_empty());
+
+        // The events delivered again do not bring back the cleared results.
+        for event in &events {
+            service.apply(event).await.unwrap();
+        }
+        assert!(service.list_results(stylo_id).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,362 @@
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::adapter::{OrganizationBook, StyloBook};
+use crate::models::{
+    CreateStyloCommand, ModifyStyloCommand, OrganizationIdentifier, Stylo, StyloChangeKind,
+    StyloIdentifier,
+};
+use crate::service::OutboxRelay;
+use crate::Result;
+
+/// StyloServiceError
+/// Different errors returned by the StyloService.
+#[derive(Debug, Error)]
+pub enum StyloServiceError {
+    /// Stylo not found
+    #[error("There is no stylo with stylo_id='{0}'.")]
+    StyloNotFound(StyloIdentifier),
+
+    /// Owner or actor organization not found
+    #[error("There is no organization with organization_id='{0}'.")]
+    OrganizationNotFound(OrganizationIdentifier),
+
+    /// The display name of the stylo is empty
+    #[error("The display name of a stylo cannot be empty.")]
+    EmptyDisplayName,
+
+    /// Stylo is already locked
+    #[error("The stylo with stylo_id='{0}' is already locked.")]
+    AlreadyLocked(StyloIdentifier),
+
+    /// Stylo is not locked
+    #[error("The stylo with stylo_id='{0}' is not locked.")]
+    NotLocked(StyloIdentifier),
+}
+
+/// Stylo service
+/// It manages the stylos an organization grants to write on its behalf. Every
+/// change is dispatched as a stylo event.
+pub struct StyloService {
+    stylo_book: Arc<dyn StyloBook>,
+    organization_book: Arc<dyn OrganizationBook>,
+    relay: Arc<OutboxRelay>,
+}
+
+impl StyloService {
+    /// Create a new stylo service
+    pub fn new(
+        stylo_book: Arc<dyn StyloBook>,
+        organization_book: Arc<dyn OrganizationBook>,
+        relay: Arc<OutboxRelay>,
+    ) -> Self {
+        Self {
+            stylo_book,
+            organization_book,
+            relay,
+        }
+    }
+
+    /// Grant a stylo.
+    ///
+    /// An error is raised if the owner or the actor organization does not
+    /// exist or if the display name is empty.
+    pub async fn grant(&self, command: CreateStyloCommand) -> Result<Stylo> {
+        if command.display_name.trim().is_empty() {
+            return Err(StyloServiceError::EmptyDisplayName.into());
+        }
+        self.check_organization(command.owner_organization_id)
+            .await?;
+        self.check_organization(command.actor_organization_id)
+            .await?;
+
+        let stylo = self.stylo_book.add(command).await?;
+
+        self.relay.flush_or_log().await;
+
+        Ok(stylo)
+    }
+
+    /// Get a stylo.
+    ///
+    /// An error is raised if the stylo does not exist.
+    pub async fn get(&self, stylo_id: StyloIdentifier) -> Result<Stylo> {
+        self.stylo_book
+            .get(stylo_id)
+            .await?
+            .ok_or_else(|| StyloServiceError::StyloNotFound(stylo_id).into())
+    }
+
+    /// Modify the display name or the email of a stylo.
+    ///
+    /// An error is raised if the stylo does not exist or if the new display
+    /// name is empty.
+    pub async fn modify(
+        &self,
+        stylo_id: StyloIdentifier,
+        command: ModifyStyloCommand,
+    ) -> Result<Stylo> {
+        let mut stylo = self.get(stylo_id).await?;
+        if command
+            .display_name
+            .as_ref()
+            .is_some_and(|name| name.trim().is_empty())
+        {
+            return Err(StyloServiceError::EmptyDisplayName.into());
+        }
+        stylo.modify(command)?;
+
+        self.sync(stylo, StyloChangeKind::Modified).await
+    }
+
+    /// Lock a stylo, it cannot be used to write anymore until it is unlocked.
+    ///
+    /// An error is raised if the stylo does not exist or is already locked.
+    pub async fn lock(&self, stylo_id: StyloIdentifier) -> Result<Stylo> {
+        let mut stylo = self.get(stylo_id).await?;
+        if stylo.is_locked {
+            return Err(StyloServiceError::AlreadyLocked(stylo_id).into());
+        }
+        stylo.is_locked = true;
+
+        self.sync(stylo, StyloChangeKind::Locked).await
+    }
+
+    /// Unlock a stylo.
+    ///
+    /// An error is raised if the stylo does not exist or is not locked.
+    pub async fn unlock(&self, stylo_id: StyloIdentifier) -> Result<Stylo> {
+        let mut stylo = self.get(stylo_id).await?;
+        if !stylo.is_locked {
+            return Err(StyloServiceError::NotLocked(stylo_id).into());
+        }
+        stylo.is_locked = false;
+
+        self.sync(stylo, StyloChangeKind::Unlocked).await
+    }
+
+    /// Revoke a stylo, it is removed for good.
+    ///
+    /// The notes and thoughts written with the stylo are kept. An error is
+    /// raised if the stylo does not exist.
+    pub async fn revoke(&self, stylo_id: StyloIdentifier) -> Result<Stylo> {
+        let stylo = self
+            .stylo_book
+            .delete(stylo_id)
+            .await?
+            .ok_or(StyloServiceError::StyloNotFound(stylo_id))?;
+
+        self.relay.flush_or_log().await;
+
+        Ok(stylo)
+    }
+
+    /// List the stylos owned by an organization, oldest first.
+    ///
+    /// An error is raised if the organization does not exist.
+    pub async fn list_by_owner(
+        &self,
+        organization_id: OrganizationIdentifier,
+    ) -> Result<Vec<Stylo>> {
+        self.check_organization(organization_id).await?;
+
+        self.stylo_book.list_by_owner(organization_id).await
+    }
+
+    async fn check_organization(&self, organization_id: OrganizationIdentifier) -> Result<()> {
+        self.organization_book
+            .get(organization_id)
+            .await?
+            .ok_or(StyloServiceError::OrganizationNotFound(organization_id))?;
+
+        Ok(())
+    }
+
+    async fn sync(&self, stylo: Stylo, change_kind: StyloChangeKind) -> Result<Stylo> {
+        let stylo = self.stylo_book.sync(stylo, change_kind).await?;
+
+        self.relay.flush_or_log().await;
+
+        Ok(stylo)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use uuid::Uuid;
+
+    use crate::models::ModelKind;
+    use crate::testkit::create_organization;
+    use crate::Container;
+
+    use super::*;
+
+    fn command(
+        owner_organization_id: OrganizationIdentifier,
+        display_name: &str,
+    ) -> CreateStyloCommand {
+        CreateStyloCommand {
+            owner_organization_id,
+            actor_organization_id: owner_organization_id,
+            display_name: display_name.to_string(),
+            email: "ada@acme.test".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_stylo_lifecycle() {
+        let mut container = Container::default();
+        let service = container.stylo_service().unwrap();
+        let organization_book = container.organization_book().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        container.destroy();
+        let organization_id = create_organization(organization_book.as_ref())
+            .await
+            .organization
+            .organization_id;
+
+        let stylo = service
+            .grant(command(organization_id, "Ada"))
+            .await
+            .unwrap();
+        let stylo_id = stylo.stylo_id;
+        let modified = service
+            .modify(
+                stylo_id,
+                ModifyStyloCommand {
+                    email: Some("lovelace@acme.test".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(modified.display_name, "Ada");
+        assert_eq!(modified.email, "lovelace@acme.test");
+        assert!(service.lock(stylo_id).await.unwrap().is_locked);
+        assert!(!service.unlock(stylo_id).await.unwrap().is_locked);
+        assert_eq!(
+            service.list_by_owner(organization_id).await.unwrap(),
+            vec![service.get(stylo_id).await.unwrap()]
+        );
+        service.revoke(stylo_id).await.unwrap();
+        assert!(service
+            .list_by_owner(organization_id)
+            .await
+            .unwrap()
+            .is_empty());
+
+        for change_kind in [
+            StyloChangeKind::Granted,
+            StyloChangeKind::Modified,
+            StyloChangeKind::Locked,
+            StyloChangeKind::Unlocked,
+            StyloChangeKind::Revoked,
+        ] {
+            let event = receiver.recv().await.unwrap();
+            assert_eq!(
+                event.event.model,
+                ModelKind::Stylo {
+                    stylo_id,
+                    change_kind
+                }
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_grant_errors() {
+        let mut container = Container::default();
+        let service = container.stylo_service().unwrap();
+        let organization_book = container.organization_book().unwrap();
+        let _receiver = container.event_publisher_receiver().unwrap();
+        container.destroy();
+        let organization_id = create_organization(organization_book.as_ref())
+            .await
+            .organization
+            .organization_id;
+
+        let error = service
+            .grant(command(organization_id, " "))
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<StyloServiceError>(),
+            Some(StyloServiceError::EmptyDisplayName)
+        ));
+
+        let unknown_id = Uuid::new_v4();
+        let error = service
+            .grant(CreateStyloCommand {
+                actor_organization_id: unknown_id,
+                ..command(organization_id, "Ada")
+            })
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<StyloServiceError>(),
+            Some(StyloServiceError::OrganizationNotFound(id)) if *id == unknown_id
+        ));
+        assert!(service
+            .list_by_owner(organization_id)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_stylo_state_errors() {
+        let mut container = Container::default();
+        let service = container.stylo_service().unwrap();
+        let organization_book = container.organization_book().unwrap();
+        let _receiver = container.event_publisher_receiver().unwrap();
+        container.destroy();
+        let organization_id = create_organization(organization_book.as_ref())
+            .await
+            .organization
+            .organization_id;
+        let stylo_id = service
+            .grant(command(organization_id, "Ada"))
+            .await
+            .unwrap()
+            .stylo_id;
+
+        let error = service.unlock(stylo_id).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<StyloServiceError>(),
+            Some(StyloServiceError::NotLocked(id)) if *id == stylo_id
+        ));
+        service.lock(stylo_id).await.unwrap();
+        let error = service.lock(stylo_id).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<StyloServiceError>(),
+            Some(StyloServiceError::AlreadyLocked(id)) if *id == stylo_id
+        ));
+        let error = service
+            .modify(
+                stylo_id,
+                ModifyStyloCommand {
+                    display_name: Some(String::new()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<StyloServiceError>(),
+            Some(StyloServiceError::EmptyDisplayName)
+        ));
+
+        service.revoke(stylo_id).await.unwrap();
+        for error in [
+            service.get(stylo_id).await.unwrap_err(),
+            service.lock(stylo_id).await.unwrap_err(),
+            service.revoke(stylo_id).await.unwrap_err(),
+        ] {
+            assert!(matches!(
+                error.downcast_ref::<StyloServiceError>(),
+                Some(StyloServiceError::StyloNotFound(id)) if *id == stylo_id
+            ));
+        }
+    }
+}
@@ -1,24 +1,31 @@
+use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
+use std::time::Duration;
 
-use synapps::EventMessage;
 use thiserror::Error;
-use tokio::sync::mpsc::UnboundedSender;
 use uuid::Uuid;
 
-use crate::adapter::{NoteBook, ProjectBook, ThoughtBook};
+use crate::adapter::{
+    MediaStore, NoteBook, ProjectBook, ReferenceBook, StyloBook, ThoughtBook, UniverseBook,
+};
 use crate::models::{
-    CreateNoteCommand, CreateProjectCommand, CreateThoughtCommand, ModelEvent, ModelKind, Note,
-    NoteChangeKind, Project, ProjectChangeKind, Thought, ThoughtChangeKind,
+    is_media_digest, normalize_tag, CategoryTree, CreateNoteCommand, CreateProjectCommand,
+    CreateReferenceCommand, CreateThoughtCommand, CreatedThought, DanglingLink, LinkKind,
+    LinkReference, Media, ModifyThoughtCommand, Note, ParsedContent, Project, Reference,
+    ReferenceIdentifier, ResponsibilityChain, SimilarityThresholds, Stylo, TagCount, Thought,
+    ThoughtChangeKind, ThoughtFilter, ThoughtIdentifier, ThoughtLink, ThoughtStatus, ThoughtTree,
+    ThoughtVariation,
 };
+use crate::service::OutboxRelay;
 use crate::Result;
 
 /// ThoughtServiceError
 /// Different errors returned by the ThoughtService.
 #[derive(Debug, Error)]
 pub enum ThoughtServiceError {
-    /// Project not found
+    /// Project not found, with the slugs of the projects with close slugs
     #[error("There is not project with slug '{0}'.")]
-    ProjectNotFound(String),
+    ProjectNotFound(String, Vec<String>),
 
     /// Note not found
     #[error("There is no note with noted_id='{0}'.")]
@@ -29,35 +36,155 @@ pub enum ThoughtServiceError {
     ProjectAlreadyExists(String),
 
     /// Universe not found
-    #[error("Universe not found.")]
-    UniverseNotFound,
+    #[error("There is no universe with universe_id='{0}'.")]
+    UniverseNotFound(Uuid),
+
+    /// Stylo not found
+    #[error("There is no stylo with stylo_id='{0}'.")]
+    StyloNotFound(Uuid),
+
+    /// Stylo is locked and cannot be used to write
+    #[error("The stylo with stylo_id='{0}' is locked.")]
+    StyloLocked(Uuid),
 
     /// Parent thought not found
     #[error("There is no thought with thought_id='{0}'.")]
     InvalidParentReference(Uuid),
+
+    /// Parent thought belongs to another project
+    #[error("The parent thought_id='{0}' belongs to another project.")]
+    ParentInAnotherProject(Uuid),
+
+    /// Thought not found
+    #[error("There is no thought with thought_id='{0}'.")]
+    ThoughtNotFound(Uuid),
+
+    /// Answered question not found
+    #[error("There is no question with thought_id='{0}' in this project.")]
+    InvalidQuestionReference(Uuid),
+
+    /// Thought is not a question
+    #[error("The thought with thought_id='{0}' is not a question.")]
+    NotAQuestion(Uuid),
+
+    /// Refuting thought not found
+    #[error("The thought with thought_id='{0}' cannot refute this thought.")]
+    InvalidRefutation(Uuid),
+
+    /// Thought is not disputed
+    #[error("The thought with thought_id='{0}' is not disputed.")]
+    NotDisputed(Uuid),
+
+    /// Thought is already invalidated
+    #[error("The thought with thought_id='{0}' has already been invalidated.")]
+    AlreadyInvalidated(Uuid),
+
+    /// Tag is empty or contains whitespaces
+    #[error("The tag '{0}' is not valid.")]
+    InvalidTag(String),
+
+    /// Linked thought not found
+    #[error("There is no thought with thought_id='{0}' to link to.")]
+    InvalidLink(Uuid),
+
+    /// Tag is already used in the project
+    #[error("The tag '{0}' is already used in this project, merge the tags instead.")]
+    TagAlreadyExists(String),
+
+    /// Reference not found
+    #[error("There is no reference with reference_id='{0}'.")]
+    ReferenceNotFound(Uuid),
+
+    /// Cited reference not found in the project
+    #[error("There is no reference with reference_id='{0}' in this project.")]
+    InvalidReference(Uuid),
+
+    /// Citation key is already used in the project
+    #[error("The citation key '{0}' is already used in this project.")]
+    CitationKeyAlreadyExists(String),
+
+    /// Reference or BibTeX document is not valid
+    #[error("{0}")]
+    InvalidBibliography(String),
+
+    /// Media not found
+    #[error("There is no media with digest '{0}'.")]
+    MediaNotFound(String),
+
+    /// Attached media not uploaded or not matching the uploaded blob
+    #[error("The media '{0}' has not been uploaded or does not match the uploaded content.")]
+    InvalidMedia(String),
 }
 
+/// Maximum number of slugs suggested when a project is not found.
+pub const SLUG_SUGGESTION_LIMIT: usize = 5;
+
+/// Age after which a media no note nor thought refers to is deleted, it
+/// leaves time to attach an upload.
+pub const MEDIA_ORPHAN_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Delay between two sweeps of the orphan medias by
+/// [`ThoughtService::run_media_sweep`].
+pub const MEDIA_SWEEP_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
 /// Thought service
 pub struct ThoughtService {
     note_book: Arc<dyn NoteBook>,
     project_book: Arc<dyn ProjectBook>,
+    universe_book: Arc<dyn UniverseBook>,
+    stylo_book: Arc<dyn StyloBook>,
     thought_book: Arc<dyn ThoughtBook>,
-    sender: UnboundedSender<EventMessage<ModelEvent>>,
+    reference_book: Arc<dyn ReferenceBook>,
+    media_store: Arc<dyn MediaStore>,
+    relay: Arc<OutboxRelay>,
+    similarity: SimilarityThresholds,
 }
 
 impl ThoughtService {
     /// Create a new thought service
+    #[allow(clippy::too_many_arguments)]
     pub fn new(
         note_book: Arc<dyn NoteBook>,
         project_book: Arc<dyn ProjectBook>,
+        universe_book: Arc<dyn UniverseBook>,
+        stylo_book: Arc<dyn StyloBook>,
         thought_book: Arc<dyn ThoughtBook>,
-        sender: UnboundedSender<EventMessage<ModelEvent>>,
+        reference_book: Arc<dyn ReferenceBook>,
+        media_store: Arc<dyn MediaStore>,
+        relay: Arc<OutboxRelay>,
+        similarity: SimilarityThresholds,
     ) -> Self {
         Self {
             note_book,
             project_book,
+            universe_book,
+            stylo_book,
             thought_book,
-            sender,
+            reference_book,
+            media_store,
+            relay,
+            similarity,
+        }
+    }
+
+    /// Get a project by its slug.
+    ///
+    /// If the project does not exist, the raised error suggests the projects
+    /// whose slugs are close to the given one.
+    pub async fn get_project(&self, project_slug: &str) -> Result<Project> {
+        match self.project_book.get_by_slug(project_slug).await? {
+            Some(project) => Ok(project),
+            None => {
+                let suggestions = self
+                    .project_book
+                    .suggest_slugs(project_slug, self.similarity.slug, SLUG_SUGGESTION_LIMIT)
+                    .await?;
+
+                Err(
+                    ThoughtServiceError::ProjectNotFound(project_slug.to_string(), suggestions)
+                        .into(),
+                )
+            }
         }
     }
 
@@ -65,56 +192,195 @@ impl ThoughtService {
     ///
     /// The project pointed by the slug must exist since the slugification is a
     /// surjective function it is not possible to deduce the project name from
-    /// the slug. An error is raised if the project does not exist.
+    /// the slug. An error is raised if the project does not exist, if the
+    /// stylo does not exist or is locked, if a cited reference is not a
+    /// reference of the project or if a media has not been uploaded.
     pub async fn create_note(&self, command: CreateNoteCommand) -> Result<Note> {
-        let project = self
-            .project_book
-            .get_by_slug(&command.project_slug)
-            .await?
-            .ok_or_else(|| ThoughtServiceError::ProjectNotFound(command.project_slug.clone()))?;
+        let project = self.get_project(&command.project_slug).await?;
+        let stylo = self.check_stylo(command.stylo_id).await?;
+        self.check_references(project.project_id, &command.references)
+            .await?;
+        self.check_media(&command.media).await?;
 
-        let note = self.note_book.add(command, project.project_id).await?;
+        let note = self
+            .note_book
+            .add(
+                command,
+                project.project_id,
+                ResponsibilityChain::written_with(&stylo),
+            )
+            .await?;
 
-        self.send_message(ModelEvent {
-            model: ModelKind::Note {
-                note_id: note.note_id,
-                project_id: note.project_id,
-                change_kind: NoteChangeKind::Created,
-            },
-            timestamp: chrono::Utc::now(),
-        })?;
+        self.relay.flush_or_log().await;
 
         Ok(note)
     }
 
     /// Scratch a note.
     ///
-    /// An error is raised if the Note does not exist.
+    /// The medias of the note that no other note nor thought refers to are
+    /// deleted from the media store. An error is raised if the Note does not
+    /// exist.
     pub async fn scratch_note(&self, note_id: uuid::Uuid) -> Result<Note> {
+        let note = self.get_note(note_id).await?;
+        let chain = self.chain_of(note.stylo_id).await?;
         let note = self
             .note_book
-            .delete(note_id)
+            .delete(note_id, chain)
             .await?
             .ok_or(ThoughtServiceError::NoteNotFound(note_id))?;
 
-        self.send_message(ModelEvent {
-            model: ModelKind::Note {
-                note_id: note.note_id,
-                project_id: note.project_id,
-                change_kind: NoteChangeKind::Scratched,
-            },
-            timestamp: chrono::Utc::now(),
-        })?;
+        self.relay.flush_or_log().await;
+        self.collect_media(&note.media).await?;
+
+        Ok(note)
+    }
+
+    /// Delete the blobs of the given medias that are not referenced anymore.
+    async fn collect_media(&self, media: &[Media]) -> Result<()> {
+        let digests: HashSet<&str> = media.iter().map(|m| m.digest.as_str()).collect();
+
+        for digest in digests {
+            self.delete_orphan_media(digest).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Delete a blob if no note nor thought refers to it, return whether it
+    /// was deleted.
+    async fn delete_orphan_media(&self, digest: &str) -> Result<bool> {
+        if self.note_book.is_media_referenced(digest).await?
+            || self.thought_book.is_media_referenced(digest).await?
+        {
+            return Ok(false);
+        }
+
+        self.media_store.delete(digest).await
+    }
+
+    /// Delete the medias stored for longer than the given age that no note
+    /// nor thought refers to, like the uploads never attached, and return
+    /// their digests.
+    ///
+    /// Uploading a media again restarts its age.
+    pub async fn sweep_orphan_media(&self, older_than: Duration) -> Result<Vec<String>> {
+        let before = chrono::Utc::now() - older_than;
+        let mut deleted = Vec::new();
+
+        for digest in self.media_store.list_stored_before(before).await? {
+            if self.delete_orphan_media(&digest).await? {
+                deleted.push(digest);
+            }
+        }
+
+        Ok(deleted)
+    }
+
+    /// Sweep the orphan medias older than the given age at every interval.
+    /// A failed sweep is only logged, the next one deletes what it left.
+    pub async fn run_media_sweep(&self, interval: Duration, older_than: Duration) {
+        loop {
+            match self.sweep_orphan_media(older_than).await {
+                Ok(deleted) if !deleted.is_empty() => {
+                    log::debug!("Deleted {} orphan medias.", deleted.len());
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Could not sweep the orphan medias: {e}"),
+            }
+            tokio::time::sleep(interval).await;
+        }
+    }
+
+    /// Check the medias have been uploaded with the announced size.
+    async fn check_media(&self, media: &[Media]) -> Result<()> {
+        for m in media {
+            if !is_media_digest(&m.digest)
+                || m.mime_type.trim().is_empty()
+                || self.media_store.size(&m.digest).await? != Some(m.size)
+            {
+                return Err(ThoughtServiceError::InvalidMedia(m.digest.clone()).into());
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Store an uploaded media.
+    ///
+    /// The returned media can then accompany thoughts and notes. The MIME type
+    /// defaults to `application/octet-stream`.
+    pub async fn upload_media(
+        &self,
+        content: &[u8],
+        mime_type: Option<&str>,
+        filename: Option<String>,
+    ) -> Result<Media> {
+        let mime_type = mime_type
+            .map(str::trim)
+            .filter(|m| !m.is_empty())
+            .unwrap_or("application/octet-stream");
+        let media = Media::describe(content, mime_type, filename);
+        self.media_store.put(content, &media.mime_type).await?;
+
+        Ok(media)
+    }
+
+    /// Get the content of a media with the MIME type it was uploaded with,
+    /// `application/octet-stream` if it is not known.
+    ///
+    /// An error is raised if the media does not exist.
+    pub async fn download_media(&self, digest: &str) -> Result<(Vec<u8>, String)> {
+        if !is_media_digest(digest) {
+            return Err(ThoughtServiceError::MediaNotFound(digest.to_string()).into());
+        }
+
+        let content = self
+            .media_store
+            .get(digest)
+            .await?
+            .ok_or_else(|| ThoughtServiceError::MediaNotFound(digest.to_string()))?;
+        let mime_type = self
+            .media_store
+            .mime_type(digest)
+            .await?
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        Ok((content, mime_type))
+    }
+
+    /// Get a note.
+    ///
+    /// An error is raised if the Note does not exist.
+    pub async fn get_note(&self, note_id: Uuid) -> Result<Note> {
+        let note = self
+            .note_book
+            .get(note_id)
+            .await?
+            .ok_or(ThoughtServiceError::NoteNotFound(note_id))?;
 
         Ok(note)
     }
 
+    /// List the notes of a project.
+    ///
+    /// An error is raised if the project does not exist.
+    pub async fn list_notes_by_project(&self, project_slug: &str) -> Result<Vec<Note>> {
+        let project = self.get_project(project_slug).await?;
+
+        self.note_book.list_by_project(project.project_id).await
+    }
+
     /// Create a Project
     /// This returns an error if the project already exists.
     /// This returns an error if the universe does not exist.
     pub async fn create_project(&self, command: CreateProjectCommand) -> Result<Project> {
         let slug = Project::generate_slug(&command.project_name);
 
+        if self.universe_book.get(command.universe_id).await?.is_none() {
+            return Err(ThoughtServiceError::UniverseNotFound(command.universe_id).into());
+        }
+
         if self
             .project_book
             .get_by_slug(&Project::generate_slug(&slug))
@@ -126,14 +392,7 @@ impl ThoughtService {
 
         let project = self.project_book.create(command).await?;
 
-        self.send_message(ModelEvent {
-            model: ModelKind::Project {
-                project_id: project.project_id,
-                universe_id: project.universe_id,
-                change_kind: ProjectChangeKind::Created,
-            },
-            timestamp: chrono::Utc::now(),
-        })?;
+        self.relay.flush_or_log().await;
 
         Ok(project)
     }
@@ -141,371 +400,2478 @@ impl ThoughtService {
     /// Create a new thought.
     /// This returns an error if:
     /// - The project does not exist
+    /// - The stylo does not exist or is locked
     /// - The parent thought does not exist (if specified)
-    pub async fn create_thought(&self, command: CreateThoughtCommand) -> Result<Thought> {
-        let project = self
-            .project_book
-            .get_by_slug(&command.project_slug)
-            .await?
-            .ok_or_else(|| ThoughtServiceError::ProjectNotFound(command.project_slug.clone()))?;
+    /// - The parent thought belongs to another project
+    /// - The answered question does not exist in the project (if specified)
+    /// - A tag is not valid
+    /// - A linked thought does not exist
+    /// - A cited reference is not a reference of the project
+    /// - A media has not been uploaded
+    ///
+    /// The `[[...]]` links and `#tags` written in the content are added to the
+    /// links and tags of the thought. Written links that cannot be resolved do
+    /// not prevent the creation, they are reported with the created thought.
+    /// When the thought answers a question, the question is marked as answered.
+    pub async fn create_thought(
+        &self,
+        mut command: CreateThoughtCommand,
+    ) -> Result<CreatedThought> {
+        let parsed = ParsedContent::parse(&command.content);
+        command.tags.extend(parsed.tags);
+        command.tags = Self::normalize_tags(&command.tags)?;
+        self.check_links(None, &command.links).await?;
+
+        let project = self.get_project(&command.project_slug).await?;
+        let stylo = self.check_stylo(command.stylo_id).await?;
+        self.check_references(project.project_id, &command.references)
+            .await?;
+        self.check_media(&command.media).await?;
+
+        let mut unresolved_links = Vec::new();
+        for reference in parsed.links {
+            match self.resolve_link(project.project_id, &reference).await? {
+                Some(target_id) => {
+                    if !command.links.iter().any(|l| l.target_id == target_id) {
+                        command.links.push(ThoughtLink {
+                            target_id,
+                            kind: LinkKind::default(),
+                        });
+                    }
+                }
+                None => unresolved_links.push(reference.as_text()),
+            }
+        }
 
-        // Verify parent exists if specified
+        // Verify parent exists in the same project if specified
         if let Some(parent_id) = command.parent_id {
-            if self.thought_book.get(parent_id).await?.is_none() {
-                return Err(ThoughtServiceError::InvalidParentReference(parent_id).into());
+            let parent = self
+                .thought_book
+                .get(parent_id)
+                .await?
+                .ok_or(ThoughtServiceError::InvalidParentReference(parent_id))?;
+
+            if parent.project_id != project.project_id {
+                return Err(ThoughtServiceError::ParentInAnotherProject(parent_id).into());
             }
         }
 
-        let thought = self.thought_book.add(command, project.project_id).await?;
+        // Verify the answered thought is a question of the same project, the
+        // answer and the answered question are then stored together
+        let chain = ResponsibilityChain::written_with(&stylo);
+        let thought = match command.answers {
+            Some(question_id) => {
+                self.thought_book
+                    .get(question_id)
+                    .await?
+                    .filter(|q| {
+                        q.variation == ThoughtVariation::Question
+                            && q.project_id == project.project_id
+                    })
+                    .ok_or(ThoughtServiceError::InvalidQuestionReference(question_id))?;
+
+                self.thought_book
+                    .add_answer(command, project.project_id, chain)
+                    .await?
+            }
+            None => {
+                self.thought_book
+                    .add(command, project.project_id, chain)
+                    .await?
+            }
+        };
 
-        self.send_message(ModelEvent {
-            model: ModelKind::Thought {
-                thought_id: thought.thought_id,
-                project_id: thought.project_id,
-                change_kind: ThoughtChangeKind::Created,
-            },
-            timestamp: chrono::Utc::now(),
-        })?;
+        self.relay.flush_or_log().await;
 
-        Ok(thought)
+        Ok(CreatedThought {
+            thought,
+            unresolved_links,
+        })
     }
 
-    fn send_message(&self, event: ModelEvent) -> Result<()> {
-        let event_message = EventMessage {
-            sender: "thought".to_string(),
-            topic: "model".to_string(),
-            timestamp: chrono::Utc::now(),
-            event,
+    /// Find the thought a link written in a content refers to.
+    /// Full identifiers may refer to thoughts of any project, short identifiers
+    /// must match exactly one thought of the project.
+    async fn resolve_link(
+        &self,
+        project_id: Uuid,
+        reference: &LinkReference,
+    ) -> Result<Option<ThoughtIdentifier>> {
+        let target_id = match reference {
+            LinkReference::Id(thought_id) => self
+                .thought_book
+                .get(*thought_id)
+                .await?
+                .map(|t| t.thought_id),
+            LinkReference::ShortId(short_id) => {
+                match self
+                    .thought_book
+                    .list_by_short_id(project_id, short_id)
+                    .await?
+                    .as_slice()
+                {
+                    [thought] => Some(thought.thought_id),
+                    _ => None,
+                }
+            }
+            LinkReference::Unknown(_) => None,
         };
-        self.sender.send(event_message)?;
 
-        Ok(())
+        Ok(target_id)
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use chrono::Utc;
-    use uuid::Uuid;
+    /// List the thoughts of a project matching the given filter.
+    ///
+    /// An error is raised if the project does not exist.
+    pub async fn list_thoughts_by_project(
+        &self,
+        project_slug: &str,
+        filter: &ThoughtFilter,
+    ) -> Result<Vec<Thought>> {
+        let project = self.get_project(project_slug).await?;
+
+        self.thought_book
+            .list_by_project(project.project_id, filter)
+            .await
+    }
 
-    use crate::{
-        models::{ProjectChangeKind, ThoughtChangeKind},
-        Container,
-    };
+    /// Modify the content, the tags, the category, the links, the references or
+    /// the medias of a thought.
+    ///
+    /// This returns an error if:
+    /// - The thought does not exist
+    /// - A tag is not valid
+    /// - A linked thought does not exist or is the thought itself
+    /// - A cited reference is not a reference of the project
+    /// - A media has not been uploaded
+    pub async fn modify_thought(
+        &self,
+        thought_id: ThoughtIdentifier,
+        command: ModifyThoughtCommand,
+    ) -> Result<Thought> {
+        let mut thought = self.get_thought(thought_id).await?;
+
+        if let Some(tags) = command.tags {
+            thought.tags = Self::normalize_tags(&tags)?;
+        }
+        if let Some(content) = command.content {
+            thought.content = content;
+        }
+        if let Some(category) = command.category {
+            thought.category = Some(category);
+        }
+        if let Some(links) = command.links {
+            self.check_links(Some(thought_id), &links).await?;
+            thought.links = links;
+        }
+        if let Some(references) = command.references {
+            self.check_references(thought.project_id, &references)
+                .await?;
+            thought.references = references;
+        }
+        if let Some(media) = command.media {
+            self.check_media(&media).await?;
+            thought.media = media;
+        }
 
-    use super::*;
+        self.sync_thought(thought, ThoughtChangeKind::Modified)
+            .await
+    }
 
-    #[tokio::test]
-    async fn test_create_note_success_project_not_exist() {
-        let mut container = Container::default();
-        let thought_service = container.thought_service().unwrap();
-        let mut receiver = container.event_publisher_receiver().unwrap();
-        container.destroy();
+    /// List the thoughts linking to a thought, whatever their project.
+    ///
+    /// An error is raised if the Thought does not exist.
+    pub async fn list_backlinks(&self, thought_id: ThoughtIdentifier) -> Result<Vec<Thought>> {
+        let thought = self.get_thought(thought_id).await?;
 
-        let command = CreateNoteCommand {
-            imported_at: Utc::now(),
-            stylo_id: Uuid::new_v4(),
-            project_slug: String::from("test-project"),
-            content: "This is a test note.".to_string(),
-        };
+        self.thought_book.list_backlinks(thought.thought_id).await
+    }
 
-        let error = thought_service
-            .create_note(command)
-            .await
-            .unwrap_err()
-            .downcast::<ThoughtServiceError>()
-            .expect("Expected ThoughtServiceError");
+    /// List the links held by the thoughts of a project whose target has been
+    /// trashed.
+    ///
+    /// An error is raised if the project does not exist.
+    pub async fn list_dangling_links(&self, project_slug: &str) -> Result<Vec<DanglingLink>> {
+        let project = self.get_project(project_slug).await?;
 
-        assert!(matches!(error, ThoughtServiceError::ProjectNotFound(_)));
+        self.thought_book
+            .list_dangling_links(project.project_id)
+            .await
+    }
 
-        // check that the event was not sent
-        assert!(receiver.try_recv().is_err());
+    /// Trash all the thoughts of a project.
+    ///
+    /// The links held by the thoughts of other projects to the trashed
+    /// thoughts are kept dangling. The medias of the trashed thoughts that no
+    /// other note nor thought refers to are deleted from the media store. The
+    /// trashed thoughts are returned, an error is raised if the project does
+    /// not exist.
+    pub async fn trash_thoughts(&self, project_slug: &str) -> Result<Vec<Thought>> {
+        let project = self.get_project(project_slug).await?;
+        let chain = self.project_chain(&project).await?;
+        let thoughts = self
+            .thought_book
+            .trash_by_project(project.project_id, chain)
+            .await?;
+
+        self.relay.flush_or_log().await;
+        let media: Vec<Media> = thoughts.iter().flat_map(|t| t.media.clone()).collect();
+        self.collect_media(&media).await?;
+
+        Ok(thoughts)
     }
 
-    #[tokio::test]
-    async fn test_create_note_success_project_exist() {
-        let mut container = Container::default();
-        let thought_service = container.thought_service().unwrap();
-        let project_book = container.project_book().unwrap();
-        let mut receiver = container.event_publisher_receiver().unwrap();
-        container.destroy();
+    /// Check the linked thoughts exist, a thought cannot link to itself.
+    async fn check_links(
+        &self,
+        thought_id: Option<ThoughtIdentifier>,
+        links: &[ThoughtLink],
+    ) -> Result<()> {
+        for link in links {
+            if Some(link.target_id) == thought_id
+                || self.thought_book.get(link.target_id).await?.is_none()
+            {
+                return Err(ThoughtServiceError::InvalidLink(link.target_id).into());
+            }
+        }
 
-        let project_command = crate::models::CreateProjectCommand {
-            universe_id: Uuid::new_v4(),
-            project_name: "Test Project".to_string(),
-        };
-        let project = project_book.create(project_command).await.unwrap();
+        Ok(())
+    }
 
-        let command = CreateNoteCommand {
-            imported_at: Utc::now(),
-            stylo_id: Uuid::new_v4(),
-            project_slug: project.slug,
-            content: "This is a test note.".to_string(),
-        };
+    /// Check the stylo exists and is not locked, it is returned to sign the
+    /// changes written with it.
+    async fn check_stylo(&self, stylo_id: Uuid) -> Result<Stylo> {
+        let stylo = self
+            .stylo_book
+            .get(stylo_id)
+            .await?
+            .ok_or(ThoughtServiceError::StyloNotFound(stylo_id))?;
+        if stylo.is_locked {
+            return Err(ThoughtServiceError::StyloLocked(stylo_id).into());
+        }
 
-        let note = thought_service.create_note(command).await.unwrap();
+        Ok(stylo)
+    }
 
-        assert_eq!(note.content, "This is a test note.");
-        assert_eq!(note.project_id, project.project_id);
+    /// The chain of a change of a model written with a stylo, on the behalf
+    /// of the organizations of the stylo, locked or not.
+    async fn chain_of(&self, stylo_id: Uuid) -> Result<ResponsibilityChain> {
+        Ok(match self.stylo_book.get(stylo_id).await? {
+            Some(stylo) => ResponsibilityChain::written_with(&stylo),
+            None => ResponsibilityChain::stylo(stylo_id),
+        })
+    }
 
-        // check that the event was sent
-        let event = receiver.recv().await.unwrap();
-        assert_eq!(
-            event.event.model,
-            ModelKind::Note {
-                note_id: note.note_id,
-                project_id: note.project_id,
-                change_kind: NoteChangeKind::Created,
-            }
-        );
+    /// The chain of a change of all the thoughts of a project, made by the
+    /// organization of its universe. The change is signed by the instance if
+    /// the universe is unknown.
+    async fn project_chain(&self, project: &Project) -> Result<ResponsibilityChain> {
+        Ok(self
+            .universe_book
+            .get(project.universe_id)
+            .await?
+            .map(|u| ResponsibilityChain::organization(u.organization_id))
+            .unwrap_or_default())
     }
 
-    #[tokio::test]
-    async fn test_scratch_note_success() {
-        let mut container = Container::default();
-        let thought_service = container.thought_service().unwrap();
-        let note_book = container.note_book().unwrap();
-        let command = CreateNoteCommand {
-            imported_at: Utc::now(),
-            stylo_id: Uuid::new_v4(),
-            project_slug: String::from("test-project"),
-            content: "This is a test note.".to_string(),
-        };
-        let note = note_book.add(command, Uuid::new_v4()).await.unwrap();
-        let note_id = note.note_id;
-        let mut receiver = container.event_publisher_receiver().unwrap();
-        container.destroy();
+    /// Check the cited references belong to the project.
+    async fn check_references(
+        &self,
+        project_id: Uuid,
+        references: &[ReferenceIdentifier],
+    ) -> Result<()> {
+        for reference_id in references {
+            let reference = self.reference_book.get(*reference_id).await?;
+            if reference.is_none_or(|r| r.project_id != project_id) {
+                return Err(ThoughtServiceError::InvalidReference(*reference_id).into());
+            }
+        }
 
-        let note = thought_service.scratch_note(note_id).await.unwrap();
+        Ok(())
+    }
 
-        // Check that the note was scratched and is not available anymore
-        assert!(note_book.get(note_id).await.unwrap().is_none());
+    /// Create a reference in a project.
+    ///
+    /// This returns an error if:
+    /// - The project does not exist
+    /// - The reference is not valid
+    /// - The citation key is already used in the project
+    pub async fn create_reference(
+        &self,
+        project_slug: &str,
+        command: CreateReferenceCommand,
+    ) -> Result<Reference> {
+        command
+            .validate()
+            .map_err(|e| ThoughtServiceError::InvalidBibliography(e.to_string()))?;
+        let project = self.get_project(project_slug).await?;
 
-        // check that the event was sent
-        let event = receiver.recv().await.unwrap();
-        assert_eq!(
-            event.event.model,
-            ModelKind::Note {
-                note_id,
-                project_id: note.project_id,
-                change_kind: NoteChangeKind::Scratched,
-            }
-        );
+        if self
+            .reference_book
+            .get_by_citation_key(project.project_id, command.citation_key.trim())
+            .await?
+            .is_some()
+        {
+            return Err(ThoughtServiceError::CitationKeyAlreadyExists(command.citation_key).into());
+        }
+
+        self.reference_book.add(command, project.project_id).await
     }
 
-    #[tokio::test]
-    async fn test_create_project_success() {
-        let mut container = Container::default();
-        let thought_service = container.thought_service().unwrap();
-        let project_book = container.project_book().unwrap();
-        let mut receiver = container.event_publisher_receiver().unwrap();
-        container.destroy();
+    /// Get a reference.
+    ///
+    /// An error is raised if the Reference does not exist.
+    pub async fn get_reference(&self, reference_id: ReferenceIdentifier) -> Result<Reference> {
+        let reference = self
+            .reference_book
+            .get(reference_id)
+            .await?
+            .ok_or(ThoughtServiceError::ReferenceNotFound(reference_id))?;
 
-        let command = CreateProjectCommand {
-            universe_id: Uuid::new_v4(),
-            project_name: "New Project".to_string(),
-        };
+        Ok(reference)
+    }
 
-        thought_service.create_project(command).await.unwrap();
+    /// List the references of a project, sorted by citation key.
+    ///
+    /// An error is raised if the project does not exist.
+    pub async fn list_references(&self, project_slug: &str) -> Result<Vec<Reference>> {
+        let project = self.get_project(project_slug).await?;
 
-        let project = project_book
-            .get_by_slug("new-project")
+        self.reference_book
+            .list_by_project(project.project_id)
+            .await
+    }
+
+    /// Import the entries of a BibTeX document in a project.
+    ///
+    /// Entries whose citation key is already used in the project update the
+    /// existing reference, keeping its identifier so citations still hold. The
+    /// whole document is checked then imported in one unit of work, so a
+    /// failure imports nothing. The imported references are returned in the
+    /// order of the document.
+    pub async fn import_bibtex(&self, project_slug: &str, bibtex: &str) -> Result<Vec<Reference>> {
+        let commands = CreateReferenceCommand::from_bibtex(bibtex)
+            .map_err(|e| ThoughtServiceError::InvalidBibliography(e.to_string()))?;
+        for command in &commands {
+            command
+                .validate()
+                .map_err(|e| ThoughtServiceError::InvalidBibliography(e.to_string()))?;
+        }
+        let project = self.get_project(project_slug).await?;
+
+        self.reference_book
+            .import(commands, project.project_id)
+            .await
+    }
+
+    /// Export the references of a project as a BibTeX document.
+    ///
+    /// An error is raised if the project does not exist.
+    pub async fn export_bibtex(&self, project_slug: &str) -> Result<String> {
+        let references = self.list_references(project_slug).await?;
+
+        Ok(references
+            .iter()
+            .map(Reference::to_bibtex)
+            .collect::<Vec<_>>()
+            .join("\n"))
+    }
+
+    /// List the tags used in a project with the number of thoughts carrying them.
+    ///
+    /// An error is raised if the project does not exist.
+    pub async fn list_tags(&self, project_slug: &str) -> Result<Vec<TagCount>> {
+        let project = self.get_project(project_slug).await?;
+
+        self.thought_book.list_tags(project.project_id).await
+    }
+
+    /// Get the category tree of a project.
+    ///
+    /// Each category comes with the number of thoughts filed in it and under
+    /// it. An error is raised if the project does not exist.
+    pub async fn get_category_tree(&self, project_slug: &str) -> Result<Vec<CategoryTree>> {
+        let project = self.get_project(project_slug).await?;
+        let counts = self
+            .thought_book
+            .list_categories(project.project_id)
+            .await?;
+
+        Ok(CategoryTree::build(&counts))
+    }
+
+    /// Rename a tag on all the thoughts of a project.
+    ///
+    /// The modified thoughts are returned. This returns an error if:
+    /// - The project does not exist
+    /// - A tag is not valid
+    /// - The new tag is already used in the project, tags must be merged then
+    pub async fn rename_tag(
+        &self,
+        project_slug: &str,
+        tag: &str,
+        new_tag: &str,
+    ) -> Result<Vec<Thought>> {
+        let tag = Self::normalize_tags(&[tag.to_string()])?;
+        let new_tag = Self::normalize_tags(&[new_tag.to_string()])?.remove(0);
+        let project = self.get_project(project_slug).await?;
+
+        if tag[0] != new_tag
+            && self
+                .thought_book
+                .list_tags(project.project_id)
+                .await?
+                .iter()
+                .any(|t| t.tag == new_tag)
+        {
+            return Err(ThoughtServiceError::TagAlreadyExists(new_tag).into());
+        }
+
+        self.retag(&project, &tag, &new_tag).await
+    }
+
+    /// Merge several tags into one on all the thoughts of a project.
+    ///
+    /// The target tag may already be in use. The modified thoughts are
+    /// returned. An error is raised if the project does not exist or if a tag
+    /// is not valid.
+    pub async fn merge_tags(
+        &self,
+        project_slug: &str,
+        tags: &[String],
+        into: &str,
+    ) -> Result<Vec<Thought>> {
+        let tags = Self::normalize_tags(tags)?;
+        let into = Self::normalize_tags(&[into.to_string()])?.remove(0);
+        let project = self.get_project(project_slug).await?;
+
+        self.retag(&project, &tags, &into).await
+    }
+
+    /// Replace tags in a project, each modified thought is told as changed.
+    async fn retag(&self, project: &Project, from: &[String], to: &str) -> Result<Vec<Thought>> {
+        let chain = self.project_chain(project).await?;
+        let thoughts = self
+            .thought_book
+            .retag(project.project_id, from, to, chain)
+            .await?;
+
+        self.relay.flush_or_log().await;
+
+        Ok(thoughts)
+    }
+
+    /// Normalize tags, they are returned sorted and without duplicates.
+    fn normalize_tags(tags: &[String]) -> Result<Vec<String>> {
+        let mut normalized = tags
+            .iter()
+            .map(|tag| {
+                normalize_tag(tag).ok_or_else(|| ThoughtServiceError::InvalidTag(tag.clone()))
+            })
+            .collect::<std::result::Result<Vec<String>, _>>()?;
+        normalized.sort();
+        normalized.dedup();
+
+        Ok(normalized)
+    }
+
+    /// List the thoughts answering a question.
+    ///
+    /// An error is raised if the Thought does not exist or is not a question.
+    pub async fn list_answers(&self, question_id: ThoughtIdentifier) -> Result<Vec<Thought>> {
+        let question = self.get_question(question_id).await?;
+        let filter = ThoughtFilter {
+            answers: Some(question_id),
+            ..Default::default()
+        };
+
+        self.thought_book
+            .list_by_project(question.project_id, &filter)
+            .await
+    }
+
+    /// Reopen an answered question.
+    ///
+    /// This is used when the answers given so far are not satisfying. An error
+    /// is raised if the Thought does not exist or is not a question.
+    pub async fn reopen_question(&self, question_id: ThoughtIdentifier) -> Result<Thought> {
+        let mut question = self.get_question(question_id).await?;
+
+        if !question.answered {
+            return Ok(question);
+        }
+        question.answered = false;
+
+        self.sync_thought(question, ThoughtChangeKind::Modified)
+            .await
+    }
+
+    async fn get_question(&self, question_id: ThoughtIdentifier) -> Result<Thought> {
+        let question = self.get_thought(question_id).await?;
+
+        if question.variation != ThoughtVariation::Question {
+            return Err(ThoughtServiceError::NotAQuestion(question_id).into());
+        }
+
+        Ok(question)
+    }
+
+    /// Refute a thought by another thought.
+    ///
+    /// The refuted thought becomes disputed until the refutation is accepted
+    /// or dismissed. This returns an error if:
+    /// - One of the thoughts does not exist
+    /// - The refuting thought is the refuted thought or belongs to another project
+    /// - The refuted thought has already been invalidated
+    pub async fn refute(
+        &self,
+        thought_id: ThoughtIdentifier,
+        refuting_thought_id: ThoughtIdentifier,
+    ) -> Result<Thought> {
+        let mut thought = self.get_thought(thought_id).await?;
+
+        if thought.status == ThoughtStatus::Invalidated {
+            return Err(ThoughtServiceError::AlreadyInvalidated(thought_id).into());
+        }
+
+        self.thought_book
+            .get(refuting_thought_id)
+            .await?
+            .filter(|r| r.thought_id != thought_id && r.project_id == thought.project_id)
+            .ok_or(ThoughtServiceError::InvalidRefutation(refuting_thought_id))?;
+
+        thought.refuted_by = Some(refuting_thought_id);
+        thought.status = ThoughtStatus::Disputed;
+
+        self.sync_thought(thought, ThoughtChangeKind::Disputed(refuting_thought_id))
+            .await
+    }
+
+    /// Accept the refutation of a disputed thought.
+    ///
+    /// The thought is invalidated. An error is raised if the Thought does not
+    /// exist or is not disputed.
+    pub async fn accept_refutation(&self, thought_id: ThoughtIdentifier) -> Result<Thought> {
+        let (mut thought, refuting_thought_id) = self.get_disputed_thought(thought_id).await?;
+        thought.status = ThoughtStatus::Invalidated;
+
+        self.sync_thought(thought, ThoughtChangeKind::Invalidated(refuting_thought_id))
+            .await
+    }
+
+    /// Dismiss the refutation of a disputed thought.
+    ///
+    /// The thought stands again. An error is raised if the Thought does not
+    /// exist or is not disputed.
+    pub async fn dismiss_refutation(&self, thought_id: ThoughtIdentifier) -> Result<Thought> {
+        let (mut thought, _) = self.get_disputed_thought(thought_id).await?;
+        thought.refuted_by = None;
+        thought.status = ThoughtStatus::Standing;
+
+        self.sync_thought(thought, ThoughtChangeKind::Modified)
+            .await
+    }
+
+    async fn get_disputed_thought(
+        &self,
+        thought_id: ThoughtIdentifier,
+    ) -> Result<(Thought, ThoughtIdentifier)> {
+        let thought = self.get_thought(thought_id).await?;
+
+        match (thought.status, thought.refuted_by) {
+            (ThoughtStatus::Disputed, Some(refuting_thought_id)) => {
+                Ok((thought, refuting_thought_id))
+            }
+            _ => Err(ThoughtServiceError::NotDisputed(thought_id).into()),
+        }
+    }
+
+    /// Store the new state of a thought and tell how it has changed, on the
+    /// behalf of the organizations of its stylo.
+    async fn sync_thought(
+        &self,
+        thought: Thought,
+        change_kind: ThoughtChangeKind,
+    ) -> Result<Thought> {
+        let chain = self.chain_of(thought.stylo_id).await?;
+        let thought = self.thought_book.sync(thought, change_kind, chain).await?;
+
+        self.relay.flush_or_log().await;
+
+        Ok(thought)
+    }
+
+    /// Get a thought.
+    ///
+    /// An error is raised if the Thought does not exist.
+    pub async fn get_thought(&self, thought_id: ThoughtIdentifier) -> Result<Thought> {
+        let thought = self
+            .thought_book
+            .get(thought_id)
+            .await?
+            .ok_or(ThoughtServiceError::ThoughtNotFound(thought_id))?;
+
+        Ok(thought)
+    }
+
+    /// Get the ancestors of a thought.
+    ///
+    /// The ancestors are returned from the direct parent up to the root
+    /// thought. An error is raised if the Thought does not exist.
+    pub async fn get_thought_ancestors(
+        &self,
+        thought_id: ThoughtIdentifier,
+    ) -> Result<Vec<Thought>> {
+        let thought = self.get_thought(thought_id).await?;
+        let mut ancestors: Vec<Thought> = Vec::new();
+        let mut visited = HashSet::from([thought.thought_id]);
+        let mut parent_id = thought.parent_id;
+
+        while let Some(id) = parent_id {
+            // Protect against cycles that could have been synced in the book.
+            if !visited.insert(id) {
+                break;
+            }
+            let Some(parent) = self.thought_book.get(id).await? else {
+                break;
+            };
+            parent_id = parent.parent_id;
+            ancestors.push(parent);
+        }
+
+        Ok(ancestors)
+    }
+
+    /// Get the tree of descendants of a thought.
+    ///
+    /// When a maximum depth is given, the descendants deeper than this depth
+    /// are not fetched, a depth of 0 returns the thought alone. An error is
+    /// raised if the Thought does not exist.
+    pub async fn get_thought_descendants(
+        &self,
+        thought_id: ThoughtIdentifier,
+        max_depth: Option<usize>,
+    ) -> Result<ThoughtTree> {
+        let root = self.get_thought(thought_id).await?;
+        let mut children_by_parent: HashMap<ThoughtIdentifier, Vec<Thought>> = HashMap::new();
+        let mut visited = HashSet::from([root.thought_id]);
+        let mut level = vec![root.thought_id];
+        let mut depth = 0;
+
+        while !level.is_empty() && max_depth.is_none_or(|max| depth < max) {
+            let mut next_level = Vec::new();
+
+            for parent_id in level {
+                let children: Vec<Thought> = self
+                    .thought_book
+                    .list_children(parent_id)
+                    .await?
+                    .into_iter()
+                    .filter(|child| visited.insert(child.thought_id))
+                    .collect();
+                next_level.extend(children.iter().map(|child| child.thought_id));
+                children_by_parent.insert(parent_id, children);
+            }
+            level = next_level;
+            depth += 1;
+        }
+
+        Ok(Self::build_tree(root, &mut children_by_parent))
+    }
+
+    fn build_tree(
+        thought: Thought,
+        children_by_parent: &mut HashMap<ThoughtIdentifier, Vec<Thought>>,
+    ) -> ThoughtTree {
+        let children = children_by_parent
+            .remove(&thought.thought_id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| Self::build_tree(child, children_by_parent))
+            .collect();
+
+        ThoughtTree { thought, children }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+    use uuid::Uuid;
+
+    use crate::{
+        adapter::Outbox,
+        models::{
+            CreateOrganizationCommand, ModelKind, NoteChangeKind, ProjectChangeKind,
+            ThoughtChangeKind,
+        },
+        testkit::{create_organization, grant_stylo},
+        Container,
+    };
+
+    use super::*;
+
+    /// Forget the events of the changes made directly in the books to set up a
+    /// test, only the events of the service calls are checked.
+    async fn clear_outbox(outbox: &dyn Outbox) {
+        let pending = outbox.pending(usize::MAX).await.unwrap();
+        let event_ids = pending.iter().map(|e| e.event_id).collect::<Vec<_>>();
+        outbox.acknowledge(&event_ids).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_create_note_success_project_not_exist() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        container.destroy();
+
+        let command = CreateNoteCommand {
+            imported_at: Utc::now(),
+            stylo_id: Uuid::new_v4(),
+            project_slug: String::from("test-project"),
+            content: "This is a test note.".to_string(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+
+        let error = thought_service
+            .create_note(command)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(error, ThoughtServiceError::ProjectNotFound(..)));
+
+        // check that the event was not sent
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_note_success_project_exist() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        let outbox = container.outbox().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+
+        let project_command = crate::models::CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+
+        clear_outbox(outbox.as_ref()).await;
+        let command = CreateNoteCommand {
+            imported_at: Utc::now(),
+            stylo_id,
+            project_slug: project.slug,
+            content: "This is a test note.".to_string(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+
+        let note = thought_service.create_note(command).await.unwrap();
+
+        assert_eq!(note.content, "This is a test note.");
+        assert_eq!(note.project_id, project.project_id);
+
+        // check that the event was sent
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(
+            event.event.model,
+            ModelKind::Note {
+                note_id: note.note_id,
+                project_id: note.project_id,
+                change_kind: NoteChangeKind::Created,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_note_dispatcher_gone() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let outbox = container.outbox().unwrap();
+        drop(container.event_publisher_receiver().unwrap());
+        let stylo_book = container.stylo_book().unwrap();
+        container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+
+        let project_command = crate::models::CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+        clear_outbox(outbox.as_ref()).await;
+
+        let command = CreateNoteCommand {
+            imported_at: Utc::now(),
+            stylo_id,
+            project_slug: project.slug,
+            content: "This is a test note.".to_string(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+
+        // The note is stored, its event waits in the outbox to be relayed.
+        let note = thought_service.create_note(command).await.unwrap();
+        let pending = outbox.pending(usize::MAX).await.unwrap();
+        assert_eq!(pending.len(), 1);
+        assert_eq!(
+            pending[0].model,
+            ModelKind::Note {
+                note_id: note.note_id,
+                project_id: note.project_id,
+                change_kind: NoteChangeKind::Created,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_with_unknown_or_locked_stylo() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        let outbox = container.outbox().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        container.destroy();
+
+        let project_command = CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+        let mut locked = grant_stylo(stylo_book.as_ref(), Uuid::new_v4()).await;
+        locked.is_locked = true;
+        stylo_book
+            .sync(locked.clone(), crate::models::StyloChangeKind::Locked)
+            .await
+            .unwrap();
+        clear_outbox(outbox.as_ref()).await;
+
+        let unknown_id = Uuid::new_v4();
+        for stylo_id in [unknown_id, locked.stylo_id] {
+            let note_error = thought_service
+                .create_note(CreateNoteCommand {
+                    imported_at: Utc::now(),
+                    stylo_id,
+                    project_slug: project.slug.clone(),
+                    content: "This is a test note.".to_string(),
+                    references: Vec::new(),
+                    media: Vec::new(),
+                })
+                .await
+                .unwrap_err();
+            let thought_error = thought_service
+                .create_thought(CreateThoughtCommand {
+                    imported_at: Utc::now(),
+                    parent_id: None,
+                    stylo_id,
+                    project_slug: project.slug.clone(),
+                    content: "This is a test thought.".to_string(),
+                    variation: ThoughtVariation::Thought,
+                    answers: None,
+                    tags: Vec::new(),
+                    category: None,
+                    links: Vec::new(),
+                    references: Vec::new(),
+                    media: Vec::new(),
+                })
+                .await
+                .unwrap_err();
+
+            for error in [note_error, thought_error] {
+                let error = error
+                    .downcast::<ThoughtServiceError>()
+                    .expect("Expected ThoughtServiceError");
+                if stylo_id == unknown_id {
+                    assert!(
+                        matches!(error, ThoughtServiceError::StyloNotFound(id) if id == stylo_id)
+                    );
+                } else {
+                    assert!(
+                        matches!(error, ThoughtServiceError::StyloLocked(id) if id == stylo_id)
+                    );
+                }
+            }
+        }
+
+        // nothing was stored
+        assert!(thought_service
+            .list_notes_by_project(&project.slug)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_signed_by_actor_organization() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        let key_store = container.key_store().unwrap();
+        let outbox = container.outbox().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        container.destroy();
+
+        let project_command = CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+        let owner_organization_id = Uuid::new_v4();
+        let actor_organization_id = Uuid::new_v4();
+        let stylo = stylo_book
+            .add(crate::models::CreateStyloCommand {
+                owner_organization_id,
+                actor_organization_id,
+                display_name: "Ada".to_string(),
+                email: "ada@acme.test".to_string(),
+            })
+            .await
+            .unwrap();
+        clear_outbox(outbox.as_ref()).await;
+
+        thought_service
+            .create_note(CreateNoteCommand {
+                imported_at: Utc::now(),
+                stylo_id: stylo.stylo_id,
+                project_slug: project.slug.clone(),
+                content: "This is a test note.".to_string(),
+                references: Vec::new(),
+                media: Vec::new(),
+            })
+            .await
+            .unwrap();
+        thought_service
+            .create_thought(CreateThoughtCommand {
+                imported_at: Utc::now(),
+                parent_id: None,
+                stylo_id: stylo.stylo_id,
+                project_slug: project.slug.clone(),
+                content: "This is a test thought.".to_string(),
+                variation: ThoughtVariation::Thought,
+                answers: None,
+                tags: Vec::new(),
+                category: None,
+                links: Vec::new(),
+                references: Vec::new(),
+                media: Vec::new(),
+            })
+            .await
+            .unwrap();
+
+        for _ in 0..2 {
+            let event = receiver.recv().await.unwrap().event;
+            assert_eq!(event.chain, ResponsibilityChain::written_with(&stylo));
+            assert_eq!(
+                event.signature.as_ref().unwrap().key_id,
+                actor_organization_id.to_string()
+            );
+            key_store.verify(&event).await.unwrap();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_changes_signed_by_organizations() {
+        let mut container = Container::default();
+        let created = container
+            .organization_service()
+            .unwrap()
+            .create(CreateOrganizationCommand {
+                name: "Acme".to_string(),
+                certificate: serde_json::Value::Null,
+            })
+            .await
+            .unwrap();
+        let thought_service = container.thought_service().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        let key_store = container.key_store().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        container.destroy();
+
+        let project = thought_service
+            .create_project(CreateProjectCommand {
+                universe_id: created.universe.universe_id,
+                project_name: "Test Project".to_string(),
+            })
+            .await
+            .unwrap();
+        let stylo = grant_stylo(stylo_book.as_ref(), Uuid::new_v4()).await;
+        let thought = thought_service
+            .create_thought(CreateThoughtCommand {
+                imported_at: Utc::now(),
+                parent_id: None,
+                stylo_id: stylo.stylo_id,
+                project_slug: project.slug.clone(),
+                content: "This is a test thought.".to_string(),
+                variation: ThoughtVariation::Thought,
+                answers: None,
+                tags: Vec::new(),
+                category: None,
+                links: Vec::new(),
+                references: Vec::new(),
+                media: Vec::new(),
+            })
+            .await
+            .unwrap()
+            .thought;
+        while receiver.try_recv().is_ok() {}
+
+        thought_service
+            .modify_thought(
+                thought.thought_id,
+                ModifyThoughtCommand {
+                    content: Some("This is a modified thought.".to_string()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        thought_service.trash_thoughts(&project.slug).await.unwrap();
+
+        let modified = receiver.recv().await.unwrap().event;
+        assert_eq!(modified.chain, ResponsibilityChain::written_with(&stylo));
+        assert_eq!(
+            modified.signature.as_ref().unwrap().key_id,
+            stylo.actor_organization_id.to_string()
+        );
+        key_store.verify(&modified).await.unwrap();
+
+        let trashed = receiver.recv().await.unwrap().event;
+        let organization_id = created.organization.organization_id;
+        assert_eq!(
+            trashed.chain,
+            ResponsibilityChain::organization(organization_id)
+        );
+        assert_eq!(
+            trashed.signature.as_ref().unwrap().key_id,
+            organization_id.to_string()
+        );
+        key_store.verify(&trashed).await.unwrap();
+        trashed
+            .verify(&created.organization.verifying_key().unwrap())
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_scratch_note_success() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let note_book = container.note_book().unwrap();
+        let command = CreateNoteCommand {
+            imported_at: Utc::now(),
+            stylo_id: Uuid::new_v4(),
+            project_slug: String::from("test-project"),
+            content: "This is a test note.".to_string(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+        let note = note_book
+            .add(command, Uuid::new_v4(), Default::default())
+            .await
+            .unwrap();
+        let note_id = note.note_id;
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        let outbox = container.outbox().unwrap();
+        container.destroy();
+
+        clear_outbox(outbox.as_ref()).await;
+        let note = thought_service.scratch_note(note_id).await.unwrap();
+
+        // Check that the note was scratched and is not available anymore
+        assert!(note_book.get(note_id).await.unwrap().is_none());
+
+        // check that the event was sent
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(
+            event.event.model,
+            ModelKind::Note {
+                note_id,
+                project_id: note.project_id,
+                change_kind: NoteChangeKind::Scratched,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_note_not_found() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        container.destroy();
+
+        let note_id = Uuid::new_v4();
+        let error = thought_service
+            .get_note(note_id)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(error, ThoughtServiceError::NoteNotFound(id) if id == note_id));
+    }
+
+    #[tokio::test]
+    async fn test_list_notes_by_project() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let note_book = container.note_book().unwrap();
+        container.destroy();
+
+        let project_command = CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+        let command = CreateNoteCommand {
+            imported_at: Utc::now(),
+            stylo_id: Uuid::new_v4(),
+            project_slug: project.slug.clone(),
+            content: "This is a test note.".to_string(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+        let note = note_book
+            .add(command, project.project_id, Default::default())
+            .await
+            .unwrap();
+
+        let notes = thought_service
+            .list_notes_by_project(&project.slug)
+            .await
+            .unwrap();
+
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].note_id, note.note_id);
+
+        let error = thought_service
+            .list_notes_by_project("unknown-project")
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(error, ThoughtServiceError::ProjectNotFound(..)));
+    }
+
+    #[tokio::test]
+    async fn test_create_project_success() {
+        let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        container.destroy();
+
+        let command = CreateProjectCommand {
+            universe_id,
+            project_name: "New Project".to_string(),
+        };
+
+        thought_service.create_project(command).await.unwrap();
+
+        let project = project_book
+            .get_by_slug("new-project")
+            .await
+            .unwrap()
+            .expect("there should be a project");
+        assert_eq!(project.project_name, "New Project");
+
+        // check that the event was sent
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(
+            event.event.model,
+            ModelKind::Project {
+                project_id: project.project_id,
+                universe_id: project.universe_id,
+                change_kind: ProjectChangeKind::Created,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_project_error_universe_not_found() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        container.destroy();
+
+        let universe_id = Uuid::new_v4();
+        let command = CreateProjectCommand {
+            universe_id,
+            project_name: "Lost Project".to_string(),
+        };
+
+        let error = thought_service
+            .create_project(command)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(
+            error,
+            ThoughtServiceError::UniverseNotFound(id) if id == universe_id
+        ));
+        assert!(project_book
+            .get_by_slug("lost-project")
+            .await
+            .unwrap()
+            .is_none());
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_create_project_error_project_already_exists() {
+        let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        container.destroy();
+
+        let command = CreateProjectCommand {
+            universe_id,
+            project_name: "Existing Project".to_string(),
+        };
+
+        // Create the project first
+        project_book.create(command.clone()).await.unwrap();
+
+        // Try to create the same project again
+        let error = thought_service
+            .create_project(command)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(
+            error,
+            ThoughtServiceError::ProjectAlreadyExists(_)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_thought_success() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        let outbox = container.outbox().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+
+        // Create a project first
+        let project_command = CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+
+        clear_outbox(outbox.as_ref()).await;
+        let command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: None,
+            stylo_id,
+            project_slug: project.slug,
+            content: "This is a test thought.".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+
+        let thought = thought_service
+            .create_thought(command)
+            .await
+            .unwrap()
+            .thought;
+        assert_eq!(thought.content, "This is a test thought.");
+        assert_eq!(thought.project_id, project.project_id);
+        assert!(thought.parent_id.is_none());
+
+        // check that the event was sent
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(
+            event.event.model,
+            ModelKind::Thought {
+                thought_id: thought.thought_id,
+                project_id: thought.project_id,
+                change_kind: ThoughtChangeKind::Created,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_thought_with_parent() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let thought_book = container.thought_book().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        let outbox = container.outbox().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+
+        // Create a project first
+        let project_command = CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+
+        // Create parent thought
+        let parent_command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: None,
+            stylo_id,
+            project_slug: project.slug.clone(),
+            content: "Parent thought".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+        let parent = thought_book
+            .add(parent_command, project.project_id, Default::default())
+            .await
+            .unwrap();
+
+        clear_outbox(outbox.as_ref()).await;
+        // Create child Thought
+        let child_command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: Some(parent.thought_id),
+            stylo_id,
+            project_slug: project.slug,
+            content: "Child thought".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+
+        let child = thought_service
+            .create_thought(child_command)
+            .await
+            .unwrap()
+            .thought;
+        assert_eq!(child.parent_id, Some(parent.thought_id));
+
+        // check that the event was sent
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(
+            event.event.model,
+            ModelKind::Thought {
+                thought_id: child.thought_id,
+                project_id: child.project_id,
+                change_kind: ThoughtChangeKind::Created,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_create_thought_project_not_exist() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        container.destroy();
+
+        let command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: None,
+            stylo_id: Uuid::new_v4(),
+            project_slug: "non-existent-project".to_string(),
+            content: "This thought should not be created".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+
+        let error = thought_service
+            .create_thought(command)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(error, ThoughtServiceError::ProjectNotFound(..)));
+    }
+
+    #[tokio::test]
+    async fn test_create_thought_parent_not_exist() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+
+        // Create a project first
+        let project_command = CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+
+        let unknown_parent_id = Uuid::new_v4();
+        let command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: Some(unknown_parent_id),
+            stylo_id,
+            project_slug: project.slug,
+            content: "This thought should not be created".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+
+        let error = thought_service
+            .create_thought(command)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(
+            error,
+            ThoughtServiceError::InvalidParentReference(parent_id) if parent_id == unknown_parent_id
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_create_thought_parent_in_another_project() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let thought_book = container.thought_book().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+
+        let project_command = CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+
+        // Create the parent thought in another project
+        let parent_command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: None,
+            stylo_id,
+            project_slug: "another-project".to_string(),
+            content: "Parent thought".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+        let parent = thought_book
+            .add(parent_command, Uuid::new_v4(), Default::default())
+            .await
+            .unwrap();
+
+        let command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: Some(parent.thought_id),
+            stylo_id,
+            project_slug: project.slug,
+            content: "This thought should not be created".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+
+        let error = thought_service
+            .create_thought(command)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(
+            error,
+            ThoughtServiceError::ParentInAnotherProject(parent_id) if parent_id == parent.thought_id
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_get_thought_not_found() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        container.destroy();
+
+        let thought_id = Uuid::new_v4();
+        let error = thought_service
+            .get_thought(thought_id)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(error, ThoughtServiceError::ThoughtNotFound(id) if id == thought_id));
+    }
+
+    #[tokio::test]
+    async fn test_thought_tree_navigation() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let thought_book = container.thought_book().unwrap();
+        container.destroy();
+
+        let project_id = Uuid::new_v4();
+        let mut parent_id = None;
+        let mut chain = Vec::new();
+
+        // Create a chain root -> child -> grandchild
+        for content in ["Root", "Child", "Grandchild"] {
+            let command = CreateThoughtCommand {
+                imported_at: Utc::now(),
+                parent_id,
+                stylo_id: Uuid::new_v4(),
+                project_slug: "test-project".to_string(),
+                content: content.to_string(),
+                variation: ThoughtVariation::Thought,
+                answers: None,
+                tags: Vec::new(),
+                category: None,
+                links: Vec::new(),
+                references: Vec::new(),
+                media: Vec::new(),
+            };
+            let thought = thought_book
+                .add(command, project_id, Default::default())
+                .await
+                .unwrap();
+            parent_id = Some(thought.thought_id);
+            chain.push(thought);
+        }
+
+        let ancestors = thought_service
+            .get_thought_ancestors(chain[2].thought_id)
+            .await
+            .unwrap();
+        let ancestor_ids: Vec<Uuid> = ancestors.iter().map(|t| t.thought_id).collect();
+        assert_eq!(ancestor_ids, vec![chain[1].thought_id, chain[0].thought_id]);
+
+        let tree = thought_service
+            .get_thought_descendants(chain[0].thought_id, None)
+            .await
+            .unwrap();
+        assert_eq!(tree.thought.thought_id, chain[0].thought_id);
+        assert_eq!(tree.children.len(), 1);
+        assert_eq!(tree.children[0].children.len(), 1);
+        assert_eq!(
+            tree.children[0].children[0].thought.thought_id,
+            chain[2].thought_id
+        );
+
+        let tree = thought_service
+            .get_thought_descendants(chain[0].thought_id, Some(1))
+            .await
+            .unwrap();
+        assert_eq!(tree.children.len(), 1);
+        assert!(tree.children[0].children.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_answer_question() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        let outbox = container.outbox().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+
+        let project_command = CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+
+        clear_outbox(outbox.as_ref()).await;
+        let question_command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: None,
+            stylo_id,
+            project_slug: project.slug.clone(),
+            content: "Is this a question?".to_string(),
+            variation: ThoughtVariation::Question,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+        let question = thought_service
+            .create_thought(question_command)
             .await
             .unwrap()
-            .expect("there should be a project");
-        assert_eq!(project.project_name, "New Project");
+            .thought;
+        assert!(!question.answered);
+        let _ = receiver.recv().await.unwrap();
+
+        let open_questions = ThoughtFilter {
+            variation: Some(ThoughtVariation::Question),
+            answered: Some(false),
+            ..Default::default()
+        };
+        let listed = thought_service
+            .list_thoughts_by_project(&project.slug, &open_questions)
+            .await
+            .unwrap();
+        assert_eq!(listed.len(), 1);
+
+        let answer_command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: None,
+            stylo_id,
+            project_slug: project.slug.clone(),
+            content: "Yes it is.".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: Some(question.thought_id),
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+        let answer = thought_service
+            .create_thought(answer_command)
+            .await
+            .unwrap()
+            .thought;
+        let _ = receiver.recv().await.unwrap();
+
+        // check that the question modification was sent
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(
+            event.event.model,
+            ModelKind::Thought {
+                thought_id: question.thought_id,
+                project_id: project.project_id,
+                change_kind: ThoughtChangeKind::Modified,
+            }
+        );
+        assert!(
+            thought_service
+                .get_thought(question.thought_id)
+                .await
+                .unwrap()
+                .answered
+        );
+        assert!(thought_service
+            .list_thoughts_by_project(&project.slug, &open_questions)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let answers = thought_service
+            .list_answers(question.thought_id)
+            .await
+            .unwrap();
+        assert_eq!(answers.len(), 1);
+        assert_eq!(answers[0].thought_id, answer.thought_id);
+
+        let reopened = thought_service
+            .reopen_question(question.thought_id)
+            .await
+            .unwrap();
+        assert!(!reopened.answered);
+    }
+
+    #[tokio::test]
+    async fn test_answer_not_a_question() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let thought_book = container.thought_book().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+
+        let project_command = CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
+        };
+        let project = project_book.create(project_command).await.unwrap();
+        let thought_command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: None,
+            stylo_id,
+            project_slug: project.slug.clone(),
+            content: "This is not a question.".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+        let thought = thought_book
+            .add(thought_command, project.project_id, Default::default())
+            .await
+            .unwrap();
+
+        let command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: None,
+            stylo_id,
+            project_slug: project.slug,
+            content: "This answer should not be created".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: Some(thought.thought_id),
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+        let error = thought_service
+            .create_thought(command)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(
+            error,
+            ThoughtServiceError::InvalidQuestionReference(id) if id == thought.thought_id
+        ));
+
+        let error = thought_service
+            .reopen_question(thought.thought_id)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+
+        assert!(matches!(error, ThoughtServiceError::NotAQuestion(_)));
+    }
+
+    #[tokio::test]
+    async fn test_refutation_workflow() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let thought_book = container.thought_book().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        let outbox = container.outbox().unwrap();
+        container.destroy();
+
+        let project_id = Uuid::new_v4();
+        let mut thoughts = Vec::new();
+        for content in ["The earth is flat.", "The earth is round."] {
+            let command = CreateThoughtCommand {
+                imported_at: Utc::now(),
+                parent_id: None,
+                stylo_id: Uuid::new_v4(),
+                project_slug: "test-project".to_string(),
+                content: content.to_string(),
+                variation: ThoughtVariation::Thought,
+                answers: None,
+                tags: Vec::new(),
+                category: None,
+                links: Vec::new(),
+                references: Vec::new(),
+                media: Vec::new(),
+            };
+            thoughts.push(
+                thought_book
+                    .add(command, project_id, Default::default())
+                    .await
+                    .unwrap(),
+            );
+        }
+        clear_outbox(outbox.as_ref()).await;
+        let (refuted, refuting) = (&thoughts[0], &thoughts[1]);
+
+        let disputed = thought_service
+            .refute(refuted.thought_id, refuting.thought_id)
+            .await
+            .unwrap();
+        assert_eq!(disputed.status, ThoughtStatus::Disputed);
+        assert_eq!(disputed.refuted_by, Some(refuting.thought_id));
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(
+            event.event.model,
+            ModelKind::Thought {
+                thought_id: refuted.thought_id,
+                project_id,
+                change_kind: ThoughtChangeKind::Disputed(refuting.thought_id),
+            }
+        );
 
-        // check that the event was sent
+        let invalidated = thought_service
+            .accept_refutation(refuted.thought_id)
+            .await
+            .unwrap();
+        assert_eq!(invalidated.status, ThoughtStatus::Invalidated);
         let event = receiver.recv().await.unwrap();
         assert_eq!(
             event.event.model,
-            ModelKind::Project {
-                project_id: project.project_id,
-                universe_id: project.universe_id,
-                change_kind: ProjectChangeKind::Created,
+            ModelKind::Thought {
+                thought_id: refuted.thought_id,
+                project_id,
+                change_kind: ThoughtChangeKind::Invalidated(refuting.thought_id),
             }
         );
+
+        let error = thought_service
+            .refute(refuted.thought_id, refuting.thought_id)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+        assert!(matches!(error, ThoughtServiceError::AlreadyInvalidated(_)));
     }
 
     #[tokio::test]
-    async fn test_create_project_error_project_already_exists() {
+    async fn test_refutation_errors() {
         let mut container = Container::default();
         let thought_service = container.thought_service().unwrap();
-        let project_book = container.project_book().unwrap();
+        let thought_book = container.thought_book().unwrap();
         container.destroy();
 
-        let command = CreateProjectCommand {
-            universe_id: Uuid::new_v4(),
-            project_name: "Existing Project".to_string(),
+        let command = CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: None,
+            stylo_id: Uuid::new_v4(),
+            project_slug: "test-project".to_string(),
+            content: "A lonely thought.".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
         };
+        let thought = thought_book
+            .add(command, Uuid::new_v4(), Default::default())
+            .await
+            .unwrap();
 
-        // Create the project first
-        project_book.create(command.clone()).await.unwrap();
-
-        // Try to create the same project again
         let error = thought_service
-            .create_project(command)
+            .refute(thought.thought_id, thought.thought_id)
             .await
             .unwrap_err()
             .downcast::<ThoughtServiceError>()
             .expect("Expected ThoughtServiceError");
+        assert!(matches!(error, ThoughtServiceError::InvalidRefutation(_)));
 
-        assert!(matches!(
-            error,
-            ThoughtServiceError::ProjectAlreadyExists(_)
-        ));
+        let error = thought_service
+            .dismiss_refutation(thought.thought_id)
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+        assert!(matches!(error, ThoughtServiceError::NotDisputed(_)));
     }
 
     #[tokio::test]
-    async fn test_create_thought_success() {
+    async fn test_tags() {
         let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
         let thought_service = container.thought_service().unwrap();
-        let project_book = container.project_book().unwrap();
         let mut receiver = container.event_publisher_receiver().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        let outbox = container.outbox().unwrap();
         container.destroy();
-
-        // Create a project first
-        let project_command = CreateProjectCommand {
-            universe_id: Uuid::new_v4(),
-            project_name: "Test Project".to_string(),
-        };
-        let project = project_book.create(project_command).await.unwrap();
-
-        let command = CreateThoughtCommand {
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+        clear_outbox(outbox.as_ref()).await;
+
+        let project = thought_service
+            .create_project(CreateProjectCommand {
+                universe_id,
+                project_name: "Test Project".to_string(),
+            })
+            .await
+            .unwrap();
+        let _ = receiver.recv().await.unwrap();
+        let create = |content: &str, tags: &[&str]| CreateThoughtCommand {
             imported_at: Utc::now(),
             parent_id: None,
-            stylo_id: Uuid::new_v4(),
-            project_slug: project.slug,
-            content: "This is a test thought.".to_string(),
+            stylo_id,
+            project_slug: project.slug.clone(),
+            content: content.to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
         };
 
-        let thought = thought_service.create_thought(command).await.unwrap();
-        assert_eq!(thought.content, "This is a test thought.");
-        assert_eq!(thought.project_id, project.project_id);
-        assert!(thought.parent_id.is_none());
+        let kant = thought_service
+            .create_thought(create(
+                "Act only by maxims.",
+                &["#Ethics", "kant", "ethics"],
+            ))
+            .await
+            .unwrap()
+            .thought;
+        assert_eq!(kant.tags, vec!["ethics".to_string(), "kant".to_string()]);
+        let mill = thought_service
+            .create_thought(create("Maximize happiness.", &["moral"]))
+            .await
+            .unwrap()
+            .thought;
+        let _ = receiver.recv().await.unwrap();
+        let _ = receiver.recv().await.unwrap();
 
-        // check that the event was sent
+        let error = thought_service
+            .create_thought(create("Untaggable.", &["two words"]))
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+        assert!(matches!(error, ThoughtServiceError::InvalidTag(_)));
+
+        let error = thought_service
+            .rename_tag(&project.slug, "moral", "ethics")
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+        assert!(matches!(error, ThoughtServiceError::TagAlreadyExists(_)));
+
+        let renamed = thought_service
+            .rename_tag(&project.slug, "moral", "morality")
+            .await
+            .unwrap();
+        assert_eq!(renamed.len(), 1);
+        assert_eq!(renamed[0].tags, vec!["morality".to_string()]);
         let event = receiver.recv().await.unwrap();
         assert_eq!(
             event.event.model,
             ModelKind::Thought {
-                thought_id: thought.thought_id,
-                project_id: thought.project_id,
-                change_kind: ThoughtChangeKind::Created,
+                thought_id: mill.thought_id,
+                project_id: project.project_id,
+                change_kind: ThoughtChangeKind::Modified,
             }
         );
+
+        let merged = thought_service
+            .merge_tags(&project.slug, &["morality".to_string()], "ethics")
+            .await
+            .unwrap();
+        assert_eq!(merged.len(), 1);
+        let _ = receiver.recv().await.unwrap();
+        assert!(receiver.try_recv().is_err());
+
+        let tags = thought_service.list_tags(&project.slug).await.unwrap();
+        assert_eq!(
+            tags,
+            vec![
+                TagCount {
+                    tag: "ethics".to_string(),
+                    count: 2
+                },
+                TagCount {
+                    tag: "kant".to_string(),
+                    count: 1
+                },
+            ]
+        );
+
+        let modified = thought_service
+            .modify_thought(
+                kant.thought_id,
+                ModifyThoughtCommand {
+                    tags: Some(vec!["deontology".to_string()]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(modified.tags, vec!["deontology".to_string()]);
+        assert_eq!(modified.content, "Act only by maxims.");
     }
 
     #[tokio::test]
-    async fn test_create_thought_with_parent() {
+    async fn test_create_thought_parses_content() {
         let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
         let thought_service = container.thought_service().unwrap();
-        let project_book = container.project_book().unwrap();
-        let thought_book = container.thought_book().unwrap();
-        let mut receiver = container.event_publisher_receiver().unwrap();
+        let _receiver = container.event_publisher_receiver().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
         container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
 
-        // Create a project first
-        let project_command = CreateProjectCommand {
-            universe_id: Uuid::new_v4(),
-            project_name: "Test Project".to_string(),
-        };
-        let project = project_book.create(project_command).await.unwrap();
-
-        // Create parent thought
-        let parent_command = CreateThoughtCommand {
+        let project = thought_service
+            .create_project(CreateProjectCommand {
+                universe_id,
+                project_name: "Test Project".to_string(),
+            })
+            .await
+            .unwrap();
+        let create = |content: String| CreateThoughtCommand {
             imported_at: Utc::now(),
             parent_id: None,
-            stylo_id: Uuid::new_v4(),
+            stylo_id,
             project_slug: project.slug.clone(),
-            content: "Parent thought".to_string(),
+            content,
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: vec!["reading".to_string()],
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
         };
-        let parent = thought_book
-            .add(parent_command, project.project_id)
+        let first = thought_service
+            .create_thought(create("Act only by maxims.".to_string()))
+            .await
+            .unwrap()
+            .thought;
+        let second = thought_service
+            .create_thought(create("Maximize happiness.".to_string()))
+            .await
+            .unwrap()
+            .thought;
+        let short_id = &second.thought_id.simple().to_string()[..8];
+
+        let created = thought_service
+            .create_thought(create(format!(
+                "#Ethics: compare [[{}]] with [[{short_id}]], see [[Critique]].",
+                first.thought_id
+            )))
             .await
             .unwrap();
 
-        // Create child Thought
-        let child_command = CreateThoughtCommand {
+        assert_eq!(created.thought.tags, vec!["ethics", "reading"]);
+        let targets: Vec<Uuid> = created.thought.links.iter().map(|l| l.target_id).collect();
+        assert_eq!(targets, vec![first.thought_id, second.thought_id]);
+        assert_eq!(created.unresolved_links, vec!["Critique".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn test_references() {
+        let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
+        let thought_service = container.thought_service().unwrap();
+        let _receiver = container.event_publisher_receiver().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+
+        let project = thought_service
+            .create_project(CreateProjectCommand {
+                universe_id,
+                project_name: "Test Project".to_string(),
+            })
+            .await
+            .unwrap();
+        let other = thought_service
+            .create_project(CreateProjectCommand {
+                universe_id,
+                project_name: "Other Project".to_string(),
+            })
+            .await
+            .unwrap();
+        let imported = thought_service
+            .import_bibtex(
+                &project.slug,
+                "@book{kant1785, author = {Immanuel Kant}, title = {Groundwork}, year = 1785}",
+            )
+            .await
+            .unwrap();
+        let kant = &imported[0];
+        let foreign = thought_service
+            .create_reference(
+                &other.slug,
+                CreateReferenceCommand {
+                    entry_type: "book".to_string(),
+                    citation_key: "mill1861".to_string(),
+                    authors: vec!["John Stuart Mill".to_string()],
+                    title: "Utilitarianism".to_string(),
+                    year: Some(1861),
+                    pages: None,
+                    extra_fields: Default::default(),
+                },
+            )
+            .await
+            .unwrap();
+
+        let reimported = thought_service
+            .import_bibtex(
+                &project.slug,
+                "@book{kant1785, author = {Immanuel Kant}, title = {Groundwork}, pages = {4}}",
+            )
+            .await
+            .unwrap();
+        assert_eq!(reimported[0].reference_id, kant.reference_id);
+        assert_eq!(reimported[0].pages.as_deref(), Some("4"));
+        assert_eq!(reimported[0].year, None);
+
+        let error = thought_service
+            .import_bibtex(&project.slug, "@book{kant1785, title = {}}")
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+        assert!(matches!(error, ThoughtServiceError::InvalidBibliography(_)));
+
+        let command = |references: Vec<Uuid>| CreateNoteCommand {
             imported_at: Utc::now(),
-            parent_id: Some(parent.thought_id),
-            stylo_id: Uuid::new_v4(),
-            project_slug: project.slug,
-            content: "Child thought".to_string(),
+            stylo_id,
+            project_slug: project.slug.clone(),
+            content: "Read the Groundwork.".to_string(),
+            references,
+            media: Vec::new(),
         };
+        let note = thought_service
+            .create_note(command(vec![kant.reference_id]))
+            .await
+            .unwrap();
+        assert_eq!(note.references, vec![kant.reference_id]);
+        let error = thought_service
+            .create_note(command(vec![foreign.reference_id]))
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+        assert!(matches!(error, ThoughtServiceError::InvalidReference(_)));
+
+        let thought = thought_service
+            .create_thought(CreateThoughtCommand {
+                imported_at: Utc::now(),
+                parent_id: None,
+                stylo_id,
+                project_slug: project.slug.clone(),
+                content: "Act only by maxims.".to_string(),
+                variation: ThoughtVariation::Thought,
+                answers: None,
+                tags: Vec::new(),
+                category: None,
+                links: Vec::new(),
+                references: vec![kant.reference_id],
+                media: Vec::new(),
+            })
+            .await
+            .unwrap()
+            .thought;
+        assert_eq!(thought.references, vec![kant.reference_id]);
+        let error = thought_service
+            .modify_thought(
+                thought.thought_id,
+                ModifyThoughtCommand {
+                    references: Some(vec![Uuid::new_v4()]),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap_err()
+            .downcast::<ThoughtServiceError>()
+            .expect("Expected ThoughtServiceError");
+        assert!(matches!(error, ThoughtServiceError::InvalidReference(_)));
 
-        let child = thought_service.create_thought(child_command).await.unwrap();
-        assert_eq!(child.parent_id, Some(parent.thought_id));
-
-        // check that the event was sent
-        let event = receiver.recv().await.unwrap();
+        let exported = thought_service.export_bibtex(&project.slug).await.unwrap();
         assert_eq!(
-            event.event.model,
-            ModelKind::Thought {
-                thought_id: child.thought_id,
-                project_id: child.project_id,
-                change_kind: ThoughtChangeKind::Created,
-            }
+            exported,
+            "@book{kant1785,\n  author = {Immanuel Kant},\n  title = {Groundwork},\n  pages = {4},\n}\n"
         );
     }
 
     #[tokio::test]
-    async fn test_create_thought_project_not_exist() {
+    async fn test_scratch_note_keeps_media_used_by_thoughts() {
         let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
         let thought_service = container.thought_service().unwrap();
+        let media_store = container.media_store().unwrap();
+        let _receiver = container.event_publisher_receiver().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
         container.destroy();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
 
-        let command = CreateThoughtCommand {
-            imported_at: Utc::now(),
-            parent_id: None,
-            stylo_id: Uuid::new_v4(),
-            project_slug: "non-existent-project".to_string(),
-            content: "This thought should not be created".to_string(),
-        };
-
+        let project = thought_service
+            .create_project(CreateProjectCommand {
+                universe_id,
+                project_name: "Test Project".to_string(),
+            })
+            .await
+            .unwrap();
+        let media = thought_service
+            .upload_media(b"A drawing", Some("image/png"), None)
+            .await
+            .unwrap();
+        let note = thought_service
+            .create_note(CreateNoteCommand {
+                imported_at: Utc::now(),
+                stylo_id,
+                project_slug: project.slug.clone(),
+                content: "A drawing.".to_string(),
+                references: Vec::new(),
+                media: vec![media.clone()],
+            })
+            .await
+            .unwrap();
+        let thought = thought_service
+            .create_thought(CreateThoughtCommand {
+                imported_at: Utc::now(),
+                parent_id: None,
+                stylo_id,
+                project_slug: project.slug.clone(),
+                content: "The drawing proves it.".to_string(),
+                variation: ThoughtVariation::Thought,
+                answers: None,
+                tags: Vec::new(),
+                category: None,
+                links: Vec::new(),
+                references: Vec::new(),
+                media: vec![media.clone()],
+            })
+            .await
+            .unwrap()
+            .thought;
+
+        thought_service.scratch_note(note.note_id).await.unwrap();
+        assert!(media_store.get(&media.digest).await.unwrap().is_some());
+
+        thought_service
+            .modify_thought(
+                thought.thought_id,
+                ModifyThoughtCommand {
+                    media: Some(Vec::new()),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
         let error = thought_service
-            .create_thought(command)
+            .modify_thought(
+                thought.thought_id,
+                ModifyThoughtCommand {
+                    media: Some(vec![Media {
+                        size: 1,
+                        ..media.clone()
+                    }]),
+                    ..Default::default()
+                },
+            )
             .await
             .unwrap_err()
             .downcast::<ThoughtServiceError>()
             .expect("Expected ThoughtServiceError");
-
-        assert!(matches!(error, ThoughtServiceError::ProjectNotFound(_)));
+        assert!(matches!(error, ThoughtServiceError::InvalidMedia(_)));
     }
 
     #[tokio::test]
-    async fn test_create_thought_parent_not_exist() {
+    async fn test_sweep_orphan_media() {
         let mut container = Container::default();
         let thought_service = container.thought_service().unwrap();
         let project_book = container.project_book().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        let media_store = container.media_store().unwrap();
+        let _receiver = container.event_publisher_receiver().unwrap();
         container.destroy();
 
-        // Create a project first
         let project_command = CreateProjectCommand {
             universe_id: Uuid::new_v4(),
             project_name: "Test Project".to_string(),
         };
         let project = project_book.create(project_command).await.unwrap();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+        let attached = thought_service
+            .upload_media(b"A drawing", Some("image/png"), None)
+            .await
+            .unwrap();
+        let orphan = thought_service
+            .upload_media(b"A drawing never attached", Some("image/png"), None)
+            .await
+            .unwrap();
+        thought_service
+            .create_thought(CreateThoughtCommand {
+                imported_at: Utc::now(),
+                parent_id: None,
+                stylo_id,
+                project_slug: project.slug.clone(),
+                content: "The drawing proves it.".to_string(),
+                variation: ThoughtVariation::Thought,
+                answers: None,
+                tags: Vec::new(),
+                category: None,
+                links: Vec::new(),
+                references: Vec::new(),
+                media: vec![attached.clone()],
+            })
+            .await
+            .unwrap();
 
-        let unknown_parent_id = Uuid::new_v4();
-        let command = CreateThoughtCommand {
-            imported_at: Utc::now(),
-            parent_id: Some(unknown_parent_id),
-            stylo_id: Uuid::new_v4(),
-            project_slug: project.slug,
-            content: "This thought should not be created".to_string(),
+        // A recent upload may still be attached, it is kept.
+        assert!(thought_service
+            .sweep_orphan_media(MEDIA_ORPHAN_AGE)
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(media_store.get(&orphan.digest).await.unwrap().is_some());
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+        let deleted = thought_service
+            .sweep_orphan_media(Duration::ZERO)
+            .await
+            .unwrap();
+        assert_eq!(deleted, vec![orphan.digest.clone()]);
+        assert!(media_store.get(&orphan.digest).await.unwrap().is_none());
+        assert!(media_store.get(&attached.digest).await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn test_trash_thoughts() {
+        let mut container = Container::default();
+        let thought_service = container.thought_service().unwrap();
+        let project_book = container.project_book().unwrap();
+        let stylo_book = container.stylo_book().unwrap();
+        let media_store = container.media_store().unwrap();
+        let outbox = container.outbox().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        container.destroy();
+
+        let project_command = CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Test Project".to_string(),
         };
+        let project = project_book.create(project_command).await.unwrap();
+        let stylo_id = grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+        let media = thought_service
+            .upload_media(b"A drawing", Some("image/png"), None)
+            .await
+            .unwrap();
+        let thought = thought_service
+            .create_thought(CreateThoughtCommand {
+                imported_at: Utc::now(),
+                parent_id: None,
+                stylo_id,
+                project_slug: project.slug.clone(),
+                content: "The drawing proves it.".to_string(),
+                variation: ThoughtVariation::Thought,
+                answers: None,
+                tags: Vec::new(),
+                category: None,
+                links: Vec::new(),
+                references: Vec::new(),
+                media: vec![media.clone()],
+            })
+            .await
+            .unwrap()
+            .thought;
+        clear_outbox(outbox.as_ref()).await;
+        while receiver.try_recv().is_ok() {}
+
+        let trashed = thought_service.trash_thoughts(&project.slug).await.unwrap();
+        assert_eq!(trashed.len(), 1);
+        assert_eq!(trashed[0].thought_id, thought.thought_id);
+        assert!(thought_service
+            .list_thoughts_by_project(&project.slug, &ThoughtFilter::default())
+            .await
+            .unwrap()
+            .is_empty());
+        assert!(media_store.get(&media.digest).await.unwrap().is_none());
+
+        let event = receiver.recv().await.unwrap();
+        assert_eq!(
+            event.event.model,
+            ModelKind::Thought {
+                thought_id: thought.thought_id,
+                project_id: project.project_id,
+                change_kind: ThoughtChangeKind::Trashed,
+            }
+        );
 
         let error = thought_service
-            .create_thought(command)
+            .trash_thoughts("unknown-project")
             .await
             .unwrap_err()
             .downcast::<ThoughtServiceError>()
             .expect("Expected ThoughtServiceError");
-
-        assert!(matches!(
-            error,
-            ThoughtServiceError::InvalidParentReference(parent_id) if parent_id == unknown_parent_id
-        ));
+        assert!(matches!(error, ThoughtServiceError::ProjectNotFound(..)));
     }
 }
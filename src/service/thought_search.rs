@@ -0,0 +1,955 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use synapps::{EventDispatcher, EventMessage, EventSubscription, TopicPatternValidator};
+use thiserror::Error;
+use tokio::sync::mpsc::{unbounded_channel, UnboundedReceiver};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::adapter::{NoteBook, ProjectBook, ThoughtBook};
+use crate::models::{
+    similarity_of, tokenize, trigrams, ModelEvent, ModelKind, Note, NoteChangeKind, Project, Query,
+    QueryDocument, QueryHit, QuerySyntaxError, SearchDocumentKind, SearchFilter, SearchHit,
+    SearchToken, SimilarityThresholds, Thought, ThoughtFilter, ThoughtVariation,
+};
+use crate::service::{ReadModel, SLUG_SUGGESTION_LIMIT};
+use crate::Result;
+
+/// BM25 term frequency saturation.
+const BM25_K1: f64 = 1.2;
+
+/// BM25 document length normalization.
+const BM25_B: f64 = 0.75;
+
+/// Number of words kept before the first match in a highlight.
+const HIGHLIGHT_CONTEXT: usize = 5;
+
+/// Maximum number of words of a highlight.
+const HIGHLIGHT_LENGTH: usize = 30;
+
+/// ThoughtSearchServiceError
+/// Different errors returned by the ThoughtSearchService.
+#[derive(Debug, Error)]
+pub enum ThoughtSearchServiceError {
+    /// Project not found, with the slugs of the projects with close slugs
+    #[error("There is not project with slug '{0}'.")]
+    ProjectNotFound(String, Vec<String>),
+
+    /// The query does not contain any word
+    #[error("The search query does not contain any word.")]
+    EmptyQuery,
+
+    /// The similarity threshold is not between 0 and 1
+    #[error("The similarity threshold {0} is not between 0 and 1.")]
+    InvalidThreshold(f64),
+
+    /// The query language expression is not valid
+    #[error("The query is not valid: {0}")]
+    InvalidQuery(#[from] QuerySyntaxError),
+}
+
+/// A note or a thought as stored in the index.
+struct IndexedDocument {
+    kind: SearchDocumentKind,
+    project_id: Uuid,
+    stylo_id: Uuid,
+    variation: Option<ThoughtVariation>,
+    tags: Vec<String>,
+    imported_at: DateTime<Utc>,
+    content: String,
+    tokens: Vec<SearchToken>,
+}
+
+impl IndexedDocument {
+    fn from_note(note: &Note) -> Self {
+        Self {
+            kind: SearchDocumentKind::Note,
+            project_id: note.project_id,
+            stylo_id: note.stylo_id,
+            variation: None,
+            tags: Vec::new(),
+            imported_at: note.imported_at,
+            content: note.content.clone(),
+            tokens: tokenize(&note.content),
+        }
+    }
+
+    fn from_thought(thought: &Thought) -> Self {
+        Self {
+            kind: SearchDocumentKind::Thought,
+            project_id: thought.project_id,
+            stylo_id: thought.stylo_id,
+            variation: Some(thought.variation),
+            tags: thought.tags.clone(),
+            imported_at: thought.imported_at,
+            content: thought.content.clone(),
+            tokens: tokenize(&thought.content),
+        }
+    }
+
+    fn matches(&self, project_id: Option<Uuid>, filter: &SearchFilter) -> bool {
+        project_id.is_none_or(|p| self.project_id == p)
+            && filter.author.is_none_or(|a| self.stylo_id == a)
+            && filter.variation.is_none_or(|v| self.variation == Some(v))
+            && filter.tag.as_ref().is_none_or(|t| self.tags.contains(t))
+    }
+
+    /// Excerpt of the content around the first match, matches are marked.
+    fn highlight(&self, terms: &HashSet<String>) -> String {
+        let Some(first) = self.tokens.iter().position(|t| terms.contains(&t.term)) else {
+            return String::new();
+        };
+        let from = first.saturating_sub(HIGHLIGHT_CONTEXT);
+        let to = (from + HIGHLIGHT_LENGTH).min(self.tokens.len());
+        let start = if from == 0 {
+            0
+        } else {
+            self.tokens[from].start
+        };
+        let end = if to == self.tokens.len() {
+            self.content.len()
+        } else {
+            self.tokens[to - 1].end
+        };
+
+        let mut highlight = String::new();
+        if start > 0 {
+            highlight.push('…');
+        }
+        let mut cursor = start;
+        for token in &self.tokens[from..to] {
+            if terms.contains(&token.term) {
+                highlight.push_str(&self.content[cursor..token.start]);
+                highlight.push_str("<mark>");
+                highlight.push_str(&self.content[token.start..token.end]);
+                highlight.push_str("</mark>");
+                cursor = token.end;
+            }
+        }
+        highlight.push_str(&self.content[cursor..end]);
+        if end < self.content.len() {
+            highlight.push('…');
+        }
+
+        highlight
+    }
+}
+
+/// Alternatives for a word of a query, each with the weight of its matches.
+type TermGroup = Vec<(String, f64)>;
+
+/// Inverted index of the notes and thoughts.
+#[derive(Default)]
+struct SearchIndex {
+    documents: HashMap<Uuid, IndexedDocument>,
+
+    /// For each term, the number of occurrences in each document.
+    postings: HashMap<String, HashMap<Uuid, u32>>,
+
+    /// For each trigram, the indexed terms containing it.
+    trigram_terms: HashMap<String, HashSet<String>>,
+
+    /// Sum of the number of words of all the documents.
+    total_length: usize,
+}
+
+impl SearchIndex {
+    fn insert(&mut self, id: Uuid, document: IndexedDocument) {
+        self.remove(id);
+        for token in &document.tokens {
+            let posting = self.postings.entry(token.term.clone()).or_insert_with(|| {
+                for trigram in trigrams(&token.term) {
+                    self.trigram_terms
+                        .entry(trigram)
+                        .or_default()
+                        .insert(token.term.clone());
+                }
+                HashMap::new()
+            });
+            *posting.entry(id).or_default() += 1;
+        }
+        self.total_length += document.tokens.len();
+        self.documents.insert(id, document);
+    }
+
+    fn remove(&mut self, id: Uuid) {
+        let Some(document) = self.documents.remove(&id) else {
+            return;
+        };
+        for token in &document.tokens {
+            let Some(posting) = self.postings.get_mut(&token.term) else {
+                continue;
+            };
+            posting.remove(&id);
+            if posting.is_empty() {
+                self.postings.remove(&token.term);
+                for trigram in trigrams(&token.term) {
+                    if let Some(terms) = self.trigram_terms.get_mut(&trigram) {
+                        terms.remove(&token.term);
+                        if terms.is_empty() {
+                            self.trigram_terms.remove(&trigram);
+                        }
+                    }
+                }
+            }
+        }
+        self.total_length -= document.tokens.len();
+    }
+
+    /// The indexed terms similar enough to a term, weighted by their
+    /// similarity.
+    fn similar_terms(&self, term: &str, threshold: f64) -> TermGroup {
+        let reference = trigrams(term);
+        let candidates: HashSet<&String> = reference
+            .iter()
+            .filter_map(|trigram| self.trigram_terms.get(trigram))
+            .flatten()
+            .collect();
+
+        candidates
+            .into_iter()
+            .map(|candidate| {
+                let similarity = similarity_of(&reference, &trigrams(candidate));
+                (candidate.clone(), similarity)
+            })
+            .filter(|(_, similarity)| *similarity >= threshold)
+            .collect()
+    }
+
+    /// BM25 ranking of the documents matching every group of terms.
+    /// A document matches a group when it contains one of its terms, only the
+    /// best weighted score of the group counts.
+    fn search(
+        &self,
+        groups: &[TermGroup],
+        accept: impl Fn(&IndexedDocument) -> bool,
+    ) -> Vec<(Uuid, f64)> {
+        let mut candidates: Option<HashSet<Uuid>> = None;
+        for group in groups {
+            let found: HashSet<Uuid> = group
+                .iter()
+                .filter_map(|(term, _)| self.postings.get(term))
+                .flat_map(|posting| posting.keys().copied())
+                .collect();
+            candidates = Some(match candidates {
+                Some(candidates) => candidates.intersection(&found).copied().collect(),
+                None => found,
+            });
+        }
+
+        candidates
+            .unwrap_or_default()
+            .into_iter()
+            .filter(|id| accept(&self.documents[id]))
+            .map(|id| {
+                let score = groups
+                    .iter()
+                    .map(|group| {
+                        group
+                            .iter()
+                            .filter_map(|(term, weight)| {
+                                let posting = self.postings.get(term)?;
+                                Some(weight * self.score(posting, id)?)
+                            })
+                            .fold(0.0, f64::max)
+                    })
+                    .sum();
+
+                (id, score)
+            })
+            .collect()
+    }
+
+    /// BM25 score of a document for a term, None if it does not contain it.
+    fn score(&self, posting: &HashMap<Uuid, u32>, id: Uuid) -> Option<f64> {
+        let frequency = f64::from(*posting.get(&id)?);
+        let count = self.documents.len() as f64;
+        let found = posting.len() as f64;
+        let length = self.documents[&id].tokens.len() as f64;
+        let average_length = self.total_length as f64 / count;
+        let idf = (1.0 + (count - found + 0.5) / (found + 0.5)).ln();
+
+        Some(
+            idf * frequency * (BM25_K1 + 1.0)
+                / (frequency + BM25_K1 * (1.0 - BM25_B + BM25_B * length / average_length)),
+        )
+    }
+}
+
+/// Thought search service
+/// It keeps an inverted index of the notes and thoughts in memory. The index
+/// is kept up to date by listening to the model events, it is rebuilt from the
+/// books when the application starts.
+pub struct ThoughtSearchService {
+    note_book: Arc<dyn NoteBook>,
+    project_book: Arc<dyn ProjectBook>,
+    thought_book: Arc<dyn ThoughtBook>,
+    similarity: SimilarityThresholds,
+    index: RwLock<SearchIndex>,
+}
+
+impl ThoughtSearchService {
+    /// Create a new thought search service with an empty index
+    pub fn new(
+        note_book: Arc<dyn NoteBook>,
+        project_book: Arc<dyn ProjectBook>,
+        thought_book: Arc<dyn ThoughtBook>,
+        similarity: SimilarityThresholds,
+    ) -> Self {
+        Self {
+            note_book,
+            project_book,
+            thought_book,
+            similarity,
+            index: RwLock::new(SearchIndex::default()),
+        }
+    }
+
+    /// Register the service to the event dispatcher.
+    /// The returned receiver must be given to [`ThoughtSearchService::listen`].
+    pub fn subscribe(
+        dispatcher: &mut EventDispatcher<ModelEvent>,
+    ) -> UnboundedReceiver<EventMessage<ModelEvent>> {
+        let (sender, receiver) = unbounded_channel();
+        let validator = Arc::new(TopicPatternValidator::new("model"));
+        dispatcher.register("thought_search", EventSubscription::new(sender, validator));
+
+        receiver
+    }
+
+    /// Update the index with the events of the receiver until it is closed.
+    /// An event that cannot be applied is logged and skipped.
+    pub async fn listen(
+        &self,
+        mut receiver: UnboundedReceiver<EventMessage<ModelEvent>>,
+    ) -> Result<()> {
+        while let Some(message) = receiver.recv().await {
+            if let Err(e) = self.apply(&message.event).await {
+                log::error!("Could not index {:?}: {e}", message.event.model);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Index all the notes and thoughts of all the projects.
+    pub async fn rebuild(&self) -> Result<()> {
+        let mut index = SearchIndex::default();
+
+        for project in self.project_book.list().await? {
+            for note in self.note_book.list_by_project(project.project_id).await? {
+                index.insert(note.note_id, IndexedDocument::from_note(&note));
+            }
+            for thought in self
+                .thought_book
+                .list_by_project(project.project_id, &ThoughtFilter::default())
+                .await?
+            {
+                index.insert(thought.thought_id, IndexedDocument::from_thought(&thought));
+            }
+        }
+        *self.index.write().await = index;
+
+        Ok(())
+    }
+
+    /// Update the index after a model change.
+    /// The changed model is fetched again so the index always reflects the
+    /// last version, whatever the change was.
+    pub async fn apply(&self, event: &ModelEvent) -> Result<()> {
+        match &event.model {
+            ModelKind::Note {
+                note_id,
+                change_kind: NoteChangeKind::Scratched,
+                ..
+            } => self.index.write().await.remove(*note_id),
+            ModelKind::Note { note_id, .. } => match self.note_book.get(*note_id).await? {
+                Some(note) => self
+                    .index
+                    .write()
+                    .await
+                    .insert(note.note_id, IndexedDocument::from_note(&note)),
+                None => self.index.write().await.remove(*note_id),
+            },
+            ModelKind::Thought { thought_id, .. } => {
+                match self.thought_book.get(*thought_id).await? {
+                    Some(thought) => self
+                        .index
+                        .write()
+                        .await
+                        .insert(thought.thought_id, IndexedDocument::from_thought(&thought)),
+                    None => self.index.write().await.remove(*thought_id),
+                }
+            }
+            ModelKind::Project { .. } | ModelKind::Stylo { .. } => {}
+        }
+
+        Ok(())
+    }
+
+    /// Search the notes and thoughts containing all the words of the query.
+    ///
+    /// Hits are ranked by relevance, most relevant first, then by import
+    /// date, newest first. An error is raised if the query has no word or if
+    /// the filtered project does not exist.
+    pub async fn search(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let groups = Self::query_terms(query)?
+            .into_iter()
+            .map(|term| vec![(term, 1.0)])
+            .collect();
+
+        self.run(groups, filter, limit).await
+    }
+
+    /// Search the notes and thoughts containing, for each word of the query, a
+    /// word similar enough to it.
+    ///
+    /// Words are compared by trigram similarity, the configured word threshold
+    /// is used when none is given. Matches count as much as they are similar
+    /// to the words of the query. An error is raised if the query has no word,
+    /// if the threshold is not between 0 and 1 or if the filtered project does
+    /// not exist.
+    pub async fn fuzzy_search(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+        threshold: Option<f64>,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let threshold = threshold.unwrap_or(self.similarity.word);
+        if !(0.0..=1.0).contains(&threshold) {
+            return Err(ThoughtSearchServiceError::InvalidThreshold(threshold).into());
+        }
+        let terms = Self::query_terms(query)?;
+        let mut groups = Vec::with_capacity(terms.len());
+        for term in &terms {
+            groups.push(self.similar_terms(term, threshold).await?);
+        }
+
+        self.run(groups, filter, limit).await
+    }
+
+    /// The terms similar enough to a term, weighted by their similarity.
+    /// The words are matched by the trigram indexes of the books when they
+    /// have some, by the search index otherwise.
+    async fn similar_terms(&self, term: &str, threshold: f64) -> Result<TermGroup> {
+        let (Some(notes), Some(thoughts)) = (
+            self.note_book.similar_words(term, threshold).await?,
+            self.thought_book.similar_words(term, threshold).await?,
+        ) else {
+            return Ok(self.index.read().await.similar_terms(term, threshold));
+        };
+
+        let mut group: HashMap<String, f64> = HashMap::new();
+        for (word, similarity) in notes.into_iter().chain(thoughts) {
+            for token in tokenize(&word) {
+                let best = group.entry(token.term).or_default();
+                *best = best.max(similarity);
+            }
+        }
+
+        Ok(group.into_iter().collect())
+    }
+
+    /// Find the notes and thoughts matching a query language expression, see
+    /// [`Query`] for the syntax.
+    ///
+    /// The documents are read from the books rather than from the index, only
+    /// from the projects the query restricts the search to if any. Matches are
+    /// sorted by import date, newest first. An error is raised if the query is
+    /// not valid or refers to a project that does not exist.
+    pub async fn query(&self, query: &str, limit: usize) -> Result<Vec<QueryHit>> {
+        let query = Query::parse(query).map_err(ThoughtSearchServiceError::from)?;
+        let mut projects = HashMap::new();
+        for slug in query.root.project_slugs() {
+            if !projects.contains_key(slug) {
+                let project = self.get_project(slug).await?;
+                projects.insert(slug.to_string(), project);
+            }
+        }
+        let searched = match query.root.required_projects() {
+            Some(slugs) => {
+                let mut searched: Vec<Project> = Vec::new();
+                for slug in slugs {
+                    if searched.iter().all(|p| p.slug != projects[slug].slug) {
+                        searched.push(projects[slug].clone());
+                    }
+                }
+                searched
+            }
+            None => self.project_book.list().await?,
+        };
+        let project_ids: HashMap<String, Uuid> = projects
+            .into_iter()
+            .map(|(slug, project)| (slug, project.project_id))
+            .collect();
+
+        let mut hits = Vec::new();
+        for project in searched {
+            for note in self.note_book.list_by_project(project.project_id).await? {
+                if query.matches(&QueryDocument::from_note(&note), &project_ids) {
+                    hits.push(QueryHit::Note(note));
+                }
+            }
+            for thought in self
+                .thought_book
+                .list_by_project(project.project_id, &ThoughtFilter::default())
+                .await?
+            {
+                if query.matches(&QueryDocument::from_thought(&thought), &project_ids) {
+                    hits.push(QueryHit::Thought(thought));
+                }
+            }
+        }
+        hits.sort_by_key(|hit| std::cmp::Reverse(hit.imported_at()));
+        hits.truncate(limit);
+
+        Ok(hits)
+    }
+
+    /// The distinct words of a query.
+    fn query_terms(query: &str) -> Result<HashSet<String>> {
+        let terms: HashSet<String> = tokenize(query).into_iter().map(|t| t.term).collect();
+        if terms.is_empty() {
+            return Err(ThoughtSearchServiceError::EmptyQuery.into());
+        }
+
+        Ok(terms)
+    }
+
+    /// Rank the documents matching the groups of terms and the filter.
+    async fn run(
+        &self,
+        groups: Vec<TermGroup>,
+        filter: &SearchFilter,
+        limit: usize,
+    ) -> Result<Vec<SearchHit>> {
+        let project_id = match &filter.project {
+            Some(slug) => Some(self.get_project(slug).await?.project_id),
+            None => None,
+        };
+
+        let index = self.index.read().await;
+        let mut ranked = index.search(&groups, |document| document.matches(project_id, filter));
+        ranked.sort_by(|(a, a_score), (b, b_score)| {
+            b_score.total_cmp(a_score).then(
+                index.documents[b]
+                    .imported_at
+                    .cmp(&index.documents[a].imported_at),
+            )
+        });
+        let terms: HashSet<String> = groups.into_iter().flatten().map(|(term, _)| term).collect();
+
+        Ok(ranked
+            .into_iter()
+            .take(limit)
+            .map(|(id, score)| {
+                let document = &index.documents[&id];
+
+                SearchHit {
+                    kind: document.kind,
+                    id,
+                    project_id: document.project_id,
+                    stylo_id: document.stylo_id,
+                    score,
+                    highlight: document.highlight(&terms),
+                }
+            })
+            .collect())
+    }
+
+    /// Get a project by its slug, suggesting close slugs if it does not exist.
+    async fn get_project(&self, project_slug: &str) -> Result<Project> {
+        match self.project_book.get_by_slug(project_slug).await? {
+            Some(project) => Ok(project),
+            None => {
+                let suggestions = self
+                    .project_book
+                    .suggest_slugs(project_slug, self.similarity.slug, SLUG_SUGGESTION_LIMIT)
+                    .await?;
+
+                Err(ThoughtSearchServiceError::ProjectNotFound(
+                    project_slug.to_string(),
+                    suggestions,
+                )
+                .into())
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl ReadModel for ThoughtSearchService {
+    async fn reset(&self) -> Result<()> {
+        *self.index.write().await = SearchIndex::default();
+
+        Ok(())
+    }
+
+    async fn apply(&self, event: &ModelEvent) -> Result<()> {
+        ThoughtSearchService::apply(self, event).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::Utc;
+
+    use crate::models::{CreateNoteCommand, CreateProjectCommand, CreateThoughtCommand};
+    use crate::testkit::{create_organization, grant_stylo};
+    use crate::Container;
+
+    use super::*;
+
+    fn thought_command(project_slug: &str, stylo_id: Uuid, content: &str) -> CreateThoughtCommand {
+        CreateThoughtCommand {
+            imported_at: Utc::now(),
+            parent_id: None,
+            stylo_id,
+            project_slug: project_slug.to_string(),
+            content: content.to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search() {
+        let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
+        let stylo_id = grant_stylo(container.stylo_book().unwrap().as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+        let service = container.thought_service().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        let search = container.thought_search().unwrap();
+        let project = service
+            .create_project(CreateProjectCommand {
+                project_name: "Reading list".to_string(),
+                universe_id,
+            })
+            .await
+            .unwrap();
+
+        let note = service
+            .create_note(CreateNoteCommand {
+                imported_at: Utc::now(),
+                stylo_id,
+                project_slug: project.slug.clone(),
+                content: "Aristotle wrote about virtue.".to_string(),
+                references: Vec::new(),
+                media: Vec::new(),
+            })
+            .await
+            .unwrap();
+        let mut command = thought_command(
+            &project.slug,
+            stylo_id,
+            "Virtue is a habit. Virtue ethics puts virtue first.",
+        );
+        command.tags = vec!["ethics".to_string()];
+        let thought = service.create_thought(command).await.unwrap().thought;
+        let mut command = thought_command(&project.slug, stylo_id, "What is a virtue?");
+        command.variation = ThoughtVariation::Question;
+        let question = service.create_thought(command).await.unwrap().thought;
+        while let Ok(message) = receiver.try_recv() {
+            search.apply(&message.event).await.unwrap();
+        }
+
+        let hits = search
+            .search("virtue", &SearchFilter::default(), 10)
+            .await
+            .unwrap();
+        let ids: Vec<Uuid> = hits.iter().map(|hit| hit.id).collect();
+        assert_eq!(ids.len(), 3);
+        assert_eq!(ids[0], thought.thought_id);
+        assert_eq!(
+            hits[1].highlight,
+            "What is a <mark>virtue</mark>?".to_string()
+        );
+
+        let hits = search
+            .search("VIRTUE aristotle", &SearchFilter::default(), 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, note.note_id);
+        assert_eq!(hits[0].kind, SearchDocumentKind::Note);
+
+        let filter = SearchFilter {
+            variation: Some(ThoughtVariation::Question),
+            ..Default::default()
+        };
+        let hits = search.search("virtue", &filter, 10).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, question.thought_id);
+
+        let filter = SearchFilter {
+            tag: Some("ethics".to_string()),
+            author: Some(thought.stylo_id),
+            project: Some(project.slug.clone()),
+            ..Default::default()
+        };
+        let hits = search.search("virtue", &filter, 10).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].id, thought.thought_id);
+
+        service.scratch_note(note.note_id).await.unwrap();
+        while let Ok(message) = receiver.try_recv() {
+            search.apply(&message.event).await.unwrap();
+        }
+        let hits = search
+            .search("aristotle", &SearchFilter::default(), 10)
+            .await
+            .unwrap();
+        assert!(hits.is_empty());
+
+        let error = search
+            .search("?!", &SearchFilter::default(), 10)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ThoughtSearchServiceError>(),
+            Some(ThoughtSearchServiceError::EmptyQuery)
+        ));
+        let filter = SearchFilter {
+            project: Some("reading-lst".to_string()),
+            ..Default::default()
+        };
+        let error = search.search("virtue", &filter, 10).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ThoughtSearchServiceError>(),
+            Some(ThoughtSearchServiceError::ProjectNotFound(_, suggestions))
+                if suggestions == &vec![project.slug.clone()]
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_fuzzy_search() {
+        let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
+        let stylo_id = grant_stylo(container.stylo_book().unwrap().as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+        let service = container.thought_service().unwrap();
+        let mut receiver = container.event_publisher_receiver().unwrap();
+        let search = container.thought_search().unwrap();
+        let project = service
+            .create_project(CreateProjectCommand {
+                project_name: "Fuzzy".to_string(),
+                universe_id,
+            })
+            .await
+            .unwrap();
+        let exact = service
+            .create_thought(thought_command(
+                &project.slug,
+                stylo_id,
+                "Philosophy of virtue",
+            ))
+            .await
+            .unwrap()
+            .thought;
+        let close = service
+            .create_thought(thought_command(
+                &project.slug,
+                stylo_id,
+                "Philosophers and virtues",
+            ))
+            .await
+            .unwrap()
+            .thought;
+        service
+            .create_thought(thought_command(&project.slug, stylo_id, "Gardening"))
+            .await
+            .unwrap();
+        while let Ok(message) = receiver.try_recv() {
+            search.apply(&message.event).await.unwrap();
+        }
+
+        let hits = search
+            .search("philosophy virtue", &SearchFilter::default(), 10)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+
+        let hits = search
+            .fuzzy_search("philosofy virtue", &SearchFilter::default(), None, 10)
+            .await
+            .unwrap();
+        let ids: Vec<Uuid> = hits.iter().map(|hit| hit.id).collect();
+        assert_eq!(ids, vec![exact.thought_id, close.thought_id]);
+        assert_eq!(
+            hits[1].highlight,
+            "<mark>Philosophers</mark> and <mark>virtues</mark>"
+        );
+
+        let hits = search
+            .fuzzy_search("philosofy virtue", &SearchFilter::default(), Some(0.9), 10)
+            .await
+            .unwrap();
+        assert!(hits.is_empty());
+
+        let error = search
+            .fuzzy_search("virtue", &SearchFilter::default(), Some(1.5), 10)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ThoughtSearchServiceError>(),
+            Some(ThoughtSearchServiceError::InvalidThreshold(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_rebuild_and_highlight() {
+        let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
+        let stylo_id = grant_stylo(container.stylo_book().unwrap().as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+        let service = container.thought_service().unwrap();
+        let _receiver = container.event_publisher_receiver().unwrap();
+        let search = container.thought_search().unwrap();
+        let project = service
+            .create_project(CreateProjectCommand {
+                project_name: "Long reads".to_string(),
+                universe_id,
+            })
+            .await
+            .unwrap();
+        let content = format!(
+            "{} the needle is here {}",
+            "word ".repeat(20),
+            "word ".repeat(40)
+        );
+        service
+            .create_thought(thought_command(&project.slug, stylo_id, &content))
+            .await
+            .unwrap();
+
+        search.rebuild().await.unwrap();
+        let hits = search
+            .search("needle", &SearchFilter::default(), 10)
+            .await
+            .unwrap();
+
+        assert_eq!(hits.len(), 1);
+        assert!(hits[0].highlight.starts_with("…word"));
+        assert!(hits[0]
+            .highlight
+            .contains("the <mark>needle</mark> is here"));
+        assert!(hits[0].highlight.ends_with('…'));
+    }
+
+    #[tokio::test]
+    async fn test_query() {
+        let mut container = Container::default();
+        let universe_id = create_organization(container.organization_book().unwrap().as_ref())
+            .await
+            .universe
+            .universe_id;
+        let stylo_id = grant_stylo(container.stylo_book().unwrap().as_ref(), Uuid::new_v4())
+            .await
+            .stylo_id;
+        let service = container.thought_service().unwrap();
+        let search = container.thought_search().unwrap();
+        let mut projects = Vec::new();
+        for project_name in ["Reading list", "Garden"] {
+            let project = service
+                .create_project(CreateProjectCommand {
+                    project_name: project_name.to_string(),
+                    universe_id,
+                })
+                .await
+                .unwrap();
+            projects.push(project);
+        }
+        let mut command = thought_command("reading-list", stylo_id, "Courage is an exact phrase.");
+        command.tags = vec!["ethics".to_string()];
+        command.imported_at = "2026-02-01T00:00:00Z".parse().unwrap();
+        let standing = service.create_thought(command).await.unwrap().thought;
+        let mut command = thought_command("reading-list", stylo_id, "Courage is overrated.");
+        command.tags = vec!["ethics".to_string()];
+        command.imported_at = "2026-03-01T00:00:00Z".parse().unwrap();
+        let refuted = service.create_thought(command).await.unwrap().thought;
+        service
+            .refute(refuted.thought_id, standing.thought_id)
+            .await
+            .unwrap();
+        let note = service
+            .create_note(CreateNoteCommand {
+                imported_at: "2025-12-01T00:00:00Z".parse().unwrap(),
+                stylo_id: standing.stylo_id,
+                project_slug: "garden".to_string(),
+                content: "Courage in the garden.".to_string(),
+                references: Vec::new(),
+                media: Vec::new(),
+            })
+            .await
+            .unwrap();
+        let ids = |hits: Vec<QueryHit>| -> Vec<Uuid> {
+            hits.into_iter()
+                .map(|hit| match hit {
+                    QueryHit::Note(note) => note.note_id,
+                    QueryHit::Thought(thought) => thought.thought_id,
+                })
+                .collect()
+        };
+
+        let hits = search.query("courage", 10).await.unwrap();
+        assert_eq!(
+            ids(hits),
+            vec![refuted.thought_id, standing.thought_id, note.note_id]
+        );
+        let hits = search
+            .query(
+                &format!(
+                    "tag:ethics AND project:reading-list -refuted author:{} \
+                     created:>2026-01-01 \"exact phrase\"",
+                    standing.stylo_id
+                ),
+                10,
+            )
+            .await
+            .unwrap();
+        assert_eq!(ids(hits), vec![standing.thought_id]);
+        let hits = search.query("project:garden OR refuted", 10).await.unwrap();
+        assert_eq!(ids(hits), vec![refuted.thought_id, note.note_id]);
+        let hits = search.query("courage", 1).await.unwrap();
+        assert_eq!(ids(hits), vec![refuted.thought_id]);
+
+        let error = search.query("courage AND", 10).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ThoughtSearchServiceError>(),
+            Some(ThoughtSearchServiceError::InvalidQuery(e)) if (e.start, e.end) == (8, 11)
+        ));
+        let error = search.query("-project:gardn", 10).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<ThoughtSearchServiceError>(),
+            Some(ThoughtSearchServiceError::ProjectNotFound(_, suggestions))
+                if suggestions == &vec!["garden".to_string()]
+        ));
+    }
+}
@@ -0,0 +1,68 @@
+use uuid::Uuid;
+
+use crate::adapter::AuditLog;
+use crate::models::{ModelEvent, ModelKind, NoteChangeKind, ProjectChangeKind};
+
+/// Run all the AuditLog checks.
+pub async fn check_audit_log(audit_log: &impl AuditLog) {
+    check_audit_append(audit_log).await;
+    check_audit_entries(audit_log).await;
+}
+
+fn note_event(note_id: Uuid, project_id: Uuid) -> ModelEvent {
+    ModelEvent::new(ModelKind::Note {
+        note_id,
+        project_id,
+        change_kind: NoteChangeKind::Created,
+    })
+}
+
+/// Appended entries are chained to the previous one, an event is recorded
+/// once.
+pub async fn check_audit_append(audit_log: &impl AuditLog) {
+    let event = note_event(Uuid::new_v4(), Uuid::new_v4());
+    let first = audit_log.append(event.clone()).await.unwrap();
+    assert_eq!(first.event, event);
+    assert_eq!(first.hash, first.compute_hash());
+
+    let second = audit_log
+        .append(note_event(Uuid::new_v4(), Uuid::new_v4()))
+        .await
+        .unwrap();
+    assert_eq!(second.check_link(Some(&first)), Ok(()));
+    assert_eq!(audit_log.append(event).await.unwrap(), first);
+    let third = audit_log
+        .append(note_event(Uuid::new_v4(), Uuid::new_v4()))
+        .await
+        .unwrap();
+    assert_eq!(third.check_link(Some(&second)), Ok(()));
+}
+
+/// The entries of a model are listed oldest first, those of a project include
+/// the entries of its notes.
+pub async fn check_audit_entries(audit_log: &impl AuditLog) {
+    let (project_id, note_id) = (Uuid::new_v4(), Uuid::new_v4());
+    let project = audit_log
+        .append(ModelEvent::new(ModelKind::Project {
+            project_id,
+            universe_id: Uuid::new_v4(),
+            change_kind: ProjectChangeKind::Created,
+        }))
+        .await
+        .unwrap();
+    let note = audit_log
+        .append(note_event(note_id, project_id))
+        .await
+        .unwrap();
+    audit_log
+        .append(note_event(Uuid::new_v4(), Uuid::new_v4()))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        audit_log.entries(project_id).await.unwrap(),
+        vec![project, note.clone()]
+    );
+    assert_eq!(audit_log.entries(note_id).await.unwrap(), vec![note]);
+    assert!(audit_log.entries(Uuid::new_v4()).await.unwrap().is_empty());
+}
@@ -0,0 +1,83 @@
+use chrono::Utc;
+use synapps::EventMessage;
+use uuid::Uuid;
+
+use crate::adapter::EventStore;
+use crate::models::{ModelEvent, ModelKind, NoteChangeKind, StoredEvent};
+
+/// Run all the EventStore checks.
+pub async fn check_event_store(event_store: &impl EventStore) {
+    check_event_store_append(event_store).await;
+    check_event_store_append_again(event_store).await;
+    check_event_store_read(event_store).await;
+}
+
+fn note_message() -> EventMessage<ModelEvent> {
+    EventMessage {
+        sender: "testkit".to_string(),
+        topic: "model".to_string(),
+        timestamp: Utc::now(),
+        event: ModelEvent::new(ModelKind::Note {
+            note_id: Uuid::new_v4(),
+            project_id: Uuid::new_v4(),
+            change_kind: NoteChangeKind::Created,
+        }),
+    }
+}
+
+async fn append(event_store: &impl EventStore) -> StoredEvent {
+    event_store
+        .append(note_message())
+        .await
+        .expect("The event should be appended.")
+}
+
+/// Appended events are numbered after the last one and keep their message.
+pub async fn check_event_store_append(event_store: &impl EventStore) {
+    let last_sequence = event_store.last_sequence().await.unwrap();
+    let message = note_message();
+
+    let stored = event_store.append(message.clone()).await.unwrap();
+    assert_eq!(stored.sequence, last_sequence + 1);
+    assert_eq!(stored.message(), message);
+    let next = append(event_store).await;
+    assert_eq!(next.sequence, last_sequence + 2);
+    assert_eq!(event_store.last_sequence().await.unwrap(), next.sequence);
+}
+
+/// An event delivered again is not recorded twice.
+pub async fn check_event_store_append_again(event_store: &impl EventStore) {
+    let message = note_message();
+    let stored = event_store.append(message.clone()).await.unwrap();
+    let last_sequence = event_store.last_sequence().await.unwrap();
+
+    let mut again = message.clone();
+    again.timestamp = Utc::now();
+    assert_eq!(event_store.append(again).await.unwrap(), stored);
+    assert_eq!(event_store.last_sequence().await.unwrap(), last_sequence);
+}
+
+/// Events are read in order from any sequence number, a page at a time.
+pub async fn check_event_store_read(event_store: &impl EventStore) {
+    let first = append(event_store).await;
+    let second = append(event_store).await;
+    let third = append(event_store).await;
+
+    let read = event_store.read(first.sequence, 10).await.unwrap();
+    assert_eq!(read, vec![first.clone(), second.clone(), third.clone()]);
+    let read = event_store.read(second.sequence, 1).await.unwrap();
+    assert_eq!(read, vec![second]);
+    let all = event_store.read(0, usize::MAX).await.unwrap();
+    assert_eq!(all.len() as u64, third.sequence);
+    assert!(all.iter().zip(1..).all(|(event, n)| event.sequence == n));
+    assert!(event_store
+        .read(third.sequence + 1, 10)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(event_store
+        .read(first.sequence, 0)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,77 @@
+use uuid::Uuid;
+
+use crate::adapter::{KeyStore, KeyStoreError};
+use crate::models::{ModelEvent, ModelKind, ProjectChangeKind, ResponsibilityChain};
+
+/// Run all the KeyStore checks.
+pub async fn check_key_store(key_store: &impl KeyStore) {
+    check_key_generation(key_store).await;
+    check_key_verify_event(key_store).await;
+    check_key_invalid_id(key_store).await;
+}
+
+/// A signing key is generated once, then always the same is given back with
+/// its public key.
+pub async fn check_key_generation(key_store: &impl KeyStore) {
+    let key_id = Uuid::new_v4().to_string();
+    assert!(key_store.verifying_key(&key_id).await.unwrap().is_none());
+
+    let key = key_store.signing_key(&key_id).await.unwrap();
+    assert_eq!(
+        key_store.signing_key(&key_id).await.unwrap().to_bytes(),
+        key.to_bytes()
+    );
+    assert_eq!(
+        key_store.verifying_key(&key_id).await.unwrap(),
+        Some(key.verifying_key())
+    );
+    let other = key_store
+        .signing_key(&Uuid::new_v4().to_string())
+        .await
+        .unwrap();
+    assert_ne!(other.to_bytes(), key.to_bytes());
+}
+
+/// An event signed with the key of its organization is verified by the
+/// store, a forged one is not.
+pub async fn check_key_verify_event(key_store: &impl KeyStore) {
+    let organization_id = Uuid::new_v4();
+    let mut event = ModelEvent::new(ModelKind::Project {
+        project_id: Uuid::new_v4(),
+        universe_id: Uuid::new_v4(),
+        change_kind: ProjectChangeKind::Created,
+    })
+    .with_chain(ResponsibilityChain {
+        owner_organization_id: Some(organization_id),
+        ..Default::default()
+    });
+
+    let error = key_store.verify(&event).await.unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<KeyStoreError>(),
+        Some(KeyStoreError::UnknownKey(key_id)) if *key_id == organization_id.to_string()
+    ));
+    let key = key_store.signing_key(&event.key_id()).await.unwrap();
+    assert!(key_store.verify(&event).await.is_err());
+    event.sign(&key);
+    key_store.verify(&event).await.unwrap();
+
+    let forger = key_store
+        .signing_key(&Uuid::new_v4().to_string())
+        .await
+        .unwrap();
+    event.sign(&forger);
+    assert!(key_store.verify(&event).await.is_err());
+}
+
+/// A key identifier that cannot name a file is refused.
+pub async fn check_key_invalid_id(key_store: &impl KeyStore) {
+    for key_id in ["", "../instance", "a/b", "key.pub"] {
+        let error = key_store.signing_key(key_id).await.unwrap_err();
+        assert!(matches!(
+            error.downcast_ref::<KeyStoreError>(),
+            Some(KeyStoreError::InvalidKeyId(..))
+        ));
+        assert!(key_store.verifying_key(key_id).await.is_err());
+    }
+}
@@ -0,0 +1,126 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::adapter::{MediaStore, MediaStoreError};
+use crate::models::media_digest;
+
+/// Run all the MediaStore checks.
+pub async fn check_media_store(media_store: &impl MediaStore) {
+    check_media_put_and_get(media_store).await;
+    check_media_delete(media_store).await;
+    check_media_list(media_store).await;
+    check_media_list_stored_before(media_store).await;
+    check_media_invalid_digest(media_store).await;
+}
+
+fn unique_content() -> Vec<u8> {
+    format!("Testkit media {}", Uuid::new_v4()).into_bytes()
+}
+
+/// A stored blob can be fetched by its digest with its MIME type, storing it
+/// again is harmless and replaces its MIME type.
+pub async fn check_media_put_and_get(media_store: &impl MediaStore) {
+    let content = unique_content();
+    let digest = media_store.put(&content, "text/plain").await.unwrap();
+
+    assert_eq!(digest, media_digest(&content));
+    assert_eq!(
+        media_store.mime_type(&digest).await.unwrap().as_deref(),
+        Some("text/plain")
+    );
+    assert_eq!(
+        media_store.put(&content, "text/markdown").await.unwrap(),
+        digest
+    );
+    assert_eq!(
+        media_store.get(&digest).await.unwrap(),
+        Some(content.clone())
+    );
+    assert_eq!(
+        media_store.size(&digest).await.unwrap(),
+        Some(content.len() as u64)
+    );
+    assert_eq!(
+        media_store.mime_type(&digest).await.unwrap().as_deref(),
+        Some("text/markdown")
+    );
+
+    let missing = media_digest(&unique_content());
+    assert!(media_store.get(&missing).await.unwrap().is_none());
+    assert!(media_store.size(&missing).await.unwrap().is_none());
+    assert!(media_store.mime_type(&missing).await.unwrap().is_none());
+}
+
+/// A deleted blob is gone, deleting it again returns false.
+pub async fn check_media_delete(media_store: &impl MediaStore) {
+    let digest = media_store
+        .put(&unique_content(), "text/plain")
+        .await
+        .unwrap();
+
+    assert!(media_store.delete(&digest).await.unwrap());
+    assert!(media_store.get(&digest).await.unwrap().is_none());
+    assert!(media_store.mime_type(&digest).await.unwrap().is_none());
+    assert!(!media_store.delete(&digest).await.unwrap());
+}
+
+/// Stored blobs are listed, deleted ones are not.
+pub async fn check_media_list(media_store: &impl MediaStore) {
+    let kept = media_store
+        .put(&unique_content(), "text/plain")
+        .await
+        .unwrap();
+    let deleted = media_store
+        .put(&unique_content(), "text/plain")
+        .await
+        .unwrap();
+    media_store.delete(&deleted).await.unwrap();
+
+    let digests = media_store.list().await.unwrap();
+    assert!(digests.contains(&kept));
+    assert!(!digests.contains(&deleted));
+    assert!(digests.windows(2).all(|w| w[0] < w[1]));
+}
+
+/// Blobs are listed by storage date, storing a blob again refreshes its date.
+pub async fn check_media_list_stored_before(media_store: &impl MediaStore) {
+    let before_put = Utc::now();
+    let digest = media_store
+        .put(&unique_content(), "text/plain")
+        .await
+        .unwrap();
+    let after_put = Utc::now() + chrono::Duration::milliseconds(10);
+    tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+
+    assert!(!media_store
+        .list_stored_before(before_put)
+        .await
+        .unwrap()
+        .contains(&digest));
+    let digests = media_store.list_stored_before(after_put).await.unwrap();
+    assert!(digests.contains(&digest));
+    assert!(digests.windows(2).all(|w| w[0] < w[1]));
+
+    let content = media_store.get(&digest).await.unwrap().unwrap();
+    media_store.put(&content, "text/plain").await.unwrap();
+    assert!(!media_store
+        .list_stored_before(after_put)
+        .await
+        .unwrap()
+        .contains(&digest));
+}
+
+/// Texts that are not digests are rejected.
+pub async fn check_media_invalid_digest(media_store: &impl MediaStore) {
+    for digest in ["../secret", "ABCDEF", ""] {
+        let error = media_store
+            .get(digest)
+            .await
+            .expect_err("An invalid digest must be rejected.");
+        assert!(matches!(
+            error.downcast_ref::<MediaStoreError>(),
+            Some(MediaStoreError::InvalidDigest(_))
+        ));
+        assert!(media_store.delete(digest).await.is_err());
+    }
+}
@@ -0,0 +1,72 @@
+//! Conformance test kit for the books.
+//!
+//! Every storage backend must behave the same way, this module provides test
+//! suites that take any implementation of the book traits and panic at the
+//! first divergence. A backend test only has to build its books and call the
+//! suites:
+//!
+//! ```ignore
+//! kaku::testkit::check_project_book(&project_book).await;
+//! kaku::testkit::check_note_book(&note_book, &project_book).await;
+//! kaku::testkit::check_thought_book(&thought_book, &project_book).await;
+//! kaku::testkit::check_reference_book(&reference_book, &project_book).await;
+//! kaku::testkit::check_saved_search_book(&saved_search_book).await;
+//! kaku::testkit::check_organization_book(&organization_book, &universe_book).await;
+//! kaku::testkit::check_stylo_book(&stylo_book, &outbox).await;
+//! kaku::testkit::check_media_store(&media_store).await;
+//! kaku::testkit::check_event_store(&event_store).await;
+//! kaku::testkit::check_audit_log(&audit_log).await;
+//! kaku::testkit::check_key_store(&key_store).await;
+//! kaku::testkit::check_outbox(&outbox, &project_book, &note_book, &thought_book).await;
+//! ```
+//!
+//! The suites only create data with unique names so they can run concurrently
+//! against a shared database.
+mod audit_log;
+mod event_store;
+mod key_store;
+mod media_store;
+mod note_book;
+mod organization_book;
+mod outbox;
+mod project_book;
+mod reference_book;
+mod saved_search_book;
+mod stylo_book;
+mod thought_book;
+
+pub use audit_log::*;
+pub use event_store::*;
+pub use key_store::*;
+pub use media_store::*;
+pub use note_book::*;
+pub use organization_book::*;
+pub use outbox::*;
+pub use project_book::*;
+pub use reference_book::*;
+pub use saved_search_book::*;
+pub use stylo_book::*;
+pub use thought_book::*;
+
+use uuid::Uuid;
+
+use crate::adapter::ProjectBook;
+use crate::models::{CreateProjectCommand, Project};
+
+/// Create a project with a unique name in the given book.
+pub async fn create_project(project_book: &impl ProjectBook) -> Project {
+    create_project_in(project_book, Uuid::new_v4()).await
+}
+
+/// Create a project with a unique name in the given universe.
+pub async fn create_project_in(project_book: &impl ProjectBook, universe_id: Uuid) -> Project {
+    let command = CreateProjectCommand {
+        universe_id,
+        project_name: format!("Testkit Project {}", Uuid::new_v4()),
+    };
+
+    project_book
+        .create(command)
+        .await
+        .expect("The project should be created.")
+}
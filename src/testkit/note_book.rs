@@ -0,0 +1,159 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::create_project;
+use crate::adapter::{NoteBook, ProjectBook};
+use crate::models::{CreateNoteCommand, Media, Note, Project};
+
+/// Run all the NoteBook checks.
+/// The project book is used to create the projects the notes belong to.
+pub async fn check_note_book(note_book: &impl NoteBook, project_book: &impl ProjectBook) {
+    check_note_add_and_get(note_book, project_book).await;
+    check_note_sync(note_book, project_book).await;
+    check_note_sync_missing(note_book, project_book).await;
+    check_note_delete(note_book, project_book).await;
+    check_note_list_by_project(note_book, project_book).await;
+    check_note_media(note_book, project_book).await;
+}
+
+pub(super) async fn add_note(note_book: &impl NoteBook, project: &Project, content: &str) -> Note {
+    let command = CreateNoteCommand {
+        imported_at: Utc::now(),
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: content.to_string(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+
+    note_book
+        .add(command, project.project_id, Default::default())
+        .await
+        .expect("The note should be added.")
+}
+
+/// An added note can be fetched by its identifier.
+pub async fn check_note_add_and_get(note_book: &impl NoteBook, project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let note = add_note(note_book, &project, "Testkit note").await;
+
+    assert_eq!(note.project_id, project.project_id);
+    let fetched = note_book
+        .get(note.note_id)
+        .await
+        .unwrap()
+        .expect("The note should be found.");
+    assert_eq!(fetched.content, "Testkit note");
+    assert_eq!(fetched.stylo_id, note.stylo_id);
+
+    assert!(note_book.get(Uuid::new_v4()).await.unwrap().is_none());
+}
+
+/// Syncing a note stores its new state.
+pub async fn check_note_sync(note_book: &impl NoteBook, project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let mut note = add_note(note_book, &project, "Testkit note").await;
+    note.content = "Updated testkit note".to_string();
+    note.references = vec![Uuid::new_v4()];
+    note_book.sync(note.clone()).await.unwrap();
+
+    let fetched = note_book
+        .get(note.note_id)
+        .await
+        .unwrap()
+        .expect("The note should be found.");
+    assert_eq!(fetched.content, "Updated testkit note");
+    assert_eq!(fetched.references, note.references);
+}
+
+/// Syncing a note that does not exist fails and does not create it.
+pub async fn check_note_sync_missing(note_book: &impl NoteBook, project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let note = Note {
+        note_id: Uuid::new_v4(),
+        imported_at: Utc::now(),
+        stylo_id: Uuid::new_v4(),
+        project_id: project.project_id,
+        content: "Missing testkit note".to_string(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+
+    assert!(note_book.sync(note.clone()).await.is_err());
+    assert!(note_book.get(note.note_id).await.unwrap().is_none());
+}
+
+/// A deleted note is returned once and is gone afterwards.
+pub async fn check_note_delete(note_book: &impl NoteBook, project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let note = add_note(note_book, &project, "Testkit note").await;
+
+    let deleted = note_book
+        .delete(note.note_id, Default::default())
+        .await
+        .unwrap()
+        .expect("The deleted note should be returned.");
+    assert_eq!(deleted.note_id, note.note_id);
+    assert!(note_book.get(note.note_id).await.unwrap().is_none());
+    assert!(note_book
+        .delete(note.note_id, Default::default())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Only the notes of the given project are listed, oldest first.
+pub async fn check_note_list_by_project(
+    note_book: &impl NoteBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let other = create_project(project_book).await;
+    let first = add_note(note_book, &project, "First testkit note").await;
+    let second = add_note(note_book, &project, "Second testkit note").await;
+    let _ = add_note(note_book, &other, "Other testkit note").await;
+
+    let listed: Vec<Uuid> = note_book
+        .list_by_project(project.project_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|n| n.note_id)
+        .collect();
+
+    assert_eq!(listed, vec![first.note_id, second.note_id]);
+    assert!(note_book
+        .list_by_project(Uuid::new_v4())
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// The medias of a note are stored and tell if a blob is still referenced.
+pub async fn check_note_media(note_book: &impl NoteBook, project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let content = format!("Testkit media {}", Uuid::new_v4());
+    let media = Media::describe(content.as_bytes(), "text/plain", None);
+    let command = CreateNoteCommand {
+        imported_at: Utc::now(),
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Testkit note with media".to_string(),
+        references: Vec::new(),
+        media: vec![media.clone()],
+    };
+
+    assert!(!note_book.is_media_referenced(&media.digest).await.unwrap());
+    let note = note_book
+        .add(command, project.project_id, Default::default())
+        .await
+        .unwrap();
+    assert_eq!(note.media, vec![media.clone()]);
+    assert!(note_book.is_media_referenced(&media.digest).await.unwrap());
+
+    note_book
+        .delete(note.note_id, Default::default())
+        .await
+        .unwrap();
+    assert!(!note_book.is_media_referenced(&media.digest).await.unwrap());
+}
@@ -0,0 +1,124 @@
+use ed25519_dalek::SigningKey;
+use uuid::Uuid;
+
+use crate::adapter::{OrganizationBook, UniverseBook};
+use crate::models::{CreateOrganizationCommand, CreatedOrganization};
+
+/// Run all the OrganizationBook and UniverseBook checks.
+/// The universe book must read the universes the organization book creates.
+pub async fn check_organization_book(
+    organization_book: &impl OrganizationBook,
+    universe_book: &impl UniverseBook,
+) {
+    check_organization_create_and_get(organization_book).await;
+    check_organization_universe(organization_book, universe_book).await;
+    check_organization_invalid(organization_book).await;
+    check_organization_list(organization_book).await;
+}
+
+/// Create an organization with a unique name in the given book.
+/// It is registered with the public key of a key that is not kept, the
+/// organization service keeps the keys of the organizations it creates.
+pub async fn create_organization(
+    organization_book: &(impl OrganizationBook + ?Sized),
+) -> CreatedOrganization {
+    let command = CreateOrganizationCommand {
+        name: format!("Testkit Organization {}", Uuid::new_v4()),
+        certificate: serde_json::json!({ "issuer": "testkit" }),
+    };
+    let key = SigningKey::generate(&mut rand_core::OsRng);
+
+    organization_book
+        .create(Uuid::new_v4(), command, &key.verifying_key())
+        .await
+        .expect("The organization should be created.")
+}
+
+/// A created organization can be fetched by its identifier.
+pub async fn check_organization_create_and_get(organization_book: &impl OrganizationBook) {
+    let created = create_organization(organization_book).await;
+
+    let fetched = organization_book
+        .get(created.organization.organization_id)
+        .await
+        .unwrap()
+        .expect("The organization should be found.");
+    assert_eq!(fetched, created.organization);
+    assert_eq!(fetched.certificate["issuer"], "testkit");
+    assert!(fetched.verifying_key().is_ok());
+
+    assert!(organization_book
+        .get(Uuid::new_v4())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// The universe of an organization is created with it and can be fetched by
+/// its identifier and by its organization.
+pub async fn check_organization_universe(
+    organization_book: &impl OrganizationBook,
+    universe_book: &impl UniverseBook,
+) {
+    let created = create_organization(organization_book).await;
+    assert_eq!(
+        created.universe.organization_id,
+        created.organization.organization_id
+    );
+
+    let fetched = universe_book
+        .get(created.universe.universe_id)
+        .await
+        .unwrap()
+        .expect("The universe should be found.");
+    assert_eq!(fetched, created.universe);
+    let fetched = universe_book
+        .get_by_organization(created.organization.organization_id)
+        .await
+        .unwrap()
+        .expect("The universe should be found by its organization.");
+    assert_eq!(fetched, created.universe);
+
+    assert!(universe_book.get(Uuid::new_v4()).await.unwrap().is_none());
+    assert!(universe_book
+        .get_by_organization(Uuid::new_v4())
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// An organization without name is refused and not stored.
+pub async fn check_organization_invalid(organization_book: &impl OrganizationBook) {
+    let organization_id = Uuid::new_v4();
+    let command = CreateOrganizationCommand {
+        name: " ".to_string(),
+        certificate: serde_json::Value::Null,
+    };
+    let key = SigningKey::generate(&mut rand_core::OsRng);
+    assert!(organization_book
+        .create(organization_id, command, &key.verifying_key())
+        .await
+        .is_err());
+    assert!(organization_book
+        .get(organization_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Organizations are listed oldest first.
+pub async fn check_organization_list(organization_book: &impl OrganizationBook) {
+    let first = create_organization(organization_book).await.organization;
+    let second = create_organization(organization_book).await.organization;
+
+    let organizations = organization_book.list().await.unwrap();
+    let first_position = organizations
+        .iter()
+        .position(|o| o.organization_id == first.organization_id)
+        .expect("The first organization should be listed.");
+    let second_position = organizations
+        .iter()
+        .position(|o| o.organization_id == second.organization_id)
+        .expect("The second organization should be listed.");
+    assert!(first_position < second_position);
+}
@@ -0,0 +1,186 @@
+use uuid::Uuid;
+
+use super::create_project;
+use super::note_book::add_note;
+use super::thought_book::{add_tagged_thought, question_command};
+use crate::adapter::{NoteBook, Outbox, ProjectBook, ThoughtBook};
+use crate::models::{
+    ModelEvent, ModelKind, NoteChangeKind, ProjectChangeKind, ResponsibilityChain,
+    ThoughtChangeKind,
+};
+
+/// Run all the Outbox checks.
+/// The books must write their events in the given outbox.
+pub async fn check_outbox(
+    outbox: &impl Outbox,
+    project_book: &impl ProjectBook,
+    note_book: &impl NoteBook,
+    thought_book: &impl ThoughtBook,
+) {
+    check_outbox_writes(outbox, project_book, note_book, thought_book).await;
+    check_outbox_answer(outbox, project_book, thought_book).await;
+    check_outbox_acknowledge(outbox, project_book).await;
+}
+
+/// The events of the given project, the suites may run against a shared
+/// outbox.
+async fn project_events(outbox: &impl Outbox, project_id: Uuid) -> Vec<ModelEvent> {
+    outbox
+        .pending(usize::MAX)
+        .await
+        .expect("The pending events should be listed.")
+        .into_iter()
+        .filter(|event| event.model.project_id() == Some(project_id))
+        .collect()
+}
+
+/// Every change stored by the books is written in the outbox, in order, the
+/// changes of existing models carry the given responsibility chain.
+pub async fn check_outbox_writes(
+    outbox: &impl Outbox,
+    project_book: &impl ProjectBook,
+    note_book: &impl NoteBook,
+    thought_book: &impl ThoughtBook,
+) {
+    let project = create_project(project_book).await;
+    let note = add_note(note_book, &project, "Outbox testkit note").await;
+    let chain = ResponsibilityChain::organization(Uuid::new_v4());
+    note_book.delete(note.note_id, chain.clone()).await.unwrap();
+    let thought = add_tagged_thought(thought_book, &project, None, &["outbox"]).await;
+    let dispute_id = Uuid::new_v4();
+    thought_book
+        .sync(
+            thought.clone(),
+            ThoughtChangeKind::Disputed(dispute_id),
+            chain.clone(),
+        )
+        .await
+        .unwrap();
+    thought_book
+        .retag(
+            project.project_id,
+            &["outbox".to_string()],
+            "relay",
+            chain.clone(),
+        )
+        .await
+        .unwrap();
+    thought_book
+        .trash_by_project(project.project_id, chain.clone())
+        .await
+        .unwrap();
+
+    let events = project_events(outbox, project.project_id).await;
+    let chains = events
+        .iter()
+        .map(|event| &event.chain)
+        .filter(|c| **c == chain)
+        .count();
+    assert_eq!(chains, 4);
+    let models = events
+        .into_iter()
+        .map(|event| event.model)
+        .collect::<Vec<_>>();
+    let thought_event = |change_kind| ModelKind::Thought {
+        thought_id: thought.thought_id,
+        project_id: project.project_id,
+        change_kind,
+    };
+    assert_eq!(
+        models,
+        vec![
+            ModelKind::Project {
+                project_id: project.project_id,
+                universe_id: project.universe_id,
+                change_kind: ProjectChangeKind::Created,
+            },
+            ModelKind::Note {
+                note_id: note.note_id,
+                project_id: project.project_id,
+                change_kind: NoteChangeKind::Created,
+            },
+            ModelKind::Note {
+                note_id: note.note_id,
+                project_id: project.project_id,
+                change_kind: NoteChangeKind::Scratched,
+            },
+            thought_event(ThoughtChangeKind::Created),
+            thought_event(ThoughtChangeKind::Disputed(dispute_id)),
+            thought_event(ThoughtChangeKind::Modified),
+            thought_event(ThoughtChangeKind::Trashed),
+        ]
+    );
+}
+
+/// An answer and its newly answered question are written together with the
+/// same responsibility chain, the question is only modified by its first
+/// answer.
+pub async fn check_outbox_answer(
+    outbox: &impl Outbox,
+    project_book: &impl ProjectBook,
+    thought_book: &impl ThoughtBook,
+) {
+    let project = create_project(project_book).await;
+    let question = thought_book
+        .add(
+            question_command(&project, None),
+            project.project_id,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    let chain = ResponsibilityChain::organization(Uuid::new_v4());
+    let mut answers = Vec::new();
+    for _ in 0..2 {
+        let answer = thought_book
+            .add_answer(
+                question_command(&project, Some(question.thought_id)),
+                project.project_id,
+                chain.clone(),
+            )
+            .await
+            .unwrap();
+        answers.push(answer.thought_id);
+    }
+
+    let events = project_events(outbox, project.project_id).await;
+    assert!(events[2..].iter().all(|event| event.chain == chain));
+    let models = events
+        .into_iter()
+        .skip(1)
+        .map(|event| event.model)
+        .collect::<Vec<_>>();
+    let thought_event = |thought_id, change_kind| ModelKind::Thought {
+        thought_id,
+        project_id: project.project_id,
+        change_kind,
+    };
+    assert_eq!(
+        models,
+        vec![
+            thought_event(question.thought_id, ThoughtChangeKind::Created),
+            thought_event(answers[0], ThoughtChangeKind::Created),
+            thought_event(question.thought_id, ThoughtChangeKind::Modified),
+            thought_event(answers[1], ThoughtChangeKind::Created),
+        ]
+    );
+}
+
+/// Acknowledged events leave the outbox, the others stay.
+pub async fn check_outbox_acknowledge(outbox: &impl Outbox, project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let other = create_project(project_book).await;
+    let events = project_events(outbox, project.project_id).await;
+    assert_eq!(events.len(), 1);
+
+    outbox
+        .acknowledge(&[events[0].event_id, Uuid::new_v4()])
+        .await
+        .unwrap();
+    assert!(project_events(outbox, project.project_id).await.is_empty());
+    let others = project_events(outbox, other.project_id).await;
+    assert_eq!(others.len(), 1);
+    outbox.acknowledge(&[events[0].event_id]).await.unwrap();
+    outbox.acknowledge(&[others[0].event_id]).await.unwrap();
+    assert!(project_events(outbox, other.project_id).await.is_empty());
+}
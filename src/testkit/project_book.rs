@@ -0,0 +1,192 @@
+use uuid::Uuid;
+
+use super::{create_project, create_project_in};
+use crate::adapter::{ProjectBook, ProjectBookError};
+use crate::models::{CreateProjectCommand, Project};
+
+/// Run all the ProjectBook checks.
+pub async fn check_project_book(project_book: &impl ProjectBook) {
+    check_project_create_and_get(project_book).await;
+    check_project_slug_uniqueness(project_book).await;
+    check_project_update_slug(project_book).await;
+    check_project_update_duplicate_slug(project_book).await;
+    check_project_delete(project_book).await;
+    check_project_list_by_universe(project_book).await;
+    check_project_list(project_book).await;
+    check_project_suggest_slugs(project_book).await;
+}
+
+/// A created project can be fetched by its identifier and its slug.
+pub async fn check_project_create_and_get(project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+
+    let fetched = project_book
+        .get(&project.project_id)
+        .await
+        .unwrap()
+        .expect("The project should be found by its identifier.");
+    assert_eq!(fetched.project_name, project.project_name);
+    assert_eq!(fetched.slug, Project::generate_slug(&project.project_name));
+
+    let fetched = project_book
+        .get_by_slug(&project.slug)
+        .await
+        .unwrap()
+        .expect("The project should be found by its slug.");
+    assert_eq!(fetched.project_id, project.project_id);
+
+    assert!(project_book.get(&Uuid::new_v4()).await.unwrap().is_none());
+    assert!(project_book
+        .get_by_slug(&format!("unknown-{}", Uuid::new_v4()))
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Two projects cannot share the same slug.
+pub async fn check_project_slug_uniqueness(project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let command = CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: project.project_name.to_uppercase(),
+    };
+    let error = project_book.create(command).await.unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<ProjectBookError>(),
+        Some(ProjectBookError::DuplicateSlug(slug)) if slug == &project.slug
+    ));
+}
+
+/// Updating the slug of a project frees the previous one.
+pub async fn check_project_update_slug(project_book: &impl ProjectBook) {
+    let mut project = create_project(project_book).await;
+    let previous_slug = project.slug.clone();
+    project.slug = format!("renamed-{}", Uuid::new_v4());
+    project_book.update(project.clone()).await.unwrap();
+
+    assert!(project_book
+        .get_by_slug(&previous_slug)
+        .await
+        .unwrap()
+        .is_none());
+    let fetched = project_book
+        .get_by_slug(&project.slug)
+        .await
+        .unwrap()
+        .expect("The project should be found by its new slug.");
+    assert_eq!(fetched.project_id, project.project_id);
+}
+
+/// Updating a project with the slug of another project fails and leaves the
+/// project untouched.
+pub async fn check_project_update_duplicate_slug(project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let other = create_project(project_book).await;
+    let mut updated = project.clone();
+    updated.slug = other.slug.clone();
+    let error = project_book.update(updated).await.unwrap_err();
+
+    assert!(matches!(
+        error.downcast_ref::<ProjectBookError>(),
+        Some(ProjectBookError::DuplicateSlug(_))
+    ));
+    let fetched = project_book
+        .get_by_slug(&project.slug)
+        .await
+        .unwrap()
+        .expect("The project should still be found by its slug.");
+    assert_eq!(fetched.project_id, project.project_id);
+    let fetched = project_book
+        .get_by_slug(&other.slug)
+        .await
+        .unwrap()
+        .expect("The other project should still be found by its slug.");
+    assert_eq!(fetched.project_id, other.project_id);
+}
+
+/// A deleted project is gone and deleting an unknown project fails.
+pub async fn check_project_delete(project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    project_book.delete(&project.project_id).await.unwrap();
+
+    assert!(project_book
+        .get(&project.project_id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(project_book
+        .get_by_slug(&project.slug)
+        .await
+        .unwrap()
+        .is_none());
+
+    let unknown_id = Uuid::new_v4();
+    let error = project_book.delete(&unknown_id).await.unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<ProjectBookError>(),
+        Some(ProjectBookError::ProjectNotFound(id)) if id == &unknown_id
+    ));
+}
+
+/// Only the projects of the given universe are listed.
+pub async fn check_project_list_by_universe(project_book: &impl ProjectBook) {
+    let universe_id = Uuid::new_v4();
+    let first = create_project_in(project_book, universe_id).await;
+    let second = create_project_in(project_book, universe_id).await;
+    let _ = create_project(project_book).await;
+
+    let mut listed: Vec<Uuid> = project_book
+        .list_by_universe(&universe_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|p| p.project_id)
+        .collect();
+    listed.sort();
+    let mut expected = vec![first.project_id, second.project_id];
+    expected.sort();
+
+    assert_eq!(listed, expected);
+}
+
+/// All the projects are listed, oldest first.
+pub async fn check_project_list(project_book: &impl ProjectBook) {
+    let first = create_project(project_book).await;
+    let second = create_project(project_book).await;
+
+    let listed: Vec<Uuid> = project_book
+        .list()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|p| p.project_id)
+        .filter(|id| [first.project_id, second.project_id].contains(id))
+        .collect();
+
+    assert_eq!(listed, vec![first.project_id, second.project_id]);
+}
+
+/// Slugs close to a misspelled slug are suggested, the closest first.
+pub async fn check_project_suggest_slugs(project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let misspelled = &project.slug[..project.slug.len() - 1];
+
+    let suggestions = project_book
+        .suggest_slugs(misspelled, 0.3, 3)
+        .await
+        .unwrap();
+    assert_eq!(suggestions.first(), Some(&project.slug));
+    assert!(suggestions.len() <= 3);
+
+    let suggestions = project_book
+        .suggest_slugs(misspelled, 1.0, 3)
+        .await
+        .unwrap();
+    assert!(suggestions.is_empty());
+    assert!(project_book
+        .suggest_slugs("zzzz-qqqq", 0.3, 3)
+        .await
+        .unwrap()
+        .is_empty());
+}
@@ -0,0 +1,244 @@
+use uuid::Uuid;
+
+use super::create_project;
+use crate::adapter::{ProjectBook, ReferenceBook, ReferenceBookError};
+use crate::models::{CreateReferenceCommand, Project, Reference};
+
+/// Run all the ReferenceBook checks.
+/// The project book is used to create the projects the references belong to.
+pub async fn check_reference_book(
+    reference_book: &impl ReferenceBook,
+    project_book: &impl ProjectBook,
+) {
+    check_reference_add_and_get(reference_book, project_book).await;
+    check_reference_duplicate_citation_key(reference_book, project_book).await;
+    check_reference_sync(reference_book, project_book).await;
+    check_reference_delete(reference_book, project_book).await;
+    check_reference_list_by_project(reference_book, project_book).await;
+    check_reference_import(reference_book, project_book).await;
+}
+
+async fn add_reference(
+    reference_book: &impl ReferenceBook,
+    project: &Project,
+    citation_key: &str,
+) -> Reference {
+    let command = CreateReferenceCommand {
+        entry_type: "book".to_string(),
+        citation_key: citation_key.to_string(),
+        authors: vec!["Testkit Author".to_string()],
+        title: "Testkit Book".to_string(),
+        year: Some(2025),
+        pages: None,
+        extra_fields: Default::default(),
+    };
+
+    reference_book
+        .add(command, project.project_id)
+        .await
+        .expect("The reference should be added.")
+}
+
+/// An added reference can be fetched by its identifier and its citation key.
+pub async fn check_reference_add_and_get(
+    reference_book: &impl ReferenceBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let reference = add_reference(reference_book, &project, "testkit2025").await;
+
+    assert_eq!(reference.project_id, project.project_id);
+    let fetched = reference_book
+        .get(reference.reference_id)
+        .await
+        .unwrap()
+        .expect("The reference should be found.");
+    assert_eq!(fetched, reference);
+    let fetched = reference_book
+        .get_by_citation_key(project.project_id, "testkit2025")
+        .await
+        .unwrap()
+        .expect("The reference should be found by its citation key.");
+    assert_eq!(fetched.reference_id, reference.reference_id);
+
+    assert!(reference_book.get(Uuid::new_v4()).await.unwrap().is_none());
+    assert!(reference_book
+        .get_by_citation_key(Uuid::new_v4(), "testkit2025")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// A citation key is unique in a project, not across projects.
+pub async fn check_reference_duplicate_citation_key(
+    reference_book: &impl ReferenceBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let other = create_project(project_book).await;
+    let _ = add_reference(reference_book, &project, "testkit2025").await;
+    let _ = add_reference(reference_book, &other, "testkit2025").await;
+    let mut second = add_reference(reference_book, &project, "testkit2026").await;
+
+    let command = CreateReferenceCommand {
+        entry_type: "book".to_string(),
+        citation_key: "testkit2025".to_string(),
+        authors: Vec::new(),
+        title: "Duplicate".to_string(),
+        year: None,
+        pages: None,
+        extra_fields: Default::default(),
+    };
+    let error = reference_book
+        .add(command, project.project_id)
+        .await
+        .expect_err("A duplicate citation key must be rejected.");
+    assert!(matches!(
+        error.downcast_ref::<ReferenceBookError>(),
+        Some(ReferenceBookError::DuplicateCitationKey(_))
+    ));
+
+    second.citation_key = "testkit2025".to_string();
+    assert!(reference_book.sync(second).await.is_err());
+}
+
+/// Syncing a reference stores its new state, a missing reference fails.
+pub async fn check_reference_sync(
+    reference_book: &impl ReferenceBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let mut reference = add_reference(reference_book, &project, "testkit2025").await;
+    reference.pages = Some("12--15".to_string());
+    reference_book.sync(reference.clone()).await.unwrap();
+
+    let fetched = reference_book
+        .get(reference.reference_id)
+        .await
+        .unwrap()
+        .expect("The reference should be found.");
+    assert_eq!(fetched.pages.as_deref(), Some("12--15"));
+
+    let missing = Reference {
+        reference_id: Uuid::new_v4(),
+        ..reference
+    };
+    assert!(reference_book.sync(missing.clone()).await.is_err());
+    assert!(reference_book
+        .get(missing.reference_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// A deleted reference is returned once and is gone afterwards.
+pub async fn check_reference_delete(
+    reference_book: &impl ReferenceBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let reference = add_reference(reference_book, &project, "testkit2025").await;
+
+    let deleted = reference_book
+        .delete(reference.reference_id)
+        .await
+        .unwrap()
+        .expect("The deleted reference should be returned.");
+    assert_eq!(deleted.reference_id, reference.reference_id);
+    assert!(reference_book
+        .get(reference.reference_id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(reference_book
+        .delete(reference.reference_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Only the references of the given project are listed, by citation key.
+pub async fn check_reference_list_by_project(
+    reference_book: &impl ReferenceBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let other = create_project(project_book).await;
+    let second = add_reference(reference_book, &project, "zeno").await;
+    let first = add_reference(reference_book, &project, "aristotle").await;
+    let _ = add_reference(reference_book, &other, "plato").await;
+
+    let listed: Vec<Uuid> = reference_book
+        .list_by_project(project.project_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|r| r.reference_id)
+        .collect();
+
+    assert_eq!(listed, vec![first.reference_id, second.reference_id]);
+}
+
+/// Importing updates the references whose citation key is already used and
+/// adds the others, nothing is imported if a reference is not valid.
+pub async fn check_reference_import(
+    reference_book: &impl ReferenceBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let existing = add_reference(reference_book, &project, "testkit2025").await;
+    let command = |citation_key: &str, title: &str| CreateReferenceCommand {
+        entry_type: "article".to_string(),
+        citation_key: citation_key.to_string(),
+        authors: vec!["Imported Author".to_string()],
+        title: title.to_string(),
+        year: None,
+        pages: Some("4".to_string()),
+        extra_fields: [("journal".to_string(), "Testkit".to_string())].into(),
+    };
+
+    let imported = reference_book
+        .import(
+            vec![
+                command("testkit2026", "Imported Article"),
+                command("testkit2025", "Updated Article"),
+            ],
+            project.project_id,
+        )
+        .await
+        .unwrap();
+    assert_eq!(imported.len(), 2);
+    assert_eq!(imported[0].citation_key, "testkit2026");
+    assert_eq!(imported[1].reference_id, existing.reference_id);
+    assert_eq!(imported[1].title, "Updated Article");
+    assert_eq!(imported[1].extra_fields["journal"], "Testkit");
+    assert_eq!(
+        reference_book
+            .list_by_project(project.project_id)
+            .await
+            .unwrap(),
+        vec![imported[1].clone(), imported[0].clone()]
+    );
+
+    let error = reference_book
+        .import(
+            vec![
+                command("testkit2027", "Never Imported"),
+                command("testkit2025", " "),
+            ],
+            project.project_id,
+        )
+        .await;
+    assert!(error.is_err());
+    assert!(reference_book
+        .get_by_citation_key(project.project_id, "testkit2027")
+        .await
+        .unwrap()
+        .is_none());
+    let fetched = reference_book
+        .get(existing.reference_id)
+        .await
+        .unwrap()
+        .expect("The reference should be found.");
+    assert_eq!(fetched.title, "Updated Article");
+}
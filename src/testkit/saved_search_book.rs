@@ -0,0 +1,205 @@
+use chrono::{Duration, DurationRound, Utc};
+use uuid::Uuid;
+
+use crate::adapter::SavedSearchBook;
+use crate::models::{
+    CreateSavedSearchCommand, SavedSearch, SavedSearchResult, SearchDocumentKind, StyloIdentifier,
+};
+
+/// Run all the SavedSearchBook checks.
+pub async fn check_saved_search_book(saved_search_book: &impl SavedSearchBook) {
+    check_saved_search_add_and_get(saved_search_book).await;
+    check_saved_search_sync(saved_search_book).await;
+    check_saved_search_delete(saved_search_book).await;
+    check_saved_search_list_by_stylo(saved_search_book).await;
+    check_saved_search_results(saved_search_book).await;
+}
+
+async fn add_saved_search(
+    saved_search_book: &impl SavedSearchBook,
+    stylo_id: StyloIdentifier,
+    name: &str,
+) -> SavedSearch {
+    let command = CreateSavedSearchCommand {
+        stylo_id,
+        name: name.to_string(),
+        query: "tag:testkit".to_string(),
+    };
+
+    saved_search_book
+        .add(command)
+        .await
+        .expect("The saved search should be added.")
+}
+
+/// A result matched some minutes ago, to the microsecond as the databases
+/// keep it.
+fn result_of(saved_search: &SavedSearch, minutes_ago: i64) -> SavedSearchResult {
+    let matched_at = Utc::now() - Duration::minutes(minutes_ago);
+
+    SavedSearchResult {
+        saved_search_id: saved_search.saved_search_id,
+        stylo_id: saved_search.stylo_id,
+        kind: SearchDocumentKind::Thought,
+        id: Uuid::new_v4(),
+        project_id: Uuid::new_v4(),
+        matched_at: matched_at
+            .duration_trunc(Duration::microseconds(1))
+            .expect("The date should be truncated."),
+    }
+}
+
+/// An added saved search can be fetched by its identifier and its name.
+pub async fn check_saved_search_add_and_get(saved_search_book: &impl SavedSearchBook) {
+    let stylo_id = Uuid::new_v4();
+    let saved_search = add_saved_search(saved_search_book, stylo_id, " Ethics ").await;
+
+    assert_eq!(saved_search.name, "Ethics");
+    let fetched = saved_search_book
+        .get(saved_search.saved_search_id)
+        .await
+        .unwrap()
+        .expect("The saved search should be found.");
+    assert_eq!(fetched, saved_search);
+    let fetched = saved_search_book
+        .get_by_name(stylo_id, "Ethics")
+        .await
+        .unwrap()
+        .expect("The saved search should be found by its name.");
+    assert_eq!(fetched.saved_search_id, saved_search.saved_search_id);
+
+    assert!(saved_search_book
+        .get(Uuid::new_v4())
+        .await
+        .unwrap()
+        .is_none());
+    assert!(saved_search_book
+        .get_by_name(Uuid::new_v4(), "Ethics")
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Syncing a saved search stores its new state but not a new stylo, a missing
+/// saved search fails.
+pub async fn check_saved_search_sync(saved_search_book: &impl SavedSearchBook) {
+    let stylo_id = Uuid::new_v4();
+    let mut saved_search = add_saved_search(saved_search_book, stylo_id, "Ethics").await;
+    saved_search.query = "tag:virtue".to_string();
+    saved_search.stylo_id = Uuid::new_v4();
+    let synced = saved_search_book.sync(saved_search.clone()).await.unwrap();
+    assert_eq!(synced.stylo_id, stylo_id);
+
+    let fetched = saved_search_book
+        .get(saved_search.saved_search_id)
+        .await
+        .unwrap()
+        .expect("The saved search should be found.");
+    assert_eq!(fetched.query, "tag:virtue");
+    assert_eq!(fetched.stylo_id, stylo_id);
+
+    let missing = SavedSearch {
+        saved_search_id: Uuid::new_v4(),
+        ..saved_search
+    };
+    assert!(saved_search_book.sync(missing.clone()).await.is_err());
+    assert!(saved_search_book
+        .get(missing.saved_search_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// A deleted saved search is returned once and is gone afterwards, with its
+/// results.
+pub async fn check_saved_search_delete(saved_search_book: &impl SavedSearchBook) {
+    let stylo_id = Uuid::new_v4();
+    let saved_search = add_saved_search(saved_search_book, stylo_id, "Ethics").await;
+    saved_search_book
+        .add_result(result_of(&saved_search, 0))
+        .await
+        .unwrap();
+
+    let deleted = saved_search_book
+        .delete(saved_search.saved_search_id)
+        .await
+        .unwrap()
+        .expect("The deleted saved search should be returned.");
+    assert_eq!(deleted.saved_search_id, saved_search.saved_search_id);
+    assert!(saved_search_book
+        .get(saved_search.saved_search_id)
+        .await
+        .unwrap()
+        .is_none());
+    assert!(saved_search_book
+        .list_results(stylo_id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert!(saved_search_book
+        .delete(saved_search.saved_search_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Only the saved searches of the given stylo are listed, by name, while all
+/// of them are listed together.
+pub async fn check_saved_search_list_by_stylo(saved_search_book: &impl SavedSearchBook) {
+    let stylo_id = Uuid::new_v4();
+    let second = add_saved_search(saved_search_book, stylo_id, "Virtue").await;
+    let first = add_saved_search(saved_search_book, stylo_id, "Ethics").await;
+    let other = add_saved_search(saved_search_book, Uuid::new_v4(), "Garden").await;
+
+    let listed: Vec<Uuid> = saved_search_book
+        .list_by_stylo(stylo_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.saved_search_id)
+        .collect();
+    assert_eq!(listed, vec![first.saved_search_id, second.saved_search_id]);
+
+    let all: Vec<Uuid> = saved_search_book
+        .list()
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.saved_search_id)
+        .collect();
+    for saved_search in [first, second, other] {
+        assert!(all.contains(&saved_search.saved_search_id));
+    }
+}
+
+/// Results are listed per stylo newest first, recorded once per document and
+/// cleared per stylo.
+pub async fn check_saved_search_results(saved_search_book: &impl SavedSearchBook) {
+    let stylo_id = Uuid::new_v4();
+    let saved_search = add_saved_search(saved_search_book, stylo_id, "Ethics").await;
+    let other = add_saved_search(saved_search_book, Uuid::new_v4(), "Ethics").await;
+    let older = result_of(&saved_search, 10);
+    let newer = result_of(&saved_search, 0);
+    let other_result = result_of(&other, 0);
+    for result in [&older, &newer, &older, &other_result] {
+        saved_search_book.add_result(result.clone()).await.unwrap();
+    }
+
+    let results = saved_search_book.list_results(stylo_id).await.unwrap();
+    assert_eq!(results, vec![newer.clone(), older.clone()]);
+
+    let cleared = saved_search_book.clear_results(stylo_id).await.unwrap();
+    assert_eq!(cleared, vec![newer, older]);
+    assert!(saved_search_book
+        .list_results(stylo_id)
+        .await
+        .unwrap()
+        .is_empty());
+    assert_eq!(
+        saved_search_book
+            .list_results(other.stylo_id)
+            .await
+            .unwrap(),
+        vec![other_result]
+    );
+}
@@ -0,0 +1,146 @@
+use uuid::Uuid;
+
+use crate::adapter::{Outbox, StyloBook};
+use crate::models::{
+    CreateStyloCommand, ModelKind, OrganizationIdentifier, Stylo, StyloChangeKind, StyloIdentifier,
+};
+
+/// Run all the StyloBook checks.
+/// The book must write its events in the given outbox.
+pub async fn check_stylo_book(stylo_book: &impl StyloBook, outbox: &impl Outbox) {
+    check_stylo_add_and_get(stylo_book).await;
+    check_stylo_sync(stylo_book).await;
+    check_stylo_delete(stylo_book).await;
+    check_stylo_list_by_owner(stylo_book).await;
+    check_stylo_events(stylo_book, outbox).await;
+}
+
+/// Grant a stylo with a unique name owned by the given organization.
+pub async fn grant_stylo(
+    stylo_book: &(impl StyloBook + ?Sized),
+    owner_organization_id: OrganizationIdentifier,
+) -> Stylo {
+    let command = CreateStyloCommand {
+        owner_organization_id,
+        actor_organization_id: owner_organization_id,
+        display_name: format!("Testkit Stylo {}", Uuid::new_v4()),
+        email: "testkit@kaku.test".to_string(),
+    };
+
+    stylo_book
+        .add(command)
+        .await
+        .expect("The stylo should be granted.")
+}
+
+/// A granted stylo can be fetched by its identifier.
+pub async fn check_stylo_add_and_get(stylo_book: &impl StyloBook) {
+    let stylo = grant_stylo(stylo_book, Uuid::new_v4()).await;
+    assert!(!stylo.is_locked);
+
+    let fetched = stylo_book
+        .get(stylo.stylo_id)
+        .await
+        .unwrap()
+        .expect("The stylo should be found.");
+    assert_eq!(fetched, stylo);
+
+    assert!(stylo_book.get(Uuid::new_v4()).await.unwrap().is_none());
+}
+
+/// Syncing a stylo stores its new state but not new organizations, a missing
+/// stylo fails.
+pub async fn check_stylo_sync(stylo_book: &impl StyloBook) {
+    let owner_organization_id = Uuid::new_v4();
+    let mut stylo = grant_stylo(stylo_book, owner_organization_id).await;
+    stylo.is_locked = true;
+    stylo.owner_organization_id = Uuid::new_v4();
+    let synced = stylo_book
+        .sync(stylo.clone(), StyloChangeKind::Locked)
+        .await
+        .unwrap();
+    assert!(synced.is_locked);
+    assert_eq!(synced.owner_organization_id, owner_organization_id);
+
+    let fetched = stylo_book
+        .get(stylo.stylo_id)
+        .await
+        .unwrap()
+        .expect("The stylo should be found.");
+    assert_eq!(fetched, synced);
+
+    let missing = Stylo {
+        stylo_id: Uuid::new_v4(),
+        ..stylo
+    };
+    assert!(stylo_book
+        .sync(missing.clone(), StyloChangeKind::Modified)
+        .await
+        .is_err());
+    assert!(stylo_book.get(missing.stylo_id).await.unwrap().is_none());
+}
+
+/// A revoked stylo is returned once and is gone afterwards.
+pub async fn check_stylo_delete(stylo_book: &impl StyloBook) {
+    let stylo = grant_stylo(stylo_book, Uuid::new_v4()).await;
+
+    let deleted = stylo_book.delete(stylo.stylo_id).await.unwrap();
+    assert_eq!(deleted, Some(stylo.clone()));
+    assert!(stylo_book.get(stylo.stylo_id).await.unwrap().is_none());
+    assert!(stylo_book.delete(stylo.stylo_id).await.unwrap().is_none());
+}
+
+/// Only the stylos owned by the organization are listed, oldest first.
+pub async fn check_stylo_list_by_owner(stylo_book: &impl StyloBook) {
+    let owner_organization_id = Uuid::new_v4();
+    let first = grant_stylo(stylo_book, owner_organization_id).await;
+    let second = grant_stylo(stylo_book, owner_organization_id).await;
+    grant_stylo(stylo_book, Uuid::new_v4()).await;
+
+    let ids = stylo_book
+        .list_by_owner(owner_organization_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|s| s.stylo_id)
+        .collect::<Vec<_>>();
+    assert_eq!(ids, vec![first.stylo_id, second.stylo_id]);
+}
+
+/// The events of the given stylo, the suites may run against a shared
+/// outbox.
+async fn stylo_events(outbox: &impl Outbox, stylo_id: StyloIdentifier) -> Vec<StyloChangeKind> {
+    outbox
+        .pending(usize::MAX)
+        .await
+        .expect("The pending events should be listed.")
+        .into_iter()
+        .filter_map(|event| match event.model {
+            ModelKind::Stylo {
+                stylo_id: id,
+                change_kind,
+            } if id == stylo_id => Some(change_kind),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Every change of a stylo is written in the outbox, in order.
+pub async fn check_stylo_events(stylo_book: &impl StyloBook, outbox: &impl Outbox) {
+    let mut stylo = grant_stylo(stylo_book, Uuid::new_v4()).await;
+    stylo.display_name = "Renamed Testkit Stylo".to_string();
+    stylo_book
+        .sync(stylo.clone(), StyloChangeKind::Modified)
+        .await
+        .unwrap();
+    stylo_book.delete(stylo.stylo_id).await.unwrap();
+
+    assert_eq!(
+        stylo_events(outbox, stylo.stylo_id).await,
+        vec![
+            StyloChangeKind::Granted,
+            StyloChangeKind::Modified,
+            StyloChangeKind::Revoked,
+        ]
+    );
+}
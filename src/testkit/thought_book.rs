@@ -0,0 +1,686 @@
+use chrono::Utc;
+use uuid::Uuid;
+
+use super::create_project;
+use crate::adapter::{ProjectBook, ThoughtBook};
+use crate::models::{
+    Category, CategoryCount, CreateThoughtCommand, DanglingLink, LinkKind, Media, Project,
+    TagCount, Thought, ThoughtChangeKind, ThoughtFilter, ThoughtIdentifier, ThoughtLink,
+    ThoughtStatus, ThoughtVariation,
+};
+
+/// Run all the ThoughtBook checks.
+/// The project book is used to create the projects the thoughts belong to.
+pub async fn check_thought_book(thought_book: &impl ThoughtBook, project_book: &impl ProjectBook) {
+    check_thought_add_and_get(thought_book, project_book).await;
+    check_thought_parent(thought_book, project_book).await;
+    check_thought_sync(thought_book, project_book).await;
+    check_thought_sync_missing(thought_book, project_book).await;
+    check_thought_list_children(thought_book, project_book).await;
+    check_thought_list_by_project(thought_book, project_book).await;
+    check_thought_tags(thought_book, project_book).await;
+    check_thought_categories(thought_book, project_book).await;
+    check_thought_links(thought_book, project_book).await;
+    check_thought_short_id(thought_book, project_book).await;
+    check_thought_media(thought_book, project_book).await;
+    check_thought_add_answer(thought_book, project_book).await;
+}
+
+async fn add_thought(
+    thought_book: &impl ThoughtBook,
+    project: &Project,
+    parent_id: Option<ThoughtIdentifier>,
+) -> Thought {
+    add_tagged_thought(thought_book, project, parent_id, &[]).await
+}
+
+pub(super) async fn add_tagged_thought(
+    thought_book: &impl ThoughtBook,
+    project: &Project,
+    parent_id: Option<ThoughtIdentifier>,
+    tags: &[&str],
+) -> Thought {
+    let command = CreateThoughtCommand {
+        imported_at: Utc::now(),
+        parent_id,
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Testkit thought".to_string(),
+        variation: ThoughtVariation::Thought,
+        answers: None,
+        tags: tags.iter().map(|t| t.to_string()).collect(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+
+    thought_book
+        .add(command, project.project_id, Default::default())
+        .await
+        .expect("The thought should be added.")
+}
+
+/// The command of a question of the project, or of an answer to the given
+/// question.
+pub(super) fn question_command(
+    project: &Project,
+    answers: Option<ThoughtIdentifier>,
+) -> CreateThoughtCommand {
+    CreateThoughtCommand {
+        imported_at: Utc::now(),
+        parent_id: None,
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Testkit question or answer".to_string(),
+        variation: match answers {
+            Some(_) => ThoughtVariation::Thought,
+            None => ThoughtVariation::Question,
+        },
+        answers,
+        tags: Vec::new(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    }
+}
+
+/// An added thought can be fetched by its identifier.
+pub async fn check_thought_add_and_get(
+    thought_book: &impl ThoughtBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let thought = add_thought(thought_book, &project, None).await;
+
+    assert_eq!(thought.project_id, project.project_id);
+    let fetched = thought_book
+        .get(thought.thought_id)
+        .await
+        .unwrap()
+        .expect("The thought should be found.");
+    assert_eq!(fetched.content, "Testkit thought");
+    assert!(fetched.parent_id.is_none());
+
+    assert!(thought_book.get(Uuid::new_v4()).await.unwrap().is_none());
+}
+
+/// A thought can be chained to an existing parent only.
+pub async fn check_thought_parent(
+    thought_book: &impl ThoughtBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let parent = add_thought(thought_book, &project, None).await;
+    let child = add_thought(thought_book, &project, Some(parent.thought_id)).await;
+
+    assert_eq!(child.parent_id, Some(parent.thought_id));
+
+    let command = CreateThoughtCommand {
+        imported_at: Utc::now(),
+        parent_id: Some(Uuid::new_v4()),
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Orphan testkit thought".to_string(),
+        variation: ThoughtVariation::Thought,
+        answers: None,
+        tags: Vec::new(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    assert!(thought_book
+        .add(command, project.project_id, Default::default())
+        .await
+        .is_err());
+}
+
+/// Syncing a thought stores its new state.
+pub async fn check_thought_sync(thought_book: &impl ThoughtBook, project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let mut thought = add_thought(thought_book, &project, None).await;
+    let refuting = add_thought(thought_book, &project, None).await;
+    thought.content = "Updated testkit thought".to_string();
+    thought.refuted_by = Some(refuting.thought_id);
+    thought.status = ThoughtStatus::Disputed;
+    thought.tags = vec!["synced".to_string()];
+    thought.category = Some("testkit.synced".parse().unwrap());
+    thought.references = vec![Uuid::new_v4()];
+    thought_book
+        .sync(
+            thought.clone(),
+            ThoughtChangeKind::Modified,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+    let fetched = thought_book
+        .get(thought.thought_id)
+        .await
+        .unwrap()
+        .expect("The thought should be found.");
+    assert_eq!(fetched.content, "Updated testkit thought");
+    assert_eq!(fetched.refuted_by, Some(refuting.thought_id));
+    assert_eq!(fetched.status, ThoughtStatus::Disputed);
+    assert_eq!(fetched.tags, vec!["synced".to_string()]);
+    assert_eq!(fetched.category, thought.category);
+    assert_eq!(fetched.references, thought.references);
+}
+
+/// Syncing a thought that does not exist fails and does not create it.
+pub async fn check_thought_sync_missing(
+    thought_book: &impl ThoughtBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let thought = Thought {
+        thought_id: Uuid::new_v4(),
+        parent_id: None,
+        imported_at: Utc::now(),
+        stylo_id: Uuid::new_v4(),
+        project_id: project.project_id,
+        content: "Missing testkit thought".to_string(),
+        variation: ThoughtVariation::Thought,
+        answers: None,
+        answered: false,
+        refuted_by: None,
+        status: ThoughtStatus::Standing,
+        tags: Vec::new(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+
+    assert!(thought_book
+        .sync(
+            thought.clone(),
+            ThoughtChangeKind::Modified,
+            Default::default()
+        )
+        .await
+        .is_err());
+    assert!(thought_book
+        .get(thought.thought_id)
+        .await
+        .unwrap()
+        .is_none());
+}
+
+/// Only the direct children of a thought are listed, oldest first.
+pub async fn check_thought_list_children(
+    thought_book: &impl ThoughtBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let parent = add_thought(thought_book, &project, None).await;
+    let first = add_thought(thought_book, &project, Some(parent.thought_id)).await;
+    let second = add_thought(thought_book, &project, Some(parent.thought_id)).await;
+    let _ = add_thought(thought_book, &project, Some(first.thought_id)).await;
+
+    let listed: Vec<Uuid> = thought_book
+        .list_children(parent.thought_id)
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.thought_id)
+        .collect();
+
+    assert_eq!(listed, vec![first.thought_id, second.thought_id]);
+    assert!(thought_book
+        .list_children(Uuid::new_v4())
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// Only the thoughts of the given project matching the filter are listed.
+pub async fn check_thought_list_by_project(
+    thought_book: &impl ThoughtBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let other = create_project(project_book).await;
+    let thought = add_thought(thought_book, &project, None).await;
+    let _ = add_thought(thought_book, &other, None).await;
+    let command = CreateThoughtCommand {
+        imported_at: Utc::now(),
+        parent_id: None,
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Testkit question".to_string(),
+        variation: ThoughtVariation::Question,
+        answers: None,
+        tags: Vec::new(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    let mut question = thought_book
+        .add(command, project.project_id, Default::default())
+        .await
+        .expect("The question should be added.");
+    let command = CreateThoughtCommand {
+        imported_at: Utc::now(),
+        parent_id: None,
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Testkit answer".to_string(),
+        variation: ThoughtVariation::Thought,
+        answers: Some(question.thought_id),
+        tags: Vec::new(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    let answer = thought_book
+        .add(command, project.project_id, Default::default())
+        .await
+        .expect("The answer should be added.");
+    question.answered = true;
+    thought_book
+        .sync(
+            question.clone(),
+            ThoughtChangeKind::Modified,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+
+    let list = |filter: ThoughtFilter| async move {
+        thought_book
+            .list_by_project(project.project_id, &filter)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.thought_id)
+            .collect::<Vec<Uuid>>()
+    };
+
+    assert_eq!(
+        list(ThoughtFilter::default()).await,
+        vec![thought.thought_id, question.thought_id, answer.thought_id]
+    );
+    let questions = ThoughtFilter {
+        variation: Some(ThoughtVariation::Question),
+        ..Default::default()
+    };
+    assert_eq!(list(questions).await, vec![question.thought_id]);
+    let open_questions = ThoughtFilter {
+        variation: Some(ThoughtVariation::Question),
+        answered: Some(false),
+        ..Default::default()
+    };
+    assert!(list(open_questions).await.is_empty());
+    let answers = ThoughtFilter {
+        answers: Some(question.thought_id),
+        ..Default::default()
+    };
+    assert_eq!(list(answers).await, vec![answer.thought_id]);
+}
+
+/// Tags are counted per project, can be filtered on and replaced in one go.
+pub async fn check_thought_tags(thought_book: &impl ThoughtBook, project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let other = create_project(project_book).await;
+    let ethics = add_tagged_thought(thought_book, &project, None, &["ethics", "kant"]).await;
+    let moral = add_tagged_thought(thought_book, &project, None, &["moral"]).await;
+    let _ = add_tagged_thought(thought_book, &project, None, &[]).await;
+    let foreign = add_tagged_thought(thought_book, &other, None, &["ethics"]).await;
+
+    let tags = thought_book.list_tags(project.project_id).await.unwrap();
+    assert_eq!(
+        tags,
+        vec![
+            TagCount {
+                tag: "ethics".to_string(),
+                count: 1
+            },
+            TagCount {
+                tag: "kant".to_string(),
+                count: 1
+            },
+            TagCount {
+                tag: "moral".to_string(),
+                count: 1
+            },
+        ]
+    );
+
+    let filter = ThoughtFilter {
+        tag: Some("kant".to_string()),
+        ..Default::default()
+    };
+    let listed = thought_book
+        .list_by_project(project.project_id, &filter)
+        .await
+        .unwrap();
+    assert_eq!(listed.len(), 1);
+    assert_eq!(listed[0].thought_id, ethics.thought_id);
+
+    let from = vec!["moral".to_string(), "ethics".to_string()];
+    let modified: Vec<Uuid> = thought_book
+        .retag(project.project_id, &from, "ethics", Default::default())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.thought_id)
+        .collect();
+    assert_eq!(modified, vec![moral.thought_id]);
+
+    let tags = thought_book.list_tags(project.project_id).await.unwrap();
+    assert_eq!(tags[0].tag, "ethics");
+    assert_eq!(tags[0].count, 2);
+    assert_eq!(tags.len(), 2);
+
+    let fetched = thought_book
+        .get(moral.thought_id)
+        .await
+        .unwrap()
+        .expect("The thought should be found.");
+    assert_eq!(fetched.tags, vec!["ethics".to_string()]);
+
+    let fetched = thought_book
+        .get(foreign.thought_id)
+        .await
+        .unwrap()
+        .expect("The thought should be found.");
+    assert_eq!(fetched.tags, vec!["ethics".to_string()]);
+}
+
+/// Categories are counted per project and filtered on by subtree.
+pub async fn check_thought_categories(
+    thought_book: &impl ThoughtBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let other = create_project(project_book).await;
+    let mut filed = Vec::new();
+    for (target, category) in [
+        (&project, "philosophy.ethics.kant"),
+        (&project, "philosophy.ethics"),
+        (&project, "philosophy.ethicsbis"),
+        (&project, "history"),
+        (&other, "philosophy.ethics"),
+    ] {
+        let mut thought = add_thought(thought_book, target, None).await;
+        thought.category = Some(category.parse().unwrap());
+        filed.push(
+            thought_book
+                .sync(thought, ThoughtChangeKind::Modified, Default::default())
+                .await
+                .unwrap(),
+        );
+    }
+    let _ = add_thought(thought_book, &project, None).await;
+
+    let category = |path: &str| path.parse::<Category>().unwrap();
+    let categories = thought_book
+        .list_categories(project.project_id)
+        .await
+        .unwrap();
+    assert_eq!(
+        categories,
+        vec![
+            CategoryCount {
+                category: category("history"),
+                count: 1
+            },
+            CategoryCount {
+                category: category("philosophy.ethics"),
+                count: 1
+            },
+            CategoryCount {
+                category: category("philosophy.ethics.kant"),
+                count: 1
+            },
+            CategoryCount {
+                category: category("philosophy.ethicsbis"),
+                count: 1
+            },
+        ]
+    );
+
+    let list = |path: &str| {
+        let filter = ThoughtFilter {
+            category: Some(category(path)),
+            ..Default::default()
+        };
+        async move {
+            thought_book
+                .list_by_project(project.project_id, &filter)
+                .await
+                .unwrap()
+                .into_iter()
+                .map(|t| t.thought_id)
+                .collect::<Vec<Uuid>>()
+        }
+    };
+
+    assert_eq!(
+        list("philosophy.ethics").await,
+        vec![filed[0].thought_id, filed[1].thought_id]
+    );
+    assert_eq!(list("philosophy").await.len(), 3);
+    assert_eq!(
+        list("philosophy.ethics.kant").await,
+        vec![filed[0].thought_id]
+    );
+    assert!(list("philosophy.ethics.kant.critique").await.is_empty());
+}
+
+/// Links are indexed in reverse and become dangling once their target is
+/// trashed with its project.
+pub async fn check_thought_links(thought_book: &impl ThoughtBook, project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let other = create_project(project_book).await;
+    let target = add_thought(thought_book, &project, None).await;
+    let link = ThoughtLink {
+        target_id: target.thought_id,
+        kind: LinkKind::Supports,
+    };
+    let mut sources = Vec::new();
+    for owner in [&other, &project] {
+        let mut source = add_thought(thought_book, owner, None).await;
+        source.links = vec![link];
+        sources.push(
+            thought_book
+                .sync(source, ThoughtChangeKind::Modified, Default::default())
+                .await
+                .unwrap(),
+        );
+    }
+    let fetched = thought_book
+        .get(sources[0].thought_id)
+        .await
+        .unwrap()
+        .expect("The thought should be found.");
+    assert_eq!(fetched.links, vec![link]);
+
+    let backlinks = |target_id: ThoughtIdentifier| async move {
+        thought_book
+            .list_backlinks(target_id)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|t| t.thought_id)
+            .collect::<Vec<Uuid>>()
+    };
+    assert_eq!(
+        backlinks(target.thought_id).await,
+        vec![sources[0].thought_id, sources[1].thought_id]
+    );
+
+    let mut unlinked = sources[1].clone();
+    unlinked.links.clear();
+    thought_book
+        .sync(unlinked, ThoughtChangeKind::Modified, Default::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        backlinks(target.thought_id).await,
+        vec![sources[0].thought_id]
+    );
+    assert!(thought_book
+        .list_dangling_links(other.project_id)
+        .await
+        .unwrap()
+        .is_empty());
+
+    let trashed: Vec<Uuid> = thought_book
+        .trash_by_project(project.project_id, Default::default())
+        .await
+        .unwrap()
+        .into_iter()
+        .map(|t| t.thought_id)
+        .collect();
+    assert_eq!(trashed, vec![target.thought_id, sources[1].thought_id]);
+    assert!(thought_book.get(target.thought_id).await.unwrap().is_none());
+    assert_eq!(
+        thought_book
+            .list_dangling_links(other.project_id)
+            .await
+            .unwrap(),
+        vec![DanglingLink {
+            thought_id: sources[0].thought_id,
+            link,
+        }]
+    );
+}
+
+/// Thoughts can be found in a project by the first digits of their identifier.
+pub async fn check_thought_short_id(
+    thought_book: &impl ThoughtBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let other = create_project(project_book).await;
+    let thought = add_thought(thought_book, &project, None).await;
+    let short_id = &thought.thought_id.simple().to_string()[..8];
+
+    let found = thought_book
+        .list_by_short_id(project.project_id, short_id)
+        .await
+        .unwrap();
+    assert_eq!(found.len(), 1);
+    assert_eq!(found[0].thought_id, thought.thought_id);
+
+    let full_id = thought.thought_id.simple().to_string();
+    assert_eq!(
+        thought_book
+            .list_by_short_id(project.project_id, &full_id)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+    assert!(thought_book
+        .list_by_short_id(other.project_id, short_id)
+        .await
+        .unwrap()
+        .is_empty());
+}
+
+/// The medias of a thought are stored and tell if a blob is still referenced.
+pub async fn check_thought_media(thought_book: &impl ThoughtBook, project_book: &impl ProjectBook) {
+    let project = create_project(project_book).await;
+    let content = format!("Testkit media {}", Uuid::new_v4());
+    let media = Media::describe(
+        content.as_bytes(),
+        "text/plain",
+        Some("media.txt".to_string()),
+    );
+    let mut thought = add_thought(thought_book, &project, None).await;
+
+    assert!(!thought_book
+        .is_media_referenced(&media.digest)
+        .await
+        .unwrap());
+    thought.media = vec![media.clone()];
+    thought_book
+        .sync(
+            thought.clone(),
+            ThoughtChangeKind::Modified,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    let fetched = thought_book
+        .get(thought.thought_id)
+        .await
+        .unwrap()
+        .expect("The thought should be found.");
+    assert_eq!(fetched.media, vec![media.clone()]);
+    assert!(thought_book
+        .is_media_referenced(&media.digest)
+        .await
+        .unwrap());
+
+    thought.media = Vec::new();
+    thought_book
+        .sync(thought, ThoughtChangeKind::Modified, Default::default())
+        .await
+        .unwrap();
+    assert!(!thought_book
+        .is_media_referenced(&media.digest)
+        .await
+        .unwrap());
+}
+
+/// An answer is stored with its question marked as answered, an answer to a
+/// missing question or a thought answering nothing is refused.
+pub async fn check_thought_add_answer(
+    thought_book: &impl ThoughtBook,
+    project_book: &impl ProjectBook,
+) {
+    let project = create_project(project_book).await;
+    let question = thought_book
+        .add(
+            question_command(&project, None),
+            project.project_id,
+            Default::default(),
+        )
+        .await
+        .unwrap();
+    assert!(!question.answered);
+
+    for _ in 0..2 {
+        let answer = thought_book
+            .add_answer(
+                question_command(&project, Some(question.thought_id)),
+                project.project_id,
+                Default::default(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(answer.answers, Some(question.thought_id));
+        assert!(thought_book.get(answer.thought_id).await.unwrap().is_some());
+        let question = thought_book
+            .get(question.thought_id)
+            .await
+            .unwrap()
+            .expect("The question should be found.");
+        assert!(question.answered);
+    }
+
+    for answers in [Some(Uuid::new_v4()), None] {
+        assert!(thought_book
+            .add_answer(
+                question_command(&project, answers),
+                project.project_id,
+                Default::default(),
+            )
+            .await
+            .is_err());
+    }
+    let thoughts = thought_book
+        .list_by_project(project.project_id, &ThoughtFilter::default())
+        .await
+        .unwrap();
+    assert_eq!(thoughts.len(), 3);
+}
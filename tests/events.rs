@@ -0,0 +1,342 @@
+// Tests for the event stream, history and audit endpoints
+use std::net::SocketAddr;
+use std::time::Duration;
+
+use kaku::adapter::{JsonlAuditLog, JsonlEventStore};
+use kaku::models::{AuditEntry, CreateNoteCommand, CreateProjectCommand, ProjectStats};
+use kaku::service::{
+    AuditLogService, EventHistoryService, EventStreamService, ProjectStatsService,
+};
+use kaku::testkit::{create_organization, grant_stylo};
+use kaku::{actor::ApiApp, Container};
+use tokio::net::TcpStream;
+use uuid::Uuid;
+
+/// Serve the API on a local port with the event dispatcher feeding the stream,
+/// the event history, the project stats and the audit log.
+async fn serve(container: &mut Container) -> SocketAddr {
+    let service = container.thought_service().unwrap();
+    let search = container.thought_search().unwrap();
+    let saved_search = container.saved_search_service().unwrap();
+    let event_stream = container.event_stream().unwrap();
+    let project_stats = container.project_stats().unwrap();
+    let audit_log = container.audit_log_service().unwrap();
+    let organizations = container.organization_service().unwrap();
+    let stylos = container.stylo_service().unwrap();
+    let mut dispatcher = container.event_dispatcher().unwrap();
+    let event_history = container.event_history().unwrap();
+    let receiver = EventStreamService::subscribe(&mut dispatcher);
+    let history_receiver = EventHistoryService::subscribe(&mut dispatcher);
+    let stats_receiver = ProjectStatsService::subscribe(&mut dispatcher);
+    let audit_receiver = AuditLogService::subscribe(&mut dispatcher);
+    tokio::spawn(dispatcher.execute());
+    let listener = event_stream.clone();
+    tokio::spawn(async move { listener.listen(receiver).await });
+    tokio::spawn(async move { event_history.listen(history_receiver).await });
+    let listener = project_stats.clone();
+    tokio::spawn(async move { listener.listen(stats_receiver).await });
+    let listener = audit_log.clone();
+    tokio::spawn(async move { listener.listen(audit_receiver).await });
+
+    let app = ApiApp::new(
+        service,
+        search,
+        saved_search,
+        event_stream,
+        project_stats,
+        audit_log,
+        organizations,
+        stylos,
+    )
+    .router();
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    addr
+}
+
+/// Send a GET request, the response is left to be read from the stream.
+async fn get(addr: SocketAddr, path: &str, last_event_id: Option<String>) -> TcpStream {
+    let stream = TcpStream::connect(addr).await.unwrap();
+    let mut request = format!("GET {path} HTTP/1.1\r\nHost: {addr}\r\n");
+    if let Some(id) = last_event_id {
+        request.push_str(&format!("Last-Event-ID: {id}\r\n"));
+    }
+    request.push_str("\r\n");
+
+    let mut written = 0;
+    while written < request.len() {
+        stream.writable().await.unwrap();
+        match stream.try_write(&request.as_bytes()[written..]) {
+            Ok(n) => written += n,
+            Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+            Err(e) => panic!("Could not send the request: {e}"),
+        }
+    }
+
+    stream
+}
+
+/// Read the response until it contains the needle, returns what was read.
+async fn read_until(stream: &TcpStream, needle: &str) -> String {
+    let mut received = Vec::new();
+    let read = async {
+        loop {
+            stream.readable().await.unwrap();
+            let mut buffer = [0; 4096];
+            match stream.try_read(&mut buffer) {
+                Ok(0) => panic!("The connection was closed."),
+                Ok(n) => received.extend_from_slice(&buffer[..n]),
+                Err(e) if e.kind() == std::io::ErrorKind::WouldBlock => continue,
+                Err(e) => panic!("Could not read the response: {e}"),
+            }
+            if String::from_utf8_lossy(&received).contains(needle) {
+                break;
+            }
+        }
+    };
+    if tokio::time::timeout(Duration::from_secs(5), read)
+        .await
+        .is_err()
+    {
+        panic!(
+            "'{needle}' never came, received:\n{}",
+            String::from_utf8_lossy(&received)
+        );
+    }
+
+    String::from_utf8_lossy(&received).into_owned()
+}
+
+/// Create a note in a project.
+async fn create_note(container: &mut Container, project_slug: &str, stylo_id: Uuid) {
+    container
+        .thought_service()
+        .unwrap()
+        .create_note(CreateNoteCommand {
+            imported_at: chrono::Utc::now(),
+            stylo_id,
+            project_slug: project_slug.to_string(),
+            content: "A streamed note".to_string(),
+            references: Vec::new(),
+            media: Vec::new(),
+        })
+        .await
+        .unwrap();
+}
+
+/// Get the stats of a project until it counts the expected number of notes.
+async fn project_stats(addr: SocketAddr, project_slug: &str, notes: u64) -> ProjectStats {
+    for _ in 0..50 {
+        let response = get(addr, &format!("/project/{project_slug}/stats"), None).await;
+        let received = read_until(&response, "}").await;
+        assert!(received.starts_with("HTTP/1.1 200"));
+        let (_, body) = received.split_once("\r\n\r\n").unwrap();
+        let stats = serde_json::from_str::<ProjectStats>(body).unwrap();
+        if stats.notes == notes {
+            return stats;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    panic!("The project never counted {notes} notes.");
+}
+
+/// Get audit entries until there are as many as expected.
+async fn audit_entries(addr: SocketAddr, path: &str, count: usize) -> Vec<AuditEntry> {
+    for _ in 0..50 {
+        let response = get(addr, path, None).await;
+        let received = read_until(&response, "]").await;
+        assert!(received.starts_with("HTTP/1.1 200"));
+        let (_, body) = received.split_once("\r\n\r\n").unwrap();
+        let entries = serde_json::from_str::<Vec<AuditEntry>>(body).unwrap();
+        if entries.len() == count {
+            return entries;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    panic!("'{path}' never listed {count} entries.");
+}
+
+/// Create an organization and return the identifier of its universe.
+async fn create_universe(container: &mut Container) -> Uuid {
+    let organization_book = container.organization_book().unwrap();
+
+    create_organization(organization_book.as_ref())
+        .await
+        .universe
+        .universe_id
+}
+
+/// Grant a stylo and return its identifier.
+/// Its event is dispatched with the next change.
+async fn create_stylo(container: &mut Container) -> Uuid {
+    let stylo_book = container.stylo_book().unwrap();
+
+    grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+        .await
+        .stylo_id
+}
+
+#[tokio::test]
+async fn test_event_stream() {
+    let mut container = Container::default();
+    let addr = serve(&mut container).await;
+    let service = container.thought_service().unwrap();
+    let epoch = container.event_stream().unwrap().epoch().simple();
+    let id = |sequence: u64| format!("id: {epoch}-{sequence}\n");
+    let universe_id = create_universe(&mut container).await;
+    let other_universe_id = create_universe(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+    for (project_name, universe_id) in
+        [("Reading list", universe_id), ("Garden", other_universe_id)]
+    {
+        service
+            .create_project(CreateProjectCommand {
+                project_name: project_name.to_string(),
+                universe_id,
+            })
+            .await
+            .unwrap();
+    }
+
+    let stream = get(addr, &format!("/events?universe={universe_id}"), None).await;
+    let received = read_until(&stream, &id(2)).await;
+    assert!(received.starts_with("HTTP/1.1 200"));
+    // The stylo event of id 1 belongs to no universe.
+    assert!(!received.contains(&id(1)));
+    assert!(received.contains("text/event-stream"));
+    assert!(received.contains("event: model\n"));
+    assert!(received.contains(r#""kind":"project""#));
+    assert!(received.contains(r#""signature":{"key_id":"instance""#));
+
+    for project_slug in ["garden", "reading-list"] {
+        create_note(&mut container, project_slug, stylo_id).await;
+    }
+    let received = read_until(&stream, &id(5)).await;
+    assert!(!received.contains(&id(3)));
+    assert!(!received.contains(&id(4)));
+    assert!(received.contains(r#""change_kind":"created""#));
+
+    let last_event_id = format!("{epoch}-2");
+    let resumed = get(addr, "/events?project=reading-list", Some(last_event_id)).await;
+    let received = read_until(&resumed, &id(5)).await;
+    assert!(!received.contains(&id(2)));
+    assert!(received.contains(r#""kind":"note""#));
+
+    // An identifier streamed before a restart replays all the kept events.
+    let last_event_id = format!("{}-4", Uuid::new_v4().simple());
+    let restarted = get(addr, "/events?project=reading-list", Some(last_event_id)).await;
+    let received = read_until(&restarted, &id(5)).await;
+    assert!(received.contains(&id(2)));
+
+    let unknown = get(addr, "/events?project=reading-lst", None).await;
+    let received = read_until(&unknown, "reading-list").await;
+    assert!(received.starts_with("HTTP/1.1 404"));
+}
+
+#[tokio::test]
+async fn test_event_history() {
+    let root = std::env::temp_dir().join(format!("kaku-events-{}", Uuid::new_v4()));
+    let path = root.join("events.jsonl");
+    let mut container = Container::default();
+    let event_store = JsonlEventStore::open(&path).await.unwrap();
+    container
+        .set_event_store(std::sync::Arc::new(event_store))
+        .unwrap();
+    let addr = serve(&mut container).await;
+    let universe_id = create_universe(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+    container
+        .thought_service()
+        .unwrap()
+        .create_project(CreateProjectCommand {
+            project_name: "Reading list".to_string(),
+            universe_id,
+        })
+        .await
+        .unwrap();
+    for _ in 0..2 {
+        create_note(&mut container, "reading-list", stylo_id).await;
+    }
+
+    let stats = project_stats(addr, "reading-list", 2).await;
+    let event_history = container.event_history().unwrap();
+    for _ in 0..50 {
+        if event_history.events(1, 10).await.unwrap().len() == 4 {
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    // The history survives the process, reopen it as after a restart.
+    let event_store = JsonlEventStore::open(&path).await.unwrap();
+    let event_history = EventHistoryService::new(std::sync::Arc::new(event_store));
+    let rebuilt = ProjectStatsService::default();
+    assert_eq!(event_history.rebuild(&[&rebuilt]).await.unwrap(), 4);
+    assert_eq!(rebuilt.get(stats.project_id).await, stats);
+
+    let response = get(addr, "/project/reading-lst/stats", None).await;
+    let received = read_until(&response, "reading-list").await;
+    assert!(received.starts_with("HTTP/1.1 404"));
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+}
+
+#[tokio::test]
+async fn test_audit_log() {
+    let root = std::env::temp_dir().join(format!("kaku-audit-{}", Uuid::new_v4()));
+    let path = root.join("audit.jsonl");
+    let mut container = Container::default();
+    let audit_log = JsonlAuditLog::open(&path).await.unwrap();
+    container
+        .set_audit_log(std::sync::Arc::new(audit_log))
+        .unwrap();
+    let addr = serve(&mut container).await;
+    let service = container.thought_service().unwrap();
+    let universe_id = create_universe(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+    let project = service
+        .create_project(CreateProjectCommand {
+            project_name: "Reading list".to_string(),
+            universe_id,
+        })
+        .await
+        .unwrap();
+    let note = service
+        .create_note(CreateNoteCommand {
+            imported_at: chrono::Utc::now(),
+            stylo_id,
+            project_slug: project.slug.clone(),
+            content: "An audited note".to_string(),
+            references: Vec::new(),
+            media: Vec::new(),
+        })
+        .await
+        .unwrap();
+    service.scratch_note(note.note_id).await.unwrap();
+
+    // The trail of a scratched note is still there.
+    let entries = audit_entries(addr, &format!("/notes/{}/audit", note.note_id), 2).await;
+    assert_eq!(entries[1].previous_hash, entries[0].hash);
+    assert!(entries
+        .iter()
+        .all(|e| e.event.chain.stylo_id == Some(note.stylo_id)));
+    let entries = audit_entries(addr, "/project/reading-list/audit", 3).await;
+    // The stylo was granted first.
+    assert_eq!(entries[0].sequence, 2);
+    assert_eq!(entries[2].hash, entries[2].compute_hash());
+    audit_entries(addr, &format!("/thought/{}/audit", Uuid::new_v4()), 0).await;
+    let entries = audit_entries(addr, &format!("/stylo/{stylo_id}/audit"), 1).await;
+    assert_eq!(entries[0].sequence, 1);
+
+    let verification = JsonlAuditLog::verify(&path).await.unwrap();
+    assert_eq!(verification.entries, 4);
+    assert_eq!(verification.broken_link, None);
+
+    let response = get(addr, "/project/reading-lst/audit", None).await;
+    let received = read_until(&response, "reading-list").await;
+    assert!(received.starts_with("HTTP/1.1 404"));
+    tokio::fs::remove_dir_all(&root).await.unwrap();
+}
@@ -0,0 +1,143 @@
+// Tests for the media endpoints
+use axum_test::multipart::{MultipartForm, Part};
+use axum_test::TestServer;
+use kaku::testkit::grant_stylo;
+use kaku::{actor::ApiApp, models::Media, Container};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn initialize_test_server(container: &mut Container) -> TestServer {
+    let service = container.thought_service().unwrap();
+    let search = container.thought_search().unwrap();
+    let saved_search = container.saved_search_service().unwrap();
+    let event_stream = container.event_stream().unwrap();
+    let project_stats = container.project_stats().unwrap();
+    let audit_log = container.audit_log_service().unwrap();
+    let organizations = container.organization_service().unwrap();
+    let stylos = container.stylo_service().unwrap();
+    let app = ApiApp::new(
+        service,
+        search,
+        saved_search,
+        event_stream,
+        project_stats,
+        audit_log,
+        organizations,
+        stylos,
+    )
+    .router();
+    TestServer::new(app).unwrap()
+}
+
+/// Grant a stylo to write the notes and thoughts with.
+async fn create_stylo(container: &mut Container) -> Uuid {
+    let stylo_book = container.stylo_book().unwrap();
+
+    grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+        .await
+        .stylo_id
+}
+
+async fn upload(client: &TestServer, content: &'static [u8]) -> Media {
+    let form = MultipartForm::new().add_part(
+        "file",
+        Part::bytes(content)
+            .file_name("drawing.png")
+            .mime_type("image/png"),
+    );
+    let response = client.post("/media").multipart(form).await;
+    assert_eq!(response.status_code(), 201);
+
+    response.json::<Vec<Media>>().remove(0)
+}
+
+#[tokio::test]
+async fn test_upload_and_download_media() {
+    let mut container = Container::default();
+    let client = initialize_test_server(&mut container).await;
+
+    let media = upload(&client, b"not really a png").await;
+    assert_eq!(media.mime_type, "image/png");
+    assert_eq!(media.size, 16);
+    assert_eq!(media.filename.as_deref(), Some("drawing.png"));
+
+    let response = client.get(&format!("/media/{}", media.digest)).await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.as_bytes().as_ref(), b"not really a png");
+    assert_eq!(
+        response.header("Content-Type").to_str().unwrap(),
+        "image/png"
+    );
+    assert_eq!(
+        response.header("ETag").to_str().unwrap(),
+        format!("\"{}\"", media.digest)
+    );
+
+    let response = client.get(&format!("/media/{}", "0".repeat(64))).await;
+    assert_eq!(response.status_code(), 404);
+    let response = client.get("/media/not-a-digest").await;
+    assert_eq!(response.status_code(), 404);
+
+    // An empty form is not even a valid multipart body.
+    let response = client.post("/media").multipart(MultipartForm::new()).await;
+    assert_eq!(response.status_code(), 400);
+}
+
+#[tokio::test]
+async fn test_note_media_collected_when_scratched() {
+    let mut container = Container::default();
+    let project_book = container.project_book().unwrap();
+    project_book
+        .create(kaku::models::CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: "Sketches".to_string(),
+        })
+        .await
+        .unwrap();
+    let _receiver = container.event_publisher_receiver().unwrap();
+    let client = initialize_test_server(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+
+    let shared = upload(&client, b"shared drawing").await;
+    let single = upload(&client, b"single drawing").await;
+    let mut note_locations = Vec::new();
+    for media in [vec![shared.clone(), single.clone()], vec![shared.clone()]] {
+        let response = client
+            .post("/project/sketches/note")
+            .json(&json!({
+                "imported_at": "2026-01-01T12:00:00Z",
+                "stylo_id": stylo_id,
+                "content": "A drawing.",
+                "media": media,
+            }))
+            .await;
+        assert_eq!(response.status_code(), 201);
+        let location = response.header("Location").to_str().unwrap().to_string();
+        note_locations.push(location.replace("/note/", "/notes/"));
+    }
+
+    let response = client
+        .post("/project/sketches/note")
+        .json(&json!({
+            "imported_at": "2026-01-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "A forged drawing.",
+            "media": [Media { size: 1, ..shared.clone() }],
+        }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+
+    let response = client.delete(&note_locations[0]).await;
+    assert_eq!(response.status_code(), 204);
+    let response = client.get(&format!("/media/{}", single.digest)).await;
+    assert_eq!(response.status_code(), 404);
+    let response = client.get(&format!("/media/{}", shared.digest)).await;
+    assert_eq!(response.status_code(), 200);
+
+    let response = client.delete(&note_locations[1]).await;
+    assert_eq!(response.status_code(), 204);
+    let response = client.get(&format!("/media/{}", shared.digest)).await;
+    assert_eq!(response.status_code(), 404);
+
+    container.destroy();
+}
@@ -1,15 +1,59 @@
 // Tests for the notes endpoint
 use axum_test::TestServer;
+use kaku::testkit::grant_stylo;
 use kaku::{actor::ApiApp, Container};
 use serde_json::json;
 use uuid::Uuid;
 
 async fn initialize_test_server(container: &mut Container) -> TestServer {
     let service = container.thought_service().unwrap();
-    let app = ApiApp::new(service).router();
+    let search = container.thought_search().unwrap();
+    let saved_search = container.saved_search_service().unwrap();
+    let event_stream = container.event_stream().unwrap();
+    let project_stats = container.project_stats().unwrap();
+    let audit_log = container.audit_log_service().unwrap();
+    let organizations = container.organization_service().unwrap();
+    let stylos = container.stylo_service().unwrap();
+    let app = ApiApp::new(
+        service,
+        search,
+        saved_search,
+        event_stream,
+        project_stats,
+        audit_log,
+        organizations,
+        stylos,
+    )
+    .router();
     TestServer::new(app).unwrap()
 }
 
+/// Grant a stylo to write the notes and thoughts with.
+async fn create_stylo(container: &mut Container) -> Uuid {
+    let stylo_book = container.stylo_book().unwrap();
+
+    grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+        .await
+        .stylo_id
+}
+
+/// Create an organization and return the identifier of its universe.
+async fn create_universe(client: &TestServer) -> Uuid {
+    let response = client
+        .post("/organization")
+        .json(&json!({
+            "name": format!("Organization {}", Uuid::new_v4()),
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let body = response.json::<serde_json::Value>();
+
+    body["universe"]["universe_id"]
+        .as_str()
+        .and_then(|id| id.parse().ok())
+        .expect("The universe should be created with the organization.")
+}
+
 #[tokio::test]
 async fn test_create_note_success() {
     let mut container = Container::default();
@@ -20,12 +64,13 @@ async fn test_create_note_success() {
     };
     project_book.create(project_command).await.unwrap();
     let client = initialize_test_server(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
 
     let response = client
         .post("/project/whatever/note")
         .json(&json!({
             "imported_at": "2023-10-01T12:00:00Z",
-            "stylo_id": "123e4567-e89b-12d3-a456-426614174000",
+            "stylo_id": stylo_id,
             "content": "This is a test note"
         }))
         .await;
@@ -40,11 +85,12 @@ async fn test_create_note_success() {
 async fn test_create_project_success() {
     let mut container = Container::default();
     let client = initialize_test_server(&mut container).await;
+    let universe_id = create_universe(&client).await;
 
     let response = client
         .post("/project/create")
         .json(&json!({
-            "universe_id": "123e4567-e89b-12d3-a456-426614174000",
+            "universe_id": universe_id,
             "project_name": "New Project"
         }))
         .await;
@@ -86,9 +132,11 @@ async fn test_scratch_note_success() {
         stylo_id: Uuid::new_v4(),
         project_slug: project.slug.clone(),
         content: "This is a test note".to_string(),
+        references: Vec::new(),
+        media: Vec::new(),
     };
     let note = note_book
-        .add(note_command, project.project_id)
+        .add(note_command, project.project_id, Default::default())
         .await
         .unwrap();
 
@@ -103,6 +151,83 @@ async fn test_scratch_note_success() {
     assert!(note_book.get(note.note_id).await.unwrap().is_none());
 }
 
+#[tokio::test]
+async fn test_get_note_success() {
+    let mut container = Container::default();
+    let project_book = container.project_book().unwrap();
+    let note_book = container.note_book().unwrap();
+
+    let project_command = kaku::models::CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: "Test Project".to_string(),
+    };
+    let project = project_book.create(project_command).await.unwrap();
+    let note_command = kaku::models::CreateNoteCommand {
+        imported_at: chrono::Utc::now(),
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "This is a test note".to_string(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    let note = note_book
+        .add(note_command, project.project_id, Default::default())
+        .await
+        .unwrap();
+
+    let client = initialize_test_server(&mut container).await;
+
+    let response = client.get(&format!("/notes/{}", note.note_id)).await;
+
+    assert_eq!(response.status_code(), 200);
+    let fetched: kaku::models::Note = response.json();
+    assert_eq!(fetched.note_id, note.note_id);
+    assert_eq!(fetched.content, "This is a test note");
+
+    let response = client.get(&format!("/notes/{}", Uuid::new_v4())).await;
+
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_list_notes_success() {
+    let mut container = Container::default();
+    let project_book = container.project_book().unwrap();
+    let note_book = container.note_book().unwrap();
+
+    let project_command = kaku::models::CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: "Test Project".to_string(),
+    };
+    let project = project_book.create(project_command).await.unwrap();
+    for content in ["First note", "Second note"] {
+        let note_command = kaku::models::CreateNoteCommand {
+            imported_at: chrono::Utc::now(),
+            stylo_id: Uuid::new_v4(),
+            project_slug: project.slug.clone(),
+            content: content.to_string(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+        note_book
+            .add(note_command, project.project_id, Default::default())
+            .await
+            .unwrap();
+    }
+
+    let client = initialize_test_server(&mut container).await;
+
+    let response = client.get("/project/test-project/notes").await;
+
+    assert_eq!(response.status_code(), 200);
+    let notes: Vec<kaku::models::Note> = response.json();
+    assert_eq!(notes.len(), 2);
+
+    let response = client.get("/project/unknown-project/notes").await;
+
+    assert_eq!(response.status_code(), 404);
+}
+
 #[tokio::test]
 async fn test_create_thought_success() {
     let mut container = Container::default();
@@ -117,7 +242,7 @@ async fn test_create_thought_success() {
 
     let client = initialize_test_server(&mut container).await;
 
-    let stylo_id = Uuid::new_v4();
+    let stylo_id = create_stylo(&mut container).await;
     let response = client
         .post("/project/test-project/thought")
         .json(&json!({
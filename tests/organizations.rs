@@ -0,0 +1,120 @@
+// Tests for the organization endpoints
+use axum_test::TestServer;
+use kaku::models::{CreatedOrganization, Organization, Universe};
+use kaku::{actor::ApiApp, Container};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn initialize_test_server(container: &mut Container) -> TestServer {
+    let service = container.thought_service().unwrap();
+    let search = container.thought_search().unwrap();
+    let saved_search = container.saved_search_service().unwrap();
+    let event_stream = container.event_stream().unwrap();
+    let project_stats = container.project_stats().unwrap();
+    let audit_log = container.audit_log_service().unwrap();
+    let organizations = container.organization_service().unwrap();
+    let stylos = container.stylo_service().unwrap();
+    let app = ApiApp::new(
+        service,
+        search,
+        saved_search,
+        event_stream,
+        project_stats,
+        audit_log,
+        organizations,
+        stylos,
+    )
+    .router();
+    TestServer::new(app).unwrap()
+}
+
+#[tokio::test]
+async fn test_create_organization() {
+    let mut container = Container::default();
+    let client = initialize_test_server(&mut container).await;
+
+    let response = client
+        .post("/organization")
+        .json(&json!({
+            "name": "Acme",
+            "certificate": { "issuer": "Registry" },
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let created = response.json::<CreatedOrganization>();
+    let location = format!("/organization/{}", created.organization.organization_id);
+    assert_eq!(response.header("Location").to_str().unwrap(), location);
+    assert_eq!(
+        created.universe.organization_id,
+        created.organization.organization_id
+    );
+
+    let response = client.get(&location).await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.json::<Organization>(), created.organization);
+    let key = container
+        .key_store()
+        .unwrap()
+        .verifying_key(&created.organization.organization_id.to_string())
+        .await
+        .unwrap();
+    assert_eq!(key, Some(created.organization.verifying_key().unwrap()));
+    let response = client.get(&format!("{location}/universe")).await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.json::<Universe>(), created.universe);
+
+    let response = client
+        .post("/project/create")
+        .json(&json!({
+            "universe_id": created.universe.universe_id,
+            "project_name": "Reading list",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+}
+
+#[tokio::test]
+async fn test_invalid_organization() {
+    let mut container = Container::default();
+    let client = initialize_test_server(&mut container).await;
+
+    let response = client
+        .post("/organization")
+        .json(&json!({ "name": " " }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+
+    let response = client
+        .get(&format!("/organization/{}", Uuid::new_v4()))
+        .await;
+    assert_eq!(response.status_code(), 404);
+    let response = client
+        .get(&format!("/organization/{}/universe", Uuid::new_v4()))
+        .await;
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_create_project_unknown_universe() {
+    let mut container = Container::default();
+    let client = initialize_test_server(&mut container).await;
+    let universe_id = Uuid::new_v4();
+
+    let response = client
+        .post("/project/create")
+        .json(&json!({
+            "universe_id": universe_id,
+            "project_name": "Reading list",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(body["universe"], json!(universe_id));
+
+    let project_book = container.project_book().unwrap();
+    assert!(project_book
+        .get_by_slug("reading-list")
+        .await
+        .unwrap()
+        .is_none());
+}
@@ -0,0 +1,290 @@
+// Tests for the PostgreSQL books
+// They run against the database pointed by KAKU_TEST_DATABASE_URL, so they are
+// ignored by default: run them with `just test-postgres` or with
+// `cargo test --features postgres --test postgres -- --ignored`.
+// They fail when the variable is not set.
+#![cfg(feature = "postgres")]
+
+use kaku::adapter::postgres::{
+    connect, PgNoteBook, PgOrganizationBook, PgOutbox, PgProjectBook, PgReferenceBook,
+    PgSavedSearchBook, PgStyloBook, PgThoughtBook, PgUniverseBook,
+};
+use kaku::adapter::{NoteBook, ProjectBook, ProjectBookError, ThoughtBook};
+use kaku::models::{
+    CreateNoteCommand, CreateProjectCommand, CreateThoughtCommand, Project, SearchFilter,
+    ThoughtChangeKind, ThoughtVariation,
+};
+use kaku::testkit::{create_organization, grant_stylo};
+use kaku::Container;
+use sqlx::PgPool;
+use uuid::Uuid;
+
+async fn test_pool() -> PgPool {
+    let database_url = std::env::var("KAKU_TEST_DATABASE_URL")
+        .expect("KAKU_TEST_DATABASE_URL should point to the test database.");
+
+    connect(&database_url).await.unwrap()
+}
+
+async fn create_project(pool: &PgPool) -> Project {
+    let command = CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: format!("Test Project {}", Uuid::new_v4()),
+    };
+
+    PgProjectBook::new(pool.clone())
+        .create(command)
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+#[ignore = "needs the PostgreSQL database of KAKU_TEST_DATABASE_URL"]
+async fn test_project_book() {
+    let pool = test_pool().await;
+    let book = PgProjectBook::new(pool.clone());
+    let project = create_project(&pool).await;
+
+    let fetched = book
+        .get_by_slug(&project.slug)
+        .await
+        .unwrap()
+        .expect("There should be a project.");
+    assert_eq!(fetched.project_id, project.project_id);
+
+    let command = CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: project.project_name.clone(),
+    };
+    let error = book.create(command).await.unwrap_err();
+    assert!(matches!(
+        error.downcast_ref::<ProjectBookError>(),
+        Some(ProjectBookError::DuplicateSlug(_))
+    ));
+
+    let projects = book.list_by_universe(&project.universe_id).await.unwrap();
+    assert_eq!(projects.len(), 1);
+
+    book.delete(&project.project_id).await.unwrap();
+    assert!(book.get(&project.project_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[ignore = "needs the PostgreSQL database of KAKU_TEST_DATABASE_URL"]
+async fn test_note_book() {
+    let pool = test_pool().await;
+    let book = PgNoteBook::new(pool.clone());
+    let project = create_project(&pool).await;
+    let command = CreateNoteCommand {
+        imported_at: chrono::Utc::now(),
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "This is a test note.".to_string(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    let note = book
+        .add(command, project.project_id, Default::default())
+        .await
+        .unwrap();
+
+    let notes = book.list_by_project(project.project_id).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].note_id, note.note_id);
+
+    let deleted = book
+        .delete(note.note_id, Default::default())
+        .await
+        .unwrap()
+        .expect("There must be a note.");
+    assert_eq!(deleted.content, "This is a test note.");
+    assert!(book.get(note.note_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+#[ignore = "needs the PostgreSQL database of KAKU_TEST_DATABASE_URL"]
+async fn test_thought_book() {
+    let pool = test_pool().await;
+    let book = PgThoughtBook::new(pool.clone());
+    let project = create_project(&pool).await;
+    let mut command = CreateThoughtCommand {
+        imported_at: chrono::Utc::now(),
+        parent_id: None,
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Parent thought".to_string(),
+        variation: ThoughtVariation::Thought,
+        answers: None,
+        tags: Vec::new(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    let parent = book
+        .add(command, project.project_id, Default::default())
+        .await
+        .unwrap();
+
+    command = CreateThoughtCommand {
+        imported_at: chrono::Utc::now(),
+        parent_id: Some(parent.thought_id),
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Child thought".to_string(),
+        variation: ThoughtVariation::Thought,
+        answers: None,
+        tags: Vec::new(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    let mut child = book
+        .add(command, project.project_id, Default::default())
+        .await
+        .unwrap();
+
+    let children = book.list_children(parent.thought_id).await.unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].thought_id, child.thought_id);
+
+    child.content = "Updated child thought".to_string();
+    book.sync(
+        child.clone(),
+        ThoughtChangeKind::Modified,
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    let fetched = book
+        .get(child.thought_id)
+        .await
+        .unwrap()
+        .expect("There must be a thought.");
+    assert_eq!(fetched.content, "Updated child thought");
+}
+
+#[tokio::test]
+#[ignore = "needs the PostgreSQL database of KAKU_TEST_DATABASE_URL"]
+async fn test_container_uses_postgres() {
+    let pool = test_pool().await;
+    let mut container = Container::default();
+    container.set_pg_pool(pool.clone()).unwrap();
+    let thought_service = container.thought_service().unwrap();
+    let organization_book = container.organization_book().unwrap();
+    let _receiver = container.event_publisher_receiver().unwrap();
+    container.destroy();
+
+    let universe = create_organization(organization_book.as_ref())
+        .await
+        .universe;
+    let project = thought_service
+        .create_project(CreateProjectCommand {
+            universe_id: universe.universe_id,
+            project_name: format!("Container Project {}", Uuid::new_v4()),
+        })
+        .await
+        .unwrap();
+
+    let stored = PgProjectBook::new(pool)
+        .get(&project.project_id)
+        .await
+        .unwrap();
+    assert!(stored.is_some());
+}
+
+#[tokio::test]
+#[ignore = "needs the PostgreSQL database of KAKU_TEST_DATABASE_URL"]
+async fn test_fuzzy_search_uses_trigram_index() {
+    let pool = test_pool().await;
+    let mut container = Container::default();
+    container.set_pg_pool(pool.clone()).unwrap();
+    let thought_service = container.thought_service().unwrap();
+    let search = container.thought_search().unwrap();
+    let stylo_book = container.stylo_book().unwrap();
+    let organization_book = container.organization_book().unwrap();
+    let mut receiver = container.event_publisher_receiver().unwrap();
+    container.destroy();
+
+    let universe = create_organization(organization_book.as_ref())
+        .await
+        .universe;
+    let stylo = grant_stylo(stylo_book.as_ref(), universe.organization_id).await;
+    let project = thought_service
+        .create_project(CreateProjectCommand {
+            universe_id: universe.universe_id,
+            project_name: format!("Fuzzy Project {}", Uuid::new_v4()),
+        })
+        .await
+        .unwrap();
+    let thought = thought_service
+        .create_thought(CreateThoughtCommand {
+            imported_at: chrono::Utc::now(),
+            parent_id: None,
+            stylo_id: stylo.stylo_id,
+            project_slug: project.slug.clone(),
+            content: "Phenomenology studies the structures of experience.".to_string(),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        })
+        .await
+        .unwrap()
+        .thought;
+    while let Ok(message) = receiver.try_recv() {
+        search.apply(&message.event).await.unwrap();
+    }
+
+    let words = PgThoughtBook::new(pool)
+        .similar_words("phenomenolgy", 0.5)
+        .await
+        .unwrap()
+        .expect("The trigram index should be used.");
+    assert!(words.iter().any(|(word, _)| word == "phenomenology"));
+
+    let filter = SearchFilter {
+        project: Some(project.slug),
+        ..Default::default()
+    };
+    let hits = search
+        .fuzzy_search("phenomenolgy", &filter, None, 10)
+        .await
+        .unwrap();
+    assert_eq!(hits.len(), 1);
+    assert_eq!(hits[0].id, thought.thought_id);
+}
+
+#[tokio::test]
+#[ignore = "needs the PostgreSQL database of KAKU_TEST_DATABASE_URL"]
+async fn test_conformance() {
+    let pool = test_pool().await;
+    let project_book = PgProjectBook::new(pool.clone());
+
+    kaku::testkit::check_project_book(&project_book).await;
+    kaku::testkit::check_note_book(&PgNoteBook::new(pool.clone()), &project_book).await;
+    kaku::testkit::check_thought_book(&PgThoughtBook::new(pool.clone()), &project_book).await;
+    kaku::testkit::check_reference_book(&PgReferenceBook::new(pool.clone()), &project_book).await;
+    kaku::testkit::check_outbox(
+        &PgOutbox::new(pool.clone()),
+        &project_book,
+        &PgNoteBook::new(pool.clone()),
+        &PgThoughtBook::new(pool.clone()),
+    )
+    .await;
+    kaku::testkit::check_stylo_book(
+        &PgStyloBook::new(pool.clone()),
+        &PgOutbox::new(pool.clone()),
+    )
+    .await;
+    kaku::testkit::check_organization_book(
+        &PgOrganizationBook::new(pool.clone()),
+        &PgUniverseBook::new(pool.clone()),
+    )
+    .await;
+    kaku::testkit::check_saved_search_book(&PgSavedSearchBook::new(pool)).await;
+}
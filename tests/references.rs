@@ -0,0 +1,211 @@
+// Tests for the references endpoints
+use axum_test::TestServer;
+use kaku::testkit::grant_stylo;
+use kaku::{
+    actor::ApiApp,
+    models::{CreatedThought, Reference},
+    Container,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn initialize_test_server(container: &mut Container) -> TestServer {
+    let service = container.thought_service().unwrap();
+    let search = container.thought_search().unwrap();
+    let saved_search = container.saved_search_service().unwrap();
+    let event_stream = container.event_stream().unwrap();
+    let project_stats = container.project_stats().unwrap();
+    let audit_log = container.audit_log_service().unwrap();
+    let organizations = container.organization_service().unwrap();
+    let stylos = container.stylo_service().unwrap();
+    let app = ApiApp::new(
+        service,
+        search,
+        saved_search,
+        event_stream,
+        project_stats,
+        audit_log,
+        organizations,
+        stylos,
+    )
+    .router();
+    TestServer::new(app).unwrap()
+}
+
+/// Grant a stylo to write the notes and thoughts with.
+async fn create_stylo(container: &mut Container) -> Uuid {
+    let stylo_book = container.stylo_book().unwrap();
+
+    grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+        .await
+        .stylo_id
+}
+
+async fn create_project(container: &mut Container, project_name: &str) {
+    let project_book = container.project_book().unwrap();
+    let project_command = kaku::models::CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: project_name.to_string(),
+    };
+    project_book.create(project_command).await.unwrap();
+}
+
+const BIBTEX: &str = r#"
+@book{kant1785,
+  author = {Immanuel Kant},
+  title = {Groundwork of the Metaphysics of Morals},
+  year = {1785},
+  publisher = {Hartknoch},
+}
+@book{mill1861,
+  author = {John Stuart Mill},
+  title = {Utilitarianism},
+  year = {1861},
+  pages = {12--15},
+}
+"#;
+
+#[tokio::test]
+async fn test_create_and_get_reference() {
+    let mut container = Container::default();
+    create_project(&mut container, "Reading List").await;
+    let client = initialize_test_server(&mut container).await;
+
+    let response = client
+        .post("/project/reading-list/references")
+        .json(&json!({
+            "citation_key": "kant1785",
+            "authors": ["Immanuel Kant"],
+            "title": "Groundwork of the Metaphysics of Morals",
+            "year": 1785,
+        }))
+        .await;
+
+    assert_eq!(response.status_code(), 201);
+    let reference: Reference = response.json();
+    assert_eq!(reference.entry_type, "book");
+    let location = response.header("Location");
+    assert_eq!(
+        location.to_str().unwrap(),
+        format!("/reference/{}", reference.reference_id)
+    );
+
+    let response = client.get(location.to_str().unwrap()).await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.json::<Reference>(), reference);
+
+    let response = client
+        .post("/project/reading-list/references")
+        .json(&json!({ "citation_key": "kant1785", "title": "Again" }))
+        .await;
+    assert_eq!(response.status_code(), 409);
+
+    let response = client
+        .post("/project/reading-list/references")
+        .json(&json!({ "citation_key": "two words", "title": "Invalid" }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+
+    let response = client.get(&format!("/reference/{}", Uuid::new_v4())).await;
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_bibtex_import_and_export() {
+    let mut container = Container::default();
+    create_project(&mut container, "Reading List").await;
+    let client = initialize_test_server(&mut container).await;
+
+    let response = client
+        .post("/project/reading-list/references.bib")
+        .text(BIBTEX)
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let imported: Vec<Reference> = response.json();
+    assert_eq!(imported.len(), 2);
+    assert_eq!(imported[1].pages.as_deref(), Some("12--15"));
+
+    let response = client.get("/project/reading-list/references.bib").await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.header("Content-Type"), "application/x-bibtex");
+    let exported = response.text();
+
+    // Importing the export again changes nothing.
+    let response = client
+        .post("/project/reading-list/references.bib")
+        .text(exported)
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let reimported: Vec<Reference> = response.json();
+    assert_eq!(reimported, imported);
+
+    let response = client.get("/project/reading-list/references").await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.json::<Vec<Reference>>(), imported);
+
+    let response = client
+        .post("/project/reading-list/references.bib")
+        .text("@book{broken, title = {Unclosed")
+        .await;
+    assert_eq!(response.status_code(), 422);
+
+    let response = client.get("/project/unknown/references.bib").await;
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_cite_references() {
+    let mut container = Container::default();
+    create_project(&mut container, "Reading List").await;
+    create_project(&mut container, "Other").await;
+    let _receiver = container.event_publisher_receiver().unwrap();
+    let client = initialize_test_server(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+
+    let kant: Vec<Reference> = client
+        .post("/project/reading-list/references.bib")
+        .text(BIBTEX)
+        .await
+        .json();
+    let foreign: Vec<Reference> = client
+        .post("/project/other/references.bib")
+        .text(BIBTEX)
+        .await
+        .json();
+
+    let response = client
+        .post("/project/reading-list/thought")
+        .json(&json!({
+            "imported_at": "2026-01-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Act only by maxims.",
+            "references": [kant[0].reference_id],
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let created: CreatedThought = response.json();
+    assert_eq!(created.thought.references, vec![kant[0].reference_id]);
+
+    let response = client
+        .post("/project/reading-list/note")
+        .json(&json!({
+            "imported_at": "2026-01-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Read the Groundwork.",
+            "references": [foreign[0].reference_id],
+        }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+    assert_eq!(
+        response.json::<serde_json::Value>()["reference"],
+        json!(foreign[0].reference_id)
+    );
+
+    let response = client
+        .patch(&format!("/thought/{}", created.thought.thought_id))
+        .json(&json!({ "references": [kant[1].reference_id] }))
+        .await;
+    assert_eq!(response.status_code(), 200);
+
+    container.destroy();
+}
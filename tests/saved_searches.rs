@@ -0,0 +1,204 @@
+// Tests for the saved search endpoints
+use std::time::Duration;
+
+use axum_test::TestServer;
+use kaku::models::{SavedSearch, SavedSearchResult};
+use kaku::service::SavedSearchService;
+use kaku::testkit::grant_stylo;
+use kaku::{actor::ApiApp, Container};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Start the API with the event dispatcher feeding the saved searches.
+async fn initialize_test_server(container: &mut Container) -> TestServer {
+    let service = container.thought_service().unwrap();
+    let search = container.thought_search().unwrap();
+    let saved_search = container.saved_search_service().unwrap();
+    let event_stream = container.event_stream().unwrap();
+    let project_stats = container.project_stats().unwrap();
+    let audit_log = container.audit_log_service().unwrap();
+    let organizations = container.organization_service().unwrap();
+    let stylos = container.stylo_service().unwrap();
+    let mut dispatcher = container.event_dispatcher().unwrap();
+    let receiver = SavedSearchService::subscribe(&mut dispatcher);
+    tokio::spawn(dispatcher.execute());
+    let listener = saved_search.clone();
+    tokio::spawn(async move { listener.listen(receiver).await });
+
+    let app = ApiApp::new(
+        service,
+        search,
+        saved_search,
+        event_stream,
+        project_stats,
+        audit_log,
+        organizations,
+        stylos,
+    )
+    .router();
+    TestServer::new(app).unwrap()
+}
+
+/// Grant a stylo to write the notes and thoughts with.
+async fn create_stylo(container: &mut Container) -> Uuid {
+    let stylo_book = container.stylo_book().unwrap();
+
+    grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+        .await
+        .stylo_id
+}
+
+/// List the new results of a stylo until the expected number has arrived.
+async fn new_results(
+    client: &TestServer,
+    stylo_id: Uuid,
+    expected: usize,
+) -> Vec<SavedSearchResult> {
+    for _ in 0..50 {
+        let response = client.get(&format!("/stylo/{stylo_id}/results")).await;
+        assert_eq!(response.status_code(), 200);
+        let results = response.json::<Vec<SavedSearchResult>>();
+        if results.len() == expected {
+            return results;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    panic!("The stylo never got {expected} new results.");
+}
+
+/// Create an organization and return the identifier of its universe.
+async fn create_universe(client: &TestServer) -> Uuid {
+    let response = client
+        .post("/organization")
+        .json(&json!({
+            "name": format!("Organization {}", Uuid::new_v4()),
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let body = response.json::<serde_json::Value>();
+
+    body["universe"]["universe_id"]
+        .as_str()
+        .and_then(|id| id.parse().ok())
+        .expect("The universe should be created with the organization.")
+}
+
+#[tokio::test]
+async fn test_saved_search_crud() {
+    let mut container = Container::default();
+    let client = initialize_test_server(&mut container).await;
+    let stylo_id = Uuid::new_v4();
+
+    let response = client
+        .post(&format!("/stylo/{stylo_id}/search"))
+        .json(&json!({ "name": "Ethics", "query": "tag:ethics" }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let saved_search = response.json::<SavedSearch>();
+    let location = format!("/stylo/{stylo_id}/search/{}", saved_search.saved_search_id);
+    assert_eq!(response.header("location"), location.as_str());
+    assert_eq!(saved_search.stylo_id, stylo_id);
+
+    let response = client
+        .post(&format!("/stylo/{stylo_id}/search"))
+        .json(&json!({ "name": "Ethics", "query": "virtue" }))
+        .await;
+    assert_eq!(response.status_code(), 409);
+    let response = client
+        .post(&format!("/stylo/{stylo_id}/search"))
+        .json(&json!({ "name": "Broken", "query": "(virtue" }))
+        .await;
+    assert_eq!(response.status_code(), 400);
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(body["kind"], "unclosed_parenthesis");
+    assert_eq!(body["start"], 0);
+    assert_eq!(body["end"], 1);
+
+    let response = client
+        .patch(&location)
+        .json(&json!({ "query": "tag:ethics virtue" }))
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let response = client.get(&location).await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.json::<SavedSearch>().query, "tag:ethics virtue");
+    let response = client
+        .get(&format!(
+            "/stylo/{}/search/{}",
+            Uuid::new_v4(),
+            saved_search.saved_search_id
+        ))
+        .await;
+    assert_eq!(response.status_code(), 404);
+
+    let response = client.get(&format!("/stylo/{stylo_id}/searches")).await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.json::<Vec<SavedSearch>>().len(), 1);
+
+    let response = client.delete(&location).await;
+    assert_eq!(response.status_code(), 200);
+    let response = client.get(&location).await;
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_saved_search_new_results() {
+    let mut container = Container::default();
+    let client = initialize_test_server(&mut container).await;
+    let author_id = create_stylo(&mut container).await;
+    let universe_id = create_universe(&client).await;
+    let stylo_id = Uuid::new_v4();
+    let response = client
+        .post("/project/create")
+        .json(&json!({
+            "universe_id": universe_id,
+            "project_name": "Reading list",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let response = client
+        .post(&format!("/stylo/{stylo_id}/search"))
+        .json(&json!({
+            "name": "Ethics",
+            "query": "project:reading-list tag:ethics -refuted",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let saved_search = response.json::<SavedSearch>();
+
+    for (content, tags) in [
+        ("Courage is a virtue.", json!(["ethics"])),
+        ("Roses need pruning.", json!([])),
+    ] {
+        let response = client
+            .post("/project/reading-list/thought")
+            .json(&json!({
+                "imported_at": "2026-01-02T12:00:00Z",
+                "stylo_id": author_id,
+                "content": content,
+                "tags": tags,
+            }))
+            .await;
+        assert_eq!(response.status_code(), 201);
+    }
+
+    let results = new_results(&client, stylo_id, 1).await;
+    assert_eq!(results[0].saved_search_id, saved_search.saved_search_id);
+    new_results(&client, Uuid::new_v4(), 0).await;
+
+    let response = client
+        .get(&format!(
+            "/stylo/{stylo_id}/search/{}/run",
+            saved_search.saved_search_id
+        ))
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let hits = response.json::<serde_json::Value>();
+    assert_eq!(hits[0]["content"], "Courage is a virtue.");
+
+    let response = client.delete(&format!("/stylo/{stylo_id}/results")).await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.json::<Vec<SavedSearchResult>>(), results);
+    new_results(&client, stylo_id, 0).await;
+}
@@ -0,0 +1,290 @@
+// Tests for the search endpoint
+use std::time::Duration;
+
+use axum_test::TestServer;
+use kaku::models::SearchHit;
+use kaku::service::ThoughtSearchService;
+use kaku::testkit::grant_stylo;
+use kaku::{actor::ApiApp, Container};
+use serde_json::json;
+use uuid::Uuid;
+
+/// Start the API with the event dispatcher feeding the search index.
+async fn initialize_test_server(container: &mut Container) -> TestServer {
+    let service = container.thought_service().unwrap();
+    let search = container.thought_search().unwrap();
+    let saved_search = container.saved_search_service().unwrap();
+    let event_stream = container.event_stream().unwrap();
+    let project_stats = container.project_stats().unwrap();
+    let audit_log = container.audit_log_service().unwrap();
+    let organizations = container.organization_service().unwrap();
+    let stylos = container.stylo_service().unwrap();
+    let mut dispatcher = container.event_dispatcher().unwrap();
+    let receiver = ThoughtSearchService::subscribe(&mut dispatcher);
+    tokio::spawn(dispatcher.execute());
+    let listener = search.clone();
+    tokio::spawn(async move { listener.listen(receiver).await });
+
+    let app = ApiApp::new(
+        service,
+        search,
+        saved_search,
+        event_stream,
+        project_stats,
+        audit_log,
+        organizations,
+        stylos,
+    )
+    .router();
+    TestServer::new(app).unwrap()
+}
+
+/// Grant a stylo to write the notes and thoughts with.
+async fn create_stylo(container: &mut Container) -> Uuid {
+    let stylo_book = container.stylo_book().unwrap();
+
+    grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+        .await
+        .stylo_id
+}
+
+/// Search until the index has caught up with the expected number of hits.
+async fn search(client: &TestServer, query: &str, expected: usize) -> Vec<SearchHit> {
+    for _ in 0..50 {
+        let response = client.get(&format!("/search?{query}")).await;
+        assert_eq!(response.status_code(), 200);
+        let hits = response.json::<Vec<SearchHit>>();
+        if hits.len() == expected {
+            return hits;
+        }
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    panic!("The search '{query}' never returned {expected} hits.");
+}
+
+/// Create an organization and return the identifier of its universe.
+async fn create_universe(client: &TestServer) -> Uuid {
+    let response = client
+        .post("/organization")
+        .json(&json!({
+            "name": format!("Organization {}", Uuid::new_v4()),
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let body = response.json::<serde_json::Value>();
+
+    body["universe"]["universe_id"]
+        .as_str()
+        .and_then(|id| id.parse().ok())
+        .expect("The universe should be created with the organization.")
+}
+
+#[tokio::test]
+async fn test_search() {
+    let mut container = Container::default();
+    let client = initialize_test_server(&mut container).await;
+    let universe_id = create_universe(&client).await;
+    let stylo_id = create_stylo(&mut container).await;
+    let other_stylo_id = create_stylo(&mut container).await;
+
+    for project_name in ["Reading list", "Garden"] {
+        let response = client
+            .post("/project/create")
+            .json(&json!({
+                "universe_id": universe_id,
+                "project_name": project_name,
+            }))
+            .await;
+        assert_eq!(response.status_code(), 201);
+    }
+    let response = client
+        .post("/project/reading-list/thought")
+        .json(&json!({
+            "imported_at": "2026-01-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Courage is the first of the virtues.",
+            "tags": ["ethics"],
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let response = client
+        .post("/project/reading-list/note")
+        .json(&json!({
+            "imported_at": "2026-01-02T12:00:00Z",
+            "stylo_id": other_stylo_id,
+            "content": "Courage, says Aristotle, lies between fear and confidence.",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let response = client
+        .post("/project/garden/thought")
+        .json(&json!({
+            "imported_at": "2026-01-03T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Pruning roses takes courage.",
+            "variation": "question",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+
+    let hits = search(&client, "q=courage", 3).await;
+    assert!(hits[0].score >= hits[1].score && hits[1].score >= hits[2].score);
+    assert!(hits
+        .iter()
+        .any(|hit| hit.highlight == "Pruning roses takes <mark>courage</mark>."));
+
+    let hits = search(&client, "q=courage&project=reading-list", 2).await;
+    assert!(hits.iter().all(|hit| hit.project_id == hits[0].project_id));
+    search(&client, "q=courage&tag=ethics", 1).await;
+    search(&client, "q=courage&variation=question", 1).await;
+    search(&client, &format!("q=courage&author={stylo_id}"), 2).await;
+    search(&client, "q=courage%20aristotle", 1).await;
+    search(&client, "q=courage&limit=1", 1).await;
+
+    let response = client.get("/search?q=courage&project=unknown").await;
+    assert_eq!(response.status_code(), 404);
+    let response = client.get("/search?q=%3F").await;
+    assert_eq!(response.status_code(), 422);
+
+    search(&client, "q=curage", 0).await;
+    search(&client, "q=curage&mode=fuzzy", 3).await;
+    search(&client, "q=curage&mode=fuzzy&similarity=0.9", 0).await;
+    let response = client.get("/search?q=curage&mode=fuzzy&similarity=2").await;
+    assert_eq!(response.status_code(), 422);
+}
+
+#[tokio::test]
+async fn test_unknown_project_suggestions() {
+    let mut container = Container::default();
+    let client = initialize_test_server(&mut container).await;
+    let universe_id = create_universe(&client).await;
+    for project_name in ["Reading list", "Reading lists", "Garden"] {
+        let response = client
+            .post("/project/create")
+            .json(&json!({
+                "universe_id": universe_id,
+                "project_name": project_name,
+            }))
+            .await;
+        assert_eq!(response.status_code(), 201);
+    }
+
+    let response = client.get("/project/reading-lst/thoughts").await;
+    assert_eq!(response.status_code(), 404);
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(
+        body["suggestions"],
+        json!(["reading-list", "reading-lists"])
+    );
+
+    let response = client
+        .post("/project/gardn/note")
+        .json(&json!({
+            "imported_at": "2026-01-02T12:00:00Z",
+            "stylo_id": Uuid::new_v4(),
+            "content": "Lost note",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 404);
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(body["suggestions"], json!(["garden"]));
+
+    let response = client.get("/search?q=note&project=zzz").await;
+    assert_eq!(response.status_code(), 404);
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(body["suggestions"], json!([]));
+}
+
+#[tokio::test]
+async fn test_query() {
+    let mut container = Container::default();
+    let client = initialize_test_server(&mut container).await;
+    let universe_id = create_universe(&client).await;
+    let stylo_id = create_stylo(&mut container).await;
+    let response = client
+        .post("/project/create")
+        .json(&json!({
+            "universe_id": universe_id,
+            "project_name": "Reading list",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    for (imported_at, content, tags) in [
+        (
+            "2025-12-01T12:00:00Z",
+            "An exact phrase from last year.",
+            ["ethics"],
+        ),
+        (
+            "2026-01-02T12:00:00Z",
+            "An exact phrase about virtue.",
+            ["ethics"],
+        ),
+        (
+            "2026-01-03T12:00:00Z",
+            "A phrase that is not exact.",
+            ["ethics"],
+        ),
+    ] {
+        let response = client
+            .post("/project/reading-list/thought")
+            .json(&json!({
+                "imported_at": imported_at,
+                "stylo_id": stylo_id,
+                "content": content,
+                "tags": tags,
+            }))
+            .await;
+        assert_eq!(response.status_code(), 201);
+    }
+
+    let response = client
+        .get("/query")
+        .add_query_param(
+            "q",
+            format!(
+                "tag:ethics AND project:reading-list -refuted author:{stylo_id} \
+                 created:>2026-01-01 \"exact phrase\""
+            ),
+        )
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let hits = response.json::<serde_json::Value>();
+    assert_eq!(hits.as_array().unwrap().len(), 1);
+    assert_eq!(hits[0]["kind"], "thought");
+    assert_eq!(hits[0]["content"], "An exact phrase about virtue.");
+
+    let response = client
+        .get("/query")
+        .add_query_param("q", "phrase")
+        .add_query_param("limit", 2)
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let hits = response.json::<serde_json::Value>();
+    assert_eq!(hits[0]["imported_at"], "2026-01-03T12:00:00Z");
+    assert_eq!(hits.as_array().unwrap().len(), 2);
+
+    let response = client
+        .get("/query")
+        .add_query_param("q", "tag:ethics (virtue OR")
+        .await;
+    assert_eq!(response.status_code(), 400);
+    assert_eq!(
+        response.json::<serde_json::Value>(),
+        json!({
+            "error": "An expression is expected after this operator.",
+            "kind": "unexpected_end",
+            "start": 19,
+            "end": 21,
+        })
+    );
+
+    let response = client
+        .get("/query")
+        .add_query_param("q", "project:reading-lst")
+        .await;
+    assert_eq!(response.status_code(), 404);
+    let body = response.json::<serde_json::Value>();
+    assert_eq!(body["suggestions"], json!(["reading-list"]));
+}
@@ -0,0 +1,278 @@
+// Tests for the SQLite books
+// Each test works in its own database file in the temporary directory.
+#![cfg(feature = "sqlite")]
+
+use std::path::PathBuf;
+
+use kaku::adapter::sqlite::{
+    connect, SqliteNoteBook, SqliteOrganizationBook, SqliteOutbox, SqliteProjectBook,
+    SqliteReferenceBook, SqliteSavedSearchBook, SqliteStyloBook, SqliteThoughtBook,
+    SqliteUniverseBook,
+};
+use kaku::adapter::{NoteBook, ProjectBook, ProjectBookError, ThoughtBook};
+use kaku::models::{
+    CreateNoteCommand, CreateProjectCommand, CreateStyloCommand, CreateThoughtCommand, Project,
+    ThoughtChangeKind, ThoughtVariation,
+};
+use kaku::testkit::create_organization;
+use kaku::Container;
+use sqlx::SqlitePool;
+use uuid::Uuid;
+
+fn database_path() -> PathBuf {
+    std::env::temp_dir().join(format!("kaku-test-{}.db", Uuid::new_v4()))
+}
+
+async fn create_project(pool: &SqlitePool) -> Project {
+    let command = CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: "Test Project".to_string(),
+    };
+
+    SqliteProjectBook::new(pool.clone())
+        .create(command)
+        .await
+        .unwrap()
+}
+
+async fn create_project_error(book: &SqliteProjectBook) -> anyhow::Error {
+    let command = CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: "Test Project".to_string(),
+    };
+
+    book.create(command).await.unwrap_err()
+}
+
+#[tokio::test]
+async fn test_project_book() {
+    let pool = connect(&database_path()).await.unwrap();
+    let book = SqliteProjectBook::new(pool.clone());
+    let project = create_project(&pool).await;
+
+    let fetched = book
+        .get_by_slug(&project.slug)
+        .await
+        .unwrap()
+        .expect("There should be a project.");
+    assert_eq!(fetched.project_id, project.project_id);
+
+    let error = create_project_error(&book).await;
+    assert!(matches!(
+        error.downcast_ref::<ProjectBookError>(),
+        Some(ProjectBookError::DuplicateSlug(_))
+    ));
+
+    let projects = book.list_by_universe(&project.universe_id).await.unwrap();
+    assert_eq!(projects.len(), 1);
+
+    book.delete(&project.project_id).await.unwrap();
+    assert!(book.get(&project.project_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_note_book() {
+    let pool = connect(&database_path()).await.unwrap();
+    let book = SqliteNoteBook::new(pool.clone());
+    let project = create_project(&pool).await;
+    let command = CreateNoteCommand {
+        imported_at: chrono::Utc::now(),
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "This is a test note.".to_string(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    let note = book
+        .add(command, project.project_id, Default::default())
+        .await
+        .unwrap();
+
+    let notes = book.list_by_project(project.project_id).await.unwrap();
+    assert_eq!(notes.len(), 1);
+    assert_eq!(notes[0].note_id, note.note_id);
+
+    let deleted = book
+        .delete(note.note_id, Default::default())
+        .await
+        .unwrap()
+        .expect("There must be a note.");
+    assert_eq!(deleted.content, "This is a test note.");
+    assert!(book.get(note.note_id).await.unwrap().is_none());
+}
+
+#[tokio::test]
+async fn test_thought_book() {
+    let pool = connect(&database_path()).await.unwrap();
+    let book = SqliteThoughtBook::new(pool.clone());
+    let project = create_project(&pool).await;
+    let command = CreateThoughtCommand {
+        imported_at: chrono::Utc::now(),
+        parent_id: None,
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Parent thought".to_string(),
+        variation: ThoughtVariation::Thought,
+        answers: None,
+        tags: Vec::new(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    let parent = book
+        .add(command, project.project_id, Default::default())
+        .await
+        .unwrap();
+
+    let command = CreateThoughtCommand {
+        imported_at: chrono::Utc::now(),
+        parent_id: Some(parent.thought_id),
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Child thought".to_string(),
+        variation: ThoughtVariation::Thought,
+        answers: None,
+        tags: Vec::new(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    let mut child = book
+        .add(command, project.project_id, Default::default())
+        .await
+        .unwrap();
+
+    let children = book.list_children(parent.thought_id).await.unwrap();
+    assert_eq!(children.len(), 1);
+    assert_eq!(children[0].thought_id, child.thought_id);
+
+    child.content = "Updated child thought".to_string();
+    book.sync(
+        child.clone(),
+        ThoughtChangeKind::Modified,
+        Default::default(),
+    )
+    .await
+    .unwrap();
+    let fetched = book
+        .get(child.thought_id)
+        .await
+        .unwrap()
+        .expect("There must be a thought.");
+    assert_eq!(fetched.content, "Updated child thought");
+}
+
+#[tokio::test]
+async fn test_data_survive_restart() {
+    let path = database_path();
+    let mut container = Container::default();
+    container
+        .set_sqlite_pool(connect(&path).await.unwrap())
+        .unwrap();
+    let thought_service = container.thought_service().unwrap();
+    let organization_book = container.organization_book().unwrap();
+    let _receiver = container.event_publisher_receiver().unwrap();
+    container.destroy();
+
+    let universe = create_organization(organization_book.as_ref())
+        .await
+        .universe;
+    let project = thought_service
+        .create_project(CreateProjectCommand {
+            universe_id: universe.universe_id,
+            project_name: "Persistent Project".to_string(),
+        })
+        .await
+        .unwrap();
+    drop(thought_service);
+
+    let pool = connect(&path).await.unwrap();
+    let stored = SqliteProjectBook::new(pool.clone())
+        .get_by_slug(&project.slug)
+        .await
+        .unwrap()
+        .expect("The project should have been persisted.");
+    assert_eq!(stored.project_id, project.project_id);
+
+    let mut container = Container::default();
+    container.set_sqlite_pool(pool).unwrap();
+    let thought_service = container.thought_service().unwrap();
+    let _receiver = container.event_publisher_receiver().unwrap();
+    container.destroy();
+    let project = thought_service
+        .create_project(CreateProjectCommand {
+            universe_id: universe.universe_id,
+            project_name: "Project After Restart".to_string(),
+        })
+        .await
+        .expect("The universe should have been persisted.");
+    assert_eq!(project.universe_id, universe.universe_id);
+}
+
+#[tokio::test]
+async fn test_stylos_survive_restart() {
+    let path = database_path();
+    let mut container = Container::default();
+    container
+        .set_sqlite_pool(connect(&path).await.unwrap())
+        .unwrap();
+    let organization_book = container.organization_book().unwrap();
+    container.destroy();
+    let organization_id = create_organization(organization_book.as_ref())
+        .await
+        .organization
+        .organization_id;
+    drop(organization_book);
+
+    let mut container = Container::default();
+    container
+        .set_sqlite_pool(connect(&path).await.unwrap())
+        .unwrap();
+    let stylo_service = container.stylo_service().unwrap();
+    let _receiver = container.event_publisher_receiver().unwrap();
+    container.destroy();
+    let stylo = stylo_service
+        .grant(CreateStyloCommand {
+            owner_organization_id: organization_id,
+            actor_organization_id: organization_id,
+            display_name: "Ada".to_string(),
+            email: "ada@acme.test".to_string(),
+        })
+        .await
+        .expect("The organization should have been persisted.");
+
+    let stylos = stylo_service.list_by_owner(organization_id).await.unwrap();
+    assert_eq!(stylos, vec![stylo]);
+}
+
+#[tokio::test]
+async fn test_conformance() {
+    let pool = connect(&database_path()).await.unwrap();
+    let project_book = SqliteProjectBook::new(pool.clone());
+
+    kaku::testkit::check_project_book(&project_book).await;
+    kaku::testkit::check_note_book(&SqliteNoteBook::new(pool.clone()), &project_book).await;
+    kaku::testkit::check_thought_book(&SqliteThoughtBook::new(pool.clone()), &project_book).await;
+    kaku::testkit::check_reference_book(&SqliteReferenceBook::new(pool.clone()), &project_book)
+        .await;
+    kaku::testkit::check_outbox(
+        &SqliteOutbox::new(pool.clone()),
+        &project_book,
+        &SqliteNoteBook::new(pool.clone()),
+        &SqliteThoughtBook::new(pool.clone()),
+    )
+    .await;
+    kaku::testkit::check_stylo_book(
+        &SqliteStyloBook::new(pool.clone()),
+        &SqliteOutbox::new(pool.clone()),
+    )
+    .await;
+    kaku::testkit::check_organization_book(
+        &SqliteOrganizationBook::new(pool.clone()),
+        &SqliteUniverseBook::new(pool.clone()),
+    )
+    .await;
+    kaku::testkit::check_saved_search_book(&SqliteSavedSearchBook::new(pool)).await;
+}
@@ -0,0 +1,224 @@
+// Tests for the stylo endpoints
+use axum_test::TestServer;
+use kaku::models::{CreatedOrganization, Stylo};
+use kaku::{actor::ApiApp, Container};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn initialize_test_server(container: &mut Container) -> TestServer {
+    let service = container.thought_service().unwrap();
+    let search = container.thought_search().unwrap();
+    let saved_search = container.saved_search_service().unwrap();
+    let event_stream = container.event_stream().unwrap();
+    let project_stats = container.project_stats().unwrap();
+    let audit_log = container.audit_log_service().unwrap();
+    let organizations = container.organization_service().unwrap();
+    let stylos = container.stylo_service().unwrap();
+    let app = ApiApp::new(
+        service,
+        search,
+        saved_search,
+        event_stream,
+        project_stats,
+        audit_log,
+        organizations,
+        stylos,
+    )
+    .router();
+    TestServer::new(app).unwrap()
+}
+
+/// Create an organization.
+async fn create_organization(client: &TestServer) -> CreatedOrganization {
+    let response = client
+        .post("/organization")
+        .json(&json!({
+            "name": format!("Organization {}", Uuid::new_v4()),
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+
+    response.json::<CreatedOrganization>()
+}
+
+/// Grant a stylo the organization gives to itself.
+async fn grant_stylo(client: &TestServer, organization_id: Uuid) -> Stylo {
+    let response = client
+        .post("/stylo")
+        .json(&json!({
+            "owner_organization_id": organization_id,
+            "actor_organization_id": organization_id,
+            "display_name": "Ada",
+            "email": "ada@acme.test",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let stylo = response.json::<Stylo>();
+    assert_eq!(
+        response.header("Location").to_str().unwrap(),
+        format!("/stylo/{}", stylo.stylo_id)
+    );
+
+    stylo
+}
+
+#[tokio::test]
+async fn test_stylo_lifecycle() {
+    let mut container = Container::default();
+    let _receiver = container.event_publisher_receiver().unwrap();
+    let client = initialize_test_server(&mut container).await;
+    let organization_id = create_organization(&client)
+        .await
+        .organization
+        .organization_id;
+    let stylo = grant_stylo(&client, organization_id).await;
+    let location = format!("/stylo/{}", stylo.stylo_id);
+
+    let response = client.get(&location).await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.json::<Stylo>(), stylo);
+
+    let response = client
+        .patch(&location)
+        .json(&json!({ "display_name": "Ada Lovelace" }))
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let modified = response.json::<Stylo>();
+    assert_eq!(modified.display_name, "Ada Lovelace");
+    assert_eq!(modified.email, "ada@acme.test");
+
+    let response = client.post(&format!("{location}/lock")).await;
+    assert_eq!(response.status_code(), 200);
+    assert!(response.json::<Stylo>().is_locked);
+    let response = client.post(&format!("{location}/lock")).await;
+    assert_eq!(response.status_code(), 409);
+    let response = client.post(&format!("{location}/unlock")).await;
+    assert_eq!(response.status_code(), 200);
+    assert!(!response.json::<Stylo>().is_locked);
+
+    let response = client
+        .get(&format!("/organization/{organization_id}/stylos"))
+        .await;
+    assert_eq!(response.status_code(), 200);
+    assert_eq!(response.json::<Vec<Stylo>>().len(), 1);
+
+    let response = client.delete(&location).await;
+    assert_eq!(response.status_code(), 204);
+    let response = client.get(&location).await;
+    assert_eq!(response.status_code(), 404);
+    let response = client.delete(&location).await;
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_invalid_stylo() {
+    let mut container = Container::default();
+    let _receiver = container.event_publisher_receiver().unwrap();
+    let client = initialize_test_server(&mut container).await;
+    let organization_id = create_organization(&client)
+        .await
+        .organization
+        .organization_id;
+    let unknown_id = Uuid::new_v4();
+
+    let response = client
+        .post("/stylo")
+        .json(&json!({
+            "owner_organization_id": organization_id,
+            "actor_organization_id": unknown_id,
+            "display_name": "Ada",
+            "email": "ada@acme.test",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+    assert_eq!(
+        response.json::<serde_json::Value>()["organization"],
+        json!(unknown_id)
+    );
+    let response = client
+        .post("/stylo")
+        .json(&json!({
+            "owner_organization_id": organization_id,
+            "actor_organization_id": organization_id,
+            "display_name": " ",
+            "email": "ada@acme.test",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+
+    let stylo = grant_stylo(&client, organization_id).await;
+    let response = client
+        .patch(&format!("/stylo/{}", stylo.stylo_id))
+        .json(&json!({ "display_name": "" }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+    let response = client
+        .post(&format!("/stylo/{}/unlock", stylo.stylo_id))
+        .await;
+    assert_eq!(response.status_code(), 409);
+
+    let response = client.get(&format!("/stylo/{unknown_id}")).await;
+    assert_eq!(response.status_code(), 404);
+    let response = client.post(&format!("/stylo/{unknown_id}/lock")).await;
+    assert_eq!(response.status_code(), 404);
+    let response = client
+        .get(&format!("/organization/{unknown_id}/stylos"))
+        .await;
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_write_with_unknown_or_locked_stylo() {
+    let mut container = Container::default();
+    let _receiver = container.event_publisher_receiver().unwrap();
+    let client = initialize_test_server(&mut container).await;
+    let created = create_organization(&client).await;
+    let response = client
+        .post("/project/create")
+        .json(&json!({
+            "universe_id": created.universe.universe_id,
+            "project_name": "Reading list",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let stylo = grant_stylo(&client, created.organization.organization_id).await;
+    let response = client
+        .post(&format!("/stylo/{}/lock", stylo.stylo_id))
+        .await;
+    assert_eq!(response.status_code(), 200);
+
+    for (stylo_id, status_code) in [(Uuid::new_v4(), 422), (stylo.stylo_id, 403)] {
+        for path in [
+            "/project/reading-list/note",
+            "/project/reading-list/thought",
+        ] {
+            let response = client
+                .post(path)
+                .json(&json!({
+                    "imported_at": "2026-01-01T12:00:00Z",
+                    "stylo_id": stylo_id,
+                    "content": "Written with a stylo.",
+                }))
+                .await;
+            assert_eq!(response.status_code(), status_code);
+            assert_eq!(
+                response.json::<serde_json::Value>()["stylo"],
+                json!(stylo_id)
+            );
+        }
+    }
+
+    let response = client
+        .post(&format!("/stylo/{}/unlock", stylo.stylo_id))
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let response = client
+        .post("/project/reading-list/note")
+        .json(&json!({
+            "imported_at": "2026-01-01T12:00:00Z",
+            "stylo_id": stylo.stylo_id,
+            "content": "Written with a stylo.",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+}
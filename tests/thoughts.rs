@@ -0,0 +1,564 @@
+// Tests for the thoughts endpoints
+use axum_test::TestServer;
+use kaku::testkit::grant_stylo;
+use kaku::{
+    actor::ApiApp,
+    models::{
+        CategoryTree, CreateThoughtCommand, CreatedThought, DanglingLink, LinkKind, TagCount,
+        Thought, ThoughtStatus, ThoughtTree, ThoughtVariation,
+    },
+    Container,
+};
+use serde_json::json;
+use uuid::Uuid;
+
+async fn initialize_test_server(container: &mut Container) -> TestServer {
+    let service = container.thought_service().unwrap();
+    let search = container.thought_search().unwrap();
+    let saved_search = container.saved_search_service().unwrap();
+    let event_stream = container.event_stream().unwrap();
+    let project_stats = container.project_stats().unwrap();
+    let audit_log = container.audit_log_service().unwrap();
+    let organizations = container.organization_service().unwrap();
+    let stylos = container.stylo_service().unwrap();
+    let app = ApiApp::new(
+        service,
+        search,
+        saved_search,
+        event_stream,
+        project_stats,
+        audit_log,
+        organizations,
+        stylos,
+    )
+    .router();
+    TestServer::new(app).unwrap()
+}
+
+/// Grant a stylo to write the notes and thoughts with.
+async fn create_stylo(container: &mut Container) -> Uuid {
+    let stylo_book = container.stylo_book().unwrap();
+
+    grant_stylo(stylo_book.as_ref(), Uuid::new_v4())
+        .await
+        .stylo_id
+}
+
+async fn create_thought_chain(container: &mut Container, length: usize) -> Vec<Thought> {
+    let thought_book = container.thought_book().unwrap();
+    let project_id = Uuid::new_v4();
+    let mut parent_id = None;
+    let mut chain = Vec::new();
+
+    for index in 0..length {
+        let command = CreateThoughtCommand {
+            imported_at: chrono::Utc::now(),
+            parent_id,
+            stylo_id: Uuid::new_v4(),
+            project_slug: "test-project".to_string(),
+            content: format!("Thought {index}"),
+            variation: ThoughtVariation::Thought,
+            answers: None,
+            tags: Vec::new(),
+            category: None,
+            links: Vec::new(),
+            references: Vec::new(),
+            media: Vec::new(),
+        };
+        let thought = thought_book
+            .add(command, project_id, Default::default())
+            .await
+            .unwrap();
+        parent_id = Some(thought.thought_id);
+        chain.push(thought);
+    }
+
+    chain
+}
+
+#[tokio::test]
+async fn test_get_thought_success() {
+    let mut container = Container::default();
+    let chain = create_thought_chain(&mut container, 1).await;
+    let client = initialize_test_server(&mut container).await;
+
+    let response = client
+        .get(&format!("/thought/{}", chain[0].thought_id))
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+    let thought: Thought = response.json();
+    assert_eq!(thought.thought_id, chain[0].thought_id);
+}
+
+#[tokio::test]
+async fn test_get_thought_not_found() {
+    let mut container = Container::default();
+    let client = initialize_test_server(&mut container).await;
+
+    let response = client.get(&format!("/thought/{}", Uuid::new_v4())).await;
+
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_get_thought_ancestors() {
+    let mut container = Container::default();
+    let chain = create_thought_chain(&mut container, 3).await;
+    let client = initialize_test_server(&mut container).await;
+
+    let response = client
+        .get(&format!("/thought/{}/ancestors", chain[2].thought_id))
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+    let ancestors: Vec<Thought> = response.json();
+    assert_eq!(ancestors.len(), 2);
+    assert_eq!(ancestors[0].thought_id, chain[1].thought_id);
+    assert_eq!(ancestors[1].thought_id, chain[0].thought_id);
+}
+
+#[tokio::test]
+async fn test_get_thought_descendants_with_depth() {
+    let mut container = Container::default();
+    let chain = create_thought_chain(&mut container, 3).await;
+    let client = initialize_test_server(&mut container).await;
+
+    let response = client
+        .get(&format!("/thought/{}/descendants", chain[0].thought_id))
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+    let tree: ThoughtTree = response.json();
+    assert_eq!(tree.children[0].children.len(), 1);
+
+    let response = client
+        .get(&format!(
+            "/thought/{}/descendants?depth=1",
+            chain[0].thought_id
+        ))
+        .await;
+
+    assert_eq!(response.status_code(), 200);
+    let tree: ThoughtTree = response.json();
+    assert_eq!(tree.children.len(), 1);
+    assert!(tree.children[0].children.is_empty());
+}
+
+#[tokio::test]
+async fn test_create_thought_with_parent() {
+    let mut container = Container::default();
+    let project_book = container.project_book().unwrap();
+    let project_command = kaku::models::CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: "Test Project".to_string(),
+    };
+    let project = project_book.create(project_command).await.unwrap();
+    let thought_book = container.thought_book().unwrap();
+    let parent_command = CreateThoughtCommand {
+        imported_at: chrono::Utc::now(),
+        parent_id: None,
+        stylo_id: Uuid::new_v4(),
+        project_slug: project.slug.clone(),
+        content: "Parent thought".to_string(),
+        variation: ThoughtVariation::Thought,
+        answers: None,
+        tags: Vec::new(),
+        category: None,
+        links: Vec::new(),
+        references: Vec::new(),
+        media: Vec::new(),
+    };
+    let parent = thought_book
+        .add(parent_command, project.project_id, Default::default())
+        .await
+        .unwrap();
+    let client = initialize_test_server(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+
+    let response = client
+        .post("/project/test-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Child thought",
+            "parent_id": parent.thought_id,
+        }))
+        .await;
+
+    assert_eq!(response.status_code(), 201);
+    let children = thought_book.list_children(parent.thought_id).await.unwrap();
+    assert_eq!(children.len(), 1);
+}
+
+#[tokio::test]
+async fn test_create_thought_with_invalid_parent() {
+    let mut container = Container::default();
+    let project_book = container.project_book().unwrap();
+    let project_command = kaku::models::CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: "Test Project".to_string(),
+    };
+    project_book.create(project_command).await.unwrap();
+    let foreign_parent = create_thought_chain(&mut container, 1).await.remove(0);
+    let client = initialize_test_server(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+
+    let unknown_parent_id = Uuid::new_v4();
+    let response = client
+        .post("/project/test-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Orphan thought",
+            "parent_id": unknown_parent_id,
+        }))
+        .await;
+
+    assert_eq!(response.status_code(), 422);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["parent_id"], json!(unknown_parent_id));
+
+    let response = client
+        .post("/project/test-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Cross project thought",
+            "parent_id": foreign_parent.thought_id,
+        }))
+        .await;
+
+    assert_eq!(response.status_code(), 422);
+}
+
+#[tokio::test]
+async fn test_list_open_questions() {
+    let mut container = Container::default();
+    let project_book = container.project_book().unwrap();
+    let project_command = kaku::models::CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: "Test Project".to_string(),
+    };
+    project_book.create(project_command).await.unwrap();
+    let client = initialize_test_server(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+
+    let response = client
+        .post("/project/test-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Why is the sky blue?",
+            "variation": "question",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let location = response.header("Location");
+    let question_id = location.to_str().unwrap().trim_start_matches("/thought/");
+
+    let response = client
+        .get("/project/test-project/thoughts?variation=question&answered=false")
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let questions: Vec<Thought> = response.json();
+    assert_eq!(questions.len(), 1);
+    assert_eq!(questions[0].variation, ThoughtVariation::Question);
+
+    let response = client
+        .post("/project/test-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Because of the Rayleigh scattering.",
+            "answers": question_id,
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+
+    let response = client
+        .get("/project/test-project/thoughts?variation=question&answered=false")
+        .await;
+    let questions: Vec<Thought> = response.json();
+    assert!(questions.is_empty());
+
+    let response = client.get(&format!("/thought/{question_id}/answers")).await;
+    let answers: Vec<Thought> = response.json();
+    assert_eq!(answers.len(), 1);
+
+    let response = client.post(&format!("/thought/{question_id}/reopen")).await;
+    assert_eq!(response.status_code(), 200);
+    let question: Thought = response.json();
+    assert!(!question.answered);
+}
+
+#[tokio::test]
+async fn test_refute_thought() {
+    let mut container = Container::default();
+    let chain = create_thought_chain(&mut container, 2).await;
+    let client = initialize_test_server(&mut container).await;
+    let (refuted, refuting) = (&chain[0], &chain[1]);
+
+    let response = client
+        .post(&format!("/thought/{}/refute", refuted.thought_id))
+        .json(&json!({ "refuted_by": refuting.thought_id }))
+        .await;
+    assert_eq!(response.status_code(), 200);
+
+    let response = client
+        .get(&format!("/thought/{}", refuted.thought_id))
+        .await;
+    let thought: Thought = response.json();
+    assert_eq!(thought.status, ThoughtStatus::Disputed);
+    assert_eq!(thought.refuted_by, Some(refuting.thought_id));
+
+    let response = client
+        .post(&format!(
+            "/thought/{}/refutation/accept",
+            refuted.thought_id
+        ))
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let thought: Thought = response.json();
+    assert_eq!(thought.status, ThoughtStatus::Invalidated);
+
+    let response = client
+        .post(&format!(
+            "/thought/{}/refutation/dismiss",
+            refuted.thought_id
+        ))
+        .await;
+    assert_eq!(response.status_code(), 409);
+
+    let response = client
+        .post(&format!("/thought/{}/refute", refuting.thought_id))
+        .json(&json!({ "refuted_by": Uuid::new_v4() }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+}
+
+#[tokio::test]
+async fn test_thought_tags() {
+    let mut container = Container::default();
+    let project_book = container.project_book().unwrap();
+    let project_command = kaku::models::CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: "Test Project".to_string(),
+    };
+    project_book.create(project_command).await.unwrap();
+    let client = initialize_test_server(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+
+    let response = client
+        .post("/project/test-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Act only by maxims.",
+            "tags": ["ethics", "kant"],
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+    let location = response.header("Location");
+    let location = location.to_str().unwrap();
+
+    let response = client
+        .post("/project/test-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "Untaggable.",
+            "tags": ["two words"],
+        }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+
+    let response = client
+        .patch(location)
+        .json(&json!({ "tags": ["ethics", "moral"] }))
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let thought: Thought = response.json();
+    assert_eq!(thought.tags, vec!["ethics", "moral"]);
+
+    let response = client
+        .post("/project/test-project/tags/rename")
+        .json(&json!({ "tag": "moral", "new_tag": "ethics" }))
+        .await;
+    assert_eq!(response.status_code(), 409);
+
+    let response = client
+        .post("/project/test-project/tags/merge")
+        .json(&json!({ "tags": ["moral"], "into": "ethics" }))
+        .await;
+    assert_eq!(response.status_code(), 200);
+    let modified: Vec<Thought> = response.json();
+    assert_eq!(modified.len(), 1);
+
+    let response = client.get("/project/test-project/tags").await;
+    assert_eq!(response.status_code(), 200);
+    let tags: Vec<TagCount> = response.json();
+    assert_eq!(tags.len(), 1);
+    assert_eq!(tags[0].tag, "ethics");
+
+    let response = client
+        .get("/project/test-project/thoughts?tag=ethics")
+        .await;
+    let thoughts: Vec<Thought> = response.json();
+    assert_eq!(thoughts.len(), 1);
+
+    let response = client.get("/project/unknown-project/tags").await;
+    assert_eq!(response.status_code(), 404);
+}
+
+#[tokio::test]
+async fn test_thought_categories() {
+    let mut container = Container::default();
+    let project_book = container.project_book().unwrap();
+    let project_command = kaku::models::CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: "Test Project".to_string(),
+    };
+    project_book.create(project_command).await.unwrap();
+    let client = initialize_test_server(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+
+    for category in ["philosophy.ethics.kant", "philosophy.ethics", "history"] {
+        let response = client
+            .post("/project/test-project/thought")
+            .json(&json!({
+                "imported_at": "2023-10-01T12:00:00Z",
+                "stylo_id": stylo_id,
+                "content": "A filed thought.",
+                "category": category,
+            }))
+            .await;
+        assert_eq!(response.status_code(), 201);
+    }
+
+    let response = client
+        .post("/project/test-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "A misfiled thought.",
+            "category": "philosophy..kant",
+        }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+
+    let response = client
+        .get("/project/test-project/thoughts?category=philosophy")
+        .await;
+    let thoughts: Vec<Thought> = response.json();
+    assert_eq!(thoughts.len(), 2);
+
+    let response = client.get("/project/test-project/categories").await;
+    assert_eq!(response.status_code(), 200);
+    let trees: Vec<CategoryTree> = response.json();
+    assert_eq!(trees.len(), 2);
+    assert_eq!(trees[1].category.as_str(), "philosophy");
+    assert_eq!((trees[1].count, trees[1].total), (0, 2));
+    assert_eq!(trees[1].children[0].count, 1);
+}
+
+#[tokio::test]
+async fn test_thought_links() {
+    let mut container = Container::default();
+    let project_book = container.project_book().unwrap();
+    for project_name in ["Test Project", "Other Project"] {
+        let project_command = kaku::models::CreateProjectCommand {
+            universe_id: Uuid::new_v4(),
+            project_name: project_name.to_string(),
+        };
+        project_book.create(project_command).await.unwrap();
+    }
+    let client = initialize_test_server(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+
+    let response = client
+        .post("/project/test-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "A linked thought.",
+        }))
+        .await;
+    let location = response.header("Location");
+    let target_id = location.to_str().unwrap().trim_start_matches("/thought/");
+
+    let response = client
+        .post("/project/other-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "A linking thought.",
+            "links": [{ "target_id": target_id, "kind": "supports" }],
+        }))
+        .await;
+    assert_eq!(response.status_code(), 201);
+
+    let unknown_id = Uuid::new_v4();
+    let response = client
+        .post("/project/other-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "A badly linking thought.",
+            "links": [{ "target_id": unknown_id }],
+        }))
+        .await;
+    assert_eq!(response.status_code(), 422);
+    let body: serde_json::Value = response.json();
+    assert_eq!(body["link"], json!(unknown_id));
+
+    let response = client.get(&format!("/thought/{target_id}/backlinks")).await;
+    assert_eq!(response.status_code(), 200);
+    let backlinks: Vec<Thought> = response.json();
+    assert_eq!(backlinks.len(), 1);
+    assert_eq!(backlinks[0].links[0].kind, LinkKind::Supports);
+
+    let response = client.delete("/project/test-project/thoughts").await;
+    assert_eq!(response.status_code(), 200);
+    let trashed: Vec<Thought> = response.json();
+    assert_eq!(trashed.len(), 1);
+    assert_eq!(trashed[0].thought_id.to_string(), target_id);
+    let response = client.get(&format!("/thought/{target_id}")).await;
+    assert_eq!(response.status_code(), 404);
+    let response = client.delete("/project/unknown-project/thoughts").await;
+    assert_eq!(response.status_code(), 404);
+
+    let response = client.get("/project/other-project/links/dangling").await;
+    assert_eq!(response.status_code(), 200);
+    let dangling: Vec<DanglingLink> = response.json();
+    assert_eq!(dangling.len(), 1);
+    assert_eq!(dangling[0].link.target_id.to_string(), target_id);
+}
+
+#[tokio::test]
+async fn test_create_thought_reports_unresolved_links() {
+    let mut container = Container::default();
+    let project_book = container.project_book().unwrap();
+    let project_command = kaku::models::CreateProjectCommand {
+        universe_id: Uuid::new_v4(),
+        project_name: "Test Project".to_string(),
+    };
+    project_book.create(project_command).await.unwrap();
+    let client = initialize_test_server(&mut container).await;
+    let stylo_id = create_stylo(&mut container).await;
+
+    let response = client
+        .post("/project/test-project/thought")
+        .json(&json!({
+            "imported_at": "2023-10-01T12:00:00Z",
+            "stylo_id": stylo_id,
+            "content": "About #ethics, see [[0123abcd]].",
+        }))
+        .await;
+
+    assert_eq!(response.status_code(), 201);
+    let created: CreatedThought = response.json();
+    assert_eq!(created.thought.tags, vec!["ethics"]);
+    assert!(created.thought.links.is_empty());
+    assert_eq!(created.unresolved_links, vec!["0123abcd"]);
+}